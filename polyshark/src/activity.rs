@@ -0,0 +1,174 @@
+use crate::storage::StorageResult;
+use crate::types::{Market, MarketId, TokenId, Trade};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const HOURS: usize = 24;
+
+/// Streamed trades kept for the nightly profile rebuild
+pub const TRADE_HISTORY_SECS: u64 = 7 * 86_400;
+
+/// How scan cadence and WebSocket attention follow each market's busy hours
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScanConfig {
+    #[serde(default)]
+    pub enabled: bool,            // Off: every market is scanned on every book update
+    #[serde(default = "default_profiles_path")]
+    pub profiles_path: PathBuf,   // Where `run` keeps the profiles between restarts
+    #[serde(default = "default_busy_interval_ms")]
+    pub busy_interval_ms: u64,    // Scan interval in a market's busiest hour
+    #[serde(default = "default_quiet_interval_ms")]
+    pub quiet_interval_ms: u64,   // Scan interval in hours with no recorded volume
+    #[serde(default = "default_recompute_hour")]
+    pub recompute_hour_utc: u64,  // Profiles are rebuilt once a day after this hour
+    #[serde(default = "default_min_trades")]
+    pub min_trades: usize,        // Fewer recorded trades than this and the market scans at the midpoint
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            profiles_path: default_profiles_path(),
+            busy_interval_ms: default_busy_interval_ms(),
+            quiet_interval_ms: default_quiet_interval_ms(),
+            recompute_hour_utc: default_recompute_hour(),
+            min_trades: default_min_trades(),
+        }
+    }
+}
+
+fn default_profiles_path() -> PathBuf {
+    PathBuf::from("volume_profiles.json")
+}
+
+fn default_busy_interval_ms() -> u64 {
+    500
+}
+
+fn default_quiet_interval_ms() -> u64 {
+    30_000
+}
+
+fn default_recompute_hour() -> u64 {
+    4
+}
+
+fn default_min_trades() -> usize {
+    50
+}
+
+/// Share of a market's daily volume traded in each UTC hour
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeProfile {
+    pub hourly: [f64; HOURS],  // Sums to 1
+    pub trades: usize,         // Trades the profile was built from
+}
+
+impl VolumeProfile {
+    /// Activity in `hour` relative to the busiest hour, in [0, 1]
+    pub fn intensity(&self, hour: usize) -> f64 {
+        let peak = self.hourly.iter().cloned().fold(0.0, f64::max);
+        if peak <= 0.0 { 0.0 } else { self.hourly[hour % HOURS] / peak }
+    }
+}
+
+/// Intraday volume profiles for every recorded market
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VolumeProfiles {
+    pub computed_at: u64,  // Seconds
+    pub markets: HashMap<MarketId, VolumeProfile>,
+}
+
+impl VolumeProfiles {
+    /// Build profiles from recorded trades (millisecond timestamps)
+    pub fn compute<'a>(trades: impl IntoIterator<Item = &'a Trade>, markets: &[Market], now: u64) -> Self {
+        let market_of: HashMap<&TokenId, &MarketId> = markets.iter()
+            .flat_map(|m| m.clob_token_ids.iter().map(move |t| (t, &m.id)))
+            .collect();
+
+        let mut volume: HashMap<MarketId, ([f64; HOURS], usize)> = HashMap::new();
+        for trade in trades {
+            let Some(market_id) = market_of.get(&trade.token_id) else {
+                continue;
+            };
+            let (hours, count) = volume.entry((*market_id).clone()).or_insert(([0.0; HOURS], 0));
+            hours[hour_of(trade.timestamp / 1000)] += trade.size * trade.price;
+            *count += 1;
+        }
+
+        let markets = volume.into_iter()
+            .filter_map(|(id, (mut hourly, trades))| {
+                let total: f64 = hourly.iter().sum();
+                if total <= 0.0 {
+                    return None;
+                }
+                hourly.iter_mut().for_each(|v| *v /= total);
+                Some((id, VolumeProfile { hourly, trades }))
+            })
+            .collect();
+        Self { computed_at: now, markets }
+    }
+
+    /// Nightly rebuild from `trades`: markets with enough of them get a fresh profile,
+    /// the others keep the one they had
+    pub fn rebuild<'a>(&mut self, config: &ScanConfig, trades: impl IntoIterator<Item = &'a Trade>, markets: &[Market], now: u64) {
+        for (id, profile) in Self::compute(trades, markets, now).markets {
+            if profile.trades >= config.min_trades || !self.markets.contains_key(&id) {
+                self.markets.insert(id, profile);
+            }
+        }
+        self.computed_at = now;
+    }
+
+    pub fn load(path: &Path) -> StorageResult<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> StorageResult<()> {
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Whether the nightly rebuild is due: not yet computed today and past the configured hour
+    pub fn stale(&self, config: &ScanConfig, now: u64) -> bool {
+        let today = now / 86_400;
+        self.computed_at / 86_400 < today && (hour_of(now) as u64) >= config.recompute_hour_utc
+    }
+
+    /// Activity of a market right now relative to its busiest hour (unknown markets: 0.5)
+    pub fn intensity(&self, config: &ScanConfig, market_id: &MarketId, now: u64) -> f64 {
+        match self.markets.get(market_id) {
+            Some(profile) if profile.trades >= config.min_trades => profile.intensity(hour_of(now)),
+            _ => 0.5,
+        }
+    }
+
+    /// How often to scan a market now: busy interval at peak hours, quiet interval when dead,
+    /// interpolated geometrically in between
+    pub fn scan_interval(&self, config: &ScanConfig, market_id: &MarketId, now: u64) -> Duration {
+        let busy = config.busy_interval_ms.max(1) as f64;
+        let quiet = config.quiet_interval_ms.max(config.busy_interval_ms).max(1) as f64;
+        let intensity = self.intensity(config, market_id, now).clamp(0.0, 1.0);
+        Duration::from_millis((quiet * (busy / quiet).powf(intensity)).round() as u64)
+    }
+
+    /// Tokens to hold WebSocket subscriptions for, most active markets first, capped at `max`
+    pub fn watchlist(&self, config: &ScanConfig, markets: &[Market], now: u64, max: usize) -> Vec<String> {
+        let mut ranked: Vec<(&Market, f64)> = markets.iter()
+            .map(|m| (m, self.intensity(config, &m.id, now)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| b.0.volume_24hr.total_cmp(&a.0.volume_24hr)));
+        ranked.into_iter()
+            .flat_map(|(m, _)| m.clob_token_ids.iter().map(|t| t.to_string()))
+            .take(max)
+            .collect()
+    }
+}
+
+fn hour_of(secs: u64) -> usize {
+    ((secs % 86_400) / 3_600) as usize
+}
@@ -0,0 +1,165 @@
+use crate::bus::Signal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Supervised mode: trades above a size threshold wait for an operator instead of
+/// auto-executing, while a new configuration is still earning trust
+#[derive(Debug, Clone, Deserialize)]
+pub struct SupervisedConfig {
+    #[serde(default = "default_min_notional")]
+    pub min_notional: f64,  // USDC at or above which an intent needs approval
+    #[serde(default = "default_approval_timeout_ms")]
+    pub timeout_ms: u64,    // Unanswered intents are dropped, never executed, after this
+}
+
+impl Default for SupervisedConfig {
+    fn default() -> Self {
+        Self { min_notional: default_min_notional(), timeout_ms: default_approval_timeout_ms() }
+    }
+}
+
+fn default_min_notional() -> f64 {
+    50.0
+}
+
+fn default_approval_timeout_ms() -> u64 {
+    60_000
+}
+
+/// A trade the bot wants to make, held for approval
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeIntent {
+    pub id: u64,
+    pub signal: Signal,
+    pub size: f64,       // Shares per leg
+    pub notional: f64,   // USDC at the signal's prices
+    pub created_ms: u64,
+    pub expires_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntentStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Expired,
+}
+
+/// What the trading loop should do with a new intent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Admission {
+    Execute,      // Below the threshold (or supervision off): trade now
+    Queued(u64),  // Wait for an operator; poll `take_approved`
+}
+
+/// Entry returned by `GET /intents`
+#[derive(Debug, Clone, Serialize)]
+pub struct IntentView {
+    pub intent: TradeIntent,
+    pub status: IntentStatus,
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+    next_id: u64,
+    intents: BTreeMap<u64, IntentView>,
+}
+
+/// Intents awaiting an operator, shared between the trading loop and whichever front end
+/// answers them (control API today). Approved intents were priced when queued, so the loop
+/// should re-check them against current books before executing.
+#[derive(Debug)]
+pub struct ApprovalQueue {
+    pub config: Option<SupervisedConfig>,  // None = supervision off, everything executes
+    state: Mutex<QueueState>,
+}
+
+impl ApprovalQueue {
+    pub fn new(config: Option<SupervisedConfig>) -> Self {
+        Self { config, state: Mutex::new(QueueState::default()) }
+    }
+
+    /// Execute small intents straight away, queue large ones
+    pub fn admit(&self, signal: Signal, size: f64, notional: f64, now_ms: u64) -> Admission {
+        let Some(config) = self.config.as_ref().filter(|c| notional >= c.min_notional) else {
+            return Admission::Execute;
+        };
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.next_id += 1;
+        let id = state.next_id;
+        let intent = TradeIntent { id, signal, size, notional, created_ms: now_ms, expires_ms: now_ms + config.timeout_ms };
+        state.intents.insert(id, IntentView { intent, status: IntentStatus::Pending });
+        Admission::Queued(id)
+    }
+
+    /// Operator verdict on a pending intent
+    pub fn decide(&self, id: u64, approve: bool, now_ms: u64) -> Result<IntentStatus, String> {
+        let mut state = self.state.lock().map_err(|_| "approval queue poisoned".to_string())?;
+        let view = state.intents.get_mut(&id).ok_or_else(|| format!("no intent {}", id))?;
+        if view.status == IntentStatus::Pending && now_ms >= view.intent.expires_ms {
+            view.status = IntentStatus::Expired;
+        }
+        if view.status != IntentStatus::Pending {
+            return Err(format!("intent {} is already {:?}", id, view.status).to_lowercase());
+        }
+        view.status = if approve { IntentStatus::Approved } else { IntentStatus::Rejected };
+        Ok(view.status)
+    }
+
+    /// Remove and return approved intents, expiring overdue ones and dropping anything
+    /// already rejected or expired
+    pub fn take_approved(&self, now_ms: u64) -> Vec<TradeIntent> {
+        let Ok(mut state) = self.state.lock() else {
+            return Vec::new();
+        };
+        let mut approved = Vec::new();
+        state.intents.retain(|_, view| {
+            if view.status == IntentStatus::Pending && now_ms >= view.intent.expires_ms {
+                view.status = IntentStatus::Expired;
+            }
+            match view.status {
+                IntentStatus::Pending => true,
+                IntentStatus::Approved => {
+                    approved.push(view.intent.clone());
+                    false
+                }
+                IntentStatus::Rejected | IntentStatus::Expired => false,
+            }
+        });
+        approved
+    }
+
+    /// Everything not yet collected by the trading loop, oldest first
+    pub fn snapshot(&self) -> Vec<IntentView> {
+        self.state.lock().map(|s| s.intents.values().cloned().collect()).unwrap_or_default()
+    }
+
+    /// Serve `GET /intents` and `POST /intents/{id}/approve|reject`, or `None` if the
+    /// path isn't one of them
+    pub(crate) fn handle_request(&self, method: &str, path: &str) -> Option<(&'static str, String)> {
+        let rest = path.strip_prefix("/intents")?;
+        if rest.is_empty() && method == "GET" {
+            return Some(("200 OK", serde_json::to_string(&self.snapshot()).unwrap_or_default()));
+        }
+        let (id, action) = rest.strip_prefix('/')?.split_once('/')?;
+        let approve = match (method, action) {
+            ("POST", "approve") => true,
+            ("POST", "reject") => false,
+            _ => return None,
+        };
+        let Ok(id) = id.parse() else {
+            return Some(("400 Bad Request", r#"{"error":"invalid intent id"}"#.to_string()));
+        };
+        Some(match self.decide(id, approve, now_ms()) {
+            Ok(status) => ("200 OK", serde_json::json!({ "id": id, "status": status }).to_string()),
+            Err(err) => ("409 Conflict", serde_json::json!({ "error": err }).to_string()),
+        })
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
@@ -1,11 +1,55 @@
+use crate::compliance::ComplianceGate;
 use crate::constraint::ConstraintChecker;
-use crate::types::{ArbitrageSignal, Market};
+use crate::fees::polymarket_fee;
+use crate::lifecycle::MarketLifecycle;
+use crate::slippage::{classify, default_liquidity_tiers, LiquidityTier};
+use crate::tape::TradeTape;
+use crate::types::{ArbitrageSignal, CategoricalSignal, CrossMarketSignal, Event, Market, MarketId};
+use crate::quality::{QualityConfig, QualityTable};
+use crate::volatility::{VolatilityConfig, VolatilityTable};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Markets whose last signal was judged not worth trading, muted until a deadline so the
+/// same opportunity isn't re-evaluated and re-fired on every book update
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SignalCooldowns {
+    until: HashMap<MarketId, u64>,  // Seconds
+}
+
+impl SignalCooldowns {
+    pub fn start(&mut self, market_id: &MarketId, now: u64, secs: u64) {
+        self.until.insert(market_id.clone(), now + secs);
+    }
+
+    pub fn is_cooling(&self, market_id: &MarketId, now: u64) -> bool {
+        self.until.get(market_id).is_some_and(|until| now < *until)
+    }
+
+    /// Forget cooldowns that have run out
+    pub fn prune(&mut self, now: u64) {
+        self.until.retain(|_, until| now < *until);
+    }
+
+    pub fn len(&self) -> usize {
+        self.until.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.until.is_empty()
+    }
+}
 
 /// Arbitrage detector
 #[derive(Debug)]
 pub struct ArbitrageDetector {
     pub constraint_checker: ConstraintChecker,
     pub min_profit_threshold: f64,  // Minimum expected profit to trade
+    pub liquidity_tiers: Vec<LiquidityTier>,
+    pub volatility: Option<(VolatilityTable, VolatilityConfig)>,  // Jumpy markets need more edge
+    pub compliance: ComplianceGate,  // Markets the user may not enter are never signalled
+    pub quality: Option<(QualityTable, QualityConfig)>,  // Markets with messy resolutions are never signalled
+    pub pairs: Vec<(MarketId, MarketId)>,  // Duplicate markets confirmed with `polyshark pairs`
 }
 
 impl ArbitrageDetector {
@@ -13,28 +57,144 @@ impl ArbitrageDetector {
         Self {
             constraint_checker: ConstraintChecker::new(min_spread),
             min_profit_threshold: min_profit,
+            liquidity_tiers: default_liquidity_tiers(),
+            volatility: None,
+            compliance: ComplianceGate::default(),
+            quality: None,
+            pairs: Vec::new(),
         }
     }
 
+    pub fn with_tiers(mut self, tiers: Vec<LiquidityTier>) -> Self {
+        self.liquidity_tiers = tiers;
+        self
+    }
+
+    pub fn with_volatility(mut self, table: VolatilityTable, config: VolatilityConfig) -> Self {
+        self.volatility = Some((table, config));
+        self
+    }
+
+    pub fn with_compliance(mut self, gate: ComplianceGate) -> Self {
+        self.compliance = gate;
+        self
+    }
+
+    pub fn with_quality(mut self, table: QualityTable, config: QualityConfig) -> Self {
+        self.quality = Some((table, config));
+        self
+    }
+
+    pub fn with_pairs(mut self, pairs: Vec<(MarketId, MarketId)>) -> Self {
+        self.pairs = pairs;
+        self
+    }
+
+    /// Whether a market is in the tradable universe: allowed for this user and not
+    /// flagged as a resolution risk
+    fn admits(&self, market: &Market) -> bool {
+        self.compliance.allows(market)
+            && self.quality.as_ref().is_none_or(|(table, config)| table.passes(config, &market.id))
+    }
+
+    /// Effective minimum edge for a market: the base threshold plus its volatility add-on
+    pub fn min_edge(&self, market_id: &MarketId) -> f64 {
+        let extra = self.volatility.as_ref().map(|(table, config)| table.extra_edge(config, market_id)).unwrap_or(0.0);
+        self.constraint_checker.min_spread_threshold + extra
+    }
+
+    fn check(&self, market: &Market) -> Option<ArbitrageSignal> {
+        if !self.admits(market) {
+            return None;
+        }
+        self.constraint_checker.check_violation_above(market, self.min_edge(&market.id))
+    }
+
+    /// Per-unit buffer for a market of this depth; unknown depth gets the thinnest tier's buffer
+    pub fn slippage_buffer(&self, depth: Option<f64>) -> f64 {
+        match depth {
+            Some(d) => classify(&self.liquidity_tiers, d).map(|t| t.buffer),
+            None => self.liquidity_tiers.iter().min_by(|a, b| a.min_depth.total_cmp(&b.min_depth)).map(|t| t.buffer),
+        }
+        .unwrap_or(0.0)
+    }
+
     /// Scan markets for arbitrage opportunities
     pub fn scan(&self, markets: &[Market]) -> Vec<ArbitrageSignal> {
         markets.iter()
             .filter(|m| m.active && m.accepting_orders)
-            .filter_map(|m| self.constraint_checker.check_violation(m))
+            .filter_map(|m| self.check(m))
+            .collect()
+    }
+
+    /// Scan bucketed (scalar/range) events for categorical mispricing. Membership comes from
+    /// the Gamma event itself: a set inferred from a market list can miss a bucket, and the
+    /// rest would then sum below 1 with no real edge.
+    pub fn scan_event_sets(&self, events: &[Event]) -> Vec<CategoricalSignal> {
+        let mut signals: Vec<CategoricalSignal> = events.iter()
+            .filter(|e| e.is_exclusive() && !e.closed)
+            .filter_map(|e| self.check_set(&e.id, &e.markets()))
+            .collect();
+        signals.sort_by(|a, b| b.spread.total_cmp(&a.spread));
+        signals
+    }
+
+    fn check_set(&self, event_id: &str, buckets: &[&Market]) -> Option<CategoricalSignal> {
+        // A bucket that isn't tradable (or that we may not trade) means we can't complete the set
+        if !buckets.iter().all(|m| m.active && m.accepting_orders && self.admits(m)) {
+            return None;
+        }
+        // The whole set is only as calm as its jumpiest bucket
+        let min_edge = buckets.iter().map(|m| self.min_edge(&m.id)).fold(0.0, f64::max);
+        self.constraint_checker.check_categorical_above(event_id, buckets, min_edge)
+    }
+
+    /// Scan confirmed duplicate pairs for cross-market mispricing. Pairs with a side
+    /// missing from `markets` (not watched, or no longer listed) are skipped.
+    pub fn scan_pairs(&self, markets: &HashMap<MarketId, Market>) -> Vec<CrossMarketSignal> {
+        let mut signals: Vec<CrossMarketSignal> = self.pairs.iter()
+            .filter_map(|(a, b)| Some((markets.get(a)?, markets.get(b)?)))
+            .filter(|(a, b)| [a, b].iter().all(|m| m.active && m.accepting_orders && self.admits(m)))
+            .filter_map(|(a, b)| {
+                let min_edge = self.min_edge(&a.id).max(self.min_edge(&b.id));
+                self.constraint_checker.check_pair_above(a, b, min_edge)
+            })
+            .collect();
+        signals.sort_by(|a, b| b.spread.total_cmp(&a.spread));
+        signals
+    }
+
+    /// Scan only markets whose lifecycle state is `Tradable`
+    pub fn scan_tradable(&self, markets: &[Market], lifecycle: &MarketLifecycle) -> Vec<ArbitrageSignal> {
+        markets.iter()
+            .filter(|m| lifecycle.is_tradable(&m.id))
+            .filter_map(|m| self.check(m))
+            .collect()
+    }
+
+    /// Scan, suppressing markets with no trade inside `max_trade_age_ms`
+    pub fn scan_fresh(&self, markets: &[Market], tape: &TradeTape, now_ms: u64, max_trade_age_ms: u64) -> Vec<ArbitrageSignal> {
+        markets.iter()
+            .filter(|m| m.active && m.accepting_orders)
+            .filter(|m| !tape.is_stale(m, now_ms, max_trade_age_ms))
+            .filter_map(|m| self.check(m))
             .collect()
     }
 
-    /// Calculate expected profit after costs
+    /// Calculate expected profit after costs, including the liquidity-tier buffer for `depth`
     pub fn expected_profit(
         &self,
         signal: &ArbitrageSignal,
         size: f64,
         fee_rate: f64,
         slippage: f64,
+        depth: Option<f64>,
     ) -> f64 {
         let gross = signal.edge * size;
-        let fee_cost = size * signal.yes_price * fee_rate * 2.0; // Both legs
-        let slippage_cost = size * slippage;
+        // Fee per leg scales with min(p, 1 - p), not flat notional
+        let fee_cost = polymarket_fee(fee_rate, signal.yes_price, size)
+            + polymarket_fee(fee_rate, signal.no_price, size);
+        let slippage_cost = size * (slippage + self.slippage_buffer(depth));
         
         gross - fee_cost - slippage_cost
     }
@@ -46,7 +206,8 @@ impl ArbitrageDetector {
         size: f64,
         fee_rate: f64,
         slippage: f64,
+        depth: Option<f64>,
     ) -> bool {
-        self.expected_profit(signal, size, fee_rate, slippage) > self.min_profit_threshold
+        self.expected_profit(signal, size, fee_rate, slippage, depth) > self.min_profit_threshold
     }
 }
\ No newline at end of file
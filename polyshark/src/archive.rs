@@ -0,0 +1,89 @@
+use crate::provider::MarketProvider;
+use crate::storage::StorageResult;
+use crate::types::{format_date, Market};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Markets fetched per Gamma request
+const PAGE_SIZE: usize = 500;
+
+/// Daily snapshots of the full Gamma market list (prices, liquidity, metadata)
+/// as gzipped JSON lines: `<dir>/markets-YYYY-MM-DD.jsonl.gz`
+#[derive(Debug, Clone)]
+pub struct MarketArchiver {
+    pub dir: PathBuf,
+}
+
+impl MarketArchiver {
+    pub fn new(dir: &Path) -> Self {
+        Self { dir: dir.to_path_buf() }
+    }
+
+    /// Archive file for the UTC day containing `now`
+    pub fn path_for(&self, now: u64) -> PathBuf {
+        self.dir.join(format!("markets-{}.jsonl.gz", format_date(now)))
+    }
+
+    /// Take today's snapshot unless it already exists; returns the file and market count
+    pub async fn snapshot(&self, source: &impl MarketProvider, now: u64) -> StorageResult<Option<(PathBuf, usize)>> {
+        let path = self.path_for(now);
+        if path.exists() {
+            return Ok(None);
+        }
+
+        let mut markets = Vec::new();
+        loop {
+            // Closed markets too: resolved ones are what universe and spread research look back on
+            let page = source.all_markets(PAGE_SIZE, markets.len()).await?;
+            let done = page.len() < PAGE_SIZE;
+            markets.extend(page);
+            if done {
+                break;
+            }
+        }
+
+        fs::create_dir_all(&self.dir)?;
+        // Write to a temp file first so a crash never leaves a truncated archive for the day
+        let tmp = path.with_extension("gz.partial");
+        Self::write(&tmp, &markets)?;
+        fs::rename(&tmp, &path)?;
+        Ok(Some((path, markets.len())))
+    }
+
+    /// Read one snapshot back
+    pub fn load(path: &Path) -> StorageResult<Vec<Market>> {
+        let reader = BufReader::new(GzDecoder::new(File::open(path)?));
+        let mut markets = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                markets.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(markets)
+    }
+
+    /// All snapshot files, oldest first
+    pub fn snapshots(&self) -> StorageResult<Vec<PathBuf>> {
+        let mut files: Vec<PathBuf> = fs::read_dir(&self.dir)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("markets-") && n.ends_with(".jsonl.gz")))
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    fn write(path: &Path, markets: &[Market]) -> StorageResult<()> {
+        let mut out = GzEncoder::new(BufWriter::new(File::create(path)?), Compression::default());
+        for market in markets {
+            serde_json::to_writer(&mut out, market)?;
+            out.write_all(b"\n")?;
+        }
+        out.finish()?.flush()?;
+        Ok(())
+    }
+}
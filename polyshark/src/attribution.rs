@@ -0,0 +1,114 @@
+use crate::journal::Journal;
+use crate::lots::{LotBook, LotMethod};
+use crate::manual::MANUAL_TAG;
+use crate::types::Side;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Journal tag on fills that hedged a failed or partial leg
+pub const HEDGE_TAG: &str = "hedge";
+
+/// Journal tag on fills of our own resting orders
+pub const MAKER_TAG: &str = "maker-fill";
+
+/// Journal tag on inventory adopted from the exchange by reconciliation
+pub const FOUND_TAG: &str = "found";
+
+/// Why a position (or one lot of it) was opened
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EntryReason {
+    #[default]
+    ArbLeg,     // Taker leg of a detected arbitrage
+    Hedge,      // Covering a leg that failed or filled partially
+    MakerFill,  // A resting passive order got filled
+    Manual,     // `buy` / `sell` from the command line
+    Found,      // Held on the exchange but never bought by us (reconciliation)
+}
+
+impl EntryReason {
+    pub fn tag(self) -> &'static str {
+        match self {
+            EntryReason::ArbLeg => "arb-leg",
+            EntryReason::Hedge => HEDGE_TAG,
+            EntryReason::MakerFill => MAKER_TAG,
+            EntryReason::Manual => MANUAL_TAG,
+            EntryReason::Found => FOUND_TAG,
+        }
+    }
+
+    /// Reason behind a journaled buy, from its tags; untagged fills are arbitrage legs
+    pub fn from_tags(tags: &[String]) -> Self {
+        let has = |tag: &str| tags.iter().any(|t| t.eq_ignore_ascii_case(tag));
+        if has(MANUAL_TAG) {
+            EntryReason::Manual
+        } else if has(HEDGE_TAG) {
+            EntryReason::Hedge
+        } else if has(MAKER_TAG) {
+            EntryReason::MakerFill
+        } else if has(FOUND_TAG) {
+            EntryReason::Found
+        } else {
+            EntryReason::ArbLeg
+        }
+    }
+}
+
+/// PnL of everything opened for one reason
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AttributionRow {
+    pub reason: EntryReason,
+    pub fills: usize,        // Opening (buy) fills
+    pub bought: f64,         // Cost of those fills, fees included
+    pub closed_size: f64,
+    pub realized_pnl: f64,   // Over closed lots, buy and sell fees included
+    pub winners: usize,      // Closed lots with a gain
+    pub losers: usize,
+    pub open_size: f64,
+    pub open_cost: f64,      // Still-open lots at cost; marked nowhere, so no unrealized PnL
+}
+
+impl AttributionRow {
+    pub fn win_rate(&self) -> f64 {
+        let closed = self.winners + self.losers;
+        if closed == 0 { 0.0 } else { self.winners as f64 / closed as f64 }
+    }
+
+    pub fn pnl_per_fill(&self) -> f64 {
+        if self.fills == 0 { 0.0 } else { self.realized_pnl / self.fills as f64 }
+    }
+}
+
+/// Realized PnL split by why each lot was opened. Sales close lots under the lot method,
+/// so a hedge sold against an arbitrage leg's shares is charged to whichever lot it
+/// consumes, the same way tax lots are matched.
+pub fn attribute(journal: &Journal, method: LotMethod) -> Vec<AttributionRow> {
+    let lots = LotBook::from_journal(journal, method);
+    let mut rows: BTreeMap<EntryReason, AttributionRow> = BTreeMap::new();
+
+    for e in journal.entries.iter().filter(|e| e.side == Side::Buy) {
+        let r = row(&mut rows, EntryReason::from_tags(&e.tags));
+        r.fills += 1;
+        r.bought += e.size * e.price + e.fee;
+    }
+    for lot in &lots.closed {
+        let r = row(&mut rows, lot.reason);
+        r.closed_size += lot.size;
+        r.realized_pnl += lot.gain();
+        if lot.gain() > 0.0 {
+            r.winners += 1;
+        } else {
+            r.losers += 1;
+        }
+    }
+    for lot in lots.open.values().flatten() {
+        let r = row(&mut rows, lot.reason);
+        r.open_size += lot.size;
+        r.open_cost += lot.size * lot.cost;
+    }
+    rows.into_values().collect()
+}
+
+fn row(rows: &mut BTreeMap<EntryReason, AttributionRow>, reason: EntryReason) -> &mut AttributionRow {
+    rows.entry(reason).or_insert_with(|| AttributionRow { reason, ..Default::default() })
+}
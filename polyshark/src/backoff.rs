@@ -0,0 +1,130 @@
+use crate::bus::{EventBus, RiskEvent};
+use crate::orders::OrderError;
+use crate::types::MarketId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How quickly a market that keeps failing at placement stops being signalled
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackoffConfig {
+    #[serde(default = "default_max_failures")]
+    pub max_failures: u32,   // Consecutive failed placements before backing off
+    #[serde(default = "default_base_secs")]
+    pub base_secs: u64,      // First backoff; doubles with each further failure
+    #[serde(default = "default_max_secs")]
+    pub max_secs: u64,       // Backoff never grows past this
+}
+
+impl Default for BackoffConfig {
+    fn default() -> Self {
+        Self { max_failures: default_max_failures(), base_secs: default_base_secs(), max_secs: default_max_secs() }
+    }
+}
+
+fn default_max_failures() -> u32 {
+    3
+}
+
+fn default_base_secs() -> u64 {
+    60
+}
+
+fn default_max_secs() -> u64 {
+    3600
+}
+
+/// Whether a placement error says something about the market itself. Rejections (tick
+/// size, minimum size, auth, balance) repeat until someone looks; network errors and
+/// killed FOK orders are transient or just a lost race, and self-trade refusals never
+/// reached the exchange.
+pub fn counts_against_market(error: &OrderError) -> bool {
+    matches!(error, OrderError::Rejected(_))
+}
+
+/// Failure streak of one market
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MarketFailures {
+    pub failures: u32,          // Consecutive, reset by a successful placement
+    pub last_error: String,
+    pub until: u64,             // Not signalled before this (seconds); 0 = not backing off
+}
+
+/// Per-market exponential backoff for markets whose orders keep failing, so a broken
+/// market raises one diagnostic alert instead of hammering the API on every signal
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecutionBackoff {
+    #[serde(skip)]
+    pub config: BackoffConfig,
+    markets: HashMap<MarketId, MarketFailures>,
+    #[serde(skip)]
+    bus: Option<EventBus>,
+}
+
+impl ExecutionBackoff {
+    pub fn new(config: BackoffConfig) -> Self {
+        Self { config, ..Default::default() }
+    }
+
+    pub fn with_bus(mut self, bus: EventBus) -> Self {
+        self.bus = Some(bus);
+        self
+    }
+
+    /// Backoff after the given streak, doubling per failure past `max_failures`
+    pub fn delay_secs(&self, failures: u32) -> u64 {
+        if failures < self.config.max_failures.max(1) {
+            return 0;
+        }
+        let doublings = (failures - self.config.max_failures.max(1)).min(32);
+        self.config.base_secs.saturating_mul(1 << doublings).min(self.config.max_secs)
+    }
+
+    /// Record a failed placement; returns the backoff deadline when the market is now
+    /// backing off. Errors that don't count against the market are ignored.
+    pub fn record_failure(&mut self, market_id: &MarketId, error: &OrderError, now: u64) -> Option<u64> {
+        if !counts_against_market(error) {
+            return None;
+        }
+        let entry = self.markets.entry(market_id.clone()).or_default();
+        entry.failures += 1;
+        entry.last_error = error.to_string();
+        let failures = entry.failures;
+        let delay = self.delay_secs(failures);
+        if delay == 0 {
+            return None;
+        }
+        let until = now + delay;
+        let entry = self.markets.get_mut(market_id)?;
+        entry.until = until;
+        let reason = entry.last_error.clone();
+        eprintln!(
+            "⚠️  market {} failed {} placements in a row ({}), not signalling it for {}s",
+            market_id, failures, reason, delay
+        );
+        if let Some(bus) = &self.bus {
+            bus.publish_risk(RiskEvent::MarketBackoff { market_id: market_id.clone(), failures, until, reason });
+        }
+        Some(until)
+    }
+
+    /// A placement went through: the market is healthy again
+    pub fn record_success(&mut self, market_id: &MarketId) {
+        self.markets.remove(market_id);
+    }
+
+    /// Whether signals for the market should be dropped right now
+    pub fn is_backing_off(&self, market_id: &MarketId, now: u64) -> bool {
+        self.markets.get(market_id).is_some_and(|m| now < m.until)
+    }
+
+    pub fn failures(&self, market_id: &MarketId) -> Option<&MarketFailures> {
+        self.markets.get(market_id)
+    }
+
+    /// Markets currently backed off, soonest to resume first
+    pub fn backing_off(&self, now: u64) -> Vec<(&MarketId, &MarketFailures)> {
+        let mut out: Vec<_> = self.markets.iter().filter(|(_, m)| now < m.until).collect();
+        out.sort_by_key(|(id, m)| (m.until, (*id).clone()));
+        out
+    }
+}
@@ -0,0 +1,634 @@
+use crate::costcurve::{max_pair_size, CostCurve};
+use crate::fees::polymarket_fee;
+use crate::types::{MarketId, OrderBook, Side};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// One simulated arbitrage trade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestTrade {
+    #[serde(default)]
+    pub trade_id: String,  // Stable across runs: market, timestamp and occurrence at that timestamp
+    pub market_id: MarketId,
+    pub timestamp: u64,
+    pub size: f64,
+    pub edge: f64,   // Gross edge per unit at entry
+    pub fees: f64,
+    pub pnl: f64,    // Net of fees and slippage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prices: Option<[f64; 2]>,  // YES and NO entry prices, for re-pricing fees
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<f64>,        // Notional of the shallower book at entry, for re-pricing slippage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_ms: Option<u64>, // Fill time to the millisecond, when the run recorded it
+}
+
+impl BacktestTrade {
+    /// Fill time in ms; older results only have whole seconds
+    pub fn fill_ms(&self) -> u64 {
+        self.timestamp_ms.unwrap_or(self.timestamp * 1000)
+    }
+
+    /// YES and NO prices; older results only have the edge, so the pair's cost is split evenly
+    pub fn leg_prices(&self) -> [f64; 2] {
+        self.prices.unwrap_or([(1.0 - self.edge) / 2.0; 2])
+    }
+
+    /// Fee for both legs at `rate` on the fee curve, which charges min(p, 1 - p) per share
+    pub fn curve_fee(&self, rate: f64) -> f64 {
+        self.leg_prices().iter().map(|p| polymarket_fee(rate, *p, self.size)).sum()
+    }
+
+    /// Slippage for both legs at `rate` of the pair's cost, growing with the share of the
+    /// book the trade takes (a book no deeper than the trade doubles it)
+    pub fn depth_slippage(&self, rate: f64) -> f64 {
+        let notional = self.size * self.leg_prices().iter().sum::<f64>();
+        let share = self.depth.filter(|d| *d > 0.0).map_or(0.0, |d| (notional / d).min(1.0));
+        rate * notional * (1.0 + share)
+    }
+}
+
+/// Headline numbers for a run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BacktestMetrics {
+    pub trades: usize,
+    pub total_pnl: f64,
+    pub total_fees: f64,
+    pub win_rate: f64,
+    pub avg_pnl: f64,
+    pub max_drawdown: f64,
+}
+
+impl BacktestMetrics {
+    pub fn from_trades(trades: &[BacktestTrade]) -> Self {
+        let mut m = Self { trades: trades.len(), ..Default::default() };
+        let (mut equity, mut peak, mut wins) = (0.0_f64, 0.0_f64, 0);
+        for t in trades {
+            m.total_pnl += t.pnl;
+            m.total_fees += t.fees;
+            if t.pnl > 0.0 {
+                wins += 1;
+            }
+            equity += t.pnl;
+            peak = peak.max(equity);
+            m.max_drawdown = m.max_drawdown.max(peak - equity);
+        }
+        if !trades.is_empty() {
+            m.win_rate = wins as f64 / trades.len() as f64;
+            m.avg_pnl = m.total_pnl / trades.len() as f64;
+        }
+        m
+    }
+
+    /// Named values in a stable order for comparisons
+    pub fn named(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("trades", self.trades as f64),
+            ("total_pnl", self.total_pnl),
+            ("total_fees", self.total_fees),
+            ("win_rate", self.win_rate),
+            ("avg_pnl", self.avg_pnl),
+            ("max_drawdown", self.max_drawdown),
+        ]
+    }
+}
+
+/// Seedable generator for fill and competition models (SplitMix64), so a run
+/// with the same seed and inputs makes the same draws on every machine
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// True with probability `p` (e.g., a maker fill or losing a race to a competitor)
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+}
+
+/// Saved output of a backtest run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestResult {
+    pub label: String,  // e.g., git revision or parameter set
+    pub trades: Vec<BacktestTrade>,
+    pub metrics: BacktestMetrics,
+    #[serde(default)]
+    pub seed: Option<u64>,  // RNG seed the run used, if any model drew random numbers
+    #[serde(default)]
+    pub hash: String,       // Content hash of the trades; equal hashes mean identical results
+}
+
+impl BacktestResult {
+    /// Trades are put in a fixed (timestamp, market, size, pnl) order so neither the hash nor
+    /// the trade ids depend on the order the run happened to produce them
+    pub fn new(label: &str, mut trades: Vec<BacktestTrade>) -> Self {
+        trades.sort_by(|a, b| {
+            a.timestamp.cmp(&b.timestamp)
+                .then_with(|| a.market_id.cmp(&b.market_id))
+                .then_with(|| a.size.total_cmp(&b.size))
+                .then_with(|| a.pnl.total_cmp(&b.pnl))
+        });
+        assign_trade_ids(&mut trades);
+        let metrics = BacktestMetrics::from_trades(&trades);
+        let hash = content_hash(&trades);
+        Self { label: label.to_string(), trades, metrics, seed: None, hash }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Recompute the hash (files saved before hashing existed have none)
+    pub fn content_hash(&self) -> String {
+        content_hash(&self.trades)
+    }
+
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let mut result: Self = serde_json::from_str(&text).map_err(std::io::Error::other)?;
+        // Files saved before trade ids existed
+        assign_trade_ids(&mut result.trades);
+        Ok(result)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let text = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        fs::write(path, text)
+    }
+}
+
+/// Give every trade without one an id of `market:timestamp:n`, `n` counting trades in the
+/// same market at the same timestamp. Trades must already be in `BacktestResult` order.
+fn assign_trade_ids(trades: &mut [BacktestTrade]) {
+    let mut seen: HashMap<(MarketId, u64), u32> = HashMap::new();
+    for trade in trades.iter_mut() {
+        let n = seen.entry((trade.market_id.clone(), trade.timestamp)).or_default();
+        if trade.trade_id.is_empty() {
+            trade.trade_id = format!("{}:{}:{}", trade.market_id, trade.timestamp, n);
+        }
+        *n += 1;
+    }
+}
+
+/// FNV-1a over the canonical JSON of the trades, as 16 hex digits
+fn content_hash(trades: &[BacktestTrade]) -> String {
+    let json = serde_json::to_vec(trades).unwrap_or_default();
+    let hash = json.iter().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| (h ^ *b as u64).wrapping_mul(0x0100_0000_01b3));
+    format!("{:016x}", hash)
+}
+
+/// Notional of one traded unit: a YES + NO pair, which costs about $1
+const UNIT_NOTIONAL: f64 = 1.0;
+
+/// Shades from losing to most profitable cell
+const SENSITIVITY_SHADES: [char; 4] = ['░', '▒', '▓', '█'];
+
+/// Symmetric grid `-max_bps..=max_bps` in `step_bps` increments
+pub fn bps_grid(max_bps: f64, step_bps: f64) -> Vec<f64> {
+    let steps = (max_bps / step_bps.max(1e-9)).floor() as i64;
+    (-steps..=steps).map(|i| i as f64 * step_bps).collect()
+}
+
+/// A run re-priced under one fee and slippage perturbation
+#[derive(Debug, Clone, Serialize)]
+pub struct SensitivityCell {
+    pub fee_bps: f64,
+    pub slippage_bps: f64,
+    pub total_pnl: f64,
+    pub total_fees: f64,
+    pub winners: usize,  // Trades still profitable
+}
+
+/// PnL of a completed run across a grid of fee and slippage errors, assuming the same
+/// trades are taken. Fee bps move the rate on the price-dependent fee curve, so cheap and
+/// lopsided pairs feel them less; slippage bps are charged on each pair's cost and grow
+/// with the share of the book the trade took.
+#[derive(Debug, Clone, Serialize)]
+pub struct SensitivityGrid {
+    pub label: String,
+    pub baseline_pnl: f64,
+    pub fee_bps: Vec<f64>,       // Rows
+    pub slippage_bps: Vec<f64>,  // Columns
+    pub cells: Vec<SensitivityCell>,  // Row-major
+    pub notional: f64,           // Total traded cost of the pairs
+}
+
+impl SensitivityGrid {
+    pub fn run(result: &BacktestResult, fee_bps: &[f64], slippage_bps: &[f64]) -> Self {
+        let notional: f64 = result.trades.iter().map(|t| t.size * t.leg_prices().iter().sum::<f64>()).sum();
+        let mut cells = Vec::with_capacity(fee_bps.len() * slippage_bps.len());
+        for &fee in fee_bps {
+            for &slippage in slippage_bps {
+                let mut cell = SensitivityCell { fee_bps: fee, slippage_bps: slippage, total_pnl: 0.0, total_fees: 0.0, winners: 0 };
+                for t in &result.trades {
+                    let extra_fee = t.curve_fee(fee / 10_000.0);
+                    let extra_slippage = t.depth_slippage(slippage / 10_000.0);
+                    let pnl = t.pnl - extra_fee - extra_slippage;
+                    cell.total_pnl += pnl;
+                    cell.total_fees += t.fees + extra_fee;
+                    if pnl > 0.0 {
+                        cell.winners += 1;
+                    }
+                }
+                cells.push(cell);
+            }
+        }
+        Self {
+            label: result.label.clone(),
+            baseline_pnl: result.metrics.total_pnl,
+            fee_bps: fee_bps.to_vec(),
+            slippage_bps: slippage_bps.to_vec(),
+            cells,
+            notional,
+        }
+    }
+
+    pub fn cell(&self, row: usize, column: usize) -> Option<&SensitivityCell> {
+        self.cells.get(row * self.slippage_bps.len() + column)
+    }
+
+    /// Share of the grid where the run still makes money
+    pub fn profitable_share(&self) -> f64 {
+        if self.cells.is_empty() {
+            return 0.0;
+        }
+        self.cells.iter().filter(|c| c.total_pnl > 0.0).count() as f64 / self.cells.len() as f64
+    }
+
+    /// Extra cost, in bps of the pairs' total cost, the run absorbs before it loses money
+    /// (negative if it already does)
+    pub fn headroom_bps(&self) -> Option<f64> {
+        (self.notional > 0.0).then(|| self.baseline_pnl / self.notional * 10_000.0)
+    }
+
+    /// Text plot: one row per fee perturbation, one shaded cell per slippage perturbation.
+    /// Losing cells are `-`; profitable ones darken with PnL.
+    pub fn render(&self) -> Vec<String> {
+        let best = self.cells.iter().map(|c| c.total_pnl).fold(0.0, f64::max);
+        let mut lines = vec![format!(
+            "{:>9} | slippage bps {:+.0} .. {:+.0}, then PnL at both ends",
+            "fee bps",
+            self.slippage_bps.first().copied().unwrap_or(0.0),
+            self.slippage_bps.last().copied().unwrap_or(0.0),
+        )];
+        for (row, fee) in self.fee_bps.iter().enumerate() {
+            let shades: String = (0..self.slippage_bps.len())
+                .filter_map(|column| self.cell(row, column))
+                .map(|c| {
+                    if c.total_pnl <= 0.0 || best <= 0.0 {
+                        '-'
+                    } else {
+                        let i = ((c.total_pnl / best) * (SENSITIVITY_SHADES.len() - 1) as f64).round() as usize;
+                        SENSITIVITY_SHADES[i.min(SENSITIVITY_SHADES.len() - 1)]
+                    }
+                })
+                .collect();
+            let pnl = |column: usize| self.cell(row, column).map(|c| c.total_pnl).unwrap_or(0.0);
+            lines.push(format!(
+                "{:>+9.0} | {}  {:>+10.2} .. {:>+10.2}",
+                fee,
+                shades,
+                pnl(0),
+                pnl(self.slippage_bps.len().saturating_sub(1)),
+            ));
+        }
+        lines
+    }
+}
+
+/// Default delay between a simulated fill and the book it is checked against
+pub const DEFAULT_LOOKAHEAD_MS: u64 = 500;
+
+/// One simulated fill checked against the books shortly after it
+#[derive(Debug, Clone, Serialize)]
+pub struct LookaheadTrade {
+    pub market_id: MarketId,
+    pub timestamp: u64,
+    pub size: f64,
+    pub available: f64,  // Pairs still buyable at the simulated unit cost after the delay
+    pub pnl: f64,
+    pub realistic_pnl: f64,
+}
+
+impl LookaheadTrade {
+    pub fn captured(&self) -> f64 {
+        if self.size <= 0.0 { 1.0 } else { (self.available / self.size).min(1.0) }
+    }
+}
+
+/// How much of a run's PnL survives checking each fill against the book `delay_ms` later.
+/// A simulator filling against the book it detected on assumes nobody else reacts; by the
+/// time real orders land, part of the edge is usually gone.
+#[derive(Debug, Clone, Serialize)]
+pub struct LookaheadReport {
+    pub label: String,
+    pub delay_ms: u64,
+    pub trades: usize,
+    pub unchecked: usize,          // No binary-market books recorded at the later time
+    pub read_errors: Vec<String>,  // Trades whose later books couldn't be read; also left out
+    pub simulated_size: f64,       // Over checked trades
+    pub captured_size: f64,
+    pub simulated_pnl: f64,
+    pub realistic_pnl: f64,
+    pub checked: Vec<LookaheadTrade>,
+}
+
+impl LookaheadReport {
+    /// Check every trade of `result`. `books_at(market, ms)` returns the market's outcome
+    /// books as they stood at that time. Only binary markets are checked. A fill keeps the
+    /// share of its size still buyable at or below its simulated unit cost (1 - edge) and
+    /// that share of its PnL.
+    pub fn run<E: std::fmt::Display>(
+        result: &BacktestResult,
+        delay_ms: u64,
+        mut books_at: impl FnMut(&MarketId, u64) -> Result<Vec<OrderBook>, E>,
+    ) -> Self {
+        let mut report = Self {
+            label: result.label.clone(),
+            delay_ms,
+            trades: result.trades.len(),
+            unchecked: 0,
+            read_errors: Vec::new(),
+            simulated_size: 0.0,
+            captured_size: 0.0,
+            simulated_pnl: 0.0,
+            realistic_pnl: 0.0,
+            checked: Vec::new(),
+        };
+        for trade in &result.trades {
+            let at = trade.fill_ms() + delay_ms;
+            let books = match books_at(&trade.market_id, at) {
+                Ok(books) => books,
+                Err(err) => {
+                    report.read_errors.push(format!("{} at {}: {}", trade.market_id, at, err));
+                    continue;
+                }
+            };
+            let [a, b] = books.as_slice() else {
+                report.unchecked += 1;
+                continue;
+            };
+            let unit_cost = UNIT_NOTIONAL - trade.edge;
+            let available = max_pair_size(&CostCurve::from_book(a, Side::Buy), &CostCurve::from_book(b, Side::Buy), unit_cost, Side::Buy);
+            let mut checked = LookaheadTrade {
+                market_id: trade.market_id.clone(),
+                timestamp: trade.timestamp,
+                size: trade.size,
+                available,
+                pnl: trade.pnl,
+                realistic_pnl: 0.0,
+            };
+            checked.realistic_pnl = trade.pnl * checked.captured();
+            report.simulated_size += trade.size;
+            report.captured_size += trade.size * checked.captured();
+            report.simulated_pnl += trade.pnl;
+            report.realistic_pnl += checked.realistic_pnl;
+            report.checked.push(checked);
+        }
+        report
+    }
+
+    /// Size-weighted share of simulated fills still there after the delay
+    pub fn capture_rate(&self) -> f64 {
+        if self.simulated_size <= 0.0 { 0.0 } else { self.captured_size / self.simulated_size }
+    }
+
+    /// Simulated minus realistic PnL: what the backtest overstates
+    pub fn optimism_gap(&self) -> f64 {
+        self.simulated_pnl - self.realistic_pnl
+    }
+
+    /// The headline numbers without the per-trade detail
+    pub fn summary(&self) -> LookaheadSummary {
+        LookaheadSummary {
+            label: self.label.clone(),
+            delay_ms: self.delay_ms,
+            trades: self.trades,
+            unchecked: self.unchecked,
+            read_errors: self.read_errors.len(),
+            capture_rate: self.capture_rate(),
+            simulated_pnl: self.simulated_pnl,
+            realistic_pnl: self.realistic_pnl,
+            optimism_gap: self.optimism_gap(),
+        }
+    }
+}
+
+/// One line per run of a lookahead check
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LookaheadSummary {
+    pub label: String,
+    pub delay_ms: u64,
+    pub trades: usize,
+    pub unchecked: usize,
+    pub read_errors: usize,
+    pub capture_rate: f64,
+    pub simulated_pnl: f64,
+    pub realistic_pnl: f64,
+    pub optimism_gap: f64,
+}
+
+/// Difference in one metric between runs
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricDiff {
+    pub name: String,
+    pub baseline: f64,
+    pub candidate: f64,
+    pub delta: f64,
+}
+
+/// Same trade (by trade id) in both runs with different PnL,
+/// or a trade only one run took (the missing side is `None`)
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeDiff {
+    pub trade_id: String,
+    pub market_id: MarketId,
+    pub timestamp: u64,
+    pub baseline_pnl: Option<f64>,
+    pub candidate_pnl: Option<f64>,
+}
+
+impl TradeDiff {
+    pub fn delta(&self) -> f64 {
+        self.candidate_pnl.unwrap_or(0.0) - self.baseline_pnl.unwrap_or(0.0)
+    }
+}
+
+/// Trade-by-trade and metric-by-metric comparison of two runs
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestComparison {
+    pub baseline: String,
+    pub candidate: String,
+    pub metrics: Vec<MetricDiff>,
+    pub trades: Vec<TradeDiff>,
+    pub regression: bool,  // Candidate PnL dropped by more than the tolerance
+    pub identical: bool,   // Content hashes match
+}
+
+/// Compare a candidate run against a baseline
+pub fn compare(baseline: &BacktestResult, candidate: &BacktestResult, tolerance: f64) -> BacktestComparison {
+    let metrics = baseline.metrics.named().into_iter()
+        .zip(candidate.metrics.named())
+        .map(|((name, b), (_, c))| MetricDiff { name: name.to_string(), baseline: b, candidate: c, delta: c - b })
+        .collect();
+
+    let base: HashMap<&str, &BacktestTrade> = baseline.trades.iter().map(|t| (t.trade_id.as_str(), t)).collect();
+    let cand: HashMap<&str, &BacktestTrade> = candidate.trades.iter().map(|t| (t.trade_id.as_str(), t)).collect();
+
+    let mut trades: Vec<TradeDiff> = base.keys().chain(cand.keys())
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .filter_map(|id| {
+            let (b, c) = (base.get(id), cand.get(id));
+            let changed = match (b, c) {
+                (Some(b), Some(c)) => (b.pnl - c.pnl).abs() > 1e-9,
+                _ => true,
+            };
+            let trade = b.or(c)?;
+            changed.then(|| TradeDiff {
+                trade_id: id.to_string(),
+                market_id: trade.market_id.clone(),
+                timestamp: trade.timestamp,
+                baseline_pnl: b.map(|t| t.pnl),
+                candidate_pnl: c.map(|t| t.pnl),
+            })
+        })
+        .collect();
+    trades.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.trade_id.cmp(&b.trade_id)));
+
+    BacktestComparison {
+        baseline: baseline.label.clone(),
+        candidate: candidate.label.clone(),
+        metrics,
+        trades,
+        regression: candidate.metrics.total_pnl < baseline.metrics.total_pnl - tolerance,
+        identical: baseline.content_hash() == candidate.content_hash(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(market: &str, timestamp: u64, size: f64, pnl: f64) -> BacktestTrade {
+        BacktestTrade {
+            trade_id: String::new(),
+            market_id: MarketId::from(market),
+            timestamp,
+            size,
+            edge: 0.02,
+            fees: 0.01,
+            pnl,
+            prices: None,
+            depth: None,
+            timestamp_ms: None,
+        }
+    }
+
+    fn trades() -> Vec<BacktestTrade> {
+        vec![trade("m1", 10, 5.0, 0.10), trade("m2", 10, 3.0, -0.05), trade("m1", 10, 2.0, 0.04), trade("m1", 20, 1.0, 0.02)]
+    }
+
+    #[test]
+    fn seeded_rng_repeats_per_seed() {
+        let draws = |seed| {
+            let mut rng = SeededRng::new(seed);
+            (0..8).map(|_| rng.next_u64()).collect::<Vec<_>>()
+        };
+        assert_eq!(draws(7), draws(7));
+        assert_ne!(draws(7), draws(8));
+
+        let mut rng = SeededRng::new(1);
+        assert!((0..1000).map(|_| rng.next_f64()).all(|x| (0.0..1.0).contains(&x)));
+    }
+
+    #[test]
+    fn result_is_independent_of_trade_order() {
+        let forward = BacktestResult::new("a", trades());
+        let mut reversed = trades();
+        reversed.reverse();
+        let backward = BacktestResult::new("b", reversed);
+
+        assert_eq!(forward.hash, backward.hash);
+        let ids = |r: &BacktestResult| r.trades.iter().map(|t| t.trade_id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&forward), ids(&backward));
+        assert_eq!(ids(&forward), vec!["m1:10:0", "m1:10:1", "m2:10:0", "m1:20:0"]);
+    }
+
+    #[test]
+    fn hash_changes_with_results() {
+        let base = BacktestResult::new("a", trades());
+        let mut changed = trades();
+        changed[0].pnl += 0.01;
+        assert_ne!(base.hash, BacktestResult::new("a", changed).hash);
+        assert_eq!(base.hash, base.content_hash());
+        assert_eq!(base.hash.len(), 16);
+    }
+
+    #[test]
+    fn compare_reports_identical_runs_and_regressions() {
+        let base = BacktestResult::new("base", trades());
+        let same = compare(&base, &BacktestResult::new("same", trades()), 0.0);
+        assert!(same.identical);
+        assert!(!same.regression);
+        assert!(same.trades.is_empty());
+
+        let mut worse = trades();
+        worse[3].pnl -= 1.0;
+        let diff = compare(&base, &BacktestResult::new("worse", worse), 0.5);
+        assert!(!diff.identical);
+        assert!(diff.regression);
+        assert_eq!(diff.trades.len(), 1);
+        assert_eq!(diff.trades[0].trade_id, "m1:20:0");
+    }
+
+    #[test]
+    fn saved_results_load_with_the_same_hash() {
+        let path = std::env::temp_dir().join(format!("polyshark-backtest-{}.json", std::process::id()));
+        let result = BacktestResult::new("saved", trades()).with_seed(42);
+        result.save(&path).unwrap();
+        let loaded = BacktestResult::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(loaded.seed, Some(42));
+        assert_eq!(loaded.hash, result.hash);
+        assert_eq!(loaded.content_hash(), result.hash);
+    }
+
+    #[test]
+    fn sensitivity_weighs_fees_and_slippage_differently() {
+        let mut lopsided = trade("m1", 10, 100.0, 1.0);
+        lopsided.prices = Some([0.95, 0.03]);
+        lopsided.depth = Some(50.0);
+        let result = BacktestResult::new("grid", vec![lopsided]);
+        let grid = SensitivityGrid::run(&result, &[0.0, 100.0], &[0.0, 100.0]);
+
+        let fee_only = grid.cell(1, 0).unwrap().total_pnl;
+        let slippage_only = grid.cell(0, 1).unwrap().total_pnl;
+        // 1% on the curve charges min(p, 1 - p): 0.05 + 0.03 per pair
+        assert!((fee_only - (1.0 - 0.08)).abs() < 1e-9);
+        // 1% of the 98 USDC cost, doubled because the trade is deeper than the book
+        assert!((slippage_only - (1.0 - 1.96)).abs() < 1e-9);
+    }
+}
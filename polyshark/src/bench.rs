@@ -0,0 +1,155 @@
+use crate::arb::ArbitrageDetector;
+use crate::backtest::SeededRng;
+use crate::booksync::BookSync;
+use crate::costcurve::CurveCache;
+use crate::outlier::{OutlierConfig, OutlierGuard};
+use crate::types::{Market, MarketId, OrderBook, PriceLevel, Side, TokenId};
+use crate::websocket::MarketEvent;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Levels per side in each synthetic book
+const BENCH_DEPTH: usize = 20;
+
+/// Timing of the book-update → cost curves → detection → sizing path over a synthetic watchlist
+#[derive(Debug, Clone)]
+pub struct UpdateBenchReport {
+    pub markets: usize,
+    pub updates: usize,
+    pub signals: usize,
+    pub elapsed: Duration,
+}
+
+impl UpdateBenchReport {
+    pub fn ns_per_update(&self) -> f64 {
+        if self.updates == 0 { 0.0 } else { self.elapsed.as_nanos() as f64 / self.updates as f64 }
+    }
+
+    pub fn updates_per_sec(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs <= 0.0 { 0.0 } else { self.updates as f64 / secs }
+    }
+}
+
+/// Feed `updates` random level changes across `markets` binary markets through sync checks,
+/// the outlier guard, book maintenance, detection and sizing, as the live loop would.
+/// Events are generated up front so only the processing is timed.
+pub fn run_update_bench(markets: usize, updates: usize, seed: u64) -> UpdateBenchReport {
+    let mut rng = SeededRng::new(seed);
+    let watchlist: Vec<Market> = (0..markets).map(bench_market).collect();
+    let mut books: HashMap<TokenId, OrderBook> = HashMap::new();
+    let mut token_market: HashMap<TokenId, (usize, usize)> = HashMap::new();
+    let mut sync = BookSync::new();
+    let mut curves = CurveCache::new();
+    let mut guard = OutlierGuard::new(OutlierConfig { max_jump: 1.0, ..OutlierConfig::default() });
+    for (i, market) in watchlist.iter().enumerate() {
+        for (outcome, token) in market.clob_token_ids.iter().enumerate() {
+            let book = bench_book(token, 0.49);
+            let snapshot = MarketEvent::Book { book: book.clone(), hash: None, seq: Some(0) };
+            sync.observe(&snapshot);
+            guard.check(&snapshot, None);
+            curves.update(&book);
+            books.insert(token.clone(), book);
+            token_market.insert(token.clone(), (i, outcome));
+        }
+    }
+
+    let tokens: Vec<&TokenId> = watchlist.iter().flat_map(|m| &m.clob_token_ids).collect();
+    let mut seqs: HashMap<&TokenId, u64> = HashMap::new();
+    let events: Vec<MarketEvent> = (0..updates)
+        .map(|i| {
+            let token = tokens[(rng.next_u64() as usize) % tokens.len().max(1)];
+            let seq = seqs.entry(token).or_insert(0);
+            *seq += 1;
+            let side = if rng.chance(0.5) { Side::Buy } else { Side::Sell };
+            let offset = 0.01 * (1 + (rng.next_u64() % BENCH_DEPTH as u64)) as f64;
+            MarketEvent::PriceChange {
+                token_id: token.clone(),
+                side,
+                price: match side {
+                    Side::Buy => 0.49 - offset + 0.01,
+                    Side::Sell => 0.49 + offset,
+                },
+                size: if rng.chance(0.1) { 0.0 } else { 10.0 + (rng.next_f64() * 500.0).round() },
+                timestamp: i as u64,
+                hash: None,
+                seq: Some(*seq),
+            }
+        })
+        .collect();
+
+    let mut watchlist = watchlist;
+    let detector = ArbitrageDetector::new(0.0, 0.0);
+    let mut signals = 0;
+    let start = Instant::now();
+    for event in &events {
+        if sync.observe(event).is_some() {
+            continue;
+        }
+        let token_id = event.token_id();
+        if guard.check(event, books.get(token_id)).is_some() {
+            continue;
+        }
+        let (Some(book), MarketEvent::PriceChange { side, price, size, timestamp, .. }) = (books.get_mut(token_id), event) else {
+            continue;
+        };
+        book.apply_level(*side, *price, *size, *timestamp);
+        let depth = curves.update(book).buy.depth();
+        let Some(&(i, outcome)) = token_market.get(token_id) else {
+            continue;
+        };
+        let market = &mut watchlist[i];
+        if let Some(ask) = book.best_ask() {
+            market.outcome_prices[outcome] = ask;
+        }
+        for signal in detector.scan(std::slice::from_ref(market)) {
+            if detector.should_trade(&signal, 10.0, market.taker_fee_rate(), 0.0, Some(depth)) {
+                signals += 1;
+            }
+        }
+    }
+
+    UpdateBenchReport { markets, updates, signals, elapsed: start.elapsed() }
+}
+
+fn bench_market(i: usize) -> Market {
+    Market {
+        id: MarketId::new(format!("bench-{}", i)),
+        question: String::new(),
+        slug: format!("bench-{}", i),
+        outcomes: vec!["Yes".to_string(), "No".to_string()],
+        outcome_prices: vec![0.5, 0.5],
+        clob_token_ids: vec![TokenId::new(format!("bench-{}-yes", i)), TokenId::new(format!("bench-{}-no", i))],
+        best_bid: None,
+        best_ask: None,
+        maker_base_fee: 0,
+        taker_base_fee: 0,
+        liquidity: 10_000.0,
+        volume_24hr: 0.0,
+        active: true,
+        accepting_orders: true,
+        condition_id: String::new(),
+        end_date: None,
+        resolution_source: None,
+        category: None,
+        tags: Vec::new(),
+        neg_risk: false,
+        event_id: None,
+        rewards_max_spread: None,
+        rewards_min_size: None,
+        restricted: false,
+        description: None,
+        uma_resolution_status: None,
+        closed: false,
+    }
+}
+
+fn bench_book(token: &TokenId, mid: f64) -> OrderBook {
+    let level = |price: f64| PriceLevel { price, size: 100.0 };
+    OrderBook {
+        token_id: token.clone(),
+        bids: (0..BENCH_DEPTH).map(|i| level(mid - 0.01 * i as f64)).collect(),
+        asks: (1..=BENCH_DEPTH).map(|i| level(mid + 0.01 * i as f64)).collect(),
+        timestamp: 0,
+    }
+}
@@ -0,0 +1,291 @@
+use crate::storage::StorageResult;
+use crate::types::{MarketId, OrderBook, PriceLevel, TokenId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// Frames per segment; every segment starts with a full snapshot, so this bounds
+/// how many deltas a random-access read has to replay
+const KEYFRAME_INTERVAL: usize = 500;
+
+/// Longest time one segment stays open (limits what a crash can lose)
+const MAX_SEGMENT_MS: u64 = 15 * 60 * 1000;
+
+const ZSTD_LEVEL: i32 = 9;
+
+/// One entry of `index.jsonl`: a sealed segment file for one token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentIndex {
+    pub market_id: MarketId,
+    pub token_id: TokenId,
+    pub from: u64,       // First frame timestamp (ms)
+    pub to: u64,         // Last frame timestamp (ms)
+    pub frames: usize,
+    pub file: PathBuf,   // Relative to the store directory
+}
+
+/// One line of a segment: a full book or the levels that changed since the previous frame
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "t", rename_all = "snake_case")]
+enum Frame {
+    Key { ts: u64, bids: Vec<(f64, f64)>, asks: Vec<(f64, f64)> },
+    Delta { ts: u64, bids: Vec<(f64, f64)>, asks: Vec<(f64, f64)> },  // Size 0 removes the level
+}
+
+struct OpenSegment {
+    market_id: MarketId,
+    from: u64,
+    to: u64,
+    frames: usize,
+    last: OrderBook,
+    buf: Vec<u8>,  // Uncompressed JSON lines, compressed when sealed
+}
+
+/// Records books as delta-encoded, zstd-compressed segments:
+/// `<dir>/<token_id>/<from_ms>.jsonl.zst`, indexed by market and time in `<dir>/index.jsonl`.
+/// Call `flush` before exiting or the open segments are lost.
+pub struct BookWriter {
+    pub dir: PathBuf,
+    open: HashMap<TokenId, OpenSegment>,
+}
+
+impl BookWriter {
+    pub fn open(dir: &Path) -> StorageResult<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self { dir: dir.to_path_buf(), open: HashMap::new() })
+    }
+
+    /// Append a book snapshot; unchanged books are skipped
+    pub fn record(&mut self, market_id: &MarketId, book: &OrderBook) -> StorageResult<()> {
+        let full = self.open.get(&book.token_id).is_some_and(|s| {
+            s.frames >= KEYFRAME_INTERVAL || book.timestamp.saturating_sub(s.from) >= MAX_SEGMENT_MS || s.market_id != *market_id
+        });
+        if full {
+            self.seal(&book.token_id)?;
+        }
+
+        let frame = match self.open.get(&book.token_id) {
+            Some(segment) => {
+                let bids = diff_levels(&segment.last.bids, &book.bids);
+                let asks = diff_levels(&segment.last.asks, &book.asks);
+                if bids.is_empty() && asks.is_empty() {
+                    return Ok(());
+                }
+                Frame::Delta { ts: book.timestamp, bids, asks }
+            }
+            None => Frame::Key { ts: book.timestamp, bids: pairs(&book.bids), asks: pairs(&book.asks) },
+        };
+
+        let segment = self.open.entry(book.token_id.clone()).or_insert_with(|| OpenSegment {
+            market_id: market_id.clone(),
+            from: book.timestamp,
+            to: book.timestamp,
+            frames: 0,
+            last: book.clone(),
+            buf: Vec::new(),
+        });
+        serde_json::to_writer(&mut segment.buf, &frame)?;
+        segment.buf.push(b'\n');
+        segment.frames += 1;
+        segment.to = segment.to.max(book.timestamp);
+        segment.last = book.clone();
+        Ok(())
+    }
+
+    /// Seal every open segment
+    pub fn flush(&mut self) -> StorageResult<()> {
+        let tokens: Vec<TokenId> = self.open.keys().cloned().collect();
+        for token in tokens {
+            self.seal(&token)?;
+        }
+        Ok(())
+    }
+
+    fn seal(&mut self, token_id: &TokenId) -> StorageResult<()> {
+        let Some(segment) = self.open.remove(token_id) else {
+            return Ok(());
+        };
+        let file = PathBuf::from(token_id.as_str()).join(format!("{}.jsonl.zst", segment.from));
+        let path = self.dir.join(&file);
+        fs::create_dir_all(path.parent().unwrap_or(&self.dir))?;
+        // Temp file first so the index never points at a half-written segment
+        let tmp = path.with_extension("zst.partial");
+        fs::write(&tmp, zstd::encode_all(segment.buf.as_slice(), ZSTD_LEVEL)?)?;
+        fs::rename(&tmp, &path)?;
+
+        let entry = SegmentIndex {
+            market_id: segment.market_id,
+            token_id: token_id.clone(),
+            from: segment.from,
+            to: segment.to,
+            frames: segment.frames,
+            file,
+        };
+        let mut index = OpenOptions::new().create(true).append(true).open(self.dir.join("index.jsonl"))?;
+        writeln!(index, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+}
+
+/// Random access over a book store written by `BookWriter`
+#[derive(Debug, Clone, Default)]
+pub struct BookReader {
+    pub dir: PathBuf,
+    segments: HashMap<TokenId, Vec<SegmentIndex>>,  // Sorted by `from`
+}
+
+impl BookReader {
+    pub fn open(dir: &Path) -> StorageResult<Self> {
+        let mut segments: HashMap<TokenId, Vec<SegmentIndex>> = HashMap::new();
+        let index = dir.join("index.jsonl");
+        if index.exists() {
+            for line in BufReader::new(File::open(index)?).lines() {
+                let line = line?;
+                if !line.trim().is_empty() {
+                    let entry: SegmentIndex = serde_json::from_str(&line)?;
+                    segments.entry(entry.token_id.clone()).or_default().push(entry);
+                }
+            }
+        }
+        for list in segments.values_mut() {
+            list.sort_by_key(|s| s.from);
+        }
+        Ok(Self { dir: dir.to_path_buf(), segments })
+    }
+
+    /// Tokens recorded for a market
+    pub fn tokens(&self, market_id: &MarketId) -> Vec<TokenId> {
+        let mut tokens: Vec<TokenId> = self.segments.iter()
+            .filter(|(_, list)| list.iter().any(|s| s.market_id == *market_id))
+            .map(|(token, _)| token.clone())
+            .collect();
+        tokens.sort();
+        tokens
+    }
+
+    /// Segments for a token overlapping `[from, to]`
+    pub fn segments(&self, token_id: &TokenId, from: u64, to: u64) -> impl Iterator<Item = &SegmentIndex> {
+        self.segments.get(token_id).into_iter().flatten().filter(move |s| s.to >= from && s.from <= to)
+    }
+
+    /// The book as it stood at `ts`: the last recorded state at or before it
+    pub fn book_at(&self, token_id: &TokenId, ts: u64) -> StorageResult<Option<OrderBook>> {
+        let Some(list) = self.segments.get(token_id) else {
+            return Ok(None);
+        };
+        let n = list.partition_point(|s| s.from <= ts);
+        let Some(segment) = n.checked_sub(1).map(|i| &list[i]) else {
+            return Ok(None);
+        };
+        let mut state = None;
+        self.replay(segment, |book, _| {
+            if book.timestamp > ts {
+                return false;
+            }
+            state = Some(book.clone());
+            true
+        })?;
+        Ok(state)
+    }
+
+    /// Books of every recorded outcome of a market at `ts`
+    pub fn market_at(&self, market_id: &MarketId, ts: u64) -> StorageResult<Vec<OrderBook>> {
+        let mut books = Vec::new();
+        for token in self.tokens(market_id) {
+            books.extend(self.book_at(&token, ts)?);
+        }
+        Ok(books)
+    }
+
+    /// Every recorded state of a token in `[from, to]`, ordered by timestamp
+    pub fn load_books(&self, token_id: &TokenId, from: u64, to: u64) -> StorageResult<Vec<OrderBook>> {
+        let mut books = Vec::new();
+        for segment in self.segments(token_id, from, to) {
+            self.replay(segment, |book, _| {
+                if book.timestamp > to {
+                    return false;
+                }
+                if book.timestamp >= from {
+                    books.push(book.clone());
+                }
+                true
+            })?;
+        }
+        Ok(books)
+    }
+
+    /// Recorded markets, sorted
+    pub fn markets(&self) -> Vec<MarketId> {
+        let mut markets: Vec<MarketId> = self.segments.values().flatten().map(|s| s.market_id.clone()).collect();
+        markets.sort();
+        markets.dedup();
+        markets
+    }
+
+    /// Every frame of a segment in the order it was written, with whether it was a full
+    /// snapshot. For integrity checks; reads should use `book_at` / `load_books`.
+    pub fn walk(&self, segment: &SegmentIndex, mut visit: impl FnMut(&OrderBook, bool)) -> StorageResult<()> {
+        self.replay(segment, |book, keyframe| {
+            visit(book, keyframe);
+            true
+        })
+    }
+
+    /// Decode a segment, calling `visit` with the book after each frame (and whether the
+    /// frame was a keyframe) until it returns false
+    fn replay(&self, segment: &SegmentIndex, mut visit: impl FnMut(&OrderBook, bool) -> bool) -> StorageResult<()> {
+        let data = zstd::decode_all(File::open(self.dir.join(&segment.file))?)?;
+        let mut book = OrderBook { token_id: segment.token_id.clone(), bids: Vec::new(), asks: Vec::new(), timestamp: segment.from };
+        for line in data.split(|b| *b == b'\n').filter(|l| !l.is_empty()) {
+            let keyframe = match serde_json::from_slice::<Frame>(line)? {
+                Frame::Key { ts, bids, asks } => {
+                    book.bids = levels(&bids);
+                    book.asks = levels(&asks);
+                    book.timestamp = ts;
+                    true
+                }
+                Frame::Delta { ts, bids, asks } => {
+                    apply_levels(&mut book.bids, &bids);
+                    apply_levels(&mut book.asks, &asks);
+                    book.timestamp = ts;
+                    false
+                }
+            };
+            book.sort_levels();
+            if !visit(&book, keyframe) {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn pairs(levels: &[PriceLevel]) -> Vec<(f64, f64)> {
+    levels.iter().map(|l| (l.price, l.size)).collect()
+}
+
+fn levels(pairs: &[(f64, f64)]) -> Vec<PriceLevel> {
+    pairs.iter().map(|&(price, size)| PriceLevel { price, size }).collect()
+}
+
+/// Levels added or resized in `new`, plus removed ones with size 0
+fn diff_levels(old: &[PriceLevel], new: &[PriceLevel]) -> Vec<(f64, f64)> {
+    let same_price = |a: f64, b: f64| (a - b).abs() <= 1e-9;
+    let mut changes: Vec<(f64, f64)> = new.iter()
+        .filter(|n| !old.iter().any(|o| same_price(o.price, n.price) && o.size == n.size))
+        .map(|n| (n.price, n.size))
+        .collect();
+    changes.extend(old.iter().filter(|o| !new.iter().any(|n| same_price(o.price, n.price))).map(|o| (o.price, 0.0)));
+    changes
+}
+
+fn apply_levels(levels: &mut Vec<PriceLevel>, changes: &[(f64, f64)]) {
+    for &(price, size) in changes {
+        levels.retain(|l| (l.price - price).abs() > 1e-9);
+        if size > 0.0 {
+            levels.push(PriceLevel { price, size });
+        }
+    }
+}
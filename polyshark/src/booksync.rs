@@ -0,0 +1,195 @@
+use crate::bus::{EventBus, RiskEvent};
+#[cfg(feature = "network")]
+use crate::clob::ClobClient;
+#[cfg(feature = "network")]
+use crate::types::OrderBook;
+use crate::types::TokenId;
+use crate::websocket::MarketEvent;
+use std::collections::HashMap;
+
+/// Why a local book can no longer be trusted
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncIssue {
+    /// A heartbeat's hash differs from the hash of the last update we applied
+    HashMismatch { token_id: TokenId, exchange: String, local: String },
+    /// Sequence numbers skipped: updates were lost in between
+    SequenceGap { token_id: TokenId, expected: u64, received: u64 },
+    /// Update for a token we never got a snapshot of
+    NoSnapshot { token_id: TokenId },
+    /// Our subscriber fell behind the book topic and updates were dropped
+    Lagged { token_id: TokenId, skipped: u64 },
+}
+
+impl SyncIssue {
+    pub fn token_id(&self) -> &TokenId {
+        match self {
+            SyncIssue::HashMismatch { token_id, .. }
+            | SyncIssue::SequenceGap { token_id, .. }
+            | SyncIssue::NoSnapshot { token_id }
+            | SyncIssue::Lagged { token_id, .. } => token_id,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            SyncIssue::HashMismatch { exchange, local, .. } => format!("book hash {} != local {}", exchange, local),
+            SyncIssue::SequenceGap { expected, received, .. } => format!("sequence gap: expected {}, got {}", expected, received),
+            SyncIssue::NoSnapshot { .. } => "update before snapshot".to_string(),
+            SyncIssue::Lagged { skipped, .. } => format!("{} updates dropped while lagging", skipped),
+        }
+    }
+}
+
+/// Divergence counters, for the metrics endpoint
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SyncStats {
+    pub hash_checks: u64,
+    pub hash_mismatches: u64,
+    pub sequence_gaps: u64,
+    pub lags: u64,
+    pub resyncs: u64,
+    pub resync_failures: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct TokenSync {
+    hash: Option<String>,  // Exchange hash of the book after the last applied event
+    seq: Option<u64>,
+    stale: bool,           // Diverged; waiting for a fresh snapshot
+}
+
+/// Verifies that locally maintained books stay in step with the exchange.
+///
+/// The exchange stamps every book event with the hash of its book *after* the event, and
+/// periodically sends heartbeats carrying the current hash. If we applied every update, the
+/// heartbeat hash equals the hash of the last event we saw; anything else means we missed one.
+#[derive(Debug, Clone, Default)]
+pub struct BookSync {
+    pub stats: SyncStats,
+    tokens: HashMap<TokenId, TokenSync>,
+    bus: Option<EventBus>,  // Divergence alerts go out as risk events
+}
+
+impl BookSync {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_bus(mut self, bus: EventBus) -> Self {
+        self.bus = Some(bus);
+        self
+    }
+
+    /// Check an event before it is applied; an issue means the token's book needs a resync
+    pub fn observe(&mut self, event: &MarketEvent) -> Option<SyncIssue> {
+        // Borrowed until an issue needs an owned id: the clean path allocates nothing
+        let token_id = event.token_id();
+        let issue = match event {
+            MarketEvent::LastTrade(_) => return None,
+            MarketEvent::Book { hash, seq, .. } => {
+                self.tokens.insert(token_id.clone(), TokenSync { hash: hash.clone(), seq: *seq, stale: false });
+                return None;
+            }
+            MarketEvent::PriceChange { hash, seq, .. } => {
+                let Some(state) = self.tokens.get_mut(token_id) else {
+                    return self.flag(SyncIssue::NoSnapshot { token_id: token_id.clone() });
+                };
+                if state.stale {
+                    return None;
+                }
+                let gap = gap(state.seq, *seq);
+                state.seq = seq.or(state.seq);
+                if let Some(hash) = hash {
+                    // Reuses the stored hash's buffer
+                    match &mut state.hash {
+                        Some(stored) => stored.clone_from(hash),
+                        None => state.hash = Some(hash.clone()),
+                    }
+                }
+                gap.map(|(expected, received)| SyncIssue::SequenceGap { token_id: token_id.clone(), expected, received })
+            }
+            MarketEvent::Heartbeat { hash, seq, .. } => {
+                let state = self.tokens.get_mut(token_id)?;
+                if state.stale {
+                    return None;
+                }
+                // Heartbeats repeat the last sequence number rather than consuming one
+                let gap = match (state.seq, *seq) {
+                    (Some(last), Some(now)) if now > last => Some((last + 1, now)),
+                    _ => None,
+                };
+                let mismatch = match (hash, &state.hash) {
+                    (Some(exchange), Some(local)) => {
+                        self.stats.hash_checks += 1;
+                        (exchange != local).then(|| (exchange.clone(), local.clone()))
+                    }
+                    (Some(exchange), None) => {
+                        // Nothing to compare against yet (e.g., just resynced over REST): adopt it
+                        state.hash = Some(exchange.clone());
+                        None
+                    }
+                    _ => None,
+                };
+                match (gap, mismatch) {
+                    (Some((expected, received)), _) => Some(SyncIssue::SequenceGap { token_id: token_id.clone(), expected, received }),
+                    (None, Some((exchange, local))) => Some(SyncIssue::HashMismatch { token_id: token_id.clone(), exchange, local }),
+                    (None, None) => None,
+                }
+            }
+        };
+        issue.and_then(|issue| self.flag(issue))
+    }
+
+    /// The book subscription lagged: any token's missed updates may be among the dropped
+    /// ones, so every book we know of needs a resync
+    pub fn on_lagged(&mut self, skipped: u64) -> Vec<SyncIssue> {
+        self.stats.lags += 1;
+        let mut tokens: Vec<TokenId> = self.tokens.iter().filter(|(_, s)| !s.stale).map(|(t, _)| t.clone()).collect();
+        tokens.sort();
+        tokens.into_iter().filter_map(|token_id| self.flag(SyncIssue::Lagged { token_id, skipped })).collect()
+    }
+
+    /// Tokens whose books are diverged and not yet resynced
+    pub fn stale_tokens(&self) -> Vec<TokenId> {
+        self.tokens.iter().filter(|(_, s)| s.stale).map(|(t, _)| t.clone()).collect()
+    }
+
+    #[cfg(feature = "network")]
+    /// Replace a diverged book with a REST snapshot
+    pub async fn resync(&mut self, clob: &ClobClient, token_id: &TokenId) -> Result<OrderBook, reqwest::Error> {
+        match clob.order_book(token_id).await {
+            Ok(book) => {
+                self.stats.resyncs += 1;
+                // REST books carry no sequence; the next heartbeat re-establishes the hash
+                self.tokens.insert(token_id.clone(), TokenSync::default());
+                Ok(book)
+            }
+            Err(err) => {
+                self.stats.resync_failures += 1;
+                Err(err)
+            }
+        }
+    }
+
+    fn flag(&mut self, issue: SyncIssue) -> Option<SyncIssue> {
+        match &issue {
+            SyncIssue::HashMismatch { .. } => self.stats.hash_mismatches += 1,
+            SyncIssue::SequenceGap { .. } => self.stats.sequence_gaps += 1,
+            SyncIssue::NoSnapshot { .. } | SyncIssue::Lagged { .. } => {}
+        }
+        self.tokens.entry(issue.token_id().clone()).or_default().stale = true;
+        eprintln!("book sync: {} {}", issue.token_id(), issue.describe());
+        if let Some(bus) = &self.bus {
+            bus.publish_risk(RiskEvent::BookDiverged { token_id: issue.token_id().clone(), reason: issue.describe() });
+        }
+        Some(issue)
+    }
+}
+
+/// `(expected, received)` when `next` skips past `last + 1`
+fn gap(last: Option<u64>, next: Option<u64>) -> Option<(u64, u64)> {
+    match (last, next) {
+        (Some(last), Some(next)) if next > last + 1 => Some((last + 1, next)),
+        _ => None,
+    }
+}
@@ -0,0 +1,1929 @@
+// Most imports are only used by the trading loop, which needs the `network` feature
+#![cfg_attr(not(feature = "network"), allow(unused_imports))]
+
+use crate::activity::{VolumeProfiles, TRADE_HISTORY_SECS};
+use crate::approval::{Admission, ApprovalQueue, IntentStatus};
+use crate::arb::{ArbitrageDetector, SignalCooldowns};
+#[cfg(feature = "bookstore")]
+use crate::bookstore::BookWriter;
+use crate::attribution::EntryReason;
+use crate::booksync::BookSync;
+use crate::backoff::ExecutionBackoff;
+use crate::bus::{EventBus, Fill, Signal};
+use crate::capital::{CapitalForecast, CapitalForecaster};
+use crate::capture::{CaptureTracker, MISSED_LOG_PATH};
+#[cfg(feature = "network")]
+use crate::clob::ClobClient;
+use crate::commands::{fee_config, fee_schedule, now, open_journal, print_comparison};
+use crate::feedrift::FeeReconciler;
+use crate::fees::{FeeSchedule, SharedFeeSchedule};
+use crate::fills::FillModel;
+use crate::failover::{self, FailoverConfig, HeartbeatWriter};
+use crate::fastmove::FastMoveGuard;
+use crate::exit::{EarlyExitStrategy, ExitDecision, EXIT_TAG};
+use crate::explain::{Explainer, WhyNotReport, WHY_NOT_LOG_PATH};
+use crate::execution::{ExecutionEngine, LegFill, LegPlan, MultiLegExecutionReport, PriceImprovement};
+use crate::checkpoint::DetectorCheckpoint;
+use crate::compliance::ComplianceGate;
+use crate::config::{Profile, TradingMode};
+use crate::control::{BotView, ControlState};
+use crate::costcurve::{max_pair_size, CurveCache};
+#[cfg(feature = "health")]
+use crate::health::{cancel_on_shutdown, HealthState};
+use crate::instance::instance_id;
+use crate::journal::{Journal, JournalEntry};
+use crate::legs::{execute_pair, PairOutcome};
+use crate::lifecycle::{MarketLifecycle, MarketState};
+use crate::manual;
+use crate::merge::{PaperMerger, SetRecycler};
+use crate::outlier::OutlierGuard;
+use crate::polling::AdaptivePoller;
+use crate::priority::DataPriorities;
+use crate::orders::{OpenOrder, OrderGateway, OrderManager, OrderRequest, ReplaceOutcome, TimeInForce};
+use crate::peg::{arb_limit, Pegger, PEG_TICK};
+use crate::rebalance::{RebalanceMode, Rebalancer, REBALANCE_TAG};
+use crate::router::{RouteKind, SmartOrderRouter};
+use crate::scheduler::{ExecutionScheduler, PendingSignal};
+use crate::shadow::{ShadowCosts, ShadowRunner};
+use crate::provider::MarketProvider;
+use crate::pruning::StalePruner;
+use crate::replay::{TradeRecord, TRADE_RECORDS_PATH};
+use crate::rewards::{ComplianceRow, RewardTracker};
+use crate::reports::concentration_report;
+use crate::risk::{ConcentrationMonitor, FeeBudget, PnlCircuitBreaker, RiskMonitor, ShortfallModel};
+use crate::slippage::classify;
+use crate::storage::Storage;
+use crate::tape::MarketFlowTracker;
+use crate::types::{format_date, ArbitrageSignal, Event, Market, MarketId, OrderBook, Price, Side, Size, TokenId, Trade};
+use crate::money::Usdc;
+use crate::volatility::VolatilityTable;
+use crate::quality::{QualityTable, QUALITY_TABLE_PATH};
+use crate::matcher::{MarketMatcher, MATCHED_PAIRS_PATH};
+use crate::reconcile::{BalanceSource, Reconciler};
+use crate::snapshot::{PortfolioSnapshot, SNAPSHOTS_PATH};
+use crate::sparkline::{PriceHistory, SharedPriceHistory};
+use crate::resolution::{ResolutionMonitor, ResolutionStatus, SETTLEMENT_TAG};
+use crate::wallet::Wallet;
+#[cfg(feature = "network")]
+use crate::websocket::{MarketEvent, ShardedMarketStream, MAX_ASSETS_PER_CONNECTION};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "network")]
+/// Markets requested per Gamma page while loading the watchlist
+const PAGE_SIZE: usize = 100;
+
+#[cfg(feature = "network")]
+/// How often market volatility is re-measured from the recorded books
+const VOLATILITY_REFRESH_SECS: u64 = 3_600;
+
+#[cfg(feature = "network")]
+/// Lease owner for tokens the bot has resting orders on
+const RESTING_OWNER: &str = "resting-orders";
+
+#[cfg(feature = "network")]
+/// How often the REST fallback checks for markets due a poll
+const POLL_TICK_MS: u64 = 250;
+
+/// Detection thresholds and watchlist size for the trading loop
+#[derive(Debug, Clone, Deserialize)]
+pub struct BotConfig {
+    #[serde(default = "default_min_spread")]
+    pub min_spread: f64,      // Smallest YES+NO deviation from $1 that counts as a signal
+    #[serde(default = "default_min_profit")]
+    pub min_profit: f64,      // Expected USDC profit after costs a trade must clear
+    #[serde(default = "default_max_markets")]
+    pub max_markets: usize,   // Markets streamed and scanned
+    #[serde(default = "default_refresh_secs")]
+    pub refresh_secs: u64,    // How often the market list is reloaded
+    #[serde(default)]
+    pub record_books_ms: Option<u64>,  // Save each streamed book to storage at most this often (export-depth, volatility edge)
+    #[serde(default)]
+    pub book_store: Option<PathBuf>,   // Record every book change here for check-data and lookahead (`bookstore` feature)
+    #[serde(default = "default_book_flush_secs")]
+    pub book_flush_secs: u64,          // Open book segments are sealed this often, bounding what a crash loses
+    #[serde(default)]
+    pub reward_sample_secs: Option<u64>,  // Sample our resting quotes against reward bands this often (off when unset)
+    #[serde(default = "default_reaction_ms")]
+    pub reaction_ms: u64,              // Book update to order at the venue, for the odds another taker is first
+    #[serde(default)]
+    pub warmup_secs: u64,              // New markets are watched, not traded, this long
+    #[serde(default)]
+    pub warmup_books: u32,             // ...and until this many book updates have arrived
+    #[serde(default)]
+    pub wind_down_secs: u64,           // No new entries this close to a market's end date
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,            // A signal judged not worth trading mutes its market this long
+    #[serde(default = "default_snapshot_secs")]
+    pub snapshot_secs: u64,            // Portfolio snapshots for `diff` are appended this often
+    #[serde(default = "default_max_pending")]
+    pub max_pending: usize,            // Signals queued for capital at once; the weakest is dropped past this
+    #[serde(default = "default_why_not_window")]
+    pub why_not_window: f64,           // Rejected signals whose gross edge came this close to min_profit are explained
+}
+
+impl Default for BotConfig {
+    fn default() -> Self {
+        Self {
+            min_spread: default_min_spread(),
+            min_profit: default_min_profit(),
+            max_markets: default_max_markets(),
+            refresh_secs: default_refresh_secs(),
+            record_books_ms: None,
+            book_store: None,
+            book_flush_secs: default_book_flush_secs(),
+            reward_sample_secs: None,
+            reaction_ms: default_reaction_ms(),
+            warmup_secs: 0,
+            warmup_books: 0,
+            wind_down_secs: 0,
+            cooldown_secs: default_cooldown_secs(),
+            snapshot_secs: default_snapshot_secs(),
+            max_pending: default_max_pending(),
+            why_not_window: default_why_not_window(),
+        }
+    }
+}
+
+fn default_min_spread() -> f64 {
+    0.01
+}
+
+fn default_min_profit() -> f64 {
+    0.05
+}
+
+fn default_max_markets() -> usize {
+    500
+}
+
+fn default_refresh_secs() -> u64 {
+    300
+}
+
+fn default_reaction_ms() -> u64 {
+    250
+}
+
+fn default_cooldown_secs() -> u64 {
+    30
+}
+
+fn default_snapshot_secs() -> u64 {
+    900
+}
+
+fn default_max_pending() -> usize {
+    16
+}
+
+fn default_why_not_window() -> f64 {
+    0.05
+}
+
+fn default_book_flush_secs() -> u64 {
+    60
+}
+
+#[cfg(feature = "network")]
+/// The trading loop: streams books for the watchlist, scans each updated market and sends
+/// both legs of every tradable signal through `legs::execute_pair`
+pub struct Bot {
+    pub profile: Profile,
+    pub detector: ArbitrageDetector,
+    pub wallet: Wallet,
+    pub journal: Journal,
+    pub orders: OrderManager,
+    pub markets: HashMap<MarketId, Market>,
+    pub books: HashMap<TokenId, OrderBook>,
+    pub curves: CurveCache,  // Cost curves of `books`, rebuilt on every change for sizing
+    pub capture: CaptureTracker,
+    explainer: Explainer,                         // Breaks down near-threshold rejections
+    pub why_not: WhyNotReport,                    // ...logged to why_not.jsonl for `polyshark why-not`
+    pub bus: EventBus,
+    pub risk: RiskMonitor,
+    breaker: PnlCircuitBreaker,                   // Pauses entries when PnL drops too fast
+    pub concentration: ConcentrationMonitor,      // Early warning before one market dominates equity
+    pub shortfall: ShortfallModel,
+    pub fee_budget: Option<FeeBudget>,
+    pub improvement: PriceImprovement,            // Detection-to-fill price changes per market
+    pub recycler: Option<SetRecycler>,            // Paper only: there is no on-chain merge yet
+    pub sync: BookSync,                           // Hash and sequence checks on the streamed books
+    pub outliers: OutlierGuard,                   // Sanity checks on streamed prices and sizes
+    pub fee_drift: FeeReconciler,                 // Fees charged on our fills against the fee model
+    pub rewards: RewardTracker,                   // Maker reward qualification, when sampling is on
+    pub flow: MarketFlowTracker,                  // Public prints per market, for the competition estimate
+    pub lifecycle: MarketLifecycle,               // Warmup, wind-down and resolution per market
+    pub pruner: StalePruner,                      // Illiquid markets dropped from the watchlist
+    pub cooldowns: SignalCooldowns,               // Markets whose last signal wasn't worth trading
+    pub backoff: ExecutionBackoff,                // Markets whose placements keep being rejected
+    pub fast_moves: FastMoveGuard,                // Markets paused while their mid jumps
+    pub pegger: Pegger,                           // Resting hedges kept at their peg
+    pub resolutions: ResolutionMonitor,           // Proposal, dispute and finality of held markets
+    pub approvals: Arc<ApprovalQueue>,            // Supervised mode: large trades wait for an operator
+    approved: HashMap<MarketId, f64>,             // market -> size an operator approved, for the next scan
+    queued: HashSet<MarketId>,                    // Markets with an intent still pending
+    pub priorities: Arc<DataPriorities>,          // Tokens strategies want fresher data for
+    pub history: SharedPriceHistory,              // YES mids per market, for sparklines in alerts
+    hot_tokens: Vec<String>,                      // Prioritized tokens the stream and poller last got
+    reward_day: u64,                              // UTC day of the last reward sample
+    clob: ClobClient,                             // REST snapshots for diverged books
+    resync_due: bool,                             // A book diverged since the last resync pass
+    poller: AdaptivePoller,                       // REST polling schedule while the stream is silent
+    last_stream_ms: u64,                          // When the stream last delivered an event
+    polling: bool,                                // Books are coming from REST polls
+    last_data_ms: u64,                            // Last book or print, streamed or polled
+    heartbeat: Option<(HeartbeatWriter, FailoverConfig)>,  // Primary's sign of life for the standby
+    scheduler: ExecutionScheduler,                // Qualified signals waiting for capital, best first
+    capital: CapitalForecaster,                   // Last check on free capital before a pair is sent
+    shadow: Option<ShadowRunner>,                 // Candidate thresholds scanned next to the live ones
+    reconciler: Option<(Reconciler, ClobClient)>,  // Wallet checked against exchange balances
+    gateway: Box<dyn OrderGateway>,
+    storage: Box<dyn Storage>,
+    recorded: usize,                              // Journal entries already in storage
+    books_saved: HashMap<TokenId, u64>,           // token -> timestamp of its last saved book
+    volume: VolumeProfiles,                       // Hourly activity, when `scan.enabled`
+    trades: VecDeque<Trade>,                      // Streamed prints for the nightly profile rebuild
+    last_scan: HashMap<MarketId, u64>,            // market -> ms of its last scan
+    #[cfg(feature = "bookstore")]
+    book_writer: Option<BookWriter>,              // Compressed recording of every book change
+    outcomes: HashMap<TokenId, (MarketId, usize)>,  // token -> market and outcome index
+    events: Vec<Event>,                           // Mutually exclusive Gamma events, for categorical sets
+    control: Arc<ControlState>,                   // Operator pause and flatten switches
+    view: Option<Arc<BotView>>,                   // Positions and orders served by the control API
+    #[cfg(feature = "health")]
+    health: Option<Arc<HealthState>>,             // Liveness served on `/readyz`
+}
+
+#[cfg(feature = "network")]
+impl Bot {
+    /// Rebuild the wallet from the profile's journal and trade through `gateway`
+    pub fn new(profile: &Profile, gateway: Box<dyn OrderGateway>) -> Result<Self, String> {
+        let (storage, journal) = open_journal(profile)?;
+        #[cfg(feature = "bookstore")]
+        let book_writer = profile.bot.book_store.as_deref()
+            .map(BookWriter::open)
+            .transpose()
+            .map_err(|e| format!("book store: {}", e))?;
+        #[cfg(not(feature = "bookstore"))]
+        if profile.bot.book_store.is_some() {
+            return Err("bot.book_store requires the `bookstore` feature".to_string());
+        }
+        let volume = if profile.scan.enabled && profile.scan.profiles_path.exists() {
+            VolumeProfiles::load(&profile.scan.profiles_path).unwrap_or_else(|e| {
+                eprintln!("⚠️  volume profiles unreadable, rebuilding tonight: {}", e);
+                VolumeProfiles::default()
+            })
+        } else {
+            VolumeProfiles::default()
+        };
+        let mut wallet = Wallet::new(profile.starting_balance);
+        wallet.lots.method = profile.lot_method;
+        manual::replay_journal(&mut wallet, &journal);
+        let bus = EventBus::default();
+        // Plain detectors on both sides, so the comparison isolates the thresholds
+        let shadow = profile.shadow.as_ref().map(|config| {
+            let detector = |min_spread, min_profit| ArbitrageDetector::new(min_spread, min_profit)
+                .with_tiers(profile.liquidity_tiers.clone())
+                .with_compliance(ComplianceGate::new(profile.compliance.clone()));
+            let costs = ShadowCosts { size: config.size, fee_rate: config.fee_rate, slippage: config.slippage };
+            ShadowRunner::new(detector(profile.bot.min_spread, profile.bot.min_profit), detector(config.min_spread, config.min_profit), costs)
+        });
+        let mut detector = ArbitrageDetector::new(profile.bot.min_spread, profile.bot.min_profit)
+            .with_tiers(profile.liquidity_tiers.clone())
+            .with_compliance(ComplianceGate::new(profile.compliance.clone()));
+        if profile.bot.record_books_ms.is_some() {
+            // Measured from the recorded books once the watchlist is loaded
+            detector = detector.with_volatility(VolatilityTable::default(), profile.volatility.clone());
+        }
+        if Path::new(QUALITY_TABLE_PATH).exists() {
+            match QualityTable::load(Path::new(QUALITY_TABLE_PATH)) {
+                Ok(table) => detector = detector.with_quality(table, profile.quality.clone()),
+                Err(err) => eprintln!("⚠️  {} unreadable, markets are not quality-filtered: {}", QUALITY_TABLE_PATH, err),
+            }
+        }
+        if Path::new(MATCHED_PAIRS_PATH).exists() {
+            match MarketMatcher::load(Path::new(MATCHED_PAIRS_PATH), &profile.matcher) {
+                Ok(matcher) => detector = detector.with_pairs(matcher.confirmed_ids()),
+                Err(err) => eprintln!("⚠️  {} unreadable, duplicate markets are not scanned: {}", MATCHED_PAIRS_PATH, err),
+            }
+        }
+        let mut lifecycle = MarketLifecycle::new(profile.bot.wind_down_secs)
+            .with_warmup(profile.bot.warmup_secs, profile.bot.warmup_books);
+        let mut pruner = StalePruner::new(profile.prune.clone());
+        let mut cooldowns = SignalCooldowns::default();
+        let mut capture = CaptureTracker::new(Some(PathBuf::from(MISSED_LOG_PATH)));
+        match DetectorCheckpoint::load(&profile.checkpoint, now()) {
+            Ok(Some(checkpoint)) => {
+                let volatility = checkpoint.restore(&mut lifecycle, &mut pruner, &mut cooldowns, &mut capture, now());
+                if let (Some(saved), Some((table, _))) = (volatility, detector.volatility.as_mut()) {
+                    *table = saved;
+                }
+                println!("resumed detector state: {} markets cooling down", cooldowns.len());
+            }
+            Ok(None) => {}
+            Err(err) => eprintln!("⚠️  detector checkpoint unreadable, starting fresh: {}", err),
+        }
+        let history = match &profile.sparklines.path {
+            Some(path) => PriceHistory::load(profile.sparklines.clone(), path).unwrap_or_else(|err| {
+                eprintln!("⚠️  {} unreadable, starting without price history: {}", path.display(), err);
+                PriceHistory::new(profile.sparklines.clone())
+            }),
+            None => PriceHistory::new(profile.sparklines.clone()),
+        };
+        Ok(Self {
+            detector,
+            profile: profile.clone(),
+            wallet,
+            recorded: journal.entries.len(),
+            journal,
+            orders: OrderManager::with_instance(&instance_id()).with_min_resting(profile.min_resting_ms),
+            markets: HashMap::new(),
+            books: HashMap::new(),
+            curves: CurveCache::new(),
+            capture,
+            explainer: Explainer::new(profile.bot.min_profit, profile.bot.why_not_window),
+            why_not: WhyNotReport::new().with_log(PathBuf::from(WHY_NOT_LOG_PATH)),
+            risk: RiskMonitor::with_bus(bus.clone()),
+            breaker: PnlCircuitBreaker::new(profile.risk.max_pnl_swing, profile.risk.pnl_swing_window_secs),
+            concentration: ConcentrationMonitor::new(profile.risk.concentration_alert).with_bus(bus.clone()),
+            sync: BookSync::new().with_bus(bus.clone()),
+            outliers: OutlierGuard::new(profile.outliers.clone()).with_bus(bus.clone()),
+            fee_drift: FeeReconciler::new(profile.fee_drift.clone()).with_bus(bus.clone()),
+            rewards: RewardTracker::new(),
+            flow: MarketFlowTracker::default(),
+            lifecycle,
+            pruner,
+            cooldowns,
+            backoff: ExecutionBackoff::new(profile.backoff.clone()).with_bus(bus.clone()),
+            fast_moves: FastMoveGuard::new(profile.fast_move.clone()).with_bus(bus.clone()),
+            pegger: Pegger::new(profile.peg.clone()),
+            resolutions: ResolutionMonitor::new(),
+            approvals: Arc::new(ApprovalQueue::new(profile.supervised.clone())),
+            approved: HashMap::new(),
+            queued: HashSet::new(),
+            priorities: Arc::new(DataPriorities::new(profile.priority.clone())),
+            history: Arc::new(Mutex::new(history)),
+            hot_tokens: Vec::new(),
+            reward_day: 0,
+            bus,
+            shortfall: ShortfallModel::from_limits(&profile.risk),
+            fee_budget: FeeBudget::from_limits(&profile.risk),
+            improvement: PriceImprovement::new(),
+            recycler: (profile.recycle.enabled && profile.mode == TradingMode::Paper)
+                .then(|| SetRecycler::new(profile.recycle.min_size, Arc::new(PaperMerger { fee: profile.recycle.merge_fee }))),
+            clob: ClobClient::new(&profile.clob_url),
+            resync_due: false,
+            poller: AdaptivePoller::new(profile.poll.clone()),
+            last_stream_ms: 0,
+            polling: false,
+            last_data_ms: 0,
+            heartbeat: None,
+            scheduler: ExecutionScheduler::new(profile.bot.max_pending),
+            capital: CapitalForecaster::new(profile.risk.min_reserve_usdc),
+            shadow,
+            reconciler: None,
+            gateway,
+            storage,
+            books_saved: HashMap::new(),
+            volume,
+            trades: VecDeque::new(),
+            last_scan: HashMap::new(),
+            #[cfg(feature = "bookstore")]
+            book_writer,
+            outcomes: HashMap::new(),
+            events: Vec::new(),
+            control: Arc::new(ControlState::new()),
+            view: None,
+            #[cfg(feature = "health")]
+            health: None,
+        })
+    }
+
+    /// Obey `control` and keep `view` (if any) up to date for the control API
+    pub fn with_control(mut self, control: Arc<ControlState>, view: Option<Arc<BotView>>) -> Self {
+        self.control = control;
+        self.view = view;
+        self.publish_view();
+        self
+    }
+
+    /// Keep `health` up to date from the market feed and watchlist
+    #[cfg(feature = "health")]
+    pub fn with_health(mut self, health: Arc<HealthState>) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Publish a heartbeat for the standby while the feed is delivering
+    pub fn with_heartbeat(mut self, writer: HeartbeatWriter, config: FailoverConfig) -> Self {
+        self.heartbeat = Some((writer, config));
+        self
+    }
+
+    /// Compare the wallet with `client`'s balances every `reconcile.interval_secs`
+    pub fn with_reconciler(mut self, client: ClobClient) -> Self {
+        let config = &self.profile.reconcile;
+        self.reconciler = Some((Reconciler::new(config.tolerance, config.action), client));
+        self
+    }
+
+    /// Standby stepping in: cancel whatever the dead primary left resting before trading
+    pub fn take_over(&mut self) -> Result<(), String> {
+        failover::take_over(self.gateway.as_mut(), &mut self.orders).map_err(|e| e.to_string())?;
+        self.orders.sync_locks(&mut self.wallet);
+        self.persist();
+        Ok(())
+    }
+
+    /// Trade until `shutdown` resolves or the market stream ends
+    pub async fn run(&mut self, source: &impl MarketProvider, shutdown: impl Future<Output = ()>) -> Result<(), String> {
+        self.refresh_markets(source).await?;
+        self.refresh_events(source).await;
+        self.refresh_volatility();
+        let mut stream = ShardedMarketStream::new(&self.profile.ws_url, MAX_ASSETS_PER_CONNECTION)
+            .with_priority_shards(self.profile.priority.tokens_per_shard);
+        stream.set_watchlist(&self.watchlist());
+        println!("watching {} markets", self.markets.len());
+
+        let mut refresh = tokio::time::interval(Duration::from_secs(self.profile.bot.refresh_secs.max(1)));
+        refresh.tick().await;
+        let mut flush = tokio::time::interval(Duration::from_secs(self.profile.bot.book_flush_secs.max(1)));
+        flush.tick().await;
+        let sample_rewards = self.profile.bot.reward_sample_secs.is_some();
+        let mut rewards = tokio::time::interval(Duration::from_secs(self.profile.bot.reward_sample_secs.unwrap_or(60).max(1)));
+        let mut poll = tokio::time::interval(Duration::from_millis(POLL_TICK_MS));
+        let supervised = self.profile.supervised.is_some();
+        let mut second = tokio::time::interval(Duration::from_secs(1));
+        let mut checkpoint = tokio::time::interval(Duration::from_secs(self.profile.checkpoint.save_every_secs.max(1)));
+        checkpoint.tick().await;
+        let mut snapshot = tokio::time::interval(Duration::from_secs(self.profile.bot.snapshot_secs.max(1)));
+        let shadowing = self.shadow.is_some();
+        let mut shadow = tokio::time::interval(Duration::from_secs(self.profile.shadow.as_ref().map_or(60, |c| c.sample_secs).max(1)));
+        let reconciling = self.reconciler.is_some();
+        let mut reconcile = tokio::time::interval(Duration::from_secs(self.profile.reconcile.interval_secs.max(1)));
+        let beating = self.heartbeat.is_some();
+        let mut beat = tokio::time::interval(Duration::from_millis(
+            self.heartbeat.as_ref().map_or(5_000, |(_, config)| config.heartbeat_interval_ms).max(100),
+        ));
+        self.last_stream_ms = now_ms();
+        self.last_data_ms = self.last_stream_ms;
+        tokio::pin!(shutdown);
+        loop {
+            tokio::select! {
+                event = stream.next() => match event {
+                    Some(event) => {
+                        self.report_data(true);
+                        self.last_stream_ms = now_ms();
+                        self.last_data_ms = self.last_stream_ms;
+                        if self.polling {
+                            println!("market feed is back, REST polling stopped");
+                            self.polling = false;
+                        }
+                        self.on_market_event(event);
+                        if self.resync_due {
+                            self.resync_books().await;
+                        }
+                    }
+                    None => {
+                        self.report_data(false);
+                        break;
+                    }
+                },
+                _ = refresh.tick() => {
+                    self.orders.expire(now_ms(), &mut self.wallet);
+                    self.flow.prune(now_ms());
+                    self.cooldowns.prune(now());
+                    // The log keeps the history; memory only needs the current day
+                    self.why_not.prune(now().saturating_sub(86_400));
+                    self.resync_books().await;
+                    self.recycle_sets();
+                    self.rebalance_inventory();
+                    self.exit_sets();
+                    self.rebuild_profiles();
+                    self.refresh_volatility();
+                    self.publish_view();
+                    match self.refresh_markets(source).await {
+                        Ok(()) => stream.set_watchlist_prioritized(&self.watchlist(), &self.hot_tokens),
+                        Err(err) => eprintln!("⚠️  market refresh failed: {}", err),
+                    }
+                    self.track_resolutions(source).await;
+                    self.refresh_events(source).await;
+                    self.scan_event_sets();
+                    self.scan_pairs();
+                    self.check_concentration();
+                    self.check_breaker();
+                }
+                _ = flush.tick() => self.flush_books(),
+                _ = poll.tick() => self.poll_books().await,
+                _ = beat.tick(), if beating => self.beat(),
+                _ = reconcile.tick(), if reconciling => self.reconcile_balances().await,
+                _ = shadow.tick(), if shadowing => self.sample_shadow(),
+                _ = second.tick() => {
+                    self.lifecycle.promote_warmed(now());
+                    self.fast_moves.refresh(now_ms());
+                    if let Some(hot) = self.refresh_priorities() {
+                        stream.set_watchlist_prioritized(&self.watchlist(), &hot);
+                    }
+                    if supervised {
+                        self.collect_approvals();
+                    }
+                }
+                _ = checkpoint.tick() => self.save_checkpoint(),
+                _ = snapshot.tick() => self.save_snapshot(),
+                _ = rewards.tick(), if sample_rewards => self.sample_rewards(),
+                _ = &mut shutdown => break,
+            }
+            if self.control.take_flatten_request() {
+                self.flatten_all();
+            }
+        }
+        self.flush_books();
+        self.save_checkpoint();
+        self.save_snapshot();
+        self.save_history();
+        self.report_shadow();
+        if sample_rewards {
+            print_compliance(&self.rewards.report());
+        }
+        let improvement = self.improvement.overall();
+        if improvement.executions + improvement.rejected > 0 {
+            println!(
+                "price improvement: {} trades, {:.0}% improved, {} worsened, {} rejected, net {:+.4} USDC",
+                improvement.executions, improvement.improved_rate() * 100.0, improvement.worsened, improvement.rejected, improvement.improvement,
+            );
+        }
+        self.persist();
+        Ok(())
+    }
+
+    /// Scan the watchlist with both the live and the shadow thresholds
+    fn sample_shadow(&mut self) {
+        let Some(runner) = &mut self.shadow else { return };
+        let markets: Vec<Market> = self.markets.values().cloned().collect();
+        runner.observe(&markets, &self.books, now_ms());
+    }
+
+    /// How the shadow thresholds fared against the live ones; both runs are saved for
+    /// `compare` when a results directory is set
+    fn report_shadow(&self) {
+        let (Some(runner), Some(config)) = (&self.shadow, &self.profile.shadow) else { return };
+        println!("shadow detector (min_spread {}, min_profit {}) against the live thresholds:", config.min_spread, config.min_profit);
+        print_comparison(&runner.report(config.tolerance), config.tolerance);
+        let Some(dir) = &config.results_dir else { return };
+        let (live, shadow) = runner.results();
+        for (result, name) in [(live, "live.json"), (shadow, "shadow.json")] {
+            let path = dir.join(name);
+            match std::fs::create_dir_all(dir).and_then(|_| result.save(&path)) {
+                Ok(()) => println!("saved {} would-be trades to {}", result.trades.len(), path.display()),
+                Err(err) => eprintln!("⚠️  saving {} failed: {}", path.display(), err),
+            }
+        }
+    }
+
+    /// One reconciliation pass against the exchange's balances of cash and held tokens
+    async fn reconcile_balances(&mut self) {
+        let Some((reconciler, client)) = &self.reconciler else {
+            return;
+        };
+        let held: Vec<TokenId> = self.wallet.positions.values()
+            .filter(|p| p.side == Side::Buy)
+            .map(|p| p.token_id.clone())
+            .collect();
+        let balances = match client.fetch_balances(&held).await {
+            Ok(balances) => balances,
+            Err(err) => {
+                eprintln!("⚠️  reconcile: balances unavailable: {}", err);
+                return;
+            }
+        };
+        let report = reconciler.reconcile(&mut self.wallet, &mut self.journal, &balances, &self.control, now());
+        for drift in &report.drifts {
+            eprintln!(
+                "⚠️  reconcile: {} drift {:+.6} (internal {:.6}, exchange {:.6})",
+                drift.asset, drift.diff(), drift.internal, drift.exchange
+            );
+        }
+        if report.halted {
+            eprintln!("⚠️  reconcile: trading paused until the drift is explained");
+        }
+        if report.corrected {
+            self.orders.sync_locks(&mut self.wallet);
+            self.persist();
+            self.publish_view();
+        }
+    }
+
+    /// Reload the watchlist: open binary markets, up to `max_markets`
+    pub async fn refresh_markets(&mut self, source: &impl MarketProvider) -> Result<(), String> {
+        let mut markets: Vec<Market> = Vec::new();
+        while markets.len() < self.profile.bot.max_markets {
+            let page = source.markets(PAGE_SIZE, markets.len()).await.map_err(|e| format!("markets: {}", e))?;
+            let done = page.len() < PAGE_SIZE;
+            markets.extend(page);
+            if done {
+                break;
+            }
+        }
+        markets.retain(|m| m.clob_token_ids.len() == 2 && m.outcome_prices.len() == 2);
+        if self.profile.scan.enabled {
+            // Markets busy at this hour get the WebSocket subscriptions first
+            let now = now();
+            let ranked = self.volume.watchlist(&self.profile.scan, &markets, now, usize::MAX);
+            let rank: HashMap<&str, usize> = ranked.iter().enumerate().map(|(i, t)| (t.as_str(), i)).collect();
+            markets.sort_by_key(|m| rank.get(m.clob_token_ids[0].as_str()).copied().unwrap_or(usize::MAX));
+        }
+        markets.truncate(self.profile.bot.max_markets);
+        self.poller.allocate(&markets, self.profile.bot.min_spread);
+
+        // New markets warm up before trading; ones that stayed illiquid leave the watchlist
+        let now = now();
+        for market in &markets {
+            self.lifecycle.sync(market, now);
+            if self.lifecycle.state(&market.id) == Some(MarketState::Discovered) && !self.pruner.is_pruned(&market.id) {
+                let _ = self.lifecycle.subscribe(&market.id, now);
+            }
+        }
+        let changes = self.pruner.update(&markets, &mut self.lifecycle, now);
+        if !changes.pruned.is_empty() || !changes.restored.is_empty() {
+            println!("watchlist: {} markets pruned, {} restored", changes.pruned.len(), changes.restored.len());
+        }
+        self.lifecycle.promote_warmed(now);
+
+        self.outcomes = markets.iter()
+            .flat_map(|m| m.clob_token_ids.iter().enumerate().map(|(i, t)| (t.clone(), (m.id.clone(), i))))
+            .collect();
+        self.books.retain(|token, _| self.outcomes.contains_key(token));
+        self.curves.retain(|token| self.outcomes.contains_key(token));
+        markets.iter().for_each(|m| self.flow.watch(m));
+        self.markets = markets.into_iter().map(|m| (m.id.clone(), m)).collect();
+        self.history.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).retain(|id| self.markets.contains_key(id));
+        self.fast_moves.retain(|id| self.markets.contains_key(id));
+        #[cfg(feature = "health")]
+        if let Some(health) = &self.health {
+            health.set_markets_loaded(!self.markets.is_empty());
+        }
+        Ok(())
+    }
+
+    /// Tell the health endpoint whether the feed is still delivering
+    #[cfg_attr(not(feature = "health"), allow(unused_variables))]
+    fn report_data(&self, connected: bool) {
+        #[cfg(feature = "health")]
+        if let Some(health) = &self.health {
+            health.set_connected(connected);
+            if connected {
+                health.mark_data(now_ms());
+            }
+        }
+    }
+
+    /// Export the forecast behind the latest execution decision on the health report
+    #[cfg_attr(not(feature = "health"), allow(unused_variables))]
+    fn report_capital(&self, forecast: &CapitalForecast) {
+        #[cfg(feature = "health")]
+        if let Some(health) = &self.health {
+            health.set_capital(forecast);
+        }
+    }
+
+    /// Cancel whatever still rests on the venue (SIGTERM, Ctrl-C); returns how many were cancelled
+    #[cfg(feature = "health")]
+    pub fn cancel_open_orders(&mut self) -> usize {
+        let cancelled = cancel_on_shutdown(self.gateway.as_mut(), &mut self.orders);
+        self.orders.sync_locks(&mut self.wallet);
+        self.persist();
+        cancelled
+    }
+
+    /// Tokens to stream: every outcome of a market that isn't pruned
+    fn watchlist(&self) -> Vec<String> {
+        let mut tokens: Vec<String> = self.outcomes.iter()
+            .filter(|(_, (market_id, _))| !self.pruner.is_pruned(market_id))
+            .map(|(t, _)| t.to_string())
+            .collect();
+        tokens.sort();
+        tokens
+    }
+
+    /// Apply one streamed event to our books and scan the market it touched
+    pub fn on_market_event(&mut self, event: MarketEvent) {
+        self.bus.publish_market(event.clone());
+        if let Some(issue) = self.sync.observe(&event) {
+            // Nothing is traded on the token until its REST snapshot is back
+            self.books.remove(issue.token_id());
+            self.curves.remove(issue.token_id());
+            self.resync_due = true;
+            self.report_sync();
+            return;
+        }
+        if self.outliers.check(&event, self.books.get(event.token_id())).is_some() {
+            // Dropped; the token isn't traded until a resynced snapshot has run clean
+            self.resync_due = true;
+            return;
+        }
+        let token_id = match event {
+            MarketEvent::Book { book, .. } => {
+                let token_id = book.token_id.clone();
+                self.books.insert(token_id.clone(), book);
+                token_id
+            }
+            MarketEvent::PriceChange { token_id, side, price, size, timestamp, .. } => {
+                let Some(book) = self.books.get_mut(&token_id) else { return };
+                book.apply_level(side, price, size, timestamp);
+                token_id
+            }
+            MarketEvent::LastTrade(trade) => {
+                if let Some((market_id, _)) = self.outcomes.get(&trade.token_id) {
+                    self.lifecycle.observe_trade(market_id);
+                }
+                self.flow.record(trade.clone());
+                if self.profile.scan.enabled {
+                    self.trades.push_back(trade);
+                }
+                return;
+            }
+            MarketEvent::Heartbeat { .. } => return,
+        };
+        if let Some(book) = self.books.get(&token_id) {
+            self.curves.update(book);
+        }
+        self.save_book(&token_id);
+        self.peg_orders(&token_id);
+        let Some((market_id, outcome)) = self.outcomes.get(&token_id).cloned() else { return };
+        self.lifecycle.observe_book(&market_id);
+        if outcome == 0 && let Some(mid) = self.books.get(&token_id).and_then(OrderBook::midpoint) {
+            self.history.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).record(&market_id, now_ms(), mid);
+            self.fast_moves.observe(&market_id, mid, now_ms());
+        }
+        // The detector prices a pair at what buying each outcome costs now
+        let ask = self.books.get(&token_id).and_then(|b| b.best_ask());
+        if let (Some(market), Some(ask)) = (self.markets.get_mut(&market_id), ask) {
+            market.outcome_prices[outcome] = ask;
+        }
+        self.evaluate(&market_id);
+        self.dispatch_scheduled();
+    }
+
+    /// Schedule the market's signal if it clears the profit threshold at a size the books
+    /// and limits allow
+    fn evaluate(&mut self, market_id: &MarketId) {
+        if self.control.is_paused() {
+            return;
+        }
+        if self.profile.scan.enabled {
+            // Quiet markets are scanned less often; the skipped updates are already in the books
+            let now_ms = now_ms();
+            let interval = self.volume.scan_interval(&self.profile.scan, market_id, now_ms / 1000).as_millis() as u64;
+            if self.last_scan.get(market_id).is_some_and(|&last| now_ms < last + interval) {
+                return;
+            }
+            self.last_scan.insert(market_id.clone(), now_ms);
+        }
+        // Warming up, winding down or pruned markets take no new entries, and neither do
+        // markets whose last signal was just judged not worth it, whose orders keep failing
+        // or whose mid is moving like news is breaking
+        if !self.lifecycle.is_tradable(market_id)
+            || self.cooldowns.is_cooling(market_id, now())
+            || self.backoff.is_backing_off(market_id, now())
+            || self.fast_moves.is_paused(market_id)
+        {
+            return;
+        }
+        let Some(market) = self.markets.get(market_id) else { return };
+        if market.clob_token_ids.iter().any(|token| self.outliers.is_suppressed(token)) {
+            return;
+        }
+        let Some(signal) = self.detector.scan(std::slice::from_ref(market)).into_iter().next() else { return };
+        // No shorting on Polymarket: only underpriced pairs (buy both outcomes) are tradable
+        if signal.recommended_side != Side::Buy {
+            return;
+        }
+        let (Some(yes), Some(no)) = (self.books.get(&market.clob_token_ids[0]), self.books.get(&market.clob_token_ids[1])) else {
+            return;
+        };
+        let (Some(yes_curves), Some(no_curves)) = (self.curves.get(&yes.token_id), self.curves.get(&no.token_id)) else {
+            return;
+        };
+        let (Some(yes_best), Some(no_best)) = (yes_curves.buy.best(), no_curves.buy.best()) else { return };
+
+        // As deep into both books as the pair's VWAP still leaves the minimum spread, within budget
+        let budget = self.profile.risk.max_position_usdc.min(self.wallet.available().value() - self.profile.risk.min_reserve_usdc);
+        let pair_vwap = |size: f64| Some(yes_curves.buy.vwap(size)? + no_curves.buy.vwap(size)?);
+        let deepest = max_pair_size(&yes_curves.buy, &no_curves.buy, 1.0 - self.profile.bot.min_spread, Side::Buy);
+        let Some(deepest_vwap) = pair_vwap(deepest) else { return };
+        let size = deepest.min(budget / deepest_vwap).floor();
+        let Some(pair_cost) = pair_vwap(size) else { return };
+        // Walking past the touch costs this much per pair over the prices the signal saw
+        let slippage = (pair_cost - yes_best - no_best).max(0.0);
+        let depth = yes_curves.notional().min(no_curves.notional());
+        // Profit is expected only on the pairs we'd get: busy markets have takers racing us for the top
+        let window = self.flow.windows_ms.first().copied().unwrap_or(60_000);
+        let competition = self.flow.stats(market_id, window, now_ms()).competition(self.profile.bot.reaction_ms);
+        let fill = FillModel::contested_fill_ratio(yes, size, Side::Buy, competition)
+            .min(FillModel::contested_fill_ratio(no, size, Side::Buy, competition));
+        if size < 1.0 {
+            return;
+        }
+        if !self.detector.should_trade(&signal, size * fill, market.taker_fee_rate(), slippage, Some(depth)) {
+            if let Some(rejection) = self.explainer.explain(&signal, size, market.taker_fee_rate(), (yes, no), false, now()) {
+                self.why_not.record(rejection);
+            }
+            self.cooldowns.start(market_id, now(), self.profile.bot.cooldown_secs);
+            return;
+        }
+        // Both legs take liquidity, so a spent budget stops them in either mode
+        if let Some(budget) = &mut self.fee_budget
+            && let Err(violation) = budget.check(&self.journal, now(), false)
+        {
+            if budget.report_breach(now()) {
+                self.risk.record(violation);
+            }
+            let tier = self.tier_of(depth);
+            self.capture.record_miss(&signal, tier, size, "fee budget spent", now());
+            return;
+        }
+        let legs = [(yes.token_id.clone(), size), (no.token_id.clone(), size)];
+        let markets: Vec<Market> = self.markets.values().cloned().collect();
+        if let Err(violation) = self.shortfall.check(&self.wallet, &markets, &self.marks(), &legs) {
+            self.risk.record(violation);
+            let tier = self.tier_of(depth);
+            self.capture.record_miss(&signal, tier, size, "shortfall limit", now());
+            return;
+        }
+        // Supervised mode: large trades wait for an operator, then run against the books as of then
+        let size = match self.approved.remove(market_id) {
+            Some(approved) => size.min(approved),
+            None if self.queued.contains(market_id) => return,
+            None => match self.approvals.admit(Signal::Binary(signal.clone()), size, size * pair_cost, now_ms()) {
+                Admission::Execute => size,
+                Admission::Queued(id) => {
+                    println!("arb {}: intent {} waiting for approval ({:.2} USDC)", market_id, id, size * pair_cost);
+                    self.queued.insert(market_id.clone());
+                    return;
+                }
+            },
+        };
+
+        // Queued by profit per dollar of capital; a full queue drops its weakest signal
+        let pending = PendingSignal {
+            expected_profit: self.detector.expected_profit(&signal, size * fill, market.taker_fee_rate(), slippage, Some(depth)),
+            capital_required: size * pair_cost,
+            latency_risk: self.profile.bot.reaction_ms as f64 / 1000.0,
+            size,
+            signal,
+        };
+        self.scheduler.cancel_market(market_id);
+        if let Some(evicted) = self.scheduler.submit(pending) {
+            let tier = self.pair_tier(&evicted.signal.market_id);
+            self.capture.record_miss(&evicted.signal, tier, evicted.size, "preempted by stronger signals", now());
+        }
+    }
+
+    /// Send queued signals, best first, while free cash covers them; the rest wait for
+    /// capital to come back
+    fn dispatch_scheduled(&mut self) {
+        if self.scheduler.is_empty() || self.control.is_paused() {
+            return;
+        }
+        let available = self.wallet.available().value() - self.profile.risk.min_reserve_usdc;
+        for pending in self.scheduler.next_batch(available) {
+            self.dispatch(pending);
+        }
+    }
+
+    /// Liquidity tier of a market's pair, from the shallower side
+    fn pair_tier(&self, market_id: &MarketId) -> Option<String> {
+        let market = self.markets.get(market_id)?;
+        let yes = self.curves.get(market.clob_token_ids.first()?)?;
+        let no = self.curves.get(market.clob_token_ids.get(1)?)?;
+        self.tier_of(yes.notional().min(no.notional()))
+    }
+
+    /// Execute a scheduled signal. The detector priced the pair off the market's outcome
+    /// prices; submit at what the books offer now, as long as a worse price still clears
+    /// the profit floor.
+    fn dispatch(&mut self, pending: PendingSignal) {
+        let PendingSignal { signal, size, .. } = pending;
+        let market_id = &signal.market_id;
+        let Some(market) = self.markets.get(market_id) else { return };
+        let (Some(yes), Some(no)) = (self.books.get(&market.clob_token_ids[0]), self.books.get(&market.clob_token_ids[1])) else {
+            return;
+        };
+        let (Some(yes_curves), Some(no_curves)) = (self.curves.get(&yes.token_id), self.curves.get(&no.token_id)) else {
+            return;
+        };
+        let depth = yes_curves.notional().min(no_curves.notional());
+        let fees = fee_schedule(&self.profile, market);
+        let Some(leg_size) = Size::new(size) else { return };
+        let engine = ExecutionEngine::new(fees.clone());
+        let plan = [
+            LegPlan { book: yes, size: leg_size, side: Side::Buy, predicted_price: signal.yes_price },
+            LegPlan { book: no, size: leg_size, side: Side::Buy, predicted_price: signal.no_price },
+        ];
+        let quote = match engine.reprice(market_id, &plan, 1.0, self.profile.bot.min_profit, &mut self.improvement) {
+            Ok(quote) => quote,
+            Err(reason) => {
+                println!("arb {}: not sent, {}", market_id, reason);
+                self.cooldowns.start(market_id, now(), self.profile.bot.cooldown_secs);
+                let tier = self.tier_of(depth);
+                self.capture.record_miss(&signal, tier, size, &reason, now());
+                return;
+            }
+        };
+        // Settles on match, so nothing is pending settlement beyond resolution payouts
+        let outlook = self.resolutions.outlook(&self.wallet);
+        let forecast = self.capital.check(&self.wallet, &self.orders, 0.0, &outlook, quote.net_cost());
+        self.report_capital(forecast.as_ref().unwrap_or_else(|rejected| rejected));
+        if let Err(forecast) = forecast {
+            println!("arb {}: not sent, {}", market_id, forecast);
+            let tier = self.tier_of(depth);
+            self.capture.record_miss(&signal, tier, size, "insufficient free capital", now());
+            return;
+        }
+        self.bus.publish_signal(Signal::Binary(signal.clone()));
+
+        // Each leg is limited at the last level its size reaches; the thinner one goes first,
+        // so if it is killed nothing executed
+        let (Some(yes_limit), Some(no_limit)) = (yes_curves.buy.price_at(size), no_curves.buy.price_at(size)) else { return };
+        let legs = [
+            (yes, yes_limit, yes_curves.buy.size_within(yes_limit, Side::Buy)),
+            (no, no_limit, no_curves.buy.size_within(no_limit, Side::Buy)),
+        ];
+        let (first, second) = if legs[0].2 <= legs[1].2 { (&legs[0], &legs[1]) } else { (&legs[1], &legs[0]) };
+        let request = |(book, price, _): &(&OrderBook, f64, f64)| -> Option<OrderRequest> {
+            Some(OrderRequest {
+                token_id: book.token_id.clone(),
+                side: Side::Buy,
+                price: Price::new(*price)?,
+                size: Size::new(size)?,
+                time_in_force: TimeInForce::Fok,
+            })
+        };
+        let (Some(first_request), Some(second_request)) = (request(first), request(second)) else { return };
+        self.gateway.observe(first.0, &fees);
+        self.gateway.observe(second.0, &fees);
+        match self.passive_leg([yes, no], size, &fees) {
+            Some(passive) => {
+                let taker = if passive == first_request.token_id { &second_request } else { &first_request };
+                self.execute_routed(&signal, taker, &passive, fees.as_ref());
+            }
+            None => self.execute(&signal, size, &first_request, &second_request, &quote, fees.as_ref()),
+        }
+    }
+
+    /// With routing on, the leg expected to cost less posted than taken. At most one leg
+    /// rests (the likelier to fill); the other takes so the pair is never left unworked.
+    fn passive_leg(&self, books: [&OrderBook; 2], size: f64, fees: &SharedFeeSchedule) -> Option<TokenId> {
+        let config = &self.profile.routing;
+        if !config.enabled {
+            return None;
+        }
+        let router = SmartOrderRouter::new(fees.clone(), config.passive_fill_rate, config.miss_penalty);
+        let window = self.flow.windows_ms.first().copied().unwrap_or(60_000);
+        books.into_iter()
+            .filter_map(|book| {
+                let flow = self.flow.level_flow(&book.token_id, book.midpoint()?, window, now_ms());
+                let route = router.route_leg_with_flow(book, size, Side::Buy, config.ttl_secs, Some(&flow))?;
+                (route.kind == RouteKind::Maker).then_some(route)
+            })
+            .max_by(|a, b| a.fill_probability.total_cmp(&b.fill_probability))
+            .map(|route| route.token_id)
+    }
+
+    /// Liquidity tier name for a pair of this depth, as the clusters report groups trades
+    fn tier_of(&self, depth: f64) -> Option<String> {
+        classify(&self.detector.liquidity_tiers, depth).map(|t| t.name.clone())
+    }
+
+    /// Mid of every streamed book
+    fn marks(&self) -> HashMap<TokenId, f64> {
+        self.books.iter().filter_map(|(token, book)| Some((token.clone(), book.midpoint()?))).collect()
+    }
+
+    /// Send both legs, then book whatever the venue filled
+    fn execute(&mut self, signal: &ArbitrageSignal, size: f64, first: &OrderRequest, second: &OrderRequest, quote: &MultiLegExecutionReport, fees: &dyn FeeSchedule) {
+        let outcome = execute_pair(self.gateway.as_mut(), &mut self.orders, first, second, now_ms());
+        let fills = self.take_fills();
+        self.reconcile_fees(&fills, fees);
+        // Complete pairs are what both legs filled; each leg's average price makes up their cost
+        let leg = |token_id: &TokenId| {
+            let (size, cost) = fills.iter()
+                .filter(|f| f.token_id == *token_id)
+                .fold((0.0, 0.0), |(size, cost), f| (size + f.size, cost + f.size * f.price));
+            (size, if size > 0.0 { cost / size } else { 0.0 })
+        };
+        let ((first_size, first_price), (second_size, second_price)) = (leg(&first.token_id), leg(&second.token_id));
+        let paired = first_size.min(second_size);
+        let pair_cost = (paired > 0.0).then_some(first_price + second_price);
+        let tier = self.curves.get(&first.token_id).zip(self.curves.get(&second.token_id))
+            .and_then(|(a, b)| self.tier_of(a.notional().min(b.notional())));
+        self.capture.record_pair(signal, tier, size, &outcome, paired, pair_cost, now());
+        let failure = match &outcome {
+            PairOutcome::Filled { .. } => None,
+            PairOutcome::Missed { reason } | PairOutcome::Exposed { reason, .. } => Some(reason),
+            PairOutcome::NeedsHedge { first, second } => first.as_ref().err().or(second.as_ref().err()),
+        };
+        match failure {
+            Some(error) => {
+                self.backoff.record_failure(&signal.market_id, error, now());
+            }
+            None => self.backoff.record_success(&signal.market_id),
+        }
+        match &outcome {
+            PairOutcome::Filled { .. } => println!("arb {}: bought {} pairs at {:.4}", signal.market_id, size, signal.yes_price + signal.no_price),
+            PairOutcome::Missed { reason } => println!("arb {}: missed ({})", signal.market_id, reason),
+            PairOutcome::Exposed { failed, reason, .. } => {
+                eprintln!("⚠️  arb {}: {} leg failed after the other filled ({}); hedging", signal.market_id, failed.token_id, reason)
+            }
+            PairOutcome::NeedsHedge { .. } => eprintln!("⚠️  arb {}: legs went out as IOC and may be uneven", signal.market_id),
+        }
+
+        // Both legs are journaled and alerted as one trade: one grouped entry per leg at its VWAP
+        let legs = [first, second].into_iter()
+            .map(|request| {
+                let mid = self.books.get(&request.token_id).and_then(OrderBook::midpoint);
+                // Improvement is measured from the detector's price to the fill
+                let predicted = quote.legs.iter().find(|l| l.token_id == request.token_id).map_or(request.price.value(), |l| l.predicted_price);
+                LegFill::from_fills(request, &fills, predicted, mid, fees)
+            })
+            .collect();
+        let report = MultiLegExecutionReport::new(signal.market_id.clone(), legs);
+        let cash_before = self.wallet.usdc;
+        for fill in &fills {
+            self.book_fill(fill, EntryReason::ArbLeg);
+        }
+        let ids = self.journal.record_execution(&report, now(), &[EntryReason::ArbLeg.tag().to_string()]);
+        self.save_trade_records(signal, &ids, [first, second], cash_before);
+        if report.legs.iter().any(|l| l.filled > 0.0) {
+            self.improvement.record(&report);
+            self.bus.publish_execution(report);
+        }
+        // A leg that filled without its pair is covered by buying the other, at most at break-even
+        if !matches!(outcome, PairOutcome::Filled { .. }) && (first_size - second_size).abs() > 1e-9 {
+            let (short, missing, long_price) = if first_size > second_size {
+                (&second.token_id, first_size - second_size, first_price)
+            } else {
+                (&first.token_id, second_size - first_size, second_price)
+            };
+            self.hedge(&signal.market_id, short, missing, 1.0 - long_price, ids.first().copied());
+        }
+        self.orders.sync_locks(&mut self.wallet);
+        self.persist();
+        self.check_concentration();
+        self.publish_view();
+    }
+
+    /// Take one leg, then rest the other pegged for what filled, no higher than still
+    /// clears the minimum spread; if it can't rest, the taken leg is hedged at once
+    fn execute_routed(&mut self, signal: &ArbitrageSignal, taker: &OrderRequest, passive: &TokenId, fees: &dyn FeeSchedule) {
+        let market_id = &signal.market_id;
+        if let Err(err) = self.orders.submit(self.gateway.as_mut(), taker, now_ms()) {
+            println!("arb {}: missed ({})", market_id, err);
+            self.backoff.record_failure(market_id, &err, now());
+            return;
+        }
+        self.backoff.record_success(market_id);
+        let fills = self.take_fills();
+        self.reconcile_fees(&fills, fees);
+        let (size, cost) = fills.iter().fold((0.0, 0.0), |(size, cost), f| (size + f.size, cost + f.size * f.price + f.fee));
+        let mut group = None;
+        for fill in &fills {
+            let id = self.apply_fill(market_id, fill, EntryReason::ArbLeg, group);
+            group.get_or_insert(id);
+        }
+        if size > 1e-9 {
+            let per_share = cost / size;
+            let limit = arb_limit(Side::Buy, per_share, self.profile.bot.min_spread);
+            match self.rest_pegged(market_id, passive, size, limit) {
+                Some(price) => println!("arb {}: took {:.2} of {} at {:.4}, resting {} at {:.2}", market_id, size, taker.token_id, per_share, passive, price),
+                None => self.hedge(market_id, passive, size, 1.0 - per_share, group),
+            }
+        }
+        self.orders.sync_locks(&mut self.wallet);
+        self.persist();
+        self.check_concentration();
+        self.publish_view();
+    }
+
+    /// Buy `size` of the short leg at no more than `max_price`; fills are journaled as
+    /// hedges in the trade's group and what doesn't fill rests pegged at the same limit
+    fn hedge(&mut self, market_id: &MarketId, short: &TokenId, size: f64, max_price: f64, group: Option<u64>) {
+        let (Some(price), Some(wanted)) = (Price::new(max_price), Size::new(size)) else { return };
+        if let (Some(book), Some(market)) = (self.books.get(short), self.markets.get(market_id)) {
+            self.gateway.observe(book, &fee_schedule(&self.profile, market));
+        }
+        let request = OrderRequest { token_id: short.clone(), side: Side::Buy, price, size: wanted, time_in_force: TimeInForce::Ioc };
+        if let Err(err) = self.orders.submit(self.gateway.as_mut(), &request, now_ms()) {
+            eprintln!("⚠️  arb {}: hedging {} failed: {}", market_id, short, err);
+        }
+        let fills = self.take_fills();
+        if let Some(market) = self.markets.get(market_id) {
+            let schedule = fee_schedule(&self.profile, market);
+            self.reconcile_fees(&fills, schedule.as_ref());
+        }
+        let hedged: f64 = fills.iter().map(|f| f.size).sum();
+        for fill in &fills {
+            self.apply_fill(market_id, fill, EntryReason::Hedge, group);
+        }
+        if hedged + 1e-9 < size {
+            match self.rest_pegged(market_id, short, size - hedged, max_price) {
+                Some(price) => println!("arb {}: hedged {:.2} of {:.2} on {}, resting the rest at {:.2}", market_id, hedged, size, short, price),
+                None => eprintln!("⚠️  arb {}: hedged {:.2} of {:.2} on {}; one-sided position left open", market_id, hedged, size, short),
+            }
+        }
+    }
+
+    /// Rest a buy of `size` on `token_id` at its peg, never above `limit`, and keep it
+    /// pegged as the book moves; returns the price it went out at
+    fn rest_pegged(&mut self, market_id: &MarketId, token_id: &TokenId, size: f64, limit: f64) -> Option<f64> {
+        let book = self.books.get(token_id)?;
+        let price = self.pegger.target(book, Side::Buy, PEG_TICK, limit, None)?;
+        let request = OrderRequest {
+            token_id: token_id.clone(),
+            side: Side::Buy,
+            price: Price::new(price)?,
+            size: Size::new(size)?,
+            time_in_force: TimeInForce::Gtc,
+        };
+        let now = now_ms();
+        match self.orders.submit(self.gateway.as_mut(), &request, now) {
+            Ok(order_id) => {
+                self.pegger.track(&order_id, token_id.clone(), Side::Buy, PEG_TICK, limit, now);
+                // A passive price shouldn't match anything; if it did, that's a maker fill
+                self.take_fills();
+                Some(price)
+            }
+            Err(err) => {
+                eprintln!("⚠️  arb {}: resting a buy on {} failed: {}", market_id, token_id, err);
+                None
+            }
+        }
+    }
+
+    /// Reprice pegged orders resting on the token, then book what each replaced order
+    /// turned out to have matched since we last knew
+    fn peg_orders(&mut self, token_id: &TokenId) {
+        if self.pegger.is_empty() {
+            return;
+        }
+        let (Some(book), Some((market_id, _))) = (self.books.get(token_id), self.outcomes.get(token_id)) else { return };
+        let Some(market) = self.markets.get(market_id) else { return };
+        let fees = fee_schedule(&self.profile, market);
+        let market_id = market_id.clone();
+        self.gateway.observe(book, &fees);
+        let known: HashMap<String, OpenOrder> = self.orders.orders_for(token_id).map(|o| (o.order_id.clone(), o.clone())).collect();
+        let outcomes = self.pegger.update(&mut self.orders, self.gateway.as_mut(), book, now_ms());
+        for outcome in &outcomes {
+            let (old_id, filled) = match outcome {
+                ReplaceOutcome::Replaced { old_id, filled, .. } | ReplaceOutcome::Gone { old_id, filled } => (old_id, *filled),
+                ReplaceOutcome::PlaceFailed { old_id, error, filled } => {
+                    eprintln!("⚠️  peg: placing {} again failed: {}", old_id, error);
+                    (old_id, *filled)
+                }
+                ReplaceOutcome::Unsettled { old_id, error } => {
+                    eprintln!("⚠️  peg: {} was cancelled but its fills couldn't be read ({}); not placed again", old_id, error);
+                    continue;
+                }
+                // Still resting; retried on a later update
+                ReplaceOutcome::CancelFailed { .. } => continue,
+            };
+            let Some(order) = known.get(old_id).filter(|_| filled > 1e-9) else { continue };
+            let fill = Fill {
+                order_id: Some(old_id.clone()),
+                token_id: order.token_id.clone(),
+                side: order.side,
+                price: order.price,
+                size: filled,
+                fee: fees.fee(order.price, filled, true),
+                timestamp: now_ms(),
+            };
+            self.apply_fill(&market_id, &fill, EntryReason::MakerFill, None);
+        }
+        if !outcomes.is_empty() {
+            self.orders.sync_locks(&mut self.wallet);
+            self.persist();
+        }
+    }
+
+    /// Fills since the last call. Fills of our own resting orders are booked and journaled
+    /// here as maker fills; the others are returned for the caller to book.
+    fn take_fills(&mut self) -> Vec<Fill> {
+        let (maker, taker): (Vec<Fill>, Vec<Fill>) = self.gateway.take_fills().into_iter()
+            .partition(|f| f.order_id.as_ref().is_some_and(|id| self.orders.open.contains_key(id)));
+        for fill in &maker {
+            if let Some(order_id) = &fill.order_id {
+                self.orders.record_fill(order_id, fill.size);
+            }
+            let market_id = self.outcomes.get(&fill.token_id).map(|(m, _)| m.clone()).unwrap_or_default();
+            if let Some(market) = self.markets.get(&market_id) {
+                let schedule = fee_schedule(&self.profile, market);
+                self.reconcile_fees(std::slice::from_ref(fill), schedule.as_ref());
+            }
+            self.apply_fill(&market_id, fill, EntryReason::MakerFill, None);
+        }
+        taker
+    }
+
+    /// Keep each journaled leg's opportunity as seen (books, fees and the levels it was priced
+    /// across) so `replay` can re-run it
+    fn save_trade_records(&self, signal: &ArbitrageSignal, ids: &[u64], requests: [&OrderRequest; 2], cash_before: Usdc) {
+        let Some(market) = self.markets.get(&signal.market_id) else { return };
+        for entry in ids.iter().filter_map(|id| self.journal.get(*id)) {
+            let Some(request) = requests.iter().find(|r| r.token_id == entry.token_id) else { continue };
+            // Traded token first
+            let mut tokens: Vec<&TokenId> = market.clob_token_ids.iter().collect();
+            tokens.sort_by_key(|t| **t != request.token_id);
+            let books: Vec<OrderBook> = tokens.into_iter().filter_map(|t| self.books.get(t).cloned()).collect();
+            let record = TradeRecord {
+                trade_id: entry.id,
+                timestamp: entry.timestamp,
+                market: market.clone(),
+                token_id: request.token_id.clone(),
+                walk: books.first().map(|b| b.walk(request.size.value(), request.side)),
+                books,
+                side: request.side,
+                size: request.size,
+                limit: Some(request.price),
+                fees: fee_config(&self.profile, market),
+                cash_before,
+                edge: Some(signal.edge),
+            };
+            if let Err(err) = record.save(Path::new(TRADE_RECORDS_PATH)) {
+                eprintln!("warning: could not save trade record: {}", err);
+            }
+        }
+    }
+
+    /// Operator `/flatten-all`: cancel resting orders and sell every long position into the
+    /// bids within the control config's slippage cap. Entries stay paused until `/resume`.
+    fn flatten_all(&mut self) {
+        println!("flatten requested: cancelling orders and selling positions");
+        if let Err(err) = self.orders.cancel_all(self.gateway.as_mut()) {
+            eprintln!("⚠️  flatten: cancelling open orders failed: {}", err);
+        }
+        let max_slippage = self.profile.control.as_ref().map(|c| c.flatten_max_slippage).unwrap_or(0.05);
+        let mut held: Vec<(TokenId, f64)> = self.wallet.positions.values()
+            .filter(|p| p.side == Side::Buy && p.size > 0.0)
+            .map(|p| (p.token_id.clone(), p.size))
+            .collect();
+        held.sort_by(|a, b| a.0.cmp(&b.0));
+        for (token_id, size) in &held {
+            let (Some(book), Some((market_id, _))) = (self.books.get(token_id), self.outcomes.get(token_id)) else {
+                eprintln!("⚠️  flatten: no book for {}, left open", token_id);
+                continue;
+            };
+            let Some((sell, floor)) = manual::flatten_size(book, *size, max_slippage) else {
+                eprintln!("⚠️  flatten: no bids for {} within {:.1}% of mid", token_id, max_slippage * 100.0);
+                continue;
+            };
+            let (Some(price), Some(size)) = (Price::new(floor.clamp(0.0, 1.0)), Size::new(sell)) else { continue };
+            if let Some(market) = self.markets.get(market_id) {
+                self.gateway.observe(book, &fee_schedule(&self.profile, market));
+            }
+            let request = OrderRequest { token_id: token_id.clone(), side: Side::Sell, price, size, time_in_force: TimeInForce::Ioc };
+            if let Err(err) = self.submit_sell(&request) {
+                eprintln!("⚠️  flatten: selling {} failed: {}", token_id, err);
+            }
+        }
+        for fill in self.take_fills() {
+            let market_id = self.outcomes.get(&fill.token_id).map(|(m, _)| m.clone()).unwrap_or_default();
+            if let Some(market) = self.markets.get(&market_id) {
+                let schedule = fee_schedule(&self.profile, market);
+                self.reconcile_fees(std::slice::from_ref(&fill), schedule.as_ref());
+            }
+            let id = self.apply_fill(&market_id, &fill, EntryReason::Manual, None);
+            self.journal.tag(id, manual::FLATTEN_TAG);
+        }
+        self.orders.sync_locks(&mut self.wallet);
+        self.persist();
+        self.publish_view();
+        let open = self.wallet.positions.values().filter(|p| p.side == Side::Buy).count();
+        if open > 0 {
+            eprintln!("⚠️  flatten: {} positions still open", open);
+        }
+    }
+
+    /// Submit a sell only out of held inventory; a naked sell is recorded and alerted instead
+    fn submit_sell(&mut self, request: &OrderRequest) -> Result<String, String> {
+        self.risk.check_sell(&self.wallet, &request.token_id, request.size.value()).map_err(|violation| violation.to_string())?;
+        self.orders.submit(self.gateway.as_mut(), request, now_ms()).map_err(|err| err.to_string())
+    }
+
+    /// Replace every diverged or quarantined book with a REST snapshot; failures stay stale
+    /// and are retried on the next refresh
+    async fn resync_books(&mut self) {
+        self.resync_due = false;
+        let mut tokens = self.sync.stale_tokens();
+        tokens.extend(self.outliers.needs_resync());
+        tokens.sort();
+        tokens.dedup();
+        for token_id in tokens {
+            if !self.outcomes.contains_key(&token_id) {
+                continue;
+            }
+            match self.sync.resync(&self.clob, &token_id).await {
+                Ok(book) => self.on_market_event(MarketEvent::Book { book, hash: None, seq: None }),
+                Err(err) => eprintln!("⚠️  book resync for {} failed: {}", token_id, err),
+            }
+        }
+        self.report_sync();
+    }
+
+    /// REST fallback while the stream is silent: fetch the books of the markets the poller
+    /// says are due, within one tick's share of the request budget
+    async fn poll_books(&mut self) {
+        let now_ms = now_ms();
+        if now_ms.saturating_sub(self.last_stream_ms) < self.profile.poll.fallback_after_ms {
+            return;
+        }
+        if !self.polling {
+            eprintln!("⚠️  market feed silent, polling books over REST");
+            self.polling = true;
+        }
+        let mut budget = (self.profile.poll.requests_per_sec * POLL_TICK_MS as f64 / 1000.0).ceil().max(1.0) as usize;
+        for market_id in self.poller.due(now_ms) {
+            let Some(market) = self.markets.get(&market_id) else {
+                continue;
+            };
+            if budget < market.clob_token_ids.len() {
+                break;
+            }
+            budget -= market.clob_token_ids.len();
+            let tokens = market.clob_token_ids.clone();
+            self.poller.mark_polled(&market_id, now_ms);
+            for token_id in tokens {
+                match self.clob.order_book(&token_id).await {
+                    Ok(book) => {
+                        self.last_data_ms = now_ms;
+                        self.on_market_event(MarketEvent::Book { book, hash: None, seq: None });
+                    }
+                    Err(err) => eprintln!("⚠️  book poll for {} failed: {}", token_id, err),
+                }
+            }
+        }
+    }
+
+    /// Re-scan markets whose intent an operator approved, so they trade only if the books
+    /// still allow it; an approval the scan can't use is dropped
+    fn collect_approvals(&mut self) {
+        let now_ms = now_ms();
+        for intent in self.approvals.take_approved(now_ms) {
+            if let Signal::Binary(signal) = intent.signal {
+                self.approved.insert(signal.market_id, intent.size);
+            }
+        }
+        self.queued = self.approvals.snapshot().into_iter()
+            .filter(|view| view.status == IntentStatus::Pending)
+            .filter_map(|view| match view.intent.signal {
+                Signal::Binary(signal) => Some(signal.market_id),
+                Signal::Categorical(_) | Signal::CrossMarket(_) => None,
+            })
+            .collect();
+        let markets: Vec<MarketId> = self.approved.keys().cloned().collect();
+        for market_id in markets {
+            self.last_scan.remove(&market_id);
+            self.evaluate(&market_id);
+        }
+        self.dispatch_scheduled();
+        self.approved.clear();
+    }
+
+    /// Save warmups, pruning, cooldowns, capture history and volatility so a restart
+    /// picks up where this run left off
+    fn save_checkpoint(&self) {
+        let config = &self.profile.checkpoint;
+        let checkpoint = DetectorCheckpoint::capture(
+            &self.lifecycle,
+            &self.pruner,
+            &self.cooldowns,
+            &self.capture,
+            self.detector.volatility.as_ref().map(|(table, _)| table),
+            config.max_capture_records,
+            now(),
+        );
+        if let Err(err) = checkpoint.save(&config.path) {
+            eprintln!("⚠️  detector checkpoint {}: {}", config.path.display(), err);
+        }
+    }
+
+    /// Heartbeat for the standby, withheld once the feed has been silent for half the
+    /// takeover window: a primary trading blind should be replaced, not kept alive
+    fn beat(&mut self) {
+        let now_ms = now_ms();
+        let Some((writer, config)) = &mut self.heartbeat else { return };
+        if now_ms.saturating_sub(self.last_data_ms) > config.takeover_after_ms / 2 {
+            return;
+        }
+        if let Err(err) = writer.beat(now_ms) {
+            eprintln!("⚠️  heartbeat {}: {}", writer.path.display(), err);
+        }
+    }
+
+    /// Divergence and resync counters for the health report
+    fn report_sync(&self) {
+        #[cfg(feature = "health")]
+        if let Some(health) = &self.health {
+            health.set_book_sync(self.sync.stats);
+        }
+    }
+
+    /// Score our resting quotes against every watched market's reward band; when a UTC day
+    /// ends its qualification is printed and days older than a week are dropped
+    fn sample_rewards(&mut self) {
+        let now_ms = now_ms();
+        let day = now_ms / 1000 / 86_400;
+        if self.reward_day != 0 && day > self.reward_day {
+            let finished = format_date(self.reward_day * 86_400);
+            let rows: Vec<ComplianceRow> = self.rewards.report().into_iter().filter(|r| r.date == finished).collect();
+            print_compliance(&rows);
+            self.rewards.prune(now_ms.saturating_sub(7 * 86_400_000));
+        }
+        self.reward_day = day;
+        let orders: Vec<OpenOrder> = self.orders.open.values().cloned().collect();
+        for market in self.markets.values() {
+            self.rewards.sample(market, &self.books, &orders, now_ms);
+        }
+    }
+
+    /// Re-measure every watched market's mid volatility from the books recorded in storage,
+    /// at most once per `VOLATILITY_REFRESH_SECS`
+    fn refresh_volatility(&mut self) {
+        let Some((table, config)) = &mut self.detector.volatility else { return };
+        let now = now();
+        if table.computed_at > 0 && now < table.computed_at + VOLATILITY_REFRESH_SECS {
+            return;
+        }
+        let markets: Vec<Market> = self.markets.values().cloned().collect();
+        match VolatilityTable::compute(self.storage.as_mut(), &markets, config, now) {
+            Ok(fresh) => *table = fresh,
+            Err(err) => eprintln!("⚠️  measuring market volatility failed: {}", err),
+        }
+    }
+
+    /// Recompute the volume profiles from the streamed prints once a night and save them
+    fn rebuild_profiles(&mut self) {
+        let now = now();
+        let cutoff = now.saturating_sub(TRADE_HISTORY_SECS) * 1000;
+        while self.trades.front().is_some_and(|t| t.timestamp < cutoff) {
+            self.trades.pop_front();
+        }
+        if !self.profile.scan.enabled || !self.volume.stale(&self.profile.scan, now) {
+            return;
+        }
+        let markets: Vec<Market> = self.markets.values().cloned().collect();
+        self.volume.rebuild(&self.profile.scan, &self.trades, &markets, now);
+        if let Err(err) = self.volume.save(&self.profile.scan.profiles_path) {
+            eprintln!("⚠️  saving volume profiles failed: {}", err);
+        }
+    }
+
+    /// Reload the mutually exclusive events; their membership comes from Gamma, never from
+    /// grouping the watchlist. A failed page keeps the previous list.
+    async fn refresh_events(&mut self, source: &impl MarketProvider) {
+        let mut events = Vec::new();
+        let mut offset = 0;
+        while offset < self.profile.bot.max_markets {
+            let page = match source.events(PAGE_SIZE, offset).await {
+                Ok(page) => page,
+                Err(err) => {
+                    eprintln!("⚠️  event refresh failed: {}", err);
+                    return;
+                }
+            };
+            let done = page.len() < PAGE_SIZE;
+            offset += page.len();
+            events.extend(page.into_iter().filter(|e| e.is_exclusive() && !e.closed));
+            if done {
+                break;
+            }
+        }
+        self.events = events;
+    }
+
+    /// Check every exclusive event's full bucket set, at streamed asks where the watchlist
+    /// has them, and publish the mispriced ones. Sets are signalled, not traded.
+    fn scan_event_sets(&mut self) {
+        for event in &mut self.events {
+            for bucket in &mut event.markets {
+                if let Some(streamed) = self.markets.get(&bucket.market.id) {
+                    bucket.market.outcome_prices = streamed.outcome_prices.clone();
+                }
+            }
+        }
+        for signal in self.detector.scan_event_sets(&self.events) {
+            println!(
+                "categorical {}: {} buckets sum to {:.4} ({:?} every YES)",
+                signal.event_id, signal.market_ids.len(), signal.sum, signal.recommended_side
+            );
+            self.bus.publish_signal(Signal::Categorical(signal));
+        }
+    }
+
+    /// Price confirmed duplicate markets against each other
+    fn scan_pairs(&mut self) {
+        for signal in self.detector.scan_pairs(&self.markets) {
+            println!(
+                "cross-market: YES {} at {:.4} + NO {} at {:.4} leaves {:.4}",
+                signal.yes_market, signal.yes_price, signal.no_market, signal.no_price, signal.spread
+            );
+            self.bus.publish_signal(Signal::CrossMarket(signal));
+        }
+    }
+
+    /// Follow every held market through proposal and dispute, and redeem its positions
+    /// once the outcome is final
+    async fn track_resolutions(&mut self, source: &impl MarketProvider) {
+        let held: HashSet<MarketId> = self.journal.entries.iter()
+            .filter(|e| self.wallet.positions.contains_key(&e.token_id))
+            .map(|e| e.market_id.clone())
+            .collect();
+        for market_id in held {
+            let market = match self.markets.get(&market_id) {
+                Some(market) => market.clone(),
+                // Closed markets drop out of the active listing
+                None => match source.market_by_id(market_id.as_str()).await {
+                    Ok(Some(market)) => market,
+                    Ok(None) => continue,
+                    Err(err) => {
+                        eprintln!("⚠️  resolution status of {} unavailable: {}", market_id, err);
+                        continue;
+                    }
+                },
+            };
+            let Some(change) = self.resolutions.observe(&market) else { continue };
+            match (change.from, change.to) {
+                (_, ResolutionStatus::Disputed) => eprintln!("⚠️  {} outcome disputed; its positions settle only once the ruling is final", market_id),
+                (Some(from), to) => println!("resolution: {} {:?} -> {:?}", market_id, from, to),
+                (None, _) => {}
+            }
+        }
+
+        let held: HashMap<TokenId, (f64, f64)> = self.wallet.positions.iter()
+            .map(|(token, p)| (token.clone(), (p.size, p.entry_price)))
+            .collect();
+        let now = now();
+        let settled = self.resolutions.settle_all(&mut self.wallet, now);
+        for (market_id, pnl) in &settled {
+            let mut group = None;
+            for (token_id, payout) in self.resolutions.payouts(market_id) {
+                let Some(&(size, entry_price)) = held.get(token_id) else { continue };
+                let id = self.journal.record(JournalEntry {
+                    id: 0,
+                    timestamp: now,
+                    market_id: market_id.clone(),
+                    token_id: token_id.clone(),
+                    side: Side::Sell,
+                    size,
+                    price: *payout,
+                    fee: 0.0,
+                    realized_pnl: Some((payout - entry_price) * size),
+                    tags: vec![SETTLEMENT_TAG.to_string()],
+                    note: None,
+                    mae: None,
+                    instance_id: None,
+                    group,
+                });
+                group.get_or_insert(id);
+                if let Some(entry) = self.journal.entries.last_mut() {
+                    entry.group = group;
+                }
+            }
+            println!("settled {}: pnl {:+.4}", market_id, pnl);
+        }
+        if !settled.is_empty() {
+            self.persist();
+        }
+    }
+
+    /// Turn complete YES+NO sets back into USDC and journal the legs they close
+    fn recycle_sets(&mut self) {
+        let Some(recycler) = &mut self.recycler else { return };
+        let markets: Vec<Market> = self.markets.values().cloned().collect();
+        let profile = &self.profile;
+        let report = recycler.recycle(&mut self.wallet, &markets, &self.books, now(), |market| {
+            ExecutionEngine::new(fee_schedule(profile, market))
+        });
+        for recycled in &report.recycled {
+            let mut group = None;
+            for leg in &recycled.legs {
+                let id = self.journal.record(JournalEntry {
+                    id: 0,
+                    timestamp: now(),
+                    market_id: recycled.set.market_id.clone(),
+                    token_id: leg.token_id.clone(),
+                    side: Side::Sell,
+                    size: recycled.set.size,
+                    price: leg.price,
+                    fee: leg.fee,
+                    realized_pnl: Some(leg.realized_pnl),
+                    tags: vec![recycled.method.tag().to_string()],
+                    note: None,
+                    mae: None,
+                    instance_id: None,
+                    group,
+                });
+                group.get_or_insert(id);
+                if let Some(entry) = self.journal.entries.last_mut() {
+                    entry.group = group;
+                }
+            }
+            println!(
+                "complete sets: {:?} {:.2} in {} for {:.2} USDC (pnl {:+.4})",
+                recycled.method, recycled.set.size, recycled.set.market_id, recycled.proceeds, recycled.realized_pnl()
+            );
+        }
+        for (market_id, err) in &report.failures {
+            eprintln!("⚠️  complete sets: merge failed for {}: {}", market_id, err);
+        }
+        if !report.recycled.is_empty() {
+            println!("complete sets: recycled {:.2} USDC this scan, {:.2} total", report.capital_recycled(), recycler.total_recycled);
+            self.persist();
+        }
+    }
+
+    /// Execute mode: sell the planned trims of one-sided inventory into the bids. Fills are
+    /// booked and journaled like any other, so a restart replays them.
+    fn rebalance_inventory(&mut self) {
+        if self.profile.rebalance.mode != RebalanceMode::Execute {
+            return;
+        }
+        let markets: Vec<Market> = self.markets.values().cloned().collect();
+        let rebalancer = Rebalancer::new(self.profile.rebalance.clone());
+        let profile = &self.profile;
+        let plan = rebalancer.plan(&self.wallet, &markets, &self.books, |market| fee_schedule(profile, market));
+        let requests = rebalancer.orders(&plan);
+        if requests.is_empty() {
+            return;
+        }
+        println!("rebalance: skew {:+.2}, selling {} trims", plan.skew_before, requests.len());
+        for request in &requests {
+            let (Some(book), Some((market_id, _))) = (self.books.get(&request.token_id), self.outcomes.get(&request.token_id)) else {
+                continue;
+            };
+            if let Some(market) = self.markets.get(market_id) {
+                self.gateway.observe(book, &fee_schedule(&self.profile, market));
+            }
+            if let Err(err) = self.submit_sell(request) {
+                eprintln!("⚠️  rebalance: selling {} failed: {}", request.token_id, err);
+            }
+        }
+        for fill in self.take_fills() {
+            let market_id = self.outcomes.get(&fill.token_id).map(|(m, _)| m.clone()).unwrap_or_default();
+            if let Some(market) = self.markets.get(&market_id) {
+                let schedule = fee_schedule(&self.profile, market);
+                self.reconcile_fees(std::slice::from_ref(&fill), schedule.as_ref());
+            }
+            let id = self.apply_fill(&market_id, &fill, EntryReason::Manual, None);
+            self.journal.tag(id, REBALANCE_TAG);
+        }
+        self.orders.sync_locks(&mut self.wallet);
+        self.persist();
+    }
+
+    /// Sell held YES+NO sets back into the books where that beats holding to resolution
+    fn exit_sets(&mut self) {
+        if !self.profile.exit.enabled {
+            return;
+        }
+        let config = &self.profile.exit;
+        let mut exits: Vec<(EarlyExitStrategy, ExitDecision)> = self.markets.values()
+            .filter_map(|market| {
+                let (yes, no) = (market.clob_token_ids.first()?, market.clob_token_ids.get(1)?);
+                let strategy = EarlyExitStrategy::new(fee_schedule(&self.profile, market), config.min_gain, config.annual_hold_rate);
+                let decision = strategy.evaluate(market, &self.wallet, self.books.get(yes)?, self.books.get(no)?, now())?;
+                Some((strategy, decision))
+            })
+            .collect();
+        if exits.is_empty() {
+            return;
+        }
+        exits.sort_by(|a, b| a.1.market_id.cmp(&b.1.market_id));
+        for (strategy, decision) in &exits {
+            println!(
+                "early exit: selling {:.2} sets of {} for {:+.4} USDC over holding",
+                decision.size, decision.market_id, decision.gain(),
+            );
+            if let Err(err) = self.exit_set(strategy, decision) {
+                eprintln!("⚠️  early exit of {} failed: {}", decision.market_id, err);
+            }
+        }
+        self.orders.sync_locks(&mut self.wallet);
+        self.persist();
+        self.publish_view();
+    }
+
+    /// Sell both legs of one set and journal the fills as one group. A leg the venue
+    /// refuses stops the exit; whatever already filled is still booked.
+    fn exit_set(&mut self, strategy: &EarlyExitStrategy, decision: &ExitDecision) -> Result<(), String> {
+        let mut result = Ok(());
+        for request in strategy.orders(decision) {
+            if let Some(book) = self.books.get(&request.token_id) {
+                self.gateway.observe(book, &strategy.fees);
+            }
+            if let Err(err) = self.submit_sell(&request) {
+                result = Err(format!("selling {}: {}", request.token_id, err));
+                break;
+            }
+        }
+        let mut group = None;
+        for fill in self.take_fills() {
+            self.reconcile_fees(std::slice::from_ref(&fill), strategy.fees.as_ref());
+            let id = self.apply_fill(&decision.market_id, &fill, EntryReason::Manual, group);
+            group.get_or_insert(id);
+            self.journal.tag(id, EXIT_TAG);
+        }
+        result
+    }
+
+    /// Hold priority on every token with a resting order and hand the hot set to the
+    /// poller; returns the hot tokens when they changed since the last call
+    fn refresh_priorities(&mut self) -> Option<Vec<String>> {
+        let now = now_ms();
+        let resting: HashSet<&TokenId> = self.orders.open.values().map(|o| &o.token_id).collect();
+        for lease in self.priorities.leases(now).iter().filter(|l| l.owner == RESTING_OWNER) {
+            if !resting.contains(&lease.token_id) {
+                self.priorities.release(RESTING_OWNER, &lease.token_id);
+            }
+        }
+        for token_id in resting {
+            self.priorities.request(RESTING_OWNER, token_id, now);
+        }
+
+        let hot: Vec<String> = self.priorities.hot_tokens(now).iter().map(|t| t.to_string()).collect();
+        if hot == self.hot_tokens {
+            return None;
+        }
+        let markets: Vec<Market> = self.markets.values().cloned().collect();
+        self.poller.set_hot(self.priorities.hot_markets(&markets, now));
+        self.poller.allocate(&markets, self.profile.bot.min_spread);
+        self.hot_tokens = hot.clone();
+        Some(hot)
+    }
+
+    /// Warn about markets grown past the concentration alert level, counting resting buys
+    /// and the payouts of undisputed proposals
+    fn check_concentration(&mut self) {
+        let markets: Vec<Market> = self.markets.values().cloned().collect();
+        let pending = self.resolutions.outlook(&self.wallet).incoming;
+        let rows = concentration_report(&self.wallet, &self.orders, &markets, &self.marks(), pending, self.concentration.alert_share);
+        self.concentration.check(&rows);
+    }
+
+    /// Feed realized plus unrealized PnL (at book mids) to the circuit breaker; a trip pauses
+    /// entries until an operator resumes
+    fn check_breaker(&mut self) {
+        let marks = self.marks();
+        let realized: f64 = self.journal.entries.iter().filter_map(|e| e.realized_pnl).sum();
+        let unrealized: f64 = self.wallet.positions.values()
+            .filter_map(|p| Some(p.unrealized_pnl(*marks.get(&p.token_id)?)))
+            .sum();
+        if let Some(trip) = self.breaker.observe(now(), realized + unrealized, &self.journal, &self.control) {
+            self.risk.alert_breaker(&trip);
+        }
+    }
+
+    /// Keep the mid history across restarts, when a path is configured
+    fn save_history(&self) {
+        let Some(path) = &self.profile.sparklines.path else { return };
+        if let Err(err) = self.history.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).save(path) {
+            eprintln!("⚠️  saving price history to {} failed: {}", path.display(), err);
+        }
+    }
+
+    /// Record cash, locked funds, positions and resting orders for `diff`
+    fn save_snapshot(&self) {
+        let snapshot = PortfolioSnapshot::capture(&self.wallet, &self.orders, &self.marks(), now());
+        if let Err(err) = snapshot.append(Path::new(SNAPSHOTS_PATH)) {
+            eprintln!("⚠️  {}: {}", SNAPSHOTS_PATH, err);
+        }
+    }
+
+    /// Compare the fees charged on taker fills with what the fee model predicted
+    fn reconcile_fees(&mut self, fills: &[Fill], schedule: &dyn FeeSchedule) {
+        for fill in fills {
+            self.fee_drift.record(schedule, fill, false, fill.token_id.as_str());
+        }
+        self.fee_drift.check();
+    }
+
+    /// Refresh the positions and orders the control API serves
+    fn publish_view(&self) {
+        if let Some(view) = &self.view {
+            view.update_positions(&self.wallet);
+            view.update_orders(&self.orders);
+        }
+    }
+
+    /// Move cash and inventory for one fill and journal it; returns the journal id
+    fn apply_fill(&mut self, market_id: &MarketId, fill: &Fill, reason: EntryReason, group: Option<u64>) -> u64 {
+        let (realized_pnl, mae) = self.book_fill(fill, reason);
+        let id = self.journal.record(JournalEntry {
+            id: 0,
+            timestamp: fill.timestamp / 1000,
+            market_id: market_id.clone(),
+            token_id: fill.token_id.clone(),
+            side: fill.side,
+            size: fill.size,
+            price: fill.price,
+            fee: fill.fee,
+            realized_pnl,
+            tags: vec![reason.tag().to_string()],
+            note: None,
+            mae,
+            instance_id: None,
+            group,
+        });
+        if let Some(entry) = self.journal.entries.last_mut() {
+            entry.group = Some(group.unwrap_or(id));
+        }
+        id
+    }
+
+    /// Settle a fill against the wallet and announce it; returns its realized PnL and MAE (sells)
+    fn book_fill(&mut self, fill: &Fill, reason: EntryReason) -> (Option<f64>, Option<f64>) {
+        let now = fill.timestamp / 1000;
+        let reference = fill.order_id.as_deref().unwrap_or(fill.token_id.as_str());
+        let (realized_pnl, mae) = match fill.side {
+            Side::Buy => {
+                // The venue already executed it: record the cash even if it overdraws
+                let cost = Usdc(fill.price * fill.size + fill.fee);
+                if !self.wallet.deduct(cost, "buy fill", Some(reference)) {
+                    eprintln!("⚠️  fill {} cost more than the available cash", reference);
+                    self.wallet.debit_unchecked(cost, "buy fill", Some(reference));
+                }
+                self.wallet.add_to_position(&fill.token_id, Side::Buy, fill.size, fill.price, fill.fee, now, reason);
+                (None, None)
+            }
+            Side::Sell => {
+                self.wallet.credit(Usdc(fill.price * fill.size - fill.fee), "sell fill", Some(reference));
+                self.wallet.mark_excursion(&fill.token_id, fill.price);
+                let mae = self.wallet.positions.get(fill.token_id.as_str()).map(|p| p.max_adverse_excursion);
+                let closed = self.wallet.sell_from_position(&fill.token_id, fill.size, fill.price, fill.fee, now);
+                (Some(closed.iter().map(|l| l.gain()).sum()), mae)
+            }
+        };
+        self.wallet.record_fee(Usdc(fill.fee), Some(reference));
+        self.bus.publish_fill(fill.clone());
+        (realized_pnl, mae)
+    }
+
+    /// Record the token's book in the book store, and save it to storage if
+    /// `record_books_ms` has passed since the last one
+    fn save_book(&mut self, token_id: &TokenId) {
+        #[cfg(feature = "bookstore")]
+        if let (Some(writer), Some(book), Some((market_id, _))) = (&mut self.book_writer, self.books.get(token_id), self.outcomes.get(token_id))
+            && let Err(err) = writer.record(market_id, book)
+        {
+            eprintln!("⚠️  book store write failed for {}: {}", token_id, err);
+        }
+        let (Some(interval), Some(book)) = (self.profile.bot.record_books_ms, self.books.get(token_id)) else { return };
+        if self.books_saved.get(token_id).is_some_and(|&saved| book.timestamp < saved + interval) {
+            return;
+        }
+        match self.storage.append_book(book) {
+            Ok(()) => {
+                self.books_saved.insert(token_id.clone(), book.timestamp);
+            }
+            Err(err) => eprintln!("⚠️  book write failed for {}: {}", token_id, err),
+        }
+    }
+
+    /// Seal the book store's open segments so they survive a crash
+    fn flush_books(&mut self) {
+        #[cfg(feature = "bookstore")]
+        if let Some(writer) = &mut self.book_writer
+            && let Err(err) = writer.flush()
+        {
+            eprintln!("⚠️  book store flush failed: {}", err);
+        }
+    }
+
+    /// Write journal entries recorded since the last call
+    fn persist(&mut self) {
+        match self.journal.append_to(self.storage.as_mut(), self.recorded) {
+            Ok(()) => self.recorded = self.journal.entries.len(),
+            Err(err) => eprintln!("⚠️  journal write failed: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "network")]
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+#[cfg(feature = "network")]
+/// One line per market that qualified for maker rewards at least once that day
+fn print_compliance(rows: &[ComplianceRow]) {
+    for row in rows.iter().filter(|r| r.qualified_pct > 0.0) {
+        println!(
+            "rewards {} {}: {:.1}% qualified, {:.1}% two-sided, avg score {:.2} ({} samples)",
+            row.date, row.market_id, row.qualified_pct, row.two_sided_pct, row.avg_score, row.samples,
+        );
+    }
+}
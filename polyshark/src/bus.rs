@@ -0,0 +1,179 @@
+use crate::execution::MultiLegExecutionReport;
+use crate::types::{ArbitrageSignal, CategoricalSignal, CrossMarketSignal, MarketId, Side, TokenId, Trade};
+use crate::websocket::MarketEvent;
+#[cfg(feature = "network")]
+use crate::websocket::ShardedMarketStream;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+#[cfg(feature = "network")]
+use tokio::task::JoinHandle;
+
+/// Messages buffered per topic before slow subscribers start lagging
+pub const DEFAULT_CAPACITY: usize = 4096;
+
+/// Book snapshot or level change (never a trade print)
+pub type BookUpdate = MarketEvent;
+
+/// Last-sale print
+pub type TradePrint = Trade;
+
+/// Detector output
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "signal", rename_all = "snake_case")]
+pub enum Signal {
+    Binary(ArbitrageSignal),
+    Categorical(CategoricalSignal),
+    CrossMarket(CrossMarketSignal),
+}
+
+/// Order state change reported by the order manager
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderStatus {
+    Placed,
+    Cancelled,
+    Rejected(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct OrderUpdate {
+    pub order_id: String,
+    pub token_id: TokenId,
+    pub status: OrderStatus,
+    pub timestamp: u64,  // ms
+}
+
+/// Execution against one of our orders (or a simulated one)
+#[derive(Debug, Clone)]
+pub struct Fill {
+    pub order_id: Option<String>,  // None for simulated taker fills
+    pub token_id: TokenId,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+    pub fee: f64,
+    pub timestamp: u64,  // ms
+}
+
+/// Anything the risk side wants the rest of the bot to know
+#[derive(Debug, Clone)]
+pub enum RiskEvent {
+    Paused,
+    Resumed,
+    FlattenRequested,
+    LimitBreached { limit: String, value: f64, max: f64 },
+    BalanceDrift { amount: f64 },
+    NakedSell { token_id: TokenId, requested: f64, held: f64 },
+    CircuitBreaker { drop: f64, trade_ids: Vec<u64> },  // Trading paused until manual resume
+    BookDiverged { token_id: TokenId, reason: String },  // Local book out of step; resync pending
+    FeedQuarantined { token_id: TokenId, reason: String },  // Insane update dropped; token not traded until resynced
+    FeeDrift { predicted: f64, charged: f64, fills: usize },  // Charged fees systematically off the fee model
+    Concentration { market_id: MarketId, share: f64, alert_share: f64 },  // One market is too large a share of equity
+    FastMove { market_id: MarketId, from: f64, to: f64, window_ms: u64 },  // Mid moved too fast to trust; market paused
+    FastMoveCleared { market_id: MarketId, paused_ms: u64 },  // Mid settled again; market resumed
+    MarketBackoff { market_id: MarketId, failures: u32, until: u64, reason: String },  // Repeated placement failures; market not signalled until `until` (s)
+}
+
+/// Next item on a book subscription
+#[derive(Debug, Clone)]
+pub enum BookFeed {
+    Update(BookUpdate),
+    Lagged(u64),  // This many updates were dropped: every book built from the topic is suspect until resynced
+}
+
+/// Subscription to the book topic. Book updates are deltas, so unlike the other topics a
+/// slow subscriber can't just skip what it missed; lag is surfaced for a resync.
+#[derive(Debug)]
+pub struct BookReceiver(broadcast::Receiver<BookUpdate>);
+
+impl BookReceiver {
+    /// `None` once every publisher is gone
+    pub async fn recv(&mut self) -> Option<BookFeed> {
+        match self.0.recv().await {
+            Ok(update) => Some(BookFeed::Update(update)),
+            Err(RecvError::Lagged(skipped)) => Some(BookFeed::Lagged(skipped)),
+            Err(RecvError::Closed) => None,
+        }
+    }
+}
+
+/// Typed channels between subsystems. Cloning is cheap; every clone
+/// publishes to the same topics, and each subscriber sees every message.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    pub books: broadcast::Sender<BookUpdate>,
+    pub trades: broadcast::Sender<TradePrint>,
+    pub signals: broadcast::Sender<Signal>,
+    pub orders: broadcast::Sender<OrderUpdate>,
+    pub fills: broadcast::Sender<Fill>,
+    pub risk: broadcast::Sender<RiskEvent>,
+    pub executions: broadcast::Sender<MultiLegExecutionReport>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            books: broadcast::channel(capacity).0,
+            trades: broadcast::channel(capacity).0,
+            signals: broadcast::channel(capacity).0,
+            orders: broadcast::channel(capacity).0,
+            fills: broadcast::channel(capacity).0,
+            risk: broadcast::channel(capacity).0,
+            executions: broadcast::channel(capacity).0,
+        }
+    }
+
+    /// Subscribe to book updates; see `BookReceiver`
+    pub fn subscribe_books(&self) -> BookReceiver {
+        BookReceiver(self.books.subscribe())
+    }
+
+    /// Route a websocket event to the book or trade topic.
+    /// Publishing with no subscribers is not an error; the message is dropped.
+    pub fn publish_market(&self, event: MarketEvent) {
+        match event {
+            MarketEvent::LastTrade(trade) => {
+                let _ = self.trades.send(trade);
+            }
+            other => {
+                let _ = self.books.send(other);
+            }
+        }
+    }
+
+    pub fn publish_signal(&self, signal: Signal) {
+        let _ = self.signals.send(signal);
+    }
+
+    pub fn publish_order(&self, update: OrderUpdate) {
+        let _ = self.orders.send(update);
+    }
+
+    pub fn publish_fill(&self, fill: Fill) {
+        let _ = self.fills.send(fill);
+    }
+
+    pub fn publish_risk(&self, event: RiskEvent) {
+        let _ = self.risk.send(event);
+    }
+
+    pub fn publish_execution(&self, report: MultiLegExecutionReport) {
+        let _ = self.executions.send(report);
+    }
+}
+
+#[cfg(feature = "network")]
+/// Market data subsystem: pump the sharded websocket stream onto the bus
+pub fn spawn_market_feed(mut stream: ShardedMarketStream, bus: EventBus) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        while let Some(event) = stream.next().await {
+            bus.publish_market(event);
+        }
+    })
+}
@@ -0,0 +1,107 @@
+use crate::orders::OrderManager;
+use crate::resolution::SettlementOutlook;
+use crate::types::Side;
+use crate::wallet::Wallet;
+use serde::Serialize;
+use std::fmt;
+
+/// Free capital before and after a prospective trade
+#[derive(Debug, Clone, Serialize)]
+pub struct CapitalForecast {
+    pub cash: f64,
+    pub locked_in_orders: f64,     // Resting buy orders
+    pub pending_settlements: f64,  // Matched but not yet settled on chain
+    pub reserve: f64,
+    pub trade_cost: f64,
+    pub free_before: f64,
+    pub free_after: f64,
+    pub incoming: f64,             // Undisputed proposed payouts, not yet credited
+    pub disputed: f64,             // Payouts held up by disputes: left out entirely
+}
+
+impl CapitalForecast {
+    /// The trade leaves at least the reserve untouched
+    pub fn accepted(&self) -> bool {
+        self.free_after >= 0.0
+    }
+
+    /// Free capital once undisputed proposals finalize and pay out
+    pub fn free_after_settlement(&self) -> f64 {
+        self.free_after + self.incoming
+    }
+}
+
+impl fmt::Display for CapitalForecast {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cash {:.2} - locked {:.2} - settling {:.2} - reserve {:.2} = free {:.2}; after trade {:.2} -> {:.2} ({})",
+            self.cash,
+            self.locked_in_orders,
+            self.pending_settlements,
+            self.reserve,
+            self.free_before,
+            self.trade_cost,
+            self.free_after,
+            if self.accepted() { "ok" } else { "rejected" }
+        )?;
+        if self.incoming > 0.0 || self.disputed > 0.0 {
+            write!(f, "; +{:.2} settling ({:.2} disputed, not counted)", self.incoming, self.disputed)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks signals against the capital that is actually spendable
+#[derive(Debug, Clone)]
+pub struct CapitalForecaster {
+    pub min_reserve: f64,  // USDC that must always stay free
+}
+
+impl CapitalForecaster {
+    pub fn new(min_reserve: f64) -> Self {
+        Self { min_reserve }
+    }
+
+    /// Forecast free capital if a trade costing `trade_cost` were executed now. Payouts
+    /// from resolving markets are reported but never spent before they are credited.
+    pub fn forecast(
+        &self,
+        wallet: &Wallet,
+        orders: &OrderManager,
+        pending_settlements: f64,
+        outlook: &SettlementOutlook,
+        trade_cost: f64,
+    ) -> CapitalForecast {
+        let locked_in_orders: f64 = orders.open.values()
+            .filter(|o| o.side == Side::Buy)
+            .map(|o| o.remaining() * o.price)
+            .sum();
+        let free_before = wallet.usdc.value() - locked_in_orders - pending_settlements - self.min_reserve;
+
+        CapitalForecast {
+            cash: wallet.usdc.value(),
+            locked_in_orders,
+            pending_settlements,
+            reserve: self.min_reserve,
+            trade_cost,
+            free_before,
+            free_after: free_before - trade_cost,
+            incoming: outlook.incoming,
+            disputed: outlook.disputed,
+        }
+    }
+
+    /// Forecast; `Err` carries the forecast of a rejected trade
+    pub fn check(
+        &self,
+        wallet: &Wallet,
+        orders: &OrderManager,
+        pending_settlements: f64,
+        outlook: &SettlementOutlook,
+        trade_cost: f64,
+    ) -> Result<CapitalForecast, CapitalForecast> {
+        let forecast = self.forecast(wallet, orders, pending_settlements, outlook, trade_cost);
+        if forecast.accepted() { Ok(forecast) } else { Err(forecast) }
+    }
+}
@@ -0,0 +1,234 @@
+use crate::legs::PairOutcome;
+use crate::types::{ArbitrageSignal, MarketId};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+pub const MISSED_LOG_PATH: &str = "missed.jsonl";
+
+/// Read back a missed-signal log
+pub fn load_missed(path: &Path) -> std::io::Result<Vec<CaptureRecord>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut records = Vec::new();
+    for line in reader.lines() {
+        if let Ok(record) = serde_json::from_str::<CaptureRecord>(&line?) {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+/// Extra cost per unit over the signal's prices still counted as "at expected prices"
+const PRICE_TOLERANCE: f64 = 0.001;
+
+/// What became of a signal that passed `should_trade`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureOutcome {
+    Captured,  // Full fill at (or better than) the signal's prices
+    Slipped,   // Full fill, but paid more than expected
+    Partial,   // Only part of the size filled
+    Missed,    // Nothing filled
+}
+
+/// One tracked signal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureRecord {
+    pub timestamp: u64,
+    pub market_id: MarketId,
+    pub edge: f64,
+    #[serde(default)]
+    pub tier: Option<String>,      // Liquidity tier of the market's books at the signal
+    pub expected_size: f64,
+    pub filled_size: f64,
+    pub expected_cost: f64,        // Per unit pair, from the signal
+    pub fill_cost: Option<f64>,    // Per unit pair actually paid
+    pub outcome: CaptureOutcome,
+    pub reason: Option<String>,    // Why it was not captured
+}
+
+impl CaptureRecord {
+    /// Share of the opportunity captured: 1 for full fills, the fill ratio for partials
+    pub fn captured_fraction(&self) -> f64 {
+        match self.outcome {
+            CaptureOutcome::Captured | CaptureOutcome::Slipped => 1.0,
+            CaptureOutcome::Partial if self.expected_size > 0.0 => (self.filled_size / self.expected_size).clamp(0.0, 1.0),
+            _ => 0.0,
+        }
+    }
+}
+
+/// Counts behind a capture rate
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CaptureStats {
+    pub signals: u32,
+    pub captured: u32,
+    pub slipped: u32,
+    pub partial: u32,
+    pub missed: u32,
+    captured_weight: f64,
+}
+
+impl CaptureStats {
+    fn add(&mut self, record: &CaptureRecord) {
+        self.signals += 1;
+        match record.outcome {
+            CaptureOutcome::Captured => self.captured += 1,
+            CaptureOutcome::Slipped => self.slipped += 1,
+            CaptureOutcome::Partial => self.partial += 1,
+            CaptureOutcome::Missed => self.missed += 1,
+        }
+        self.captured_weight += record.captured_fraction();
+    }
+
+    /// Fraction of opportunity captured (partials count by fill ratio)
+    pub fn rate(&self) -> f64 {
+        if self.signals == 0 { 0.0 } else { self.captured_weight / self.signals as f64 }
+    }
+}
+
+/// Tracks every tradable signal through to its fills and logs the ones we missed
+#[derive(Debug, Clone, Default)]
+pub struct CaptureTracker {
+    pub records: Vec<CaptureRecord>,
+    pub missed_log: Option<PathBuf>,  // JSON lines of everything not fully captured at expected prices
+}
+
+impl CaptureTracker {
+    pub fn new(missed_log: Option<PathBuf>) -> Self {
+        Self { records: Vec::new(), missed_log }
+    }
+
+    /// Record fills for a signal; `fill_cost` is the average paid per YES+NO pair
+    pub fn record_fill(&mut self, signal: &ArbitrageSignal, tier: Option<String>, expected_size: f64, filled_size: f64, fill_cost: Option<f64>, now: u64) -> CaptureOutcome {
+        let expected_cost = signal.yes_price + signal.no_price;
+        let (outcome, reason) = if filled_size <= 0.0 {
+            (CaptureOutcome::Missed, Some("nothing filled".to_string()))
+        } else if filled_size + 1e-9 < expected_size {
+            (CaptureOutcome::Partial, Some(format!("filled {:.2} of {:.2}", filled_size, expected_size)))
+        } else if fill_cost.is_some_and(|c| c > expected_cost + PRICE_TOLERANCE) {
+            let cost = fill_cost.unwrap_or_default();
+            (CaptureOutcome::Slipped, Some(format!("paid {:.4} per pair, expected {:.4}", cost, expected_cost)))
+        } else {
+            (CaptureOutcome::Captured, None)
+        };
+        self.push(CaptureRecord {
+            timestamp: now,
+            market_id: signal.market_id.clone(),
+            edge: signal.edge,
+            tier,
+            expected_size,
+            filled_size,
+            expected_cost,
+            fill_cost,
+            outcome,
+            reason,
+        });
+        outcome
+    }
+
+    /// Record a signal that never reached the exchange (risk veto, stale book, queue overflow...)
+    pub fn record_miss(&mut self, signal: &ArbitrageSignal, tier: Option<String>, expected_size: f64, reason: &str, now: u64) {
+        self.push(CaptureRecord {
+            timestamp: now,
+            market_id: signal.market_id.clone(),
+            edge: signal.edge,
+            tier,
+            expected_size,
+            filled_size: 0.0,
+            expected_cost: signal.yes_price + signal.no_price,
+            fill_cost: None,
+            outcome: CaptureOutcome::Missed,
+            reason: Some(reason.to_string()),
+        });
+    }
+
+    /// Record the result of sending both legs; `filled_size` is the pairs both legs filled
+    /// and `fill_cost` what they cost on average
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_pair(&mut self, signal: &ArbitrageSignal, tier: Option<String>, size: f64, outcome: &PairOutcome, filled_size: f64, fill_cost: Option<f64>, now: u64) -> CaptureOutcome {
+        match outcome {
+            PairOutcome::Filled { .. } => self.record_fill(signal, tier, size, filled_size, fill_cost, now),
+            PairOutcome::Missed { reason } => {
+                self.record_miss(signal, tier, size, &reason.to_string(), now);
+                CaptureOutcome::Missed
+            }
+            PairOutcome::Exposed { reason, .. } => {
+                self.record_partial(signal, tier, size, filled_size, format!("second leg failed: {}", reason), now)
+            }
+            PairOutcome::NeedsHedge { first, second } => match (first, second) {
+                (Ok(_), Ok(_)) => self.record_fill(signal, tier, size, filled_size, fill_cost, now),
+                (Err(a), Err(_)) => {
+                    self.record_miss(signal, tier, size, &a.to_string(), now);
+                    CaptureOutcome::Missed
+                }
+                (Err(e), Ok(_)) | (Ok(_), Err(e)) => self.record_partial(signal, tier, size, filled_size, format!("one IOC leg failed: {}", e), now),
+            },
+        }
+    }
+
+    /// Capture stats per market, worst rate first
+    pub fn by_market(&self) -> Vec<(MarketId, CaptureStats)> {
+        let mut markets: BTreeMap<&MarketId, CaptureStats> = BTreeMap::new();
+        for r in &self.records {
+            markets.entry(&r.market_id).or_default().add(r);
+        }
+        let mut rows: Vec<(MarketId, CaptureStats)> = markets.into_iter().map(|(id, s)| (id.clone(), s)).collect();
+        rows.sort_by(|a, b| a.1.rate().total_cmp(&b.1.rate()));
+        rows
+    }
+
+    /// Capture stats per UTC hour of day
+    pub fn by_hour(&self) -> [CaptureStats; 24] {
+        let mut hours = [CaptureStats::default(); 24];
+        for r in &self.records {
+            hours[((r.timestamp % 86_400) / 3_600) as usize].add(r);
+        }
+        hours
+    }
+
+    pub fn overall(&self) -> CaptureStats {
+        let mut stats = CaptureStats::default();
+        for r in &self.records {
+            stats.add(r);
+        }
+        stats
+    }
+
+    /// Everything not captured in full at expected prices
+    pub fn missed(&self) -> impl Iterator<Item = &CaptureRecord> {
+        self.records.iter().filter(|r| r.outcome != CaptureOutcome::Captured)
+    }
+
+    fn record_partial(&mut self, signal: &ArbitrageSignal, tier: Option<String>, size: f64, filled_size: f64, reason: String, now: u64) -> CaptureOutcome {
+        // A leg failed: only what both legs filled is a captured pair, the rest is exposure
+        self.push(CaptureRecord {
+            timestamp: now,
+            market_id: signal.market_id.clone(),
+            edge: signal.edge,
+            tier,
+            expected_size: size,
+            filled_size,
+            expected_cost: signal.yes_price + signal.no_price,
+            fill_cost: None,
+            outcome: CaptureOutcome::Partial,
+            reason: Some(reason),
+        });
+        CaptureOutcome::Partial
+    }
+
+    fn push(&mut self, record: CaptureRecord) {
+        if record.outcome != CaptureOutcome::Captured
+            && let Some(path) = &self.missed_log
+        {
+            let line = serde_json::to_string(&record).unwrap_or_default();
+            let written = OpenOptions::new().create(true).append(true).open(path).and_then(|mut f| writeln!(f, "{}", line));
+            if let Err(err) = written {
+                eprintln!("warning: could not log missed opportunity: {}", err);
+            }
+        }
+        self.records.push(record);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::arb::SignalCooldowns;
+use crate::capture::{CaptureRecord, CaptureTracker};
+use crate::lifecycle::{LifecycleState, MarketLifecycle};
+use crate::pruning::{PrunerState, StalePruner};
+use crate::storage::StorageResult;
+use crate::volatility::VolatilityTable;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where and how often per-market detector state is saved
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckpointConfig {
+    #[serde(default = "default_checkpoint_path")]
+    pub path: PathBuf,
+    #[serde(default = "default_save_every_secs")]
+    pub save_every_secs: u64,
+    #[serde(default = "default_max_age_hours")]
+    pub max_age_hours: u64,         // Older checkpoints are ignored: the markets have moved on
+    #[serde(default = "default_max_capture_records")]
+    pub max_capture_records: usize, // Most recent capture records kept in the file
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            path: default_checkpoint_path(),
+            save_every_secs: default_save_every_secs(),
+            max_age_hours: default_max_age_hours(),
+            max_capture_records: default_max_capture_records(),
+        }
+    }
+}
+
+fn default_checkpoint_path() -> PathBuf {
+    PathBuf::from("detector_state.json")
+}
+
+fn default_save_every_secs() -> u64 {
+    60
+}
+
+fn default_max_age_hours() -> u64 {
+    24
+}
+
+fn default_max_capture_records() -> usize {
+    10_000
+}
+
+/// Everything the detector learned about each market, so a restart resumes warmups,
+/// keeps pruned markets pruned and doesn't re-fire signals it had just rejected
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DetectorCheckpoint {
+    pub saved_at: u64,  // Seconds
+    pub lifecycle: LifecycleState,
+    pub pruner: PrunerState,
+    pub cooldowns: SignalCooldowns,
+    pub capture: Vec<CaptureRecord>,
+    pub volatility: Option<VolatilityTable>,  // Per-market extra edge
+}
+
+impl DetectorCheckpoint {
+    pub fn capture(
+        lifecycle: &MarketLifecycle,
+        pruner: &StalePruner,
+        cooldowns: &SignalCooldowns,
+        capture: &CaptureTracker,
+        volatility: Option<&VolatilityTable>,
+        max_capture_records: usize,
+        now: u64,
+    ) -> Self {
+        let skip = capture.records.len().saturating_sub(max_capture_records);
+        Self {
+            saved_at: now,
+            lifecycle: lifecycle.export_state(),
+            pruner: pruner.export_state(),
+            cooldowns: cooldowns.clone(),
+            capture: capture.records[skip..].to_vec(),
+            volatility: volatility.cloned(),
+        }
+    }
+
+    /// Load the checkpoint at `config.path`; `None` if there is none or it is too old
+    pub fn load(config: &CheckpointConfig, now: u64) -> StorageResult<Option<Self>> {
+        let text = match fs::read_to_string(&config.path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let checkpoint: Self = serde_json::from_str(&text)?;
+        let fresh = now.saturating_sub(checkpoint.saved_at) <= config.max_age_hours * 3600;
+        Ok(fresh.then_some(checkpoint))
+    }
+
+    /// Replace the file atomically so a crash mid-save leaves the previous checkpoint
+    pub fn save(&self, path: &Path) -> StorageResult<()> {
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string(self)?)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// Put the saved state back into freshly built components. Expired cooldowns are
+    /// dropped; the volatility table is returned for the detector's `with_volatility`.
+    pub fn restore(
+        self,
+        lifecycle: &mut MarketLifecycle,
+        pruner: &mut StalePruner,
+        cooldowns: &mut SignalCooldowns,
+        capture: &mut CaptureTracker,
+        now: u64,
+    ) -> Option<VolatilityTable> {
+        lifecycle.restore_state(self.lifecycle);
+        pruner.restore_state(self.pruner);
+        *cooldowns = self.cooldowns;
+        cooldowns.prune(now);
+        capture.records.splice(0..0, self.capture);
+        self.volatility
+    }
+}
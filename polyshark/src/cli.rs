@@ -0,0 +1,415 @@
+use crate::config::LIVE_ACK_FLAG;
+use crate::depth::DepthFormat;
+use crate::journal::JournalFilter;
+use crate::lots::LotMethod;
+use crate::matcher::PairStatus;
+use crate::output::OutputFormat;
+use crate::types::{parse_iso8601, Price, Side, Size, TokenId};
+
+pub const USAGE: &str = "\
+usage: polyshark [--config <file>] [--profile <name>] [--i-understand-live-trading] [command]
+
+commands:
+  run (default) [--headless] [--health-addr <host:port>] [--markets <file>]
+                                    start the trading loop; headless serves /healthz and /readyz;
+                                    --markets watches markets from a .json/.csv file instead of Gamma
+  buy  --market <slug> --outcome <name> --size <n> --limit <p> [--dry-run] [--tag <t>[,<t>..]] [--note <text>]
+  sell --market <slug> --outcome <name> --size <n> --limit <p> [--dry-run] [--tag <t>[,<t>..]] [--note <text>]
+  compare <baseline.json> <candidate.json> [--tolerance <usdc>] [--expect-identical]
+                                    fail on regression (or on any difference with --expect-identical)
+  flatten --max-slippage <fraction> exit every open position now with taker orders, within the cap
+  aging [--hurdle <annual rate>] [--output table|json|csv]
+                                    capital lock-up, carry and per-event exposure of open positions
+  rebalance [--output table|json|csv]
+                                    trims that would even out one-sided YES/NO inventory near break-even
+  archive [--dir <path>]            snapshot every Gamma market (run daily)
+  quality [--dir <path>] [--top <n>] [--output table|json|csv]
+                                    score markets for resolution risk from the archive
+  replay-trade <trade-id>           re-run detection and execution for a past trade
+  export-depth --token <id>[,<id>..] [--from <ms>] [--to <ms>] [--format long|wide] [--out <file>]
+                                    depth recorded by `run` (bot.record_books_ms) as CSV for pandas/Polars
+  check-data [--dir <path>] [--max-gap-ms <n>] [--output table|json|csv]
+                                    gaps, out-of-order frames, crossed books and missing snapshots
+                                    per market in a book recording; exits 1 if any are found
+  export-lots [--method fifo|lifo] [--out <file>]
+                                    closed tax lots from the journal as CSV
+  attribution [--method fifo|lifo] [--output table|json|csv]
+                                    realized PnL by entry reason (arb leg, hedge, maker fill, manual)
+  balance [--output table|json|csv] cash, locked funds, equity and positions now
+  orders [--output table|json|csv]  resting orders as of the last snapshot
+  diff --from <ts> --to <ts> [--output table|json|csv]
+                                    positions, equity, locked funds and orders between two times
+                                    (unix seconds or YYYY-MM-DD[THH:MM:SSZ])
+  clusters [--min-trades <n>] [--output table|json|csv]
+                                    net PnL and capture rate by category, hour, liquidity and edge
+  sensitivity <result.json> [--max-bps <n>] [--step-bps <n>]
+                                    re-price a backtest under fee and slippage errors
+  lookahead <result.json>.. [--dir <path>] [--delay-ms <n>] [--output table|json|csv]
+                                    how much of each run's fills the recorded books still offered
+                                    shortly after (optimism gap)
+  report [<filter>] [--output table|json|csv]
+                                    fills, volume, fees, realized PnL and adverse excursion (MAE) per
+                                    tag of matching journal entries
+  export [<filter>] [--out <file>]  matching journal entries as CSV
+                                    filter: --tag <t>[,<t>..] --exclude-tag <t>[,<t>..] --market <id>
+                                    --since <ts> --until <ts>
+  annotate <entry-id> [--tag <t>] [--untag <t>] [--note <text>]
+                                    tag, untag or note a journal entry
+  why-not [--day <date>] [--output table|json|csv]
+                                    near-threshold signals the bot passed on that UTC day (today by
+                                    default), totalled by the cost that killed them
+  pairs [--propose] [--markets <n>] [--output table|json|csv]
+                                    markets that look like the same question; --propose scans the
+                                    first <n> active Gamma markets for new candidates
+  pairs confirm|reject <market-id> <market-id>
+                                    record a review decision; the bot prices confirmed pairs
+                                    against each other
+  selftest [--ws-secs <n>]          check read-only live endpoints for API changes
+  bench [--markets <n>] [--updates <n>]
+                                    time book-update processing over a synthetic watchlist
+
+query commands print a table by default; json is the report as serialized, csv its main table";
+
+/// Manually placed order
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManualOrder {
+    pub market: String,   // Market slug
+    pub outcome: String,  // Outcome name, e.g., "yes"
+    pub side: Side,
+    pub size: Size,
+    pub limit: Price,     // Worst acceptable VWAP
+    pub dry_run: bool,    // Preview fee and slippage only
+    pub tags: Vec<String>,     // Journaled alongside "manual"
+    pub note: Option<String>,  // Free-form note on the journal entry
+}
+
+/// Flags accepted before or after any command
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GlobalOptions {
+    pub config: Option<String>,
+    pub profile: Option<String>,
+    pub live_acknowledged: bool,
+}
+
+/// Parsed command line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cli {
+    pub global: GlobalOptions,
+    pub command: Command,
+}
+
+/// Subcommand
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Run { headless: bool, health_addr: String, markets: Option<String> },
+    Trade(ManualOrder),
+    Compare { baseline: String, candidate: String, tolerance: f64, expect_identical: bool },
+    Flatten { max_slippage: f64 },
+    Aging { hurdle: f64, output: OutputFormat },
+    Rebalance { output: OutputFormat },
+    Archive { dir: String },
+    Quality { dir: String, top: usize, output: OutputFormat },
+    ReplayTrade { trade_id: u64 },
+    SelfTest { ws_secs: u64 },
+    ExportDepth { tokens: Vec<TokenId>, from: u64, to: u64, format: DepthFormat, out: Option<String> },
+    ExportLots { method: Option<LotMethod>, out: Option<String> },
+    CheckData { dir: String, max_gap_ms: u64, output: OutputFormat },
+    Attribution { method: Option<LotMethod>, output: OutputFormat },
+    Clusters { min_trades: usize, output: OutputFormat },
+    Diff { from: u64, to: u64, output: OutputFormat },
+    Bench { markets: usize, updates: usize },
+    Sensitivity { result: String, max_bps: f64, step_bps: f64 },
+    Lookahead { results: Vec<String>, dir: String, delay_ms: u64, output: OutputFormat },
+    Report { filter: JournalFilter, output: OutputFormat },
+    Export { filter: JournalFilter, out: Option<String> },
+    Annotate { id: u64, tag: Option<String>, untag: Option<String>, note: Option<String> },
+    WhyNot { day: Option<u64>, output: OutputFormat },
+    Balance { output: OutputFormat },
+    Orders { output: OutputFormat },
+    Pairs { propose: Option<usize>, output: OutputFormat },
+    ReviewPair { market_a: String, market_b: String, status: PairStatus },
+}
+
+/// Parse arguments (without the program name)
+pub fn parse_args(args: &[String]) -> Result<Cli, String> {
+    let mut global = GlobalOptions::default();
+    let mut rest = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--config" => global.config = Some(iter.next().ok_or("missing value for --config")?.clone()),
+            "--profile" => global.profile = Some(iter.next().ok_or("missing value for --profile")?.clone()),
+            flag if flag == LIVE_ACK_FLAG => global.live_acknowledged = true,
+            _ => rest.push(arg.clone()),
+        }
+    }
+    Ok(Cli { global, command: parse_command(&rest)? })
+}
+
+fn parse_command(args: &[String]) -> Result<Command, String> {
+    match args.first().map(String::as_str) {
+        None | Some("run") => Ok(Command::Run {
+            headless: has_flag(args, "--headless"),
+            health_addr: flag_value(args, "--health-addr").unwrap_or("0.0.0.0:8080").to_string(),
+            markets: flag_value(args, "--markets").map(String::from),
+        }),
+        Some("buy") => parse_trade(&args[1..], Side::Buy),
+        Some("sell") => parse_trade(&args[1..], Side::Sell),
+        Some("compare") => parse_compare(&args[1..]),
+        Some("flatten") => {
+            let max_slippage: f64 = parse_num(args, "--max-slippage")?;
+            if !(0.0..1.0).contains(&max_slippage) {
+                return Err("--max-slippage must be a fraction between 0 and 1".to_string());
+            }
+            Ok(Command::Flatten { max_slippage })
+        }
+        Some("aging") => Ok(Command::Aging {
+            hurdle: match flag_value(args, "--hurdle") {
+                Some(v) => v.parse().map_err(|_| "invalid value for --hurdle".to_string())?,
+                None => 0.10,
+            },
+            output: parse_output(args)?,
+        }),
+        Some("rebalance") => Ok(Command::Rebalance { output: parse_output(args)? }),
+        Some("archive") => Ok(Command::Archive {
+            dir: flag_value(args, "--dir").unwrap_or("archive").to_string(),
+        }),
+        Some("quality") => Ok(Command::Quality {
+            dir: flag_value(args, "--dir").unwrap_or("archive").to_string(),
+            top: match flag_value(args, "--top") {
+                Some(v) => v.parse().map_err(|_| "invalid value for --top".to_string())?,
+                None => 20,
+            },
+            output: parse_output(args)?,
+        }),
+        Some("replay-trade") => Ok(Command::ReplayTrade {
+            trade_id: args.get(1)
+                .ok_or("replay-trade needs a trade id")?
+                .parse()
+                .map_err(|_| "invalid trade id".to_string())?,
+        }),
+        Some("selftest") => Ok(Command::SelfTest {
+            ws_secs: match flag_value(args, "--ws-secs") {
+                Some(v) => v.parse().map_err(|_| "invalid value for --ws-secs".to_string())?,
+                None => 60,
+            },
+        }),
+        Some("export-depth") => parse_export_depth(&args[1..]),
+        Some("export-lots") => parse_export_lots(&args[1..]),
+        Some("check-data") => Ok(Command::CheckData {
+            dir: flag_value(args, "--dir").unwrap_or("books").to_string(),
+            max_gap_ms: match flag_value(args, "--max-gap-ms") {
+                Some(v) => v.parse().map_err(|_| "invalid value for --max-gap-ms".to_string())?,
+                None => crate::datacheck::DEFAULT_MAX_GAP_MS,
+            },
+            output: parse_output(args)?,
+        }),
+        Some("attribution") => Ok(Command::Attribution {
+            method: match flag_value(args, "--method") {
+                Some(m) => Some(LotMethod::parse(m).ok_or("--method must be fifo or lifo")?),
+                None => None,
+            },
+            output: parse_output(args)?,
+        }),
+        Some("clusters") => Ok(Command::Clusters {
+            min_trades: match flag_value(args, "--min-trades") {
+                Some(v) => v.parse().map_err(|_| "invalid value for --min-trades".to_string())?,
+                None => 1,
+            },
+            output: parse_output(args)?,
+        }),
+        Some("diff") => {
+            let (from, to) = (parse_time(args, "--from")?, parse_time(args, "--to")?);
+            if from > to {
+                return Err("--from must not be after --to".to_string());
+            }
+            Ok(Command::Diff { from, to, output: parse_output(args)? })
+        }
+        Some("sensitivity") => {
+            let result = match args.get(1) {
+                Some(path) if !path.starts_with("--") => path.clone(),
+                _ => return Err("sensitivity needs a backtest result file".to_string()),
+            };
+            let max_bps = if flag_value(args, "--max-bps").is_some() { parse_num(args, "--max-bps")? } else { 50.0 };
+            let step_bps = if flag_value(args, "--step-bps").is_some() { parse_num(args, "--step-bps")? } else { 10.0 };
+            if max_bps < 0.0 || step_bps <= 0.0 {
+                return Err("--max-bps must be >= 0 and --step-bps > 0".to_string());
+            }
+            Ok(Command::Sensitivity { result, max_bps, step_bps })
+        }
+        Some("lookahead") => {
+            let results: Vec<String> = args[1..].iter().take_while(|a| !a.starts_with("--")).cloned().collect();
+            if results.is_empty() {
+                return Err("lookahead needs at least one backtest result file".to_string());
+            }
+            Ok(Command::Lookahead {
+                results,
+                dir: flag_value(args, "--dir").unwrap_or("books").to_string(),
+                delay_ms: match flag_value(args, "--delay-ms") {
+                    Some(v) => v.parse().map_err(|_| "invalid value for --delay-ms".to_string())?,
+                    None => crate::backtest::DEFAULT_LOOKAHEAD_MS,
+                },
+                output: parse_output(args)?,
+            })
+        }
+        Some("bench") => Ok(Command::Bench {
+            markets: match flag_value(args, "--markets") {
+                Some(v) => v.parse().map_err(|_| "invalid value for --markets".to_string())?,
+                None => 1_000,
+            },
+            updates: match flag_value(args, "--updates") {
+                Some(v) => v.parse().map_err(|_| "invalid value for --updates".to_string())?,
+                None => 1_000_000,
+            },
+        }),
+        Some("report") => Ok(Command::Report { filter: parse_filter(args)?, output: parse_output(args)? }),
+        Some("export") => Ok(Command::Export { filter: parse_filter(args)?, out: flag_value(args, "--out").map(String::from) }),
+        Some("annotate") => {
+            let id = match args.get(1) {
+                Some(id) if !id.starts_with("--") => id.parse().map_err(|_| "invalid journal entry id".to_string())?,
+                _ => return Err("annotate needs a journal entry id".to_string()),
+            };
+            let (tag, untag, note) = (flag_value(args, "--tag"), flag_value(args, "--untag"), flag_value(args, "--note"));
+            if tag.is_none() && untag.is_none() && note.is_none() {
+                return Err("annotate needs --tag, --untag or --note".to_string());
+            }
+            Ok(Command::Annotate { id, tag: tag.map(String::from), untag: untag.map(String::from), note: note.map(String::from) })
+        }
+        Some("balance") => Ok(Command::Balance { output: parse_output(args)? }),
+        Some("orders") => Ok(Command::Orders { output: parse_output(args)? }),
+        Some("why-not") => Ok(Command::WhyNot {
+            day: if flag_value(args, "--day").is_some() { Some(parse_time(args, "--day")?) } else { None },
+            output: parse_output(args)?,
+        }),
+        Some("pairs") => match args.get(1).map(String::as_str) {
+            Some(decision @ ("confirm" | "reject")) => match (args.get(2), args.get(3)) {
+                (Some(a), Some(b)) if !a.starts_with("--") && !b.starts_with("--") => Ok(Command::ReviewPair {
+                    market_a: a.clone(),
+                    market_b: b.clone(),
+                    status: if decision == "confirm" { PairStatus::Confirmed } else { PairStatus::Rejected },
+                }),
+                _ => Err(format!("pairs {} needs two market ids", decision)),
+            },
+            _ => Ok(Command::Pairs {
+                propose: match flag_value(args, "--markets") {
+                    _ if !has_flag(args, "--propose") => None,
+                    Some(v) => Some(v.parse().map_err(|_| "invalid value for --markets".to_string())?),
+                    None => Some(1000),
+                },
+                output: parse_output(args)?,
+            }),
+        },
+        Some(other) => Err(format!("unknown command `{}`", other)),
+    }
+}
+
+fn parse_trade(args: &[String], side: Side) -> Result<Command, String> {
+    let limit = Price::new(parse_num(args, "--limit")?).ok_or("--limit must be between 0 and 1")?;
+    let size = Size::new(parse_num(args, "--size")?).ok_or("--size must be positive")?;
+
+    Ok(Command::Trade(ManualOrder {
+        market: required(args, "--market")?.to_string(),
+        outcome: required(args, "--outcome")?.to_string(),
+        side,
+        size,
+        limit,
+        dry_run: has_flag(args, "--dry-run"),
+        tags: list(args, "--tag"),
+        note: flag_value(args, "--note").map(String::from),
+    }))
+}
+
+/// Journal selection shared by `report` and `export`
+fn parse_filter(args: &[String]) -> Result<JournalFilter, String> {
+    let time = |flag: &str| if flag_value(args, flag).is_some() { parse_time(args, flag).map(Some) } else { Ok(None) };
+    Ok(JournalFilter {
+        any_tags: list(args, "--tag"),
+        exclude_tags: list(args, "--exclude-tag"),
+        market_id: flag_value(args, "--market").map(String::from),
+        since: time("--since")?,
+        until: time("--until")?,
+    })
+}
+
+/// Comma-separated values of `flag` (empty when absent)
+fn list(args: &[String], flag: &str) -> Vec<String> {
+    flag_value(args, flag)
+        .map(|v| v.split(',').map(str::trim).filter(|t| !t.is_empty()).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+fn parse_compare(args: &[String]) -> Result<Command, String> {
+    let (baseline, candidate) = match (args.first(), args.get(1)) {
+        (Some(a), Some(b)) if !a.starts_with("--") && !b.starts_with("--") => (a.clone(), b.clone()),
+        _ => return Err("compare needs two result files".to_string()),
+    };
+    let tolerance = match flag_value(args, "--tolerance") {
+        Some(v) => v.parse().map_err(|_| "invalid value for --tolerance".to_string())?,
+        None => 0.0,
+    };
+    Ok(Command::Compare { baseline, candidate, tolerance, expect_identical: has_flag(args, "--expect-identical") })
+}
+
+fn parse_export_depth(args: &[String]) -> Result<Command, String> {
+    let tokens: Vec<TokenId> = required(args, "--token")?
+        .split(',')
+        .filter(|t| !t.is_empty())
+        .map(TokenId::from)
+        .collect();
+    let from = if flag_value(args, "--from").is_some() { parse_num(args, "--from")? } else { 0 };
+    let to = if flag_value(args, "--to").is_some() { parse_num(args, "--to")? } else { u64::MAX };
+    let format = match flag_value(args, "--format") {
+        Some(f) => DepthFormat::parse(f).ok_or("--format must be long or wide")?,
+        None => DepthFormat::Long,
+    };
+    Ok(Command::ExportDepth { tokens, from, to, format, out: flag_value(args, "--out").map(String::from) })
+}
+
+fn parse_export_lots(args: &[String]) -> Result<Command, String> {
+    let method = match flag_value(args, "--method") {
+        Some(m) => Some(LotMethod::parse(m).ok_or("--method must be fifo or lifo")?),
+        None => None,
+    };
+    Ok(Command::ExportLots { method, out: flag_value(args, "--out").map(String::from) })
+}
+
+/// `--output table|json|csv`; table when absent. `--json` is kept as shorthand.
+fn parse_output(args: &[String]) -> Result<OutputFormat, String> {
+    match flag_value(args, "--output") {
+        Some(f) => OutputFormat::parse(f).ok_or_else(|| "--output must be table, json or csv".to_string()),
+        None if has_flag(args, "--json") => Ok(OutputFormat::Json),
+        None => Ok(OutputFormat::Table),
+    }
+}
+
+/// Value following `flag`, if present
+pub fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+/// Check whether a bare switch is present
+pub fn has_flag(args: &[String], flag: &str) -> bool {
+    args.iter().any(|a| a == flag)
+}
+
+fn required<'a>(args: &'a [String], flag: &str) -> Result<&'a str, String> {
+    flag_value(args, flag).ok_or_else(|| format!("missing {}", flag))
+}
+
+/// Unix seconds, or an ISO-8601 date/time
+fn parse_time(args: &[String], flag: &str) -> Result<u64, String> {
+    let value = required(args, flag)?;
+    value.parse()
+        .ok()
+        .or_else(|| parse_iso8601(value))
+        .ok_or_else(|| format!("invalid value for {}", flag))
+}
+
+fn parse_num<T: std::str::FromStr>(args: &[String], flag: &str) -> Result<T, String> {
+    required(args, flag)?
+        .parse()
+        .map_err(|_| format!("invalid value for {}", flag))
+}
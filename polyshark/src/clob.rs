@@ -0,0 +1,411 @@
+use crate::orders::OrderStatusReport;
+#[cfg(feature = "network")]
+use crate::reconcile::{BalanceSource, ExchangeBalances, CASH_ASSET};
+#[cfg(feature = "network")]
+use crate::orders::TimeInForce;
+#[cfg(feature = "network")]
+use crate::schema::SchemaDrift;
+use crate::schema::{text_field, Field, FieldKind, Schema, SchemaMode};
+use crate::types::OrderBook;
+#[cfg(feature = "network")]
+use crate::types::TokenId;
+use crate::websocket::{num_field, parse_levels};
+#[cfg(feature = "signing")]
+use base64::engine::general_purpose::{URL_SAFE, URL_SAFE_NO_PAD};
+#[cfg(feature = "signing")]
+use base64::Engine;
+#[cfg(feature = "signing")]
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+#[cfg(feature = "signing")]
+use sha2::Sha256;
+use std::collections::HashMap;
+#[cfg(feature = "signing")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Order book, pricing and trading
+pub const CLOB_API_URL: &str = "https://clob.polymarket.com";
+
+/// A `/book` response
+pub const BOOK_SCHEMA: Schema = Schema {
+    name: "clob book",
+    fields: &[
+        Field::required("asset_id", FieldKind::Text),
+        Field::optional("bids", FieldKind::Array, "empty side; malformed levels skipped"),
+        Field::optional("asks", FieldKind::Array, "empty side; malformed levels skipped"),
+        Field::optional("timestamp", FieldKind::Number, "0, so the book reads as stale"),
+    ],
+    ignored: &["market", "hash", "min_order_size", "tick_size", "neg_risk", "last_trade_price"],
+};
+
+/// A `/data/order/{id}` response
+pub const ORDER_SCHEMA: Schema = Schema {
+    name: "clob order",
+    fields: &[
+        Field::optional("size_matched", FieldKind::Number, "nothing filled"),
+        Field::optional("status", FieldKind::Text, "not live"),
+    ],
+    ignored: &[
+        "id", "owner", "maker_address", "market", "asset_id", "side", "original_size", "price",
+        "outcome", "order_type", "expiration", "associate_trades", "created_at",
+    ],
+};
+
+/// A `/balance-allowance` response
+pub const BALANCE_SCHEMA: Schema = Schema {
+    name: "clob balance",
+    fields: &[Field::required("balance", FieldKind::Number)],
+    ignored: &["allowance", "allowances"],
+};
+
+/// Balances come back in base units; USDC and outcome tokens both have 6 decimals
+pub const BALANCE_DECIMALS: i32 = 6;
+
+/// L2 API credentials (derived once from the wallet key)
+#[derive(Debug, Clone)]
+pub struct ClobCredentials {
+    pub address: String,
+    pub api_key: String,
+    pub secret: String,      // base64url encoded
+    pub passphrase: String,
+}
+
+#[cfg(feature = "signing")]
+impl ClobCredentials {
+    /// POLY_* headers for an authenticated request. Fails when the secret isn't base64url:
+    /// signing with an empty key would only come back as an opaque 401.
+    pub fn headers(&self, method: &str, path: &str, body: &str) -> Result<Vec<(&'static str, String)>, String> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0).to_string();
+        let key = URL_SAFE.decode(&self.secret)
+            .or_else(|_| URL_SAFE_NO_PAD.decode(&self.secret))
+            .map_err(|e| format!("API secret is not base64url: {}", e))?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(&key).expect("HMAC accepts any key length");
+        mac.update(format!("{}{}{}{}", timestamp, method, path, body).as_bytes());
+        let signature = URL_SAFE.encode(mac.finalize().into_bytes());
+
+        Ok(vec![
+            ("POLY_ADDRESS", self.address.clone()),
+            ("POLY_SIGNATURE", signature),
+            ("POLY_TIMESTAMP", timestamp),
+            ("POLY_API_KEY", self.api_key.clone()),
+            ("POLY_PASSPHRASE", self.passphrase.clone()),
+        ])
+    }
+}
+
+#[cfg(feature = "network")]
+/// Why an authenticated CLOB call failed
+#[derive(Debug)]
+pub enum ClobError {
+    Http(reqwest::Error),  // Transport or non-2xx status
+    Auth(String),          // Request could not be signed; nothing was sent
+}
+
+#[cfg(feature = "network")]
+impl std::fmt::Display for ClobError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClobError::Http(err) => write!(f, "{}", err),
+            ClobError::Auth(reason) => write!(f, "signing failed: {}", reason),
+        }
+    }
+}
+
+#[cfg(feature = "network")]
+impl std::error::Error for ClobError {}
+
+#[cfg(feature = "network")]
+impl From<reqwest::Error> for ClobError {
+    fn from(err: reqwest::Error) -> Self {
+        ClobError::Http(err)
+    }
+}
+
+/// Per-order outcome of a batch cancel
+#[derive(Debug, Clone, Default)]
+pub struct CancelResponse {
+    pub canceled: Vec<String>,
+    pub not_canceled: HashMap<String, String>,  // order_id -> reason
+}
+
+/// A `POST /order` answer
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PostOrderResponse {
+    pub success: bool,
+    pub order_id: String,
+    pub status: String,  // "live", "matched", "delayed" or "unmatched"
+    pub error: String,   // Exchange's reason when `success` is false
+    pub making_amount: f64,  // Matched on arrival: what we gave (USDC for buys, shares for sells)
+    pub taking_amount: f64,  // Matched on arrival: what we got
+}
+
+#[cfg(feature = "network")]
+/// CLOB API client
+#[derive(Debug, Clone)]
+pub struct ClobClient {
+    pub base_url: String,
+    pub credentials: Option<ClobCredentials>,
+    pub drift: SchemaDrift,  // Unknown response fields seen by this client
+    http: reqwest::Client,
+}
+
+#[cfg(feature = "network")]
+impl ClobClient {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            credentials: None,
+            drift: SchemaDrift::new(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    #[cfg(feature = "signing")]
+    pub fn with_credentials(mut self, credentials: ClobCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Submit a signed order. `time_in_force` goes out as `orderType`; a GTD expiration is
+    /// part of the signed order itself. Refusals (4xx) come back as `success: false`.
+    pub async fn post_order(&self, signed: &Value, time_in_force: TimeInForce) -> Result<PostOrderResponse, ClobError> {
+        let owner = self.credentials.as_ref().map(|c| c.api_key.clone()).unwrap_or_default();
+        let body = serde_json::json!({ "order": signed, "owner": owner, "orderType": time_in_force.wire_name() }).to_string();
+        let request = self.http
+            .post(format!("{}/order", self.base_url))
+            .header("Content-Type", "application/json");
+        let response = self.authed(request, "POST", "/order", &body)?.body(body).send().await?;
+        if response.status().is_server_error() {
+            response.error_for_status_ref()?;
+        }
+        let refused = response.status().is_client_error();
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        let mut parsed = parse_post_order(&body);
+        parsed.success &= !refused;
+        Ok(parsed)
+    }
+
+    /// Cancel every open order of this account
+    pub async fn cancel_all(&self) -> Result<CancelResponse, ClobError> {
+        let request = self.http.delete(format!("{}/cancel-all", self.base_url));
+        let response: Value = self.authed(request, "DELETE", "/cancel-all", "")?
+            .send().await?.error_for_status()?.json().await?;
+        Ok(parse_cancel_response(&response))
+    }
+
+    /// Cancel many orders in one round trip; the exchange reports each id separately
+    pub async fn cancel_orders(&self, order_ids: &[String]) -> Result<CancelResponse, ClobError> {
+        let body = serde_json::to_string(order_ids).unwrap_or_default();
+        let request = self.http
+            .delete(format!("{}/orders", self.base_url))
+            .header("Content-Type", "application/json");
+        let response: Value = self.authed(request, "DELETE", "/orders", &body)?
+            .body(body).send().await?.error_for_status()?.json().await?;
+        Ok(parse_cancel_response(&response))
+    }
+
+    /// Status of one of our orders (polling fallback when the user channel is down)
+    pub async fn order_status(&self, order_id: &str) -> Result<OrderStatusReport, ClobError> {
+        let path = format!("/data/order/{}", order_id);
+        let request = self.http.get(format!("{}{}", self.base_url, path));
+        let body: Value = self.authed(request, "GET", &path, "")?
+            .send().await?.error_for_status()?.json().await?;
+        self.drift.observe(&ORDER_SCHEMA, &body);
+        Ok(parse_order_status(order_id, &body))
+    }
+
+    /// Our cash balance, or our balance of `token_id`, in USDC / shares.
+    /// `None` when the response carries no balance.
+    pub async fn balance(&self, token_id: Option<&TokenId>) -> Result<Option<f64>, ClobError> {
+        let query = match token_id {
+            Some(token_id) => format!("asset_type=CONDITIONAL&token_id={}", token_id),
+            None => "asset_type=COLLATERAL".to_string(),
+        };
+        // The query string is not part of the signed path
+        let request = self.http.get(format!("{}/balance-allowance?{}", self.base_url, query));
+        let body: Value = self.authed(request, "GET", "/balance-allowance", "")?
+            .send().await?.error_for_status()?.json().await?;
+        self.drift.observe(&BALANCE_SCHEMA, &body);
+        Ok(parse_balance(&body))
+    }
+
+    /// Sign with the L2 credentials when set (requests go out unsigned without the `signing` feature)
+    #[cfg_attr(not(feature = "signing"), allow(unused_mut, unused_variables))]
+    fn authed(&self, mut request: reqwest::RequestBuilder, method: &str, path: &str, body: &str) -> Result<reqwest::RequestBuilder, ClobError> {
+        #[cfg(feature = "signing")]
+        if let Some(creds) = &self.credentials {
+            for (name, value) in creds.headers(method, path, body).map_err(ClobError::Auth)? {
+                request = request.header(name, value);
+            }
+        }
+        Ok(request)
+    }
+
+    /// Exchange clock in unix seconds
+    pub async fn server_time(&self) -> Result<u64, reqwest::Error> {
+        let body: Value = self.http.get(format!("{}/time", self.base_url)).send().await?.error_for_status()?.json().await?;
+        Ok(body.as_u64().or_else(|| num_field(&body, "time").map(|t| t as u64)).unwrap_or(0))
+    }
+
+    /// Fetch the current book for one token
+    pub async fn order_book(&self, token_id: &TokenId) -> Result<OrderBook, reqwest::Error> {
+        let url = format!("{}/book?token_id={}", self.base_url, token_id);
+        let body: Value = self.http.get(url).send().await?.error_for_status()?.json().await?;
+        self.drift.observe(&BOOK_SCHEMA, &body);
+        Ok(parse_book(&body))
+    }
+
+    /// Minimum price increment of a token's market; `None` if the response has none
+    pub async fn tick_size(&self, token_id: &TokenId) -> Result<Option<f64>, reqwest::Error> {
+        let url = format!("{}/tick-size?token_id={}", self.base_url, token_id);
+        let body: Value = self.http.get(url).send().await?.error_for_status()?.json().await?;
+        Ok(parse_tick_size(&body))
+    }
+}
+
+/// Parse a `/book` response; fallbacks are listed in `BOOK_SCHEMA`
+pub fn parse_book(v: &Value) -> OrderBook {
+    let mut book = OrderBook {
+        token_id: text_field(v, "asset_id").unwrap_or_default().into(),
+        bids: parse_levels(&v["bids"]),
+        asks: parse_levels(&v["asks"]),
+        timestamp: num_field(v, "timestamp").map(|t| t as u64).unwrap_or(0),
+    };
+    book.sort_levels();
+    book
+}
+
+/// `parse_book`, failing in strict mode when the response doesn't match `BOOK_SCHEMA`
+pub fn parse_book_checked(v: &Value, mode: SchemaMode) -> Result<OrderBook, String> {
+    mode.validate(&BOOK_SCHEMA, v)?;
+    Ok(parse_book(v))
+}
+
+/// Parse `{ "success": .., "errorMsg": .., "orderID": .., "status": .., "makingAmount": .., "takingAmount": .. }`;
+/// 4xx bodies carry `error` instead
+pub fn parse_post_order(v: &Value) -> PostOrderResponse {
+    let error = text_field(v, "errorMsg").filter(|e| !e.is_empty()).or_else(|| text_field(v, "error")).unwrap_or_default();
+    PostOrderResponse {
+        success: v["success"].as_bool().unwrap_or(false) && error.is_empty(),
+        order_id: text_field(v, "orderID").unwrap_or_default(),
+        status: text_field(v, "status").unwrap_or_default(),
+        error,
+        making_amount: num_field(v, "makingAmount").unwrap_or(0.0),
+        taking_amount: num_field(v, "takingAmount").unwrap_or(0.0),
+    }
+}
+
+/// Parse `{ "canceled": [..], "not_canceled": { id: reason } }`
+pub fn parse_cancel_response(v: &Value) -> CancelResponse {
+    CancelResponse {
+        canceled: v["canceled"].as_array()
+            .map(|ids| ids.iter().filter_map(|i| i.as_str().map(String::from)).collect())
+            .unwrap_or_default(),
+        not_canceled: v["not_canceled"].as_object()
+            .map(|m| m.iter().map(|(id, why)| (id.clone(), why.as_str().unwrap_or_default().to_string())).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// Parse a `/tick-size` response
+pub fn parse_tick_size(v: &Value) -> Option<f64> {
+    num_field(v, "minimum_tick_size").filter(|tick| *tick > 0.0 && *tick < 1.0)
+}
+
+/// Parse a `/balance-allowance` response into whole USDC / shares
+pub fn parse_balance(v: &Value) -> Option<f64> {
+    num_field(v, "balance").map(|units| units / 10f64.powi(BALANCE_DECIMALS))
+}
+
+#[cfg(feature = "network")]
+impl BalanceSource for ClobClient {
+    async fn fetch_balances(&self, tokens: &[TokenId]) -> Result<ExchangeBalances, String> {
+        let missing = |asset: &str| format!("no balance for {} in the response", asset);
+        let usdc = self.balance(None).await.map_err(|e| e.to_string())?.ok_or_else(|| missing(CASH_ASSET))?;
+        let mut balances = ExchangeBalances { usdc, tokens: HashMap::new() };
+        for token_id in tokens {
+            let held = self.balance(Some(token_id)).await.map_err(|e| e.to_string())?.ok_or_else(|| missing(token_id.as_str()))?;
+            balances.tokens.insert(token_id.clone(), held);
+        }
+        Ok(balances)
+    }
+}
+
+/// Parse a `/data/order/{id}` response
+pub fn parse_order_status(order_id: &str, v: &Value) -> OrderStatusReport {
+    OrderStatusReport {
+        order_id: order_id.to_string(),
+        filled: num_field(v, "size_matched").unwrap_or(0.0),
+        live: v["status"].as_str().is_some_and(|s| s.eq_ignore_ascii_case("live")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn book() -> Value {
+        json!({
+            "market": "0xabc",
+            "asset_id": "111",
+            "timestamp": "1760000000123",
+            "hash": "0xdef",
+            "bids": [{ "price": "0.47", "size": "200" }, { "price": "0.48", "size": "100" }],
+            "asks": [{ "price": "0.52", "size": "50" }, { "price": "0.50", "size": "100" }],
+            "min_order_size": "5",
+            "tick_size": "0.01",
+            "neg_risk": false,
+        })
+    }
+
+    #[test]
+    fn strict_accepts_a_book_matching_the_schema() {
+        let parsed = parse_book_checked(&book(), SchemaMode::Strict).unwrap();
+        assert_eq!(parsed.token_id.as_str(), "111");
+        assert_eq!(parsed.timestamp, 1_760_000_000_123);
+        assert_eq!(parsed.best_bid(), Some(0.48));
+        assert_eq!(parsed.best_ask(), Some(0.50));
+    }
+
+    #[test]
+    fn strict_rejects_what_lenient_falls_back_on() {
+        let mut unknown = book();
+        unknown["last_update"] = json!(1);
+        let err = parse_book_checked(&unknown, SchemaMode::Strict).unwrap_err();
+        assert!(err.contains("last_update unknown"), "{}", err);
+        assert!(parse_book_checked(&unknown, SchemaMode::Lenient).is_ok());
+
+        let mut missing = book();
+        missing.as_object_mut().unwrap().remove("asset_id");
+        let err = parse_book_checked(&missing, SchemaMode::Strict).unwrap_err();
+        assert!(err.contains("asset_id missing"), "{}", err);
+
+        let mut malformed = book();
+        malformed["bids"] = json!("[]");
+        assert!(parse_book_checked(&malformed, SchemaMode::Strict).is_err());
+        assert!(parse_book_checked(&malformed, SchemaMode::Lenient).unwrap().bids.is_empty());
+    }
+
+    #[test]
+    fn balances_are_scaled_from_base_units() {
+        assert_eq!(parse_balance(&json!({ "balance": "12500000", "allowances": {} })), Some(12.5));
+        assert_eq!(parse_balance(&json!({ "allowance": "0" })), None);
+    }
+
+    #[test]
+    fn tick_sizes_parse_from_numbers_or_strings() {
+        assert_eq!(parse_tick_size(&json!({ "minimum_tick_size": 0.001 })), Some(0.001));
+        assert_eq!(parse_tick_size(&json!({ "minimum_tick_size": "0.01" })), Some(0.01));
+        assert_eq!(parse_tick_size(&json!({ "minimum_tick_size": 0 })), None);
+        assert_eq!(parse_tick_size(&json!({})), None);
+    }
+
+    #[test]
+    fn absent_optional_fields_pass_strict_mode() {
+        let parsed = parse_book_checked(&json!({ "asset_id": 111 }), SchemaMode::Strict).unwrap();
+        assert_eq!(parsed.token_id.as_str(), "111");
+        assert_eq!(parsed.timestamp, 0);
+        assert!(parsed.bids.is_empty() && parsed.asks.is_empty());
+    }
+}
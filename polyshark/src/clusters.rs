@@ -0,0 +1,134 @@
+use crate::capture::{CaptureOutcome, CaptureRecord};
+use crate::journal::Journal;
+use crate::replay::TradeRecord;
+use crate::slippage::{classify, LiquidityTier};
+use crate::types::{OrderBook, Side, TokenId};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Niche a trade belongs to
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ClusterKey {
+    pub category: String,
+    pub hours: String,  // Six-hour UTC block, e.g. "12-18"
+    pub tier: String,   // Liquidity tier name at entry
+    pub edge: String,   // Edge bucket at entry, e.g. "1-2c"
+}
+
+/// Results for one niche
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ClusterRow {
+    pub key: ClusterKey,
+    pub trades: usize,   // Opening fills
+    pub missed: usize,   // Signals in the niche that never filled
+    pub net_pnl: f64,    // Realized PnL of positions opened in the niche, fees included
+    pub fees: f64,
+}
+
+impl ClusterRow {
+    pub fn capture_rate(&self) -> f64 {
+        let signals = self.trades + self.missed;
+        if signals == 0 { 0.0 } else { self.trades as f64 / signals as f64 }
+    }
+
+    pub fn pnl_per_trade(&self) -> f64 {
+        if self.trades == 0 { 0.0 } else { self.net_pnl / self.trades as f64 }
+    }
+}
+
+/// Group historical trades by category, time of day, liquidity tier and edge, so the
+/// niches where the strategy actually pays are visible. Realized PnL is attributed to
+/// the niche the position was opened in until it is closed out; misses take the category
+/// seen for their market in other records and the tier logged with the signal. Most
+/// profitable first.
+pub fn cluster_trades(journal: &Journal, records: &[TradeRecord], missed: &[CaptureRecord], tiers: &[LiquidityTier]) -> Vec<ClusterRow> {
+    let by_id: HashMap<u64, &TradeRecord> = records.iter().map(|r| (r.trade_id, r)).collect();
+    let category_of: HashMap<&str, String> = records.iter()
+        .map(|r| (r.market.id.as_str(), r.market.category.clone().unwrap_or_else(|| "uncategorized".to_string())))
+        .collect();
+
+    let mut clusters: BTreeMap<ClusterKey, ClusterRow> = BTreeMap::new();
+    let mut opened_in: HashMap<TokenId, (ClusterKey, f64)> = HashMap::new();  // Key and open size
+    for e in &journal.entries {
+        let key = match by_id.get(&e.id) {
+            Some(record) => ClusterKey {
+                category: record.market.category.clone().unwrap_or_else(|| "uncategorized".to_string()),
+                hours: hours_bucket(e.timestamp),
+                tier: tier_name(tiers, &record.books),
+                edge: edge_bucket(record.edge),
+            },
+            None => ClusterKey {
+                category: category_of.get(e.market_id.as_str()).cloned().unwrap_or_else(|| "unknown".to_string()),
+                hours: hours_bucket(e.timestamp),
+                tier: "unknown".to_string(),
+                edge: edge_bucket(None),
+            },
+        };
+        match e.side {
+            Side::Buy => {
+                let open = opened_in.entry(e.token_id.clone()).or_insert((key, 0.0));
+                open.1 += e.size;
+                let c = cluster(&mut clusters, open.0.clone());
+                c.trades += 1;
+                c.fees += e.fee;
+            }
+            Side::Sell => {
+                let key = match opened_in.get_mut(&e.token_id) {
+                    Some((opened, size)) => {
+                        let opened = opened.clone();
+                        *size -= e.size;
+                        if *size <= 1e-9 {
+                            // Closed out: the next buy opens a position in its own niche
+                            opened_in.remove(&e.token_id);
+                        }
+                        opened
+                    }
+                    None => key,
+                };
+                let c = cluster(&mut clusters, key);
+                c.net_pnl += e.realized_pnl.unwrap_or(0.0);
+                c.fees += e.fee;
+            }
+        }
+    }
+    for m in missed.iter().filter(|m| m.outcome == CaptureOutcome::Missed) {
+        let key = ClusterKey {
+            category: category_of.get(m.market_id.as_str()).cloned().unwrap_or_else(|| "unknown".to_string()),
+            hours: hours_bucket(m.timestamp),
+            tier: m.tier.clone().unwrap_or_else(|| "unknown".to_string()),
+            edge: edge_bucket(Some(m.edge)),
+        };
+        cluster(&mut clusters, key).missed += 1;
+    }
+
+    let mut rows: Vec<ClusterRow> = clusters.into_values().collect();
+    rows.sort_by(|a, b| b.net_pnl.total_cmp(&a.net_pnl).then_with(|| a.key.cmp(&b.key)));
+    rows
+}
+
+fn cluster(clusters: &mut BTreeMap<ClusterKey, ClusterRow>, key: ClusterKey) -> &mut ClusterRow {
+    clusters.entry(key.clone()).or_insert(ClusterRow { key, trades: 0, missed: 0, net_pnl: 0.0, fees: 0.0 })
+}
+
+fn hours_bucket(secs: u64) -> String {
+    let start = (secs % 86_400) / 3_600 / 6 * 6;
+    format!("{:02}-{:02}", start, start + 6)
+}
+
+fn edge_bucket(edge: Option<f64>) -> String {
+    match edge {
+        None => "unknown",
+        Some(e) if e < 0.01 => "<1c",
+        Some(e) if e < 0.02 => "1-2c",
+        Some(e) if e < 0.05 => "2-5c",
+        Some(_) => ">=5c",
+    }
+    .to_string()
+}
+
+fn tier_name(tiers: &[LiquidityTier], books: &[OrderBook]) -> String {
+    let notional = |b: &OrderBook| b.bids.iter().chain(&b.asks).map(|l| l.price * l.size).sum::<f64>();
+    // Shallowest book of the trade is what limited it
+    let depth = books.iter().map(notional).reduce(f64::min);
+    depth.and_then(|d| classify(tiers, d)).map(|t| t.name.clone()).unwrap_or_else(|| "unknown".to_string())
+}
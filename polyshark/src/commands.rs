@@ -0,0 +1,1103 @@
+// Most imports are only used by the commands that talk to Polymarket, which needs the `network` feature
+#![cfg_attr(not(feature = "network"), allow(unused_imports))]
+
+#[cfg(feature = "archive")]
+use crate::archive::MarketArchiver;
+use crate::attribution;
+#[cfg(feature = "bookstore")]
+use crate::bookstore::BookReader;
+use crate::backtest::{self, BacktestComparison, BacktestResult};
+use crate::cli::ManualOrder;
+use crate::capture::{self, MISSED_LOG_PATH};
+#[cfg(feature = "network")]
+use crate::clob::ClobClient;
+use crate::clusters;
+use crate::compliance::ComplianceGate;
+use crate::config::{Profile, TradingMode};
+use crate::constraint::ConstraintChecker;
+#[cfg(feature = "bookstore")]
+use crate::datacheck;
+use crate::depth::{self, DepthFormat};
+use crate::execution::ExecutionEngine;
+use crate::explain::{self, WhyNotReport, WHY_NOT_LOG_PATH};
+use crate::fees::{FeeConfig, SharedFeeSchedule};
+#[cfg(feature = "network")]
+use crate::gamma::GammaClient;
+#[cfg(feature = "network")]
+use crate::gateway::live_gateway;
+use crate::instance;
+use crate::journal::{Journal, JournalFilter, JournalReport, JournalSummary};
+use crate::lots::{LotBook, LotMethod};
+use crate::manual;
+use crate::matcher::{MarketMatcher, PairStatus, MATCHED_PAIRS_PATH};
+use crate::orders::{OpenOrder, OrderGateway, OrderManager, OrderRequest, TimeInForce};
+use crate::output::{self, OutputFormat};
+use crate::paper::{PaperVenue, Submission};
+#[cfg(feature = "archive")]
+use crate::quality::{QualityHistory, QualityRow, QualityTable, QUALITY_TABLE_PATH};
+use crate::resolution::ResolutionMonitor;
+use crate::risk::RiskMonitor;
+use crate::rebalance::{RebalanceMode, Rebalancer};
+use crate::replay::{self, TradeRecord, TRADE_RECORDS_PATH};
+use crate::reports;
+use crate::storage::Storage;
+use crate::snapshot::{PortfolioDiff, PortfolioSnapshot, SNAPSHOTS_PATH};
+use crate::types::{Market, MarketId, Price, Side, Size, TokenId};
+use crate::wallet::Wallet;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Journal file from before the journal moved into `Storage`; imported once when found
+pub const JOURNAL_PATH: &str = "journal.jsonl";
+
+/// Current unix time in seconds
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Open the profile's storage and load the journal from it. A legacy `journal.jsonl` is
+/// imported the first time the storage's journal is empty.
+pub fn open_journal(profile: &Profile) -> Result<(Box<dyn Storage>, Journal), String> {
+    let mut storage = profile.storage.clone().unwrap_or_default().open().map_err(|e| format!("storage: {}", e))?;
+    let mut journal = Journal::load(storage.as_mut()).map_err(|e| format!("journal: {}", e))?;
+    if journal.entries.is_empty()
+        && let Ok(legacy) = Journal::load_jsonl(Path::new(JOURNAL_PATH))
+        && !legacy.entries.is_empty()
+    {
+        legacy.append_to(storage.as_mut(), 0).map_err(|e| format!("importing {}: {}", JOURNAL_PATH, e))?;
+        eprintln!("imported {} journal entries from {}", legacy.entries.len(), JOURNAL_PATH);
+        journal = legacy;
+    }
+    journal.instance_id = Some(instance::instance_id());  // Stamped on whatever this process records
+    Ok((storage, journal))
+}
+
+/// Fee config from the profile, falling back to the market's own fee curve
+pub fn fee_config(profile: &Profile, market: &Market) -> FeeConfig {
+    profile.fees.clone().unwrap_or(FeeConfig::PriceCurve {
+        maker_fee_bps: market.maker_base_fee,
+        taker_fee_bps: market.taker_base_fee,
+    })
+}
+
+/// Fee schedule from the profile, falling back to the market's own fee curve
+pub fn fee_schedule(profile: &Profile, market: &Market) -> SharedFeeSchedule {
+    fee_config(profile, market).build()
+}
+
+#[cfg(feature = "network")]
+/// Place a manual order against the paper wallet rebuilt from the journal
+pub async fn trade(profile: &Profile, order: &ManualOrder) -> Result<(), String> {
+    if profile.mode == TradingMode::Live {
+        return Err("live order submission is not available yet; use a paper profile".to_string());
+    }
+
+    let gamma = GammaClient::new(&profile.gamma_url);
+    let clob = ClobClient::new(&profile.clob_url);
+
+    let market = gamma.market_by_slug(&order.market).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("market `{}` not found", order.market))?;
+    let token_id = manual::outcome_token(&market, &order.outcome)
+        .ok_or_else(|| format!("market {} has no outcome `{}`", market.slug, order.outcome))?;
+    if order.side == Side::Buy
+        && let Err(block) = ComplianceGate::new(profile.compliance.clone()).check(&market)
+    {
+        return Err(format!("market {} is blocked by compliance rules: {} (add it to compliance.allow to override)", market.slug, block.describe()));
+    }
+    let book = clob.order_book(&token_id).await.map_err(|e| e.to_string())?;
+
+    if order.dry_run {
+        let engine = ExecutionEngine::new(fee_schedule(profile, &market));
+        let preview = engine.simulate(&book, order.size, order.side, Some(order.limit.value()))
+            .ok_or_else(|| format!("no liquidity at or better than {:.4} to simulate against", order.limit))?;
+        println!(
+            "if you traded now: {:?} {:.2} {} @ {:.4} — fee {:.4}, slippage {:.2}%, total {:.2}",
+            order.side,
+            preview.filed_size,
+            order.outcome,
+            preview.execution_price,
+            preview.fee_paid,
+            preview.slippage * 100.0,
+            match order.side {
+                Side::Buy => preview.total_cost,
+                Side::Sell => preview.proceeds(),
+            }
+        );
+        replay::write_walk(&mut std::io::stdout(), &book.walk(order.size.value(), order.side)).map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+
+    let (mut storage, mut journal) = open_journal(profile)?;
+    let recorded = journal.entries.len();
+
+    // Paper orders take as long to land as live ones would, and fill against the book as of then.
+    // The journal length numbers the order, so a fixed seed doesn't repeat the same draw.
+    let book = if profile.paper_latency.is_enabled() {
+        let submission = PaperVenue::for_order(profile.paper_latency.clone(), recorded as u64).submit();
+        tokio::time::sleep(submission.delay()).await;
+        if let Submission::Rejected { delay } = submission {
+            return Err(format!("order rejected (simulated) after {} ms", delay.as_millis()));
+        }
+        clob.order_book(&token_id).await.map_err(|e| e.to_string())?
+    } else {
+        book
+    };
+
+    let mut wallet = Wallet::new(profile.starting_balance);
+    wallet.lots.method = profile.lot_method;
+    manual::replay_journal(&mut wallet, &journal);
+
+    let cash_before = wallet.usdc;
+    let engine = ExecutionEngine::new(fee_schedule(profile, &market));
+    let result = manual::execute_manual(order, &market, &book, &engine, &mut wallet, &mut journal, &mut RiskMonitor::new(), now())?;
+    journal.append_to(storage.as_mut(), recorded).map_err(|e| format!("journal: {}", e))?;
+
+    if let Some(entry) = journal.entries.last() {
+        let walk = book.walk(order.size.value(), order.side);
+        let record = TradeRecord {
+            trade_id: entry.id,
+            timestamp: entry.timestamp,
+            market: market.clone(),
+            token_id,
+            books: vec![book],
+            side: order.side,
+            size: order.size,
+            limit: Some(order.limit),
+            fees: fee_config(profile, &market),
+            cash_before,
+            walk: Some(walk),
+            edge: None,
+        };
+        if let Err(err) = record.save(Path::new(TRADE_RECORDS_PATH)) {
+            eprintln!("warning: could not save trade record: {}", err);
+        }
+    }
+
+    println!(
+        "{:?} {:.2} {} @ {:.4} (fee {:.4}, slippage {:.2}%) — cash {}",
+        order.side,
+        result.filed_size,
+        order.outcome,
+        result.execution_price,
+        result.fee_paid,
+        result.slippage * 100.0,
+        profile.usd.format(wallet.usdc)
+    );
+    Ok(())
+}
+
+/// Print a backtest comparison; returns whether the candidate regressed (or changed at all, if `expect_identical`)
+pub fn compare(baseline: &str, candidate: &str, tolerance: f64, expect_identical: bool) -> Result<bool, String> {
+    let a = BacktestResult::load(Path::new(baseline)).map_err(|e| format!("{}: {}", baseline, e))?;
+    let b = BacktestResult::load(Path::new(candidate)).map_err(|e| format!("{}: {}", candidate, e))?;
+    let cmp = backtest::compare(&a, &b, tolerance);
+    print_comparison(&cmp, tolerance);
+    println!("\nhash {} -> {}{}", a.content_hash(), b.content_hash(), if cmp.identical { " (identical)" } else { "" });
+    if expect_identical && !cmp.identical {
+        println!("CHANGED: results differ from the baseline");
+    }
+    Ok(cmp.regression || (expect_identical && !cmp.identical))
+}
+
+/// Metric deltas and differing trades of a comparison
+pub fn print_comparison(cmp: &BacktestComparison, tolerance: f64) {
+    println!("{} -> {}", cmp.baseline, cmp.candidate);
+    println!("{:<14} {:>14} {:>14} {:>14}", "metric", "baseline", "candidate", "delta");
+    for m in &cmp.metrics {
+        println!("{:<14} {:>14.4} {:>14.4} {:>+14.4}", m.name, m.baseline, m.candidate, m.delta);
+    }
+    println!("\n{} trades differ", cmp.trades.len());
+    for t in &cmp.trades {
+        let fmt = |p: Option<f64>| p.map(|v| format!("{:.4}", v)).unwrap_or_else(|| "-".to_string());
+        println!(
+            "  {}: {} -> {} ({:+.4})",
+            t.trade_id, fmt(t.baseline_pnl), fmt(t.candidate_pnl), t.delta()
+        );
+    }
+    if cmp.regression {
+        println!("\nREGRESSION: total PnL fell by more than {:.4}", tolerance);
+    }
+}
+
+/// Re-price a saved backtest across a grid of fee and slippage errors
+pub fn sensitivity(path: &str, max_bps: f64, step_bps: f64) -> Result<(), String> {
+    let result = BacktestResult::load(Path::new(path)).map_err(|e| format!("{}: {}", path, e))?;
+    let grid_bps = backtest::bps_grid(max_bps, step_bps);
+    let grid = backtest::SensitivityGrid::run(&result, &grid_bps, &grid_bps);
+
+    println!("{}: {} trades, baseline PnL {:.4}", grid.label, result.trades.len(), grid.baseline_pnl);
+    for line in grid.render() {
+        println!("{}", line);
+    }
+    println!("\nprofitable in {:.0}% of the grid", grid.profitable_share() * 100.0);
+    if let Some(headroom) = grid.headroom_bps() {
+        println!("breaks even at {:+.1} bps of combined extra fee and slippage", headroom);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "network")]
+/// Exit every held position now with taker orders, never filling more than `max_slippage`
+/// away from mid (longs are sold, shorts bought back); whatever can't be exited inside the
+/// cap is reported and left in place
+pub async fn flatten(profile: &Profile, max_slippage: f64) -> Result<bool, String> {
+    // Live exits go to the exchange as IOC orders; paper ones fill against the fetched book
+    let mut live = match profile.mode {
+        TradingMode::Live => Some((live_gateway(profile)?, OrderManager::with_instance(&instance::instance_id()))),
+        TradingMode::Paper => None,
+    };
+
+    let (mut storage, mut journal) = open_journal(profile)?;
+    let recorded = journal.entries.len();
+    let mut wallet = Wallet::new(profile.starting_balance);
+    wallet.lots.method = profile.lot_method;
+    manual::replay_journal(&mut wallet, &journal);
+    let mut risk = RiskMonitor::new();
+
+    let market_of: HashMap<TokenId, MarketId> = journal.entries.iter()
+        .map(|e| (e.token_id.clone(), e.market_id.clone()))
+        .collect();
+    // Longs are sold into the bids; shorts are bought back from the asks
+    let mut held: Vec<(TokenId, Side, f64)> = wallet.positions.values()
+        .filter(|p| p.size > 0.0)
+        .map(|p| (p.token_id.clone(), p.side, p.size))
+        .collect();
+    held.sort_by(|a, b| a.0.cmp(&b.0));
+    if held.is_empty() {
+        println!("nothing to flatten");
+        return Ok(true);
+    }
+
+    let gamma = GammaClient::new(&profile.gamma_url);
+    let clob = ClobClient::new(&profile.clob_url);
+    let mut unsold = Vec::new();
+    for (token_id, held_side, size) in held {
+        let exit_side = match held_side {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        };
+        let attempt = async {
+            let market_id = market_of.get(&token_id).ok_or("no journaled market")?;
+            let market = gamma.market_by_id(market_id).await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("market {} not found", market_id))?;
+            let outcome = market.clob_token_ids.iter()
+                .position(|t| *t == token_id)
+                .and_then(|i| market.outcomes.get(i))
+                .ok_or("token not in its market")?
+                .clone();
+            let book = clob.order_book(&token_id).await.map_err(|e| e.to_string())?;
+            let (exit, limit) = match exit_side {
+                Side::Sell => manual::flatten_size(&book, size, max_slippage)
+                    .ok_or_else(|| format!("no bids within {:.1}% of mid", max_slippage * 100.0))?,
+                Side::Buy => manual::cover_size(&book, size, max_slippage)
+                    .ok_or_else(|| format!("no asks within {:.1}% of mid", max_slippage * 100.0))?,
+            };
+            let exit = Size::new(exit).ok_or("nothing to exit")?;
+            let limit = Price::new(limit.clamp(0.0, 1.0)).ok_or("invalid price limit")?;
+
+            let Some((gateway, orders)) = &mut live else {
+                let order = ManualOrder {
+                    market: market.slug.clone(),
+                    outcome,
+                    side: exit_side,
+                    size: exit,
+                    limit,
+                    dry_run: false,
+                    tags: vec![manual::FLATTEN_TAG.to_string()],
+                    note: None,
+                };
+                let engine = ExecutionEngine::new(fee_schedule(profile, &market));
+                let result = manual::execute_manual(&order, &market, &book, &engine, &mut wallet, &mut journal, &mut risk, now())?;
+                return Ok::<_, String>((result.filed_size, result.execution_price));
+            };
+            if exit_side == Side::Sell {
+                risk.check_sell(&wallet, &token_id, exit.value()).map_err(|violation| violation.to_string())?;
+            }
+            let request = OrderRequest { token_id: token_id.clone(), side: exit_side, price: limit, size: exit, time_in_force: TimeInForce::Ioc };
+            orders.submit(gateway, &request, now() * 1000).map_err(|e| e.to_string())?;
+            let (mut filled, mut notional) = (0.0, 0.0);
+            for fill in gateway.take_fills() {
+                filled += fill.size;
+                notional += fill.size * fill.price;
+                let id = manual::record_fill(&fill, &market.id, &mut wallet, &mut journal);
+                journal.tag(id, manual::FLATTEN_TAG);
+            }
+            Ok((filled, if filled > 0.0 { notional / filled } else { 0.0 }))
+        };
+        let verb = if exit_side == Side::Sell { "sold" } else { "covered" };
+        match attempt.await {
+            Ok((filled, price)) => {
+                println!("{} {:>10.2} of {:>10.2} {} @ {:.4}", verb, filled, size, token_id, price);
+                if filled + 1e-9 < size {
+                    unsold.push((token_id, size - filled, "not enough liquidity inside the slippage cap".to_string()));
+                }
+            }
+            Err(reason) => unsold.push((token_id, size, reason)),
+        }
+    }
+    journal.append_to(storage.as_mut(), recorded).map_err(|e| format!("journal: {}", e))?;
+
+    if !unsold.is_empty() {
+        println!("\nNOT EXITED:");
+        for (token_id, size, reason) in &unsold {
+            println!("  {:>10.2} {}  {}", size, token_id, reason);
+        }
+    }
+    println!("cash {}", wallet.usdc);
+    Ok(unsold.is_empty())
+}
+
+#[cfg(feature = "network")]
+/// Show how long capital has been locked per position versus its resolution date
+pub async fn aging(profile: &Profile, hurdle: f64, output: OutputFormat) -> Result<(), String> {
+    let (_, journal) = open_journal(profile)?;
+    let mut wallet = Wallet::new(profile.starting_balance);
+    manual::replay_journal(&mut wallet, &journal);
+
+    let gamma = GammaClient::new(&profile.gamma_url);
+    let clob = ClobClient::new(&profile.clob_url);
+    let mut market_ids: Vec<&str> = journal.entries.iter().map(|e| e.market_id.as_str()).collect();
+    market_ids.sort();
+    market_ids.dedup();
+
+    let mut markets = Vec::new();
+    let mut prices = HashMap::new();
+    let mut bids = HashMap::new();
+    for id in market_ids {
+        let Some(market) = gamma.market_by_id(id).await.map_err(|e| e.to_string())? else {
+            continue;
+        };
+        for token_id in market.clob_token_ids.iter().filter(|t| wallet.positions.contains_key(*t)) {
+            let Ok(book) = clob.order_book(token_id).await else { continue };
+            if let Some(mid) = book.midpoint() {
+                prices.insert(token_id.clone(), mid);
+            }
+            if let Some(bid) = book.best_bid() {
+                bids.insert(token_id.clone(), bid);
+            }
+        }
+        markets.push(market);
+    }
+
+    // Sibling markets of every event we hold, for event-level pricing
+    let mut event_ids: Vec<String> = markets.iter().filter_map(|m| m.event_id.clone()).collect();
+    event_ids.sort();
+    event_ids.dedup();
+    let mut event_markets: Vec<Market> = markets.iter().filter(|m| m.event_id.is_none()).cloned().collect();
+    let mut events = Vec::new();
+    for event_id in &event_ids {
+        match gamma.event(event_id).await.map_err(|e| e.to_string())? {
+            Some(event) if !event.markets.is_empty() => {
+                event_markets.extend(event.markets.iter().map(|m| m.market.clone()));
+                events.push(event);
+            }
+            _ => event_markets.extend(markets.iter().filter(|m| m.event_id.as_ref() == Some(event_id)).cloned()),
+        }
+    }
+
+    // Resting orders as of the bot's last snapshot, and payouts of undisputed proposals
+    let mut orders = OrderManager::new();
+    if let Some(snapshot) = PortfolioSnapshot::load_at(Path::new(SNAPSHOTS_PATH), now()).map_err(|e| format!("{}: {}", SNAPSHOTS_PATH, e))? {
+        orders.open = snapshot.open_orders.iter().map(|o| (o.order_id.clone(), OpenOrder::from(o))).collect();
+    }
+    let mut resolutions = ResolutionMonitor::new();
+    for market in &markets {
+        resolutions.observe(market);
+    }
+    let pending = resolutions.outlook(&wallet).incoming;
+
+    let report = reports::AgingReport {
+        positions: reports::aging_report(&wallet, &markets, &prices, now(), hurdle),
+        concentration: reports::concentration_report(&wallet, &orders, &markets, &prices, pending, profile.risk.concentration_alert),
+        carry: reports::carry_report(&wallet, &markets, &prices, &bids, now(), &profile.carry),
+        // Under a cent is rounding in Gamma's displayed prices, not a mispricing
+        events: reports::event_report(&wallet, &event_markets, &events, &prices, &ConstraintChecker::new(0.01)),
+    };
+    match output {
+        OutputFormat::Json => return output::print_json(&report),
+        OutputFormat::Csv => return output::print_csv(&report.positions),
+        OutputFormat::Table => {}
+    }
+
+    println!("{:<12} {:>10} {:>8} {:>8} {:>10} {:>10}  question", "market", "cost", "held(d)", "left(d)", "annual", "");
+    for r in &report.positions {
+        println!(
+            "{:<12} {:>10.2} {:>8.1} {:>8} {:>10} {:>10}  {}",
+            r.market_id,
+            r.cost_basis,
+            r.days_held,
+            r.days_to_resolution.map(|d| format!("{:.1}", d)).unwrap_or_else(|| "?".to_string()),
+            r.annualized_return.map(|a| format!("{:.1}%", a * 100.0)).unwrap_or_else(|| "?".to_string()),
+            if r.disputed { "DISPUTED" } else if r.recycle { "RECYCLE" } else { "" },
+            r.question
+        );
+    }
+
+    if !report.concentration.is_empty() {
+        println!("\n{:<12} {:>10} {:>10} {:>8}  question", "market", "value", "locked", "share");
+    }
+    for r in &report.concentration {
+        println!(
+            "{:<12} {:>10.2} {:>10.2} {:>7.1}%{}  {}",
+            r.market_id,
+            r.position_value,
+            r.locked,
+            r.share * 100.0,
+            if r.alert { "!" } else { " " },
+            r.question
+        );
+    }
+
+    if !report.carry.is_empty() {
+        println!("\n{:<12} {:>10} {:>10} {:>10} {:>10}  vs", "market", "exit", "payoff", "carry", "excess");
+    }
+    for r in &report.carry {
+        println!(
+            "{:<12} {:>10.2} {:>10.2} {:>9.1}% {:>+9.1}%  {}{}",
+            r.market_id,
+            r.exit_value,
+            r.expected_payoff,
+            r.carry * 100.0,
+            r.excess * 100.0,
+            r.alternative.name,
+            if r.exit_recommended { "  EXIT" } else { "" }
+        );
+    }
+
+    if !report.events.is_empty() {
+        println!("\n{:<12} {:>7} {:>10} {:>10} {:>8}  {:<12} title", "event", "markets", "cost", "mark", "implied", "arb");
+    }
+    for e in &report.events {
+        let arb = match (e.set_spread, e.binary_arbs) {
+            (Some(spread), _) => format!("set {:.1}c", spread * 100.0),
+            (None, 0) => "-".to_string(),
+            (None, n) => format!("{} binary", n),
+        };
+        println!(
+            "{:<12} {:>7} {:>10.2} {:>10.2} {:>7.1}%{}  {:<12} {}",
+            e.event_id,
+            e.market_ids.len(),
+            e.cost_basis,
+            e.mark_value,
+            e.implied_sum * 100.0,
+            if e.exclusive { "*" } else { " " },
+            arb,
+            e.title
+        );
+    }
+    Ok(())
+}
+
+#[cfg(feature = "network")]
+/// Propose trims of one-sided inventory against live books. Trades are only listed here;
+/// `rebalance.mode = "execute"` lets a running bot place them.
+pub async fn rebalance(profile: &Profile, output: OutputFormat) -> Result<(), String> {
+    let (_, journal) = open_journal(profile)?;
+    let mut wallet = Wallet::new(profile.starting_balance);
+    manual::replay_journal(&mut wallet, &journal);
+
+    let gamma = GammaClient::new(&profile.gamma_url);
+    let clob = ClobClient::new(&profile.clob_url);
+    let mut market_ids: Vec<&str> = journal.entries.iter().map(|e| e.market_id.as_str()).collect();
+    market_ids.sort();
+    market_ids.dedup();
+    let mut markets = Vec::new();
+    let mut books = HashMap::new();
+    for id in market_ids {
+        let Some(market) = gamma.market_by_id(id).await.map_err(|e| e.to_string())? else {
+            continue;
+        };
+        for token_id in market.clob_token_ids.iter().filter(|t| wallet.positions.contains_key(*t)) {
+            if let Ok(book) = clob.order_book(token_id).await {
+                books.insert(token_id.clone(), book);
+            }
+        }
+        markets.push(market);
+    }
+    if markets.is_empty() {
+        return Err("no held markets found".to_string());
+    }
+
+    let mut config = profile.rebalance.clone();
+    if config.mode == RebalanceMode::Off {
+        config.mode = RebalanceMode::Propose;
+    }
+    let plan = Rebalancer::new(config).plan(&wallet, &markets, &books, |market| fee_schedule(profile, market));
+    match output {
+        OutputFormat::Json => return output::print_json(&plan),
+        OutputFormat::Csv => return output::print_csv(&plan.trades),
+        OutputFormat::Table => {}
+    }
+
+    println!("skew {:+.2} (limit ±{:.2})", plan.skew_before, profile.rebalance.max_skew);
+    if plan.trades.is_empty() {
+        println!("nothing to rebalance");
+        return Ok(());
+    }
+    println!("{:<12} {:<4} {:>10} {:>8} {:>8} {:>10} {:>8}", "market", "leg", "size", "price", "fees", "proceeds", "pnl");
+    for t in &plan.trades {
+        println!(
+            "{:<12} {:<4} {:>10.2} {:>8.3} {:>8.3} {:>10.2} {:>+8.3}",
+            t.market_id,
+            format!("{:?}", t.outcome),
+            t.size,
+            t.price,
+            t.fees,
+            t.proceeds,
+            t.pnl
+        );
+    }
+    println!("frees {:.2} USDC; skew after {:+.2}", plan.freed(), plan.skew_after);
+    Ok(())
+}
+
+/// Write today's market snapshot (no-op if it already exists)
+#[cfg(all(feature = "archive", feature = "network"))]
+pub async fn archive(profile: &Profile, dir: &str) -> Result<(), String> {
+    let archiver = MarketArchiver::new(Path::new(dir));
+    let gamma = GammaClient::new(&profile.gamma_url);
+    match archiver.snapshot(&gamma, now()).await.map_err(|e| e.to_string())? {
+        Some((path, count)) => println!("archived {} markets to {}", count, path.display()),
+        None => println!("{} already exists", archiver.path_for(now()).display()),
+    }
+    Ok(())
+}
+
+/// Build resolution history from every archived snapshot, score the markets in the latest
+/// one and save the table for the detector
+#[cfg(feature = "archive")]
+pub fn quality(profile: &Profile, dir: &str, top: usize, output: OutputFormat) -> Result<(), String> {
+    let snapshots = MarketArchiver::new(Path::new(dir)).snapshots().map_err(|e| format!("{}: {}", dir, e))?;
+    let mut history = QualityHistory::new();
+    let mut latest = Vec::new();
+    for path in &snapshots {
+        // markets-YYYY-MM-DD.jsonl.gz
+        let Some(seen_at) = path.file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_prefix("markets-"))
+            .and_then(|n| n.get(..10))
+            .and_then(crate::types::parse_iso8601)
+        else {
+            continue;
+        };
+        latest = MarketArchiver::load(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        history.observe(seen_at, &latest);
+    }
+    if latest.is_empty() {
+        return Err(format!("no market snapshots in {} (run `polyshark archive` daily)", dir));
+    }
+
+    let table = QualityTable::compute(&latest, &history, &profile.quality, now());
+    table.save(Path::new(QUALITY_TABLE_PATH)).map_err(|e| format!("{}: {}", QUALITY_TABLE_PATH, e))?;
+    let below = table.scores.values().filter(|s| s.score < profile.quality.min_score).count();
+    let summary = format!(
+        "scored {} markets from {} snapshots; {} below {:.2} written to {}",
+        table.scores.len(),
+        snapshots.len(),
+        below,
+        profile.quality.min_score,
+        QUALITY_TABLE_PATH
+    );
+
+    let mut worst: Vec<QualityRow> = latest.iter()
+        .filter_map(|m| {
+            let score = table.score(&m.id).filter(|s| s.score < 1.0)?;
+            Some(QualityRow { market_id: m.id.clone(), slug: m.slug.clone(), score: score.score, reasons: score.reasons.clone() })
+        })
+        .collect();
+    worst.sort_by(|a, b| a.score.total_cmp(&b.score));
+    worst.truncate(top);
+    match output {
+        // Keep stdout to the data
+        OutputFormat::Json => {
+            eprintln!("{}", summary);
+            output::print_json(&worst)
+        }
+        OutputFormat::Csv => {
+            eprintln!("{}", summary);
+            output::print_csv(&worst)
+        }
+        OutputFormat::Table => {
+            println!("{}", summary);
+            for row in &worst {
+                println!("{:>5.2}  {:<50}  {}", row.score, row.slug, row.reasons.join("; "));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Near-threshold rejections the bot logged for one UTC day, by the cost that killed them
+pub fn why_not(day: Option<u64>, output: OutputFormat) -> Result<(), String> {
+    let day_start = day.unwrap_or_else(now) / 86_400 * 86_400;
+    let rejections = match explain::load_rejections(Path::new(WHY_NOT_LOG_PATH)) {
+        Ok(rejections) => rejections,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(format!("{}: {}", WHY_NOT_LOG_PATH, err)),
+    };
+    let rows = WhyNotReport::from_rejections(rejections).daily(day_start);
+    match output {
+        OutputFormat::Json => output::print_json(&rows),
+        OutputFormat::Csv => output::print_csv(&rows),
+        OutputFormat::Table => {
+            if rows.is_empty() {
+                println!("no near-threshold rejections logged that day");
+            }
+            for row in &rows {
+                println!(
+                    "{:<17} {:>6} rejected  {:>10.4} USDC forgone  {:>8.4} short of min profit on average",
+                    row.component, row.rejections, row.forgone_gross, row.avg_shortfall
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Saved pairs and review decisions, or an empty matcher before the first proposal
+fn open_matcher(profile: &Profile) -> Result<MarketMatcher, String> {
+    let path = Path::new(MATCHED_PAIRS_PATH);
+    if !path.exists() {
+        return Ok(MarketMatcher::from_config(&profile.matcher));
+    }
+    MarketMatcher::load(path, &profile.matcher).map_err(|e| format!("{}: {}", MATCHED_PAIRS_PATH, e))
+}
+
+/// Propose duplicate-market pairs from the first `limit` active Gamma markets; pairs
+/// already reviewed are never proposed again
+#[cfg(feature = "network")]
+pub async fn propose_pairs(profile: &Profile, limit: usize) -> Result<(), String> {
+    const PAGE: usize = 100;
+    let gamma = GammaClient::new(&profile.gamma_url);
+    let mut markets = Vec::new();
+    while markets.len() < limit {
+        let page = gamma.markets(PAGE.min(limit - markets.len()), markets.len()).await.map_err(|e| e.to_string())?;
+        if page.is_empty() {
+            break;
+        }
+        markets.extend(page);
+    }
+
+    let mut matcher = open_matcher(profile)?;
+    let proposed = matcher.propose(&markets);
+    matcher.save(Path::new(MATCHED_PAIRS_PATH)).map_err(|e| format!("{}: {}", MATCHED_PAIRS_PATH, e))?;
+    let questions: HashMap<&str, &str> = markets.iter().map(|m| (m.id.as_str(), m.question.as_str())).collect();
+    eprintln!("{} new candidate pairs from {} markets", proposed.len(), markets.len());
+    for pair in &proposed {
+        eprintln!("  {:.2}  {} {:?}", pair.similarity, pair.market_a, questions.get(pair.market_a.as_str()).unwrap_or(&""));
+        eprintln!("        {} {:?}", pair.market_b, questions.get(pair.market_b.as_str()).unwrap_or(&""));
+    }
+    Ok(())
+}
+
+/// List pending and reviewed duplicate-market pairs
+pub fn pairs(profile: &Profile, output: OutputFormat) -> Result<(), String> {
+    let rows = open_matcher(profile)?.rows();
+    match output {
+        OutputFormat::Json => output::print_json(&rows),
+        OutputFormat::Csv => output::print_csv(&rows),
+        OutputFormat::Table => {
+            if rows.is_empty() {
+                println!("no candidate pairs (run `polyshark pairs --propose`)");
+            }
+            for row in &rows {
+                let gap = row.end_gap_secs.map(|s| format!("{:.1}h", s as f64 / 3600.0)).unwrap_or_else(|| "-".to_string());
+                println!("{:<10} {:>5.2}  {:>7}  {}  {}", format!("{:?}", row.status).to_lowercase(), row.similarity, gap, row.market_a, row.market_b);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Confirm or reject a proposed pair
+pub fn review_pair(profile: &Profile, market_a: &str, market_b: &str, status: PairStatus) -> Result<(), String> {
+    let mut matcher = open_matcher(profile)?;
+    let found = match status {
+        PairStatus::Confirmed => matcher.confirm(market_a, market_b),
+        PairStatus::Rejected => matcher.reject(market_a, market_b),
+        PairStatus::Pending => return Err("a pair can only be confirmed or rejected".to_string()),
+    };
+    if !found {
+        return Err(format!("{} / {} was never proposed (run `polyshark pairs --propose`)", market_a, market_b));
+    }
+    matcher.save(Path::new(MATCHED_PAIRS_PATH)).map_err(|e| format!("{}: {}", MATCHED_PAIRS_PATH, e))?;
+    println!("{} / {} {:?}; the bot picks this up on its next start", market_a, market_b, status);
+    Ok(())
+}
+
+/// Step through a recorded trade again
+pub fn replay_trade(profile: &Profile, trade_id: u64) -> Result<(), String> {
+    let record = TradeRecord::load(Path::new(TRADE_RECORDS_PATH), trade_id)
+        .map_err(|e| format!("{}: {}", TRADE_RECORDS_PATH, e))?
+        .ok_or_else(|| format!("no recorded opportunity for trade #{}", trade_id))?;
+    let (_, journal) = open_journal(profile)?;
+    replay::replay(&record, journal.get(trade_id), &mut std::io::stdout()).map_err(|e| e.to_string())
+}
+
+/// Dump recorded books for research (stdout unless `out` is given)
+pub fn export_depth(profile: &Profile, tokens: &[TokenId], from: u64, to: u64, format: DepthFormat, out: Option<&str>) -> Result<(), String> {
+    let mut storage = profile.storage.clone().unwrap_or_default().open().map_err(|e| e.to_string())?;
+    let mut books = Vec::new();
+    for token in tokens {
+        books.extend(storage.load_books(token, from, to).map_err(|e| e.to_string())?);
+    }
+    books.sort_by_key(|b| b.timestamp);
+    if books.is_empty() {
+        return Err("no recorded books in that range (books are saved by `run` when bot.record_books_ms is set)".to_string());
+    }
+
+    let mut writer: Box<dyn Write> = match out {
+        Some(path) => Box::new(BufWriter::new(File::create(path).map_err(|e| format!("{}: {}", path, e))?)),
+        None => Box::new(BufWriter::new(std::io::stdout())),
+    };
+    let rows = depth::write_depth(&books, format, &mut writer).map_err(|e| e.to_string())?;
+    writer.flush().map_err(|e| e.to_string())?;
+    if let Some(path) = out {
+        println!("wrote {} rows from {} books to {}", rows, books.len(), path);
+    }
+    Ok(())
+}
+
+/// Check saved backtest runs against the recorded books `delay_ms` after each fill
+#[cfg(feature = "bookstore")]
+pub fn lookahead(results: &[String], dir: &str, delay_ms: u64, output: OutputFormat) -> Result<(), String> {
+    let reader = BookReader::open(Path::new(dir)).map_err(|e| format!("{}: {}", dir, e))?;
+    let mut reports = Vec::new();
+    for path in results {
+        let result = BacktestResult::load(Path::new(path)).map_err(|e| format!("{}: {}", path, e))?;
+        reports.push(backtest::LookaheadReport::run(&result, delay_ms, |market_id, ts| {
+            reader.market_at(market_id, ts)
+        }));
+    }
+    match output {
+        OutputFormat::Json => return output::print_json(&reports),
+        OutputFormat::Csv => return output::print_csv(&reports.iter().map(|r| r.summary()).collect::<Vec<_>>()),
+        OutputFormat::Table => {}
+    }
+
+    println!(
+        "{:<20} {:>7} {:>9} {:>8} {:>12} {:>12} {:>10}",
+        "run", "trades", "unchecked", "capture", "simulated", "realistic", "gap"
+    );
+    for r in &reports {
+        println!(
+            "{:<20} {:>7} {:>9} {:>7.1}% {:>+12.4} {:>+12.4} {:>10.4}",
+            r.label,
+            r.trades,
+            r.unchecked,
+            r.capture_rate() * 100.0,
+            r.simulated_pnl,
+            r.realistic_pnl,
+            r.optimism_gap()
+        );
+    }
+    println!("(fills checked against the books {}ms later; unchecked trades are left out of the totals)", delay_ms);
+    for r in &reports {
+        for err in &r.read_errors {
+            eprintln!("⚠️  {}: books unreadable for {}", r.label, err);
+        }
+    }
+    Ok(())
+}
+
+/// Integrity of a book recording, per market; Ok(false) when anything is wrong with it
+#[cfg(feature = "bookstore")]
+pub fn check_data(dir: &str, max_gap_ms: u64, output: OutputFormat) -> Result<bool, String> {
+    let reader = BookReader::open(Path::new(dir)).map_err(|e| format!("{}: {}", dir, e))?;
+    let markets = datacheck::check_store(&reader, max_gap_ms);
+    if markets.is_empty() {
+        return Err(format!("no recorded books in {}", dir));
+    }
+    let clean = markets.iter().all(datacheck::MarketCheck::is_clean);
+    match output {
+        OutputFormat::Json => return output::print_json(&markets).map(|_| clean),
+        OutputFormat::Csv => return output::print_csv(&markets).map(|_| clean),
+        OutputFormat::Table => {}
+    }
+
+    println!(
+        "{:<24} {:>6} {:>8} {:>5} {:>9} {:>6} {:>7} {:>8} {:>5} {:>7}",
+        "market", "tokens", "frames", "gaps", "max gap", "order", "crossed", "no snap", "lost", "partial"
+    );
+    for m in markets.iter().filter(|m| !m.is_clean()) {
+        println!(
+            "{:<24} {:>6} {:>8} {:>5} {:>8}s {:>6} {:>7} {:>8} {:>5} {:>7}",
+            m.market_id.as_str(),
+            m.tokens,
+            m.frames,
+            m.gaps,
+            m.longest_gap_ms / 1000,
+            m.out_of_order,
+            m.crossed,
+            m.missing_snapshots,
+            m.lost_frames,
+            m.partial_tokens
+        );
+    }
+    let dirty = markets.iter().filter(|m| !m.is_clean()).count();
+    println!("{} of {} markets have issues (gaps over {}s)", dirty, markets.len(), max_gap_ms / 1000);
+    Ok(clean)
+}
+
+/// Write closed tax lots from the journal as CSV (stdout unless `out` is given)
+pub fn export_lots(profile: &Profile, method: Option<LotMethod>, out: Option<&str>) -> Result<(), String> {
+    let (_, journal) = open_journal(profile)?;
+    let lots = LotBook::from_journal(&journal, method.unwrap_or(profile.lot_method));
+
+    let mut writer: Box<dyn Write> = match out {
+        Some(path) => Box::new(BufWriter::new(File::create(path).map_err(|e| format!("{}: {}", path, e))?)),
+        None => Box::new(BufWriter::new(std::io::stdout())),
+    };
+    let rows = lots.write_csv(&mut writer).map_err(|e| e.to_string())?;
+    writer.flush().map_err(|e| e.to_string())?;
+    if let Some(path) = out {
+        println!("wrote {} lots ({:?}, realized {:.2}) to {}", rows, lots.method, lots.realized(), path);
+    }
+    Ok(())
+}
+
+/// Realized PnL per entry reason, e.g. whether hedging failed legs is a steady drag
+pub fn attribution(profile: &Profile, method: Option<LotMethod>, output: OutputFormat) -> Result<(), String> {
+    let (_, journal) = open_journal(profile)?;
+    let method = method.unwrap_or(profile.lot_method);
+    let rows = attribution::attribute(&journal, method);
+    match output {
+        OutputFormat::Json => return output::print_json(&rows),
+        OutputFormat::Csv => return output::print_csv(&rows),
+        OutputFormat::Table => {}
+    }
+
+    println!(
+        "{:<11} {:>6} {:>10} {:>10} {:>10} {:>9} {:>8} {:>10} {:>10}",
+        "reason", "fills", "bought", "closed", "realized", "per fill", "win", "open", "open cost"
+    );
+    for r in &rows {
+        println!(
+            "{:<11} {:>6} {:>10.2} {:>10.2} {:>+10.2} {:>+9.3} {:>7.1}% {:>10.2} {:>10.2}",
+            r.reason.tag(),
+            r.fills,
+            r.bought,
+            r.closed_size,
+            r.realized_pnl,
+            r.pnl_per_fill(),
+            r.win_rate() * 100.0,
+            r.open_size,
+            r.open_cost
+        );
+    }
+    let total: f64 = rows.iter().map(|r| r.realized_pnl).sum();
+    println!("total realized {:+.2} ({:?} lots; fees included)", total, method);
+    Ok(())
+}
+
+/// Fills, volume, fees and realized PnL of the matching journal entries, per tag
+pub fn report(profile: &Profile, filter: &JournalFilter, output: OutputFormat) -> Result<(), String> {
+    let (_, journal) = open_journal(profile)?;
+    let report = JournalReport { total: journal.summary(filter), tags: journal.summary_by_tag(filter) };
+    match output {
+        OutputFormat::Json => return output::print_json(&report),
+        OutputFormat::Csv => return output::print_csv(&report.tags),
+        OutputFormat::Table => {}
+    }
+
+    println!("{:<16} {:>6} {:>12} {:>10} {:>12} {:>9} {:>9}", "tag", "fills", "volume", "fees", "realized", "max mae", "avg mae");
+    let row = |name: &str, s: &JournalSummary| {
+        println!(
+            "{:<16} {:>6} {:>12.2} {:>10.4} {:>+12.2} {:>9.2} {:>9.2}",
+            name, s.entries, s.volume, s.fees, s.realized_pnl, s.max_mae, s.avg_mae
+        );
+    };
+    for t in &report.tags {
+        row(&t.tag, &t.summary);
+    }
+    row("all", &report.total);
+    Ok(())
+}
+
+/// Matching journal entries as CSV, to `out` or stdout
+pub fn export_journal(profile: &Profile, filter: &JournalFilter, out: Option<&str>) -> Result<(), String> {
+    let (_, journal) = open_journal(profile)?;
+    let mut writer: Box<dyn Write> = match out {
+        Some(path) => Box::new(BufWriter::new(File::create(path).map_err(|e| format!("{}: {}", path, e))?)),
+        None => Box::new(BufWriter::new(std::io::stdout())),
+    };
+    let rows = journal.write_csv(&mut writer, filter).map_err(|e| e.to_string())?;
+    writer.flush().map_err(|e| e.to_string())?;
+    if let Some(path) = out {
+        println!("wrote {} journal entries to {}", rows, path);
+    }
+    Ok(())
+}
+
+/// Tag, untag or note one journal entry and save it back to storage
+pub fn annotate(profile: &Profile, id: u64, tag: Option<&str>, untag: Option<&str>, note: Option<&str>) -> Result<(), String> {
+    let (mut storage, mut journal) = open_journal(profile)?;
+    let missing = || format!("no journal entry {}", id);
+    if let Some(tag) = tag {
+        journal.tag(id, tag).then_some(()).ok_or_else(missing)?;
+    }
+    if let Some(tag) = untag {
+        journal.untag(id, tag).then_some(()).ok_or_else(missing)?;
+    }
+    if let Some(note) = note {
+        journal.set_note(id, note).then_some(()).ok_or_else(missing)?;
+    }
+    let entry = journal.get(id).ok_or_else(missing)?;
+    storage.update_entry(entry).map_err(|e| format!("journal: {}", e))?;
+    println!("entry {}: tags [{}]{}", id, entry.tags.join(", "), entry.note.as_deref().map(|n| format!(", note \"{}\"", n)).unwrap_or_default());
+    Ok(())
+}
+
+/// Portfolio at `at`: the latest recorded snapshot if no fills landed after it, otherwise
+/// rebuilt from the journal
+fn portfolio_at(profile: &Profile, journal: &Journal, at: u64) -> Result<PortfolioSnapshot, String> {
+    let recorded = PortfolioSnapshot::load_at(Path::new(SNAPSHOTS_PATH), at).map_err(|e| format!("{}: {}", SNAPSHOTS_PATH, e))?;
+    Ok(match recorded {
+        Some(s) if !journal.entries.iter().any(|e| e.timestamp > s.timestamp && e.timestamp <= at) => s,
+        _ => PortfolioSnapshot::from_journal(journal, profile.starting_balance, profile.lot_method, at),
+    })
+}
+
+/// Cash, locked funds, equity and positions now
+pub fn balance(profile: &Profile, output: OutputFormat) -> Result<(), String> {
+    let (_, journal) = open_journal(profile)?;
+    let portfolio = portfolio_at(profile, &journal, now())?;
+    match output {
+        OutputFormat::Json => return output::print_json(&portfolio),
+        OutputFormat::Csv => return output::print_csv(&portfolio.positions),
+        OutputFormat::Table => {}
+    }
+
+    for (label, value) in [("cash", portfolio.cash), ("locked", portfolio.locked), ("equity", portfolio.equity)] {
+        println!("{:<8} {:>12.2}", label, value);
+    }
+    if !portfolio.positions.is_empty() {
+        println!("\n{:<24} {:>5} {:>10} {:>8} {:>8} {:>10}", "token", "side", "size", "entry", "mark", "value");
+    }
+    for p in &portfolio.positions {
+        println!(
+            "{:<24} {:>5} {:>10.2} {:>8.3} {:>8.3} {:>10.2}",
+            p.token_id.as_str(), format!("{:?}", p.side), p.size, p.entry_price, p.mark, p.value()
+        );
+    }
+    if portfolio.from_journal {
+        println!("\nrebuilt from the journal: marks are last fill prices, locked funds unknown");
+    }
+    Ok(())
+}
+
+/// Resting orders from the latest snapshot, if no fills landed since
+pub fn orders(profile: &Profile, output: OutputFormat) -> Result<(), String> {
+    let (_, journal) = open_journal(profile)?;
+    let portfolio = portfolio_at(profile, &journal, now())?;
+    match output {
+        OutputFormat::Json => return output::print_json(&portfolio.open_orders),
+        OutputFormat::Csv => return output::print_csv(&portfolio.open_orders),
+        OutputFormat::Table => {}
+    }
+
+    if portfolio.from_journal {
+        println!("no snapshot since the last fill; resting orders are only known to the running bot");
+        return Ok(());
+    }
+    if portfolio.open_orders.is_empty() {
+        println!("no resting orders at {}", portfolio.timestamp);
+    }
+    for o in &portfolio.open_orders {
+        println!("{:<20} {:<24} {:>4} {:>10.2} @ {:.3}", o.order_id, o.token_id.as_str(), format!("{:?}", o.side), o.remaining, o.price);
+    }
+    Ok(())
+}
+
+/// Compare the portfolio at two points in time
+pub fn diff(profile: &Profile, from: u64, to: u64, output: OutputFormat) -> Result<(), String> {
+    let (_, journal) = open_journal(profile)?;
+    let diff = PortfolioDiff::between(portfolio_at(profile, &journal, from)?, portfolio_at(profile, &journal, to)?, &journal);
+    match output {
+        OutputFormat::Json => return output::print_json(&diff),
+        OutputFormat::Csv => return output::print_csv(&diff.positions),
+        OutputFormat::Table => {}
+    }
+
+    let source = |s: &PortfolioSnapshot| if s.from_journal { "journal" } else { "snapshot" };
+    println!(
+        "{:<8} {:>12} {:>12} {:>10}",
+        "", format!("{} ({})", diff.from.timestamp, source(&diff.from)), format!("{} ({})", diff.to.timestamp, source(&diff.to)), "change"
+    );
+    for (label, before, after) in [
+        ("cash", diff.from.cash, diff.to.cash),
+        ("locked", diff.from.locked, diff.to.locked),
+        ("equity", diff.from.equity, diff.to.equity),
+    ] {
+        println!("{:<8} {:>12.2} {:>12.2} {:>+10.2}", label, before, after, after - before);
+    }
+
+    let f = &diff.fills;
+    println!(
+        "\n{} fills: bought {:.2}, sold {:.2}, fees {:.2}, realized {:+.2}",
+        f.fills, f.bought, f.sold, f.fees, f.realized_pnl
+    );
+
+    if !diff.positions.is_empty() {
+        println!("\n{:<24} {:>10} {:>10} {:>10} {:>10}", "token", "size", "-> size", "value", "-> value");
+    }
+    for p in &diff.positions {
+        println!(
+            "{:<24} {:>10.2} {:>10.2} {:>10.2} {:>10.2}",
+            p.token_id.as_str(), p.size_before, p.size_after, p.value_before, p.value_after
+        );
+    }
+
+    for (label, orders) in [("added", &diff.orders_added), ("gone", &diff.orders_removed)] {
+        for o in orders {
+            println!("order {:<5} {} {:?} {:.2} @ {:.3} on {}", label, o.order_id, o.side, o.remaining, o.price, o.token_id);
+        }
+    }
+    if diff.from.from_journal || diff.to.from_journal {
+        println!("\n(rebuilt from the journal: open orders and locked funds are not recorded there)");
+    }
+    Ok(())
+}
+
+/// Net PnL and capture rate per niche (category, time of day, liquidity tier, edge)
+pub fn clusters(profile: &Profile, min_trades: usize, output: OutputFormat) -> Result<(), String> {
+    let (_, journal) = open_journal(profile)?;
+    let records = TradeRecord::load_all(Path::new(TRADE_RECORDS_PATH)).unwrap_or_default();
+    let missed = capture::load_missed(Path::new(MISSED_LOG_PATH)).unwrap_or_default();
+    let rows = clusters::cluster_trades(&journal, &records, &missed, &profile.liquidity_tiers);
+    let (shown, hidden): (Vec<clusters::ClusterRow>, Vec<_>) = rows.into_iter().partition(|r| r.trades >= min_trades);
+    match output {
+        OutputFormat::Json => return output::print_json(&shown),
+        OutputFormat::Csv => return output::print_csv(&shown),
+        OutputFormat::Table => {}
+    }
+
+    println!(
+        "{:<16} {:<6} {:<8} {:<7} {:>6} {:>6} {:>8} {:>10} {:>9} {:>8}",
+        "category", "hours", "tier", "edge", "trades", "missed", "capture", "net pnl", "per trade", "fees"
+    );
+    for r in &shown {
+        println!(
+            "{:<16} {:<6} {:<8} {:<7} {:>6} {:>6} {:>7.1}% {:>+10.2} {:>+9.3} {:>8.2}",
+            r.key.category,
+            r.key.hours,
+            r.key.tier,
+            r.key.edge,
+            r.trades,
+            r.missed,
+            r.capture_rate() * 100.0,
+            r.net_pnl,
+            r.pnl_per_trade(),
+            r.fees
+        );
+    }
+    if !hidden.is_empty() {
+        println!("({} clusters with fewer than {} trades hidden)", hidden.len(), min_trades);
+    }
+    Ok(())
+}
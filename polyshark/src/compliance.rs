@@ -0,0 +1,69 @@
+use crate::types::Market;
+use serde::Deserialize;
+
+/// User-defined jurisdiction/eligibility rules: markets matching any restriction are
+/// never entered unless explicitly listed in `allow`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ComplianceConfig {
+    #[serde(default)]
+    pub restricted_tags: Vec<String>,        // Gamma tags, case insensitive
+    #[serde(default)]
+    pub restricted_categories: Vec<String>,  // Gamma categories, case insensitive
+    #[serde(default)]
+    pub block_gamma_restricted: bool,        // Honor Gamma's own `restricted` flag
+    #[serde(default)]
+    pub allow: Vec<String>,                  // Market ids or slugs exempt from every rule
+}
+
+/// Why a market may not be traded
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComplianceBlock {
+    Tag(String),
+    Category(String),
+    GammaRestricted,
+}
+
+impl ComplianceBlock {
+    pub fn describe(&self) -> String {
+        match self {
+            ComplianceBlock::Tag(tag) => format!("restricted tag `{}`", tag),
+            ComplianceBlock::Category(category) => format!("restricted category `{}`", category),
+            ComplianceBlock::GammaRestricted => "flagged restricted by Gamma".to_string(),
+        }
+    }
+}
+
+/// Pre-trade gate applying a `ComplianceConfig`. Only entries are gated; exiting a
+/// position in a market that became restricted is always allowed.
+#[derive(Debug, Clone, Default)]
+pub struct ComplianceGate {
+    pub config: ComplianceConfig,
+}
+
+impl ComplianceGate {
+    pub fn new(config: ComplianceConfig) -> Self {
+        Self { config }
+    }
+
+    /// First rule the market breaks, if any
+    pub fn check(&self, market: &Market) -> Result<(), ComplianceBlock> {
+        let c = &self.config;
+        if c.allow.iter().any(|a| *a == market.id.as_str() || *a == market.slug) {
+            return Ok(());
+        }
+        if let Some(tag) = market.tags.iter().find(|t| c.restricted_tags.iter().any(|r| r.eq_ignore_ascii_case(t))) {
+            return Err(ComplianceBlock::Tag(tag.clone()));
+        }
+        if let Some(category) = market.category.as_ref().filter(|cat| c.restricted_categories.iter().any(|r| r.eq_ignore_ascii_case(cat))) {
+            return Err(ComplianceBlock::Category(category.clone()));
+        }
+        if c.block_gamma_restricted && market.restricted {
+            return Err(ComplianceBlock::GammaRestricted);
+        }
+        Ok(())
+    }
+
+    pub fn allows(&self, market: &Market) -> bool {
+        self.check(market).is_ok()
+    }
+}
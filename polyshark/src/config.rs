@@ -0,0 +1,332 @@
+use crate::activity::ScanConfig;
+use crate::clob::CLOB_API_URL;
+use crate::fees::FeeConfig;
+use crate::gamma::GAMMA_API_URL;
+use crate::instance::LockConfig;
+use crate::lots::LotMethod;
+use crate::merge::RecycleConfig;
+use crate::money::UsdConversion;
+use crate::pruning::PruneConfig;
+use crate::outlier::OutlierConfig;
+use crate::volatility::VolatilityConfig;
+use crate::compliance::ComplianceConfig;
+use crate::control::ControlConfig;
+use crate::paper::PaperLatencyConfig;
+use crate::polling::PollConfig;
+use crate::failover::FailoverConfig;
+use crate::gateway::LiveConfig;
+use crate::approval::SupervisedConfig;
+use crate::bot::BotConfig;
+use crate::checkpoint::CheckpointConfig;
+use crate::quality::QualityConfig;
+use crate::feedrift::FeeDriftConfig;
+use crate::priority::PriorityConfig;
+use crate::sparkline::SparklineConfig;
+use crate::backoff::BackoffConfig;
+use crate::rebalance::RebalanceConfig;
+use crate::router::RouterConfig;
+use crate::reconcile::ReconcileConfig;
+use crate::exit::ExitConfig;
+use crate::shadow::ShadowConfig;
+use crate::matcher::MatcherConfig;
+use crate::fastmove::FastMoveConfig;
+use crate::peg::PegConfig;
+use crate::reports::CarryConfig;
+use crate::risk::FeeBudgetMode;
+use crate::slippage::{default_liquidity_tiers, LiquidityTier};
+use crate::storage::StorageConfig;
+use crate::webhook::WebhookConfig;
+use crate::websocket::MARKET_WS_URL;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Flag that must accompany any live profile
+pub const LIVE_ACK_FLAG: &str = "--i-understand-live-trading";
+
+/// Whether orders go to the exchange
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradingMode {
+    Paper,  // Simulated fills against real books
+    Live,   // Real orders
+}
+
+/// Hard risk limits for a profile
+#[derive(Debug, Clone, Deserialize)]
+pub struct RiskLimits {
+    pub max_position_usdc: f64,   // Per market
+    pub max_total_exposure: f64,  // Across all markets
+    pub max_daily_loss: f64,
+    #[serde(default = "default_min_reserve")]
+    pub min_reserve_usdc: f64,    // Free capital never committed to new trades
+    #[serde(default = "default_max_pnl_swing")]
+    pub max_pnl_swing: f64,       // PnL drop that trips the circuit breaker
+    #[serde(default = "default_pnl_swing_window")]
+    pub pnl_swing_window_secs: u64,
+    #[serde(default = "default_max_es_fraction")]
+    pub max_es_fraction: f64,     // Expected shortfall cap as a fraction of equity
+    #[serde(default = "default_es_confidence")]
+    pub es_confidence: f64,       // Tail level, e.g. 0.95 averages the worst 5% of outcomes
+    #[serde(default)]
+    pub correlation_groups: HashMap<String, Vec<String>>,  // group -> market ids that resolve together
+    #[serde(default)]
+    pub daily_fee_budget: Option<f64>,  // USDC of fees per UTC day before entries are restricted
+    #[serde(default)]
+    pub fee_budget_mode: FeeBudgetMode,
+    #[serde(default = "default_concentration_alert")]
+    pub concentration_alert: f64,  // Share of equity in one market that raises an early warning
+}
+
+impl Default for RiskLimits {
+    fn default() -> Self {
+        Self {
+            max_position_usdc: 50.0,
+            max_total_exposure: 500.0,
+            max_daily_loss: 50.0,
+            min_reserve_usdc: default_min_reserve(),
+            max_pnl_swing: default_max_pnl_swing(),
+            pnl_swing_window_secs: default_pnl_swing_window(),
+            max_es_fraction: default_max_es_fraction(),
+            es_confidence: default_es_confidence(),
+            correlation_groups: HashMap::new(),
+            daily_fee_budget: None,
+            fee_budget_mode: FeeBudgetMode::default(),
+            concentration_alert: default_concentration_alert(),
+        }
+    }
+}
+
+fn default_concentration_alert() -> f64 {
+    0.25
+}
+
+fn default_min_reserve() -> f64 {
+    25.0
+}
+
+fn default_max_pnl_swing() -> f64 {
+    20.0
+}
+
+fn default_pnl_swing_window() -> u64 {
+    3600
+}
+
+fn default_max_es_fraction() -> f64 {
+    0.2
+}
+
+fn default_es_confidence() -> f64 {
+    0.95
+}
+
+/// One named set of endpoints, credentials and limits
+#[derive(Debug, Clone, Deserialize)]
+pub struct Profile {
+    pub mode: TradingMode,
+    #[serde(default = "default_gamma_url")]
+    pub gamma_url: String,
+    #[serde(default = "default_clob_url")]
+    pub clob_url: String,
+    #[serde(default = "default_ws_url")]
+    pub ws_url: String,
+    #[serde(default)]
+    pub api_key_env: Option<String>,  // Env var holding the key (never the key itself)
+    #[serde(default)]
+    pub live: Option<LiveConfig>,     // Signer and credentials for live orders
+    #[serde(default = "default_starting_balance")]
+    pub starting_balance: f64,        // Paper wallet size
+    #[serde(default)]
+    pub risk: RiskLimits,
+    #[serde(default)]
+    pub bot: BotConfig,               // Detection thresholds and watchlist for `run`
+    #[serde(default)]
+    pub fees: Option<FeeConfig>,
+    #[serde(default)]
+    pub storage: Option<StorageConfig>,
+    #[serde(default)]
+    pub lock: LockConfig,             // Single-instance lock for anything that trades or writes the journal
+    #[serde(default)]
+    pub carry: CarryConfig,           // Alternatives held positions are compared against
+    #[serde(default = "default_liquidity_tiers")]
+    pub liquidity_tiers: Vec<LiquidityTier>,  // Slippage buffer by book depth
+    #[serde(default)]
+    pub usd: UsdConversion,           // USD display alongside USDC balances
+    #[serde(default)]
+    pub webhooks: WebhookConfig,      // Where detected signals are pushed
+    #[serde(default)]
+    pub scan: ScanConfig,             // Scan cadence by each market's busy hours
+    #[serde(default)]
+    pub lot_method: LotMethod,        // Which tax lots partial closes consume first
+    #[serde(default)]
+    pub prune: PruneConfig,           // When illiquid markets leave the watchlist
+    #[serde(default)]
+    pub outliers: OutlierConfig,      // Sanity limits on streamed prices and sizes
+    #[serde(default)]
+    pub volatility: VolatilityConfig, // Extra minimum edge for jumpy markets
+    #[serde(default)]
+    pub compliance: ComplianceConfig, // Restricted tags/categories never entered
+    #[serde(default)]
+    pub paper_latency: PaperLatencyConfig,  // Injected submission delay and rejections (paper only)
+    #[serde(default)]
+    pub poll: PollConfig,             // REST polling budget when WebSocket data is unavailable
+    #[serde(default)]
+    pub failover: Option<FailoverConfig>,  // Primary/standby pair sharing the journal and lock
+    #[serde(default)]
+    pub supervised: Option<SupervisedConfig>,  // Large trades wait for operator approval
+    #[serde(default)]
+    pub control: Option<ControlConfig>,  // Authenticated local API for pause/resume/flatten and bot state
+    #[serde(default)]
+    pub checkpoint: CheckpointConfig,  // Per-market detector state kept across restarts
+    #[serde(default)]
+    pub quality: QualityConfig,       // Resolution-risk heuristics for the market universe
+    #[serde(default)]
+    pub fee_drift: FeeDriftConfig,    // When charged fees diverge from the fee model
+    #[serde(default)]
+    pub priority: PriorityConfig,     // Fresher data for tokens strategies are actively working
+    #[serde(default)]
+    pub min_resting_ms: u64,          // Orders can't be cancelled or replaced sooner (0 = no minimum)
+    #[serde(default)]
+    pub sparklines: SparklineConfig,  // Per-market mid history kept for quick context
+    #[serde(default)]
+    pub backoff: BackoffConfig,       // Markets whose placements keep failing stop being signalled
+    #[serde(default)]
+    pub rebalance: RebalanceConfig,   // Trim one-sided YES/NO inventory near break-even
+    #[serde(default)]
+    pub fast_move: FastMoveConfig,    // Pause markets whose mid moves like news is breaking
+    #[serde(default)]
+    pub peg: PegConfig,               // Keep passive legs at the touch or mid within the arb limit
+    #[serde(default)]
+    pub recycle: RecycleConfig,       // Turn complete YES+NO sets back into USDC
+    #[serde(default)]
+    pub routing: RouterConfig,        // Post a leg passively instead of taking when it is cheaper
+    #[serde(default)]
+    pub reconcile: ReconcileConfig,   // Compare the wallet with exchange balances (live only)
+    #[serde(default)]
+    pub exit: ExitConfig,             // Sell held sets early when that beats holding to resolution
+    #[serde(default)]
+    pub shadow: Option<ShadowConfig>, // Candidate detector thresholds compared against the live ones
+    #[serde(default)]
+    pub matcher: MatcherConfig,       // When two markets are proposed as the same question
+}
+
+fn default_gamma_url() -> String {
+    GAMMA_API_URL.to_string()
+}
+
+fn default_clob_url() -> String {
+    CLOB_API_URL.to_string()
+}
+
+fn default_ws_url() -> String {
+    MARKET_WS_URL.to_string()
+}
+
+fn default_starting_balance() -> f64 {
+    1000.0
+}
+
+impl Profile {
+    /// Paper profile against production data
+    pub fn paper() -> Self {
+        Self {
+            mode: TradingMode::Paper,
+            gamma_url: default_gamma_url(),
+            clob_url: default_clob_url(),
+            ws_url: default_ws_url(),
+            api_key_env: None,
+            live: None,
+            starting_balance: default_starting_balance(),
+            risk: RiskLimits::default(),
+            bot: BotConfig::default(),
+            fees: None,
+            storage: None,
+            lock: LockConfig::default(),
+            carry: CarryConfig::default(),
+            liquidity_tiers: default_liquidity_tiers(),
+            usd: UsdConversion::default(),
+            webhooks: WebhookConfig::default(),
+            scan: ScanConfig::default(),
+            lot_method: LotMethod::default(),
+            prune: PruneConfig::default(),
+            outliers: OutlierConfig::default(),
+            volatility: VolatilityConfig::default(),
+            compliance: ComplianceConfig::default(),
+            paper_latency: PaperLatencyConfig::default(),
+            poll: PollConfig::default(),
+            failover: None,
+            supervised: None,
+            control: None,
+            checkpoint: CheckpointConfig::default(),
+            quality: QualityConfig::default(),
+            fee_drift: FeeDriftConfig::default(),
+            priority: PriorityConfig::default(),
+            min_resting_ms: 0,
+            sparklines: SparklineConfig::default(),
+            backoff: BackoffConfig::default(),
+            rebalance: RebalanceConfig::default(),
+            fast_move: FastMoveConfig::default(),
+            peg: PegConfig::default(),
+            recycle: RecycleConfig::default(),
+            routing: RouterConfig::default(),
+            reconcile: ReconcileConfig::default(),
+            exit: ExitConfig::default(),
+            shadow: None,
+            matcher: MatcherConfig::default(),
+        }
+    }
+
+    /// Read the API key from the configured environment variable
+    pub fn api_key(&self) -> Option<String> {
+        self.api_key_env.as_ref().and_then(|var| std::env::var(var).ok())
+    }
+}
+
+/// Config file: a set of named profiles
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    #[serde(default = "default_profile_name")]
+    pub default_profile: String,
+    pub profiles: HashMap<String, Profile>,
+}
+
+fn default_profile_name() -> String {
+    "paper".to_string()
+}
+
+impl Default for Config {
+    /// Built-in `dev` and `paper` profiles; no live profile exists unless configured
+    fn default() -> Self {
+        let mut profiles = HashMap::new();
+        profiles.insert("dev".to_string(), Profile { starting_balance: 100.0, ..Profile::paper() });
+        profiles.insert("paper".to_string(), Profile::paper());
+        Self { default_profile: default_profile_name(), profiles }
+    }
+}
+
+impl Config {
+    /// Load from a JSON file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Pick a profile and enforce the live-trading interlock
+    pub fn select(&self, name: Option<&str>, live_acknowledged: bool) -> Result<(&str, &Profile), String> {
+        let name = name.unwrap_or(&self.default_profile);
+        let (name, profile) = self.profiles.get_key_value(name)
+            .ok_or_else(|| format!("unknown profile `{}`", name))?;
+
+        if profile.mode == TradingMode::Live {
+            if !live_acknowledged {
+                return Err(format!("profile `{}` sends real orders; rerun with {}", name, LIVE_ACK_FLAG));
+            }
+            if profile.api_key().is_none() {
+                return Err(format!("profile `{}` is live but has no API key configured", name));
+            }
+        }
+        Ok((name.as_str(), profile))
+    }
+}
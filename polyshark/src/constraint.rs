@@ -1,4 +1,4 @@
-use crate::types::{ArbitrageSignal, Market, Side};
+use crate::types::{ArbitrageSignal, CategoricalSignal, CrossMarketSignal, Market, Side};
 
 /// Binary market constraint checker
 #[derive(Debug, Clone)]
@@ -13,9 +13,14 @@ impl ConstraintChecker {
 
     /// Check if market has arbitrage opportunity
     pub fn check_violation(&self, market: &Market) -> Option<ArbitrageSignal> {
+        self.check_violation_above(market, self.min_spread_threshold)
+    }
+
+    /// As `check_violation`, against a market-specific threshold
+    pub fn check_violation_above(&self, market: &Market, min_spread: f64) -> Option<ArbitrageSignal> {
         let spread = market.get_spread();
         
-        if spread <= self.min_spread_threshold {
+        if spread <= min_spread {
             return None; // No opportunity
         }
 
@@ -35,4 +40,52 @@ impl ConstraintChecker {
             no_price: market.no_price(),
         })
     }
-}
\ No newline at end of file
+
+    /// Check a set of mutually exclusive markets (one event's buckets) whose YES prices must sum to ~1
+    pub fn check_categorical(&self, event_id: &str, markets: &[&Market]) -> Option<CategoricalSignal> {
+        self.check_categorical_above(event_id, markets, self.min_spread_threshold)
+    }
+
+    /// As `check_categorical`, against an event-specific threshold
+    pub fn check_categorical_above(&self, event_id: &str, markets: &[&Market], min_spread: f64) -> Option<CategoricalSignal> {
+        if markets.len() < 2 {
+            return None;
+        }
+
+        let yes_prices: Vec<f64> = markets.iter().map(|m| m.yes_price()).collect();
+        let sum: f64 = yes_prices.iter().sum();
+        let spread = (sum - 1.0).abs();
+
+        if spread <= min_spread {
+            return None;
+        }
+
+        Some(CategoricalSignal {
+            event_id: event_id.to_string(),
+            market_ids: markets.iter().map(|m| m.id.clone()).collect(),
+            yes_prices,
+            sum,
+            spread,
+            recommended_side: if sum > 1.0 { Side::Sell } else { Side::Buy },
+        })
+    }
+
+    /// Check two markets confirmed to ask the same question: YES in one and NO in the
+    /// other is a complete set, so the cheaper crossing must cost at least 1
+    pub fn check_pair_above(&self, a: &Market, b: &Market, min_spread: f64) -> Option<CrossMarketSignal> {
+        let (yes, no) = if a.yes_price() + b.no_price() <= b.yes_price() + a.no_price() { (a, b) } else { (b, a) };
+        let spread = 1.0 - (yes.yes_price() + no.no_price());
+
+        if spread <= min_spread {
+            return None;
+        }
+
+        Some(CrossMarketSignal {
+            yes_market: yes.id.clone(),
+            no_market: no.id.clone(),
+            yes_price: yes.yes_price(),
+            no_price: no.no_price(),
+            spread,
+        })
+    }
+}
@@ -0,0 +1,336 @@
+use crate::approval::ApprovalQueue;
+use crate::bus::{EventBus, Signal};
+use crate::heatmap::OpportunityHeatmap;
+use crate::orders::{OpenOrder, OrderManager};
+use crate::types::MarketId;
+use crate::wallet::{Position, Wallet};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast::error::RecvError;
+
+/// Signals kept for `GET /signals`
+pub const MAX_RECENT_SIGNALS: usize = 100;
+
+/// Characters of the market id shown on each `/heatmap` row
+pub const HEATMAP_LABEL_WIDTH: usize = 16;
+
+/// A client that hasn't sent its request by then is dropped
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where `run` serves the control API; the token is read from the environment
+#[derive(Debug, Clone, Deserialize)]
+pub struct ControlConfig {
+    pub bind_addr: String,            // e.g., "127.0.0.1:9100" (keep it local)
+    #[serde(default = "default_token_env")]
+    pub token_env: String,            // Env var holding the bearer token (never the token itself)
+    #[serde(default = "default_flatten_max_slippage")]
+    pub flatten_max_slippage: f64,    // `/flatten-all` sells no further than this below the mid
+}
+
+fn default_token_env() -> String {
+    "POLYSHARK_CONTROL_TOKEN".to_string()
+}
+
+fn default_flatten_max_slippage() -> f64 {
+    0.05
+}
+
+/// Shared switches the trading loop polls every iteration
+#[derive(Debug, Default)]
+pub struct ControlState {
+    paused: AtomicBool,
+    flatten_requested: AtomicBool,
+}
+
+/// Snapshot returned by the `status` command
+#[derive(Debug, Clone, Serialize)]
+pub struct ControlStatus {
+    pub paused: bool,
+    pub flatten_pending: bool,
+}
+
+/// Operator commands accepted by the control endpoint
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControlCommand {
+    Pause,
+    Resume,
+    FlattenAll,
+    Status,
+}
+
+impl ControlState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check if new entries are halted
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Consume a pending flatten request (true at most once per request)
+    pub fn take_flatten_request(&self) -> bool {
+        self.flatten_requested.swap(false, Ordering::SeqCst)
+    }
+
+    /// Apply a command and return the resulting status
+    pub fn apply(&self, command: ControlCommand) -> ControlStatus {
+        match command {
+            ControlCommand::Pause => self.paused.store(true, Ordering::SeqCst),
+            ControlCommand::Resume => self.paused.store(false, Ordering::SeqCst),
+            ControlCommand::FlattenAll => {
+                // Flattening implies no new entries until an operator resumes
+                self.paused.store(true, Ordering::SeqCst);
+                self.flatten_requested.store(true, Ordering::SeqCst);
+            }
+            ControlCommand::Status => {}
+        }
+        self.status()
+    }
+
+    /// Current switch positions
+    pub fn status(&self) -> ControlStatus {
+        ControlStatus {
+            paused: self.is_paused(),
+            flatten_pending: self.flatten_requested.load(Ordering::SeqCst),
+        }
+    }
+}
+
+/// What the running bot holds, refreshed by the trading loop for the read endpoints
+#[derive(Debug, Default)]
+pub struct BotView {
+    positions: Mutex<Vec<Position>>,
+    orders: Mutex<Vec<OpenOrder>>,
+    signals: Mutex<VecDeque<Signal>>,  // Newest last
+    heatmap: Mutex<OpportunityHeatmap>,  // Violations and captures per market over the last hour
+}
+
+impl BotView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update_positions(&self, wallet: &Wallet) {
+        if let Ok(mut positions) = self.positions.lock() {
+            *positions = wallet.positions.values().cloned().collect();
+        }
+    }
+
+    pub fn update_orders(&self, orders: &OrderManager) {
+        if let Ok(mut open) = self.orders.lock() {
+            *open = orders.open.values().cloned().collect();
+        }
+    }
+
+    pub fn push_signal(&self, signal: Signal) {
+        if let Ok(mut heatmap) = self.heatmap.lock() {
+            let now_ms = now_ms();
+            match &signal {
+                Signal::Binary(arb) => heatmap.record(&arb.market_id, arb.edge, false, now_ms),
+                Signal::Categorical(set) => {
+                    for market_id in &set.market_ids {
+                        heatmap.record(market_id, set.spread, false, now_ms);
+                    }
+                }
+                Signal::CrossMarket(pair) => {
+                    heatmap.record(&pair.yes_market, pair.spread, false, now_ms);
+                    heatmap.record(&pair.no_market, pair.spread, false, now_ms);
+                }
+            }
+            heatmap.prune(now_ms);
+        }
+        if let Ok(mut signals) = self.signals.lock() {
+            if signals.len() == MAX_RECENT_SIGNALS {
+                signals.pop_front();
+            }
+            signals.push_back(signal);
+        }
+    }
+
+    /// Count an executed signal as captured on the heatmap
+    pub fn push_capture(&self, market_id: &MarketId) {
+        if let Ok(mut heatmap) = self.heatmap.lock() {
+            heatmap.record_capture(market_id, now_ms());
+        }
+    }
+
+    /// Keep the recent-signals list and the heatmap fed from the bus
+    pub fn follow_signals(self: &Arc<Self>, bus: &EventBus) -> tokio::task::JoinHandle<()> {
+        let mut signals = bus.signals.subscribe();
+        let mut executions = bus.executions.subscribe();
+        let view = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    signal = signals.recv() => match signal {
+                        Ok(signal) => view.push_signal(signal),
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    },
+                    report = executions.recv() => match report {
+                        Ok(report) => view.push_capture(&report.market_id),
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    },
+                }
+            }
+        })
+    }
+
+    /// JSON body for a read endpoint, or `None` if the path isn't one
+    fn render(&self, path: &str) -> Option<String> {
+        let body = match path {
+            "/positions" => serde_json::to_string(&*self.positions.lock().ok()?),
+            "/orders" => serde_json::to_string(&*self.orders.lock().ok()?),
+            "/signals" => serde_json::to_string(&*self.signals.lock().ok()?),
+            "/heatmap" => {
+                let heatmap = self.heatmap.lock().ok()?;
+                serde_json::to_string(&serde_json::json!({
+                    "bucket_ms": heatmap.bucket_ms,
+                    "window_ms": heatmap.window_ms,
+                    "rows": heatmap.render(now_ms(), HEATMAP_LABEL_WIDTH),
+                }))
+            }
+            _ => return None,
+        };
+        body.ok()
+    }
+}
+
+impl ControlCommand {
+    /// Map an HTTP method and path to a command
+    pub fn from_request(method: &str, path: &str) -> Option<Self> {
+        match (method, path) {
+            ("POST", "/pause") => Some(Self::Pause),
+            ("POST", "/resume") => Some(Self::Resume),
+            ("POST", "/flatten-all") => Some(Self::FlattenAll),
+            ("GET", "/status") => Some(Self::Status),
+            _ => None,
+        }
+    }
+}
+
+/// Authenticated local HTTP control endpoint (dead-man's switch).
+/// With a `BotView` attached it also answers `GET /positions`, `/orders`, `/signals` and `/heatmap`;
+/// with an `ApprovalQueue`, `GET /intents` and `POST /intents/{id}/approve|reject`.
+#[derive(Debug, Clone)]
+pub struct ControlServer {
+    pub bind_addr: String,  // e.g., "127.0.0.1:9100" (keep it local)
+    pub token: String,      // Expected in `Authorization: Bearer <token>`
+    pub state: Arc<ControlState>,
+    pub view: Option<Arc<BotView>>,
+    pub approvals: Option<Arc<ApprovalQueue>>,
+}
+
+impl ControlServer {
+    pub fn new(bind_addr: &str, token: &str, state: Arc<ControlState>) -> Self {
+        Self {
+            bind_addr: bind_addr.to_string(),
+            token: token.to_string(),
+            state,
+            view: None,
+            approvals: None,
+        }
+    }
+
+    /// Serve the read endpoints from `view`
+    pub fn with_view(mut self, view: Arc<BotView>) -> Self {
+        self.view = Some(view);
+        self
+    }
+
+    /// Let operators answer supervised-mode intents
+    pub fn with_approvals(mut self, approvals: Arc<ApprovalQueue>) -> Self {
+        self.approvals = Some(approvals);
+        self
+    }
+
+    /// Bind and accept on a background thread; each connection is served on its own
+    /// thread, so a client that never sends its request can't block the others
+    pub fn spawn(self) -> std::io::Result<JoinHandle<()>> {
+        let listener = TcpListener::bind(&self.bind_addr)?;
+        let server = Arc::new(self);
+        Ok(thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let server = Arc::clone(&server);
+                // One request per connection; errors only affect that client
+                thread::spawn(move || server.handle(stream));
+            }
+        }))
+    }
+
+    fn handle(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or("");
+        let path = parts.next().unwrap_or("");
+
+        let mut authorized = false;
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':')
+                && name.trim().eq_ignore_ascii_case("authorization")
+                && let Some(token) = value.trim().strip_prefix("Bearer ")
+                && constant_time_eq(token.as_bytes(), self.token.as_bytes())
+            {
+                authorized = true;
+            }
+        }
+
+        let (code, body) = if !authorized {
+            ("401 Unauthorized", r#"{"error":"unauthorized"}"#.to_string())
+        } else if let Some(body) = self.view.as_ref().filter(|_| method == "GET").and_then(|v| v.render(path)) {
+            ("200 OK", body)
+        } else if let Some((code, body)) = self.approvals.as_ref().and_then(|a| a.handle_request(method, path)) {
+            (code, body)
+        } else {
+            match ControlCommand::from_request(method, path) {
+                Some(command) => {
+                    let status = self.state.apply(command);
+                    ("200 OK", serde_json::to_string(&status).unwrap_or_default())
+                }
+                None => ("404 Not Found", r#"{"error":"unknown command"}"#.to_string()),
+            }
+        };
+
+        respond(&mut stream, code, &body)
+    }
+}
+
+/// Compare secrets without returning early on the first differing byte, so response
+/// timing doesn't reveal how much of a guessed token was right
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+/// Write a one-shot JSON HTTP response
+pub(crate) fn respond(stream: &mut TcpStream, code: &str, body: &str) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        code,
+        body.len(),
+        body
+    )
+}
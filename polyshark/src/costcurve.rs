@@ -0,0 +1,335 @@
+use crate::types::{OrderBook, Side, TokenId};
+use std::collections::HashMap;
+
+/// Cumulative size → cost of taking one side of a book, best level first. Built once per
+/// book update so sizing questions are binary searches instead of book walks.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CostCurve {
+    prices: Vec<f64>,  // Level prices, best first
+    sizes: Vec<f64>,   // Size available up to and including each level
+    costs: Vec<f64>,   // Cost of taking everything up to and including each level
+}
+
+impl CostCurve {
+    /// Curve for an order on `side`: buys take the asks, sells hit the bids
+    pub fn from_book(book: &OrderBook, side: Side) -> Self {
+        let levels = match side {
+            Side::Buy => &book.asks,
+            Side::Sell => &book.bids,
+        };
+        let mut curve = Self {
+            prices: Vec::with_capacity(levels.len()),
+            sizes: Vec::with_capacity(levels.len()),
+            costs: Vec::with_capacity(levels.len()),
+        };
+        let (mut size, mut cost) = (0.0, 0.0);
+        for level in levels.iter().filter(|l| l.size > 0.0) {
+            size += level.size;
+            cost += level.size * level.price;
+            curve.prices.push(level.price);
+            curve.sizes.push(size);
+            curve.costs.push(cost);
+        }
+        curve
+    }
+
+    /// Best price on the side
+    pub fn best(&self) -> Option<f64> {
+        self.prices.first().copied()
+    }
+
+    /// Everything the side holds
+    pub fn depth(&self) -> f64 {
+        self.sizes.last().copied().unwrap_or(0.0)
+    }
+
+    /// Notional of everything the side holds
+    pub fn notional(&self) -> f64 {
+        self.costs.last().copied().unwrap_or(0.0)
+    }
+
+    /// Cost of taking `size`; `None` when the book can't fill it
+    pub fn cost(&self, size: f64) -> Option<f64> {
+        if size <= 0.0 {
+            return Some(0.0);
+        }
+        // First level whose cumulative size covers the order
+        let i = self.sizes.partition_point(|s| *s < size - 1e-9);
+        if i == self.sizes.len() {
+            return None;
+        }
+        let (before_size, before_cost) = if i == 0 { (0.0, 0.0) } else { (self.sizes[i - 1], self.costs[i - 1]) };
+        Some(before_cost + (size - before_size) * self.prices[i])
+    }
+
+    /// Price of the last level `size` reaches into: the limit that takes all of it
+    pub fn price_at(&self, size: f64) -> Option<f64> {
+        let i = self.sizes.partition_point(|s| *s < size - 1e-9);
+        self.prices.get(i).copied()
+    }
+
+    /// Volume-weighted price of taking `size`, same as `OrderBook::execution_price`
+    pub fn vwap(&self, size: f64) -> Option<f64> {
+        if size <= 0.0 {
+            return None;
+        }
+        self.cost(size).map(|c| c / size)
+    }
+
+    /// Size resting at prices at or better than `limit`, same as `OrderBook::depth_within`
+    pub fn size_within(&self, limit: f64, side: Side) -> f64 {
+        let n = match side {
+            Side::Buy => self.prices.partition_point(|p| *p <= limit + 1e-9),
+            Side::Sell => self.prices.partition_point(|p| *p >= limit - 1e-9),
+        };
+        if n == 0 { 0.0 } else { self.sizes[n - 1] }
+    }
+
+    /// Largest size whose VWAP stays at or better than `max_vwap` (at most for buys, at
+    /// least for sells). VWAP only worsens with size, so it is a search over levels plus
+    /// the exact cut inside the last one.
+    pub fn max_size_at_vwap(&self, max_vwap: f64, side: Side) -> f64 {
+        let ok = |price: f64| match side {
+            Side::Buy => price <= max_vwap + 1e-12,
+            Side::Sell => price >= max_vwap - 1e-12,
+        };
+        // Levels fully takeable: VWAP after the whole level still within bounds
+        let (mut full, mut end) = (0, self.sizes.len());
+        while full < end {
+            let mid = (full + end) / 2;
+            if ok(self.costs[mid] / self.sizes[mid]) {
+                full = mid + 1;
+            } else {
+                end = mid;
+            }
+        }
+        if full == self.sizes.len() {
+            return self.depth();
+        }
+        let (size, cost) = if full == 0 { (0.0, 0.0) } else { (self.sizes[full - 1], self.costs[full - 1]) };
+        let price = self.prices[full];
+        // Solve (cost + price * x) / (size + x) = max_vwap for the extra size x
+        let gap = price - max_vwap;
+        if gap.abs() < 1e-12 {
+            return self.sizes[full];
+        }
+        let extra = ((max_vwap * size - cost) / gap).max(0.0);
+        size + extra.min(self.sizes[full] - size)
+    }
+
+    /// Every point where the marginal price changes, as cumulative sizes
+    pub fn breakpoints(&self) -> &[f64] {
+        &self.sizes
+    }
+}
+
+/// Both sides of one book
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BookCurves {
+    pub buy: CostCurve,   // Walks the asks
+    pub sell: CostCurve,  // Walks the bids
+    pub timestamp: u64,   // Book timestamp the curves were built from
+}
+
+impl BookCurves {
+    pub fn from_book(book: &OrderBook) -> Self {
+        Self {
+            buy: CostCurve::from_book(book, Side::Buy),
+            sell: CostCurve::from_book(book, Side::Sell),
+            timestamp: book.timestamp,
+        }
+    }
+
+    pub fn midpoint(&self) -> Option<f64> {
+        Some((self.buy.best()? + self.sell.best()?) / 2.0)
+    }
+
+    /// USDC resting on both sides, as `slippage::pair_depth` measures a book
+    pub fn notional(&self) -> f64 {
+        self.buy.notional() + self.sell.notional()
+    }
+
+    pub fn side(&self, side: Side) -> &CostCurve {
+        match side {
+            Side::Buy => &self.buy,
+            Side::Sell => &self.sell,
+        }
+    }
+}
+
+/// Largest size buying (or selling) both legs of a set together keeps the combined VWAP
+/// within `max_unit`: e.g. YES + NO asks summing below 1 minus fees. Combined cost is
+/// convex in size, so the answer is found by bisecting over the merged breakpoints.
+pub fn max_pair_size(a: &CostCurve, b: &CostCurve, max_unit: f64, side: Side) -> f64 {
+    let within = |size: f64| match (a.cost(size), b.cost(size)) {
+        (Some(x), Some(y)) => match side {
+            Side::Buy => x + y <= max_unit * size + 1e-9,
+            Side::Sell => x + y >= max_unit * size - 1e-9,
+        },
+        _ => false,
+    };
+    let limit = a.depth().min(b.depth());
+    let mut points: Vec<f64> = a.breakpoints().iter().chain(b.breakpoints()).copied().filter(|s| *s <= limit + 1e-9).collect();
+    points.sort_by(f64::total_cmp);
+    points.dedup_by(|x, y| (*x - *y).abs() < 1e-9);
+
+    // Last breakpoint that still clears, then the exact cut inside the next segment
+    let ok = points.partition_point(|s| within(*s));
+    let low = if ok == 0 { 0.0 } else { points[ok - 1] };
+    let Some(&high) = points.get(ok) else {
+        return low;
+    };
+    let (Some(low_cost), Some(high_cost)) = (a.cost(low).zip(b.cost(low)), a.cost(high).zip(b.cost(high))) else {
+        return low;
+    };
+    // Cost is linear between breakpoints: solve cost(low) + slope * x = max_unit * (low + x)
+    let slope = (high_cost.0 + high_cost.1 - low_cost.0 - low_cost.1) / (high - low);
+    let gap = slope - max_unit;
+    if gap.abs() < 1e-12 {
+        return high;
+    }
+    let extra = ((max_unit * low - low_cost.0 - low_cost.1) / gap).clamp(0.0, high - low);
+    low + extra
+}
+
+/// Curves for every maintained book, rebuilt whenever the book changes
+#[derive(Debug, Clone, Default)]
+pub struct CurveCache {
+    curves: HashMap<TokenId, BookCurves>,
+}
+
+impl CurveCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rebuild the book's curves; call after every applied update
+    pub fn update(&mut self, book: &OrderBook) -> &BookCurves {
+        let curves = BookCurves::from_book(book);
+        match self.curves.get_mut(&book.token_id) {
+            // Known token: replace in place, no key allocation on the hot path
+            Some(entry) => *entry = curves,
+            None => {
+                self.curves.insert(book.token_id.clone(), curves);
+            }
+        }
+        &self.curves[&book.token_id]
+    }
+
+    pub fn get(&self, token_id: &TokenId) -> Option<&BookCurves> {
+        self.curves.get(token_id)
+    }
+
+    pub fn remove(&mut self, token_id: &TokenId) {
+        self.curves.remove(token_id);
+    }
+
+    /// Drop the curves of books no longer maintained
+    pub fn retain(&mut self, keep: impl Fn(&TokenId) -> bool) {
+        self.curves.retain(|token, _| keep(token));
+    }
+
+    pub fn len(&self) -> usize {
+        self.curves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.curves.is_empty()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PriceLevel;
+
+    fn book(token: &str, asks: &[(f64, f64)]) -> OrderBook {
+        OrderBook {
+            token_id: TokenId::from(token),
+            bids: vec![PriceLevel { price: 0.48, size: 100.0 }, PriceLevel { price: 0.47, size: 200.0 }],
+            asks: asks.iter().map(|&(price, size)| PriceLevel { price, size }).collect(),
+            timestamp: 0,
+        }
+    }
+
+    fn yes() -> OrderBook {
+        book("yes", &[(0.50, 100.0), (0.52, 50.0), (0.55, 100.0)])
+    }
+
+    fn no() -> OrderBook {
+        book("no", &[(0.45, 40.0), (0.47, 80.0), (0.49, 200.0)])
+    }
+
+    #[test]
+    fn cost_and_vwap_match_execution_price() {
+        let book = yes();
+        let curves = BookCurves::from_book(&book);
+        for side in [Side::Buy, Side::Sell] {
+            for size in [1.0, 99.5, 100.0, 120.0, 150.0, 175.0, 250.0, 300.0, 301.0] {
+                let expected = book.execution_price(size, side);
+                assert_eq!(curves.side(side).vwap(size).is_some(), expected.is_some(), "{:?} {}", side, size);
+                if let Some(price) = expected {
+                    assert!((curves.side(side).vwap(size).unwrap() - price).abs() < 1e-12);
+                    assert!((curves.side(side).cost(size).unwrap() - price * size).abs() < 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn size_within_matches_depth_within() {
+        let book = yes();
+        let curves = BookCurves::from_book(&book);
+        for limit in [0.40, 0.47, 0.475, 0.48, 0.50, 0.51, 0.52, 0.55, 0.60] {
+            for side in [Side::Buy, Side::Sell] {
+                assert_eq!(curves.side(side).size_within(limit, side), book.depth_within(limit, side), "{:?} {}", side, limit);
+            }
+        }
+    }
+
+    #[test]
+    fn max_size_at_vwap_is_the_largest_size_within_the_limit() {
+        let book = yes();
+        let curve = CostCurve::from_book(&book, Side::Buy);
+        for max_vwap in [0.50, 0.505, 0.51, 0.52, 0.53, 0.60] {
+            let size = curve.max_size_at_vwap(max_vwap, Side::Buy);
+            assert!(book.execution_price(size, Side::Buy).unwrap() <= max_vwap + 1e-9, "{}", max_vwap);
+            if size < curve.depth() {
+                assert!(book.execution_price(size + 0.01, Side::Buy).unwrap() > max_vwap, "{}", max_vwap);
+            }
+        }
+        assert_eq!(curve.max_size_at_vwap(0.49, Side::Buy), 0.0);
+        assert_eq!(curve.max_size_at_vwap(1.0, Side::Buy), curve.depth());
+    }
+
+    #[test]
+    fn max_pair_size_matches_a_walk_of_both_books() {
+        let (yes, no) = (yes(), no());
+        let (a, b) = (CostCurve::from_book(&yes, Side::Buy), CostCurve::from_book(&no, Side::Buy));
+        let pair = |size: f64| Some(yes.execution_price(size, Side::Buy)? + no.execution_price(size, Side::Buy)?);
+        for max_unit in [0.95, 0.96, 0.97, 0.99, 1.0, 1.1] {
+            let size = max_pair_size(&a, &b, max_unit, Side::Buy);
+            // Brute force: the largest size in 0.01 steps whose combined VWAP clears
+            let walked = (1..=25_000).map(|i| i as f64 / 100.0)
+                .take_while(|s| pair(*s).is_some_and(|p| p <= max_unit + 1e-9))
+                .last()
+                .unwrap_or(0.0);
+            assert!((size - walked).abs() < 0.01 + 1e-9, "{}: {} vs {}", max_unit, size, walked);
+        }
+    }
+
+    #[test]
+    fn cache_follows_book_updates() {
+        let mut book = yes();
+        let mut cache = CurveCache::new();
+        cache.update(&book);
+        book.apply_level(Side::Sell, 0.50, 0.0, 1);
+        cache.update(&book);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(&book.token_id).unwrap().buy.best(), Some(0.52));
+        assert_eq!(cache.get(&book.token_id).unwrap().buy.price_at(60.0), Some(0.55));
+        cache.retain(|token| token.as_str() != "yes");
+        assert!(cache.is_empty());
+    }
+}
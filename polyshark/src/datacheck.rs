@@ -0,0 +1,141 @@
+// Only `check-data` reads the checks, and it needs the `bookstore` feature
+#![cfg_attr(not(feature = "bookstore"), allow(dead_code))]
+
+#[cfg(feature = "bookstore")]
+use crate::bookstore::BookReader;
+use crate::types::{MarketId, OrderBook, TokenId};
+use serde::Serialize;
+
+/// Default longest silence between recorded frames. The recorder skips unchanged books,
+/// so quiet markets legitimately go a while without a frame.
+pub const DEFAULT_MAX_GAP_MS: u64 = 5 * 60 * 1000;
+
+/// Integrity findings for one recorded token
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TokenCheck {
+    pub token_id: TokenId,
+    pub frames: usize,
+    pub from: u64,                // First frame (ms)
+    pub to: u64,                  // Latest frame (ms)
+    pub gaps: usize,              // Silences longer than the allowed gap
+    pub longest_gap_ms: u64,
+    pub out_of_order: usize,      // Frames older than the one before them
+    pub crossed: usize,           // Frames leaving best bid >= best ask
+    pub missing_snapshots: usize, // Segments starting on a delta: their books are built on nothing
+    pub lost_frames: usize,       // Indexed but unreadable (missing file, truncated or corrupt segment)
+    #[serde(skip)]
+    last: Option<u64>,
+}
+
+impl TokenCheck {
+    pub fn new(token_id: TokenId) -> Self {
+        Self { token_id, ..Default::default() }
+    }
+
+    /// Account for the next frame in recorded order. `snapshot` marks a full book and
+    /// `segment_start` the first frame of a segment.
+    pub fn frame(&mut self, book: &OrderBook, snapshot: bool, segment_start: bool, max_gap_ms: u64) {
+        let ts = book.timestamp;
+        match self.last {
+            Some(last) if ts < last => self.out_of_order += 1,
+            Some(last) if ts - last > max_gap_ms => {
+                self.gaps += 1;
+                self.longest_gap_ms = self.longest_gap_ms.max(ts - last);
+            }
+            Some(_) => {}
+            None => self.from = ts,
+        }
+        if segment_start && !snapshot {
+            self.missing_snapshots += 1;
+        }
+        if matches!((book.best_bid(), book.best_ask()), (Some(bid), Some(ask)) if bid >= ask) {
+            self.crossed += 1;
+        }
+        self.frames += 1;
+        self.from = self.from.min(ts);
+        self.to = self.to.max(ts);
+        self.last = Some(ts);
+    }
+
+    pub fn issues(&self) -> usize {
+        self.gaps + self.out_of_order + self.crossed + self.missing_snapshots + self.lost_frames
+    }
+}
+
+/// Integrity findings for one market, summed over its tokens
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MarketCheck {
+    pub market_id: MarketId,
+    pub tokens: usize,
+    pub frames: usize,
+    pub from: u64,
+    pub to: u64,
+    pub gaps: usize,
+    pub longest_gap_ms: u64,
+    pub out_of_order: usize,
+    pub crossed: usize,
+    pub missing_snapshots: usize,
+    pub lost_frames: usize,
+    pub partial_tokens: usize,  // Tokens recorded over a noticeably shorter span than the market
+}
+
+impl MarketCheck {
+    /// Combine token findings. A token starting or stopping more than `max_gap_ms` away
+    /// from its siblings left the other outcomes without a counterpart for that span.
+    pub fn from_tokens(market_id: MarketId, tokens: &[TokenCheck], max_gap_ms: u64) -> Self {
+        let recorded = tokens.iter().filter(|t| t.frames > 0);
+        let from = recorded.clone().map(|t| t.from).min().unwrap_or(0);
+        let to = recorded.clone().map(|t| t.to).max().unwrap_or(0);
+        Self {
+            market_id,
+            tokens: tokens.len(),
+            frames: tokens.iter().map(|t| t.frames).sum(),
+            from,
+            to,
+            gaps: tokens.iter().map(|t| t.gaps).sum(),
+            longest_gap_ms: tokens.iter().map(|t| t.longest_gap_ms).max().unwrap_or(0),
+            out_of_order: tokens.iter().map(|t| t.out_of_order).sum(),
+            crossed: tokens.iter().map(|t| t.crossed).sum(),
+            missing_snapshots: tokens.iter().map(|t| t.missing_snapshots).sum(),
+            lost_frames: tokens.iter().map(|t| t.lost_frames).sum(),
+            partial_tokens: tokens.iter()
+                .filter(|t| t.frames == 0 || t.from - from > max_gap_ms || to - t.to > max_gap_ms)
+                .count(),
+        }
+    }
+
+    pub fn issues(&self) -> usize {
+        self.gaps + self.out_of_order + self.crossed + self.missing_snapshots + self.lost_frames + self.partial_tokens
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.issues() == 0
+    }
+}
+
+#[cfg(feature = "bookstore")]
+/// Check every market in a book store, worst first.
+/// Unreadable segments are counted, not fatal.
+pub fn check_store(reader: &BookReader, max_gap_ms: u64) -> Vec<MarketCheck> {
+    let mut markets = Vec::new();
+    for market_id in reader.markets() {
+        let mut tokens = Vec::new();
+        for token_id in reader.tokens(&market_id) {
+            let mut check = TokenCheck::new(token_id.clone());
+            for segment in reader.segments(&token_id, 0, u64::MAX).filter(|s| s.market_id == market_id) {
+                let mut seen = 0;
+                let walked = reader.walk(segment, |book, snapshot| {
+                    check.frame(book, snapshot, seen == 0, max_gap_ms);
+                    seen += 1;
+                });
+                if walked.is_err() || seen < segment.frames {
+                    check.lost_frames += segment.frames.saturating_sub(seen).max(1);
+                }
+            }
+            tokens.push(check);
+        }
+        markets.push(MarketCheck::from_tokens(market_id, &tokens, max_gap_ms));
+    }
+    markets.sort_by(|a, b| b.issues().cmp(&a.issues()).then_with(|| a.market_id.cmp(&b.market_id)));
+    markets
+}
@@ -0,0 +1,73 @@
+use crate::types::{OrderBook, PriceLevel};
+use std::io::{self, Write};
+
+/// Price grid step for the wide layout (Polymarket's standard tick)
+pub const DEPTH_TICK: f64 = 0.01;
+
+/// Layout of an exported depth matrix
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DepthFormat {
+    Long,  // One row per (timestamp, side, price): tidy, pivot in pandas/Polars
+    Wide,  // One row per (timestamp, side) with a column per price tick
+}
+
+impl DepthFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "long" => Some(DepthFormat::Long),
+            "wide" => Some(DepthFormat::Wide),
+            _ => None,
+        }
+    }
+}
+
+/// Write recorded books as CSV: price level x time x size.
+/// Returns the number of data rows written.
+pub fn write_depth(books: &[OrderBook], format: DepthFormat, out: &mut impl Write) -> io::Result<usize> {
+    match format {
+        DepthFormat::Long => write_long(books, out),
+        DepthFormat::Wide => write_wide(books, out),
+    }
+}
+
+fn sides(book: &OrderBook) -> [(&'static str, &[PriceLevel]); 2] {
+    [("bid", &book.bids), ("ask", &book.asks)]
+}
+
+fn write_long(books: &[OrderBook], out: &mut impl Write) -> io::Result<usize> {
+    writeln!(out, "timestamp_ms,token_id,side,price,size")?;
+    let mut rows = 0;
+    for book in books {
+        for (side, levels) in sides(book) {
+            for level in levels {
+                writeln!(out, "{},{},{},{},{}", book.timestamp, book.token_id, side, level.price, level.size)?;
+                rows += 1;
+            }
+        }
+    }
+    Ok(rows)
+}
+
+fn write_wide(books: &[OrderBook], out: &mut impl Write) -> io::Result<usize> {
+    let ticks = (1.0 / DEPTH_TICK).round() as usize;
+    let header: Vec<String> = (1..ticks).map(|i| format!("{:.2}", i as f64 * DEPTH_TICK)).collect();
+    writeln!(out, "timestamp_ms,token_id,side,{}", header.join(","))?;
+
+    let mut rows = 0;
+    for book in books {
+        for (side, levels) in sides(book) {
+            let mut sizes = vec![0.0; ticks - 1];
+            for level in levels {
+                // Off-grid prices (sub-tick markets) land on the nearest tick
+                let i = (level.price / DEPTH_TICK).round() as usize;
+                if (1..ticks).contains(&i) {
+                    sizes[i - 1] += level.size;
+                }
+            }
+            let cells: Vec<String> = sizes.iter().map(|s| s.to_string()).collect();
+            writeln!(out, "{},{},{},{}", book.timestamp, book.token_id, side, cells.join(","))?;
+            rows += 1;
+        }
+    }
+    Ok(rows)
+}
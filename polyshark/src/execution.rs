@@ -1,28 +1,235 @@
-use crate::fees::FeeModel;
+use crate::bus::Fill;
+use crate::fees::{FeeSchedule, SharedFeeSchedule};
 use crate::fills::FillModel;
-use crate::types::{ExecutionResult, OrderBook, Side};
+use crate::types::{ExecutionResult, MarketId, OrderBook, Side, Size, TokenId};
+use crate::money::Usdc;
+use crate::orders::OrderRequest;
 use crate::wallet::Wallet;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// What to do with a sell larger than the tokens we hold
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SellPolicy {
+    Clamp,   // Sell only what we hold
+    Reject,  // Refuse the whole order
+}
+
+/// One leg of a multi-leg trade as planned at detection time
+#[derive(Debug, Clone)]
+pub struct LegPlan<'a> {
+    pub book: &'a OrderBook,
+    pub size: Size,
+    pub side: Side,
+    pub predicted_price: f64,  // Price the detector assumed for this leg
+}
+
+/// What one leg actually did
+#[derive(Debug, Clone, Serialize)]
+pub struct LegFill {
+    pub token_id: TokenId,
+    pub side: Side,
+    pub requested: f64,
+    pub filled: f64,
+    pub predicted_price: f64,
+    pub predicted_fee: f64,  // Taker fee at the predicted price on the filled size
+    pub price: f64,     // VWAP; 0 when nothing filled
+    pub fee: f64,
+    pub slippage: f64,  // Versus mid, as a fraction
+}
+
+impl LegFill {
+    /// What `request` did, from the venue's fills for its token. `mid` is the book's
+    /// midpoint when it was sent.
+    pub fn from_fills(request: &OrderRequest, fills: &[Fill], predicted_price: f64, mid: Option<f64>, fees: &dyn FeeSchedule) -> Self {
+        let (filled, notional, fee) = fills.iter()
+            .filter(|f| f.token_id == request.token_id && f.side == request.side)
+            .fold((0.0, 0.0, 0.0), |(size, notional, fee), f| (size + f.size, notional + f.size * f.price, fee + f.fee));
+        let price = if filled > 0.0 { notional / filled } else { 0.0 };
+        LegFill {
+            token_id: request.token_id.clone(),
+            side: request.side,
+            requested: request.size.value(),
+            filled,
+            predicted_price,
+            predicted_fee: fees.fee(predicted_price, filled, false),
+            price,
+            fee,
+            slippage: match mid {
+                Some(mid) if mid > 0.0 && filled > 0.0 => ((price - mid) / mid).abs(),
+                _ => 0.0,
+            },
+        }
+    }
+
+    /// Cash out (buys) or in (sells, negative), fees included
+    pub fn net_cost(&self) -> f64 {
+        match self.side {
+            Side::Buy => self.price * self.filled + self.fee,
+            Side::Sell => -(self.price * self.filled - self.fee),
+        }
+    }
+
+    /// Dollars saved versus the predicted price on the filled size (negative = worse)
+    pub fn price_improvement(&self) -> f64 {
+        if self.filled <= 0.0 {
+            return 0.0;
+        }
+        match self.side {
+            Side::Buy => (self.predicted_price - self.price) * self.filled,
+            Side::Sell => (self.price - self.predicted_price) * self.filled,
+        }
+    }
+
+    /// `net_cost` had the filled size traded at the predicted price
+    fn predicted_cost(&self) -> f64 {
+        match self.side {
+            Side::Buy => self.predicted_price * self.filled + self.predicted_fee,
+            Side::Sell => -(self.predicted_price * self.filled - self.predicted_fee),
+        }
+    }
+}
+
+/// Every leg of an arbitrage reported as one economic unit
+#[derive(Debug, Clone, Serialize)]
+pub struct MultiLegExecutionReport {
+    pub market_id: MarketId,
+    pub legs: Vec<LegFill>,
+    pub predicted_net_cost: f64,  // The filled sizes at the detector's prices, fees included
+}
+
+impl MultiLegExecutionReport {
+    pub fn new(market_id: MarketId, legs: Vec<LegFill>) -> Self {
+        let predicted_net_cost = legs.iter().map(LegFill::predicted_cost).sum();
+        Self { market_id, legs, predicted_net_cost }
+    }
+
+    /// Combined cash out across legs, fees included
+    pub fn net_cost(&self) -> f64 {
+        self.legs.iter().map(LegFill::net_cost).sum()
+    }
+
+    pub fn total_fees(&self) -> f64 {
+        self.legs.iter().map(|l| l.fee).sum()
+    }
+
+    /// How much more the trade cost than predicted (negative = better)
+    pub fn cost_vs_predicted(&self) -> f64 {
+        self.net_cost() - self.predicted_net_cost
+    }
+
+    /// Every leg filled its full requested size
+    pub fn is_complete(&self) -> bool {
+        self.legs.iter().all(|l| l.filled + 1e-9 >= l.requested)
+    }
+
+    /// Smallest filled size across legs: the matched part of the trade
+    pub fn matched_size(&self) -> f64 {
+        self.legs.iter().map(|l| l.filled).reduce(f64::min).unwrap_or(0.0)
+    }
+
+    /// Dollars saved versus the detector's prices across all legs (negative = worse)
+    pub fn price_improvement(&self) -> f64 {
+        self.legs.iter().map(LegFill::price_improvement).sum()
+    }
+}
+
+/// Price improvement between detection and submission for one market
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImprovementStats {
+    pub executions: u64,
+    pub improved: u64,
+    pub worsened: u64,
+    pub rejected: u64,      // Worsened past the profit floor and not submitted
+    pub improvement: f64,   // Net dollars saved on executed trades
+    pub rejected_cost: f64, // Price deterioration on the rejected ones
+}
+
+impl ImprovementStats {
+    /// Share of executions that got a better price than detected
+    pub fn improved_rate(&self) -> f64 {
+        if self.executions == 0 { 0.0 } else { self.improved as f64 / self.executions as f64 }
+    }
+}
+
+/// Price improvement statistics per market
+#[derive(Debug, Clone, Default)]
+pub struct PriceImprovement {
+    pub markets: HashMap<MarketId, ImprovementStats>,
+}
+
+impl PriceImprovement {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, report: &MultiLegExecutionReport) {
+        let improvement = report.price_improvement();
+        let stats = self.markets.entry(report.market_id.clone()).or_default();
+        stats.executions += 1;
+        stats.improvement += improvement;
+        if improvement > 1e-9 {
+            stats.improved += 1;
+        } else if improvement < -1e-9 {
+            stats.worsened += 1;
+        }
+    }
+
+    pub fn record_rejection(&mut self, quote: &MultiLegExecutionReport) {
+        let stats = self.markets.entry(quote.market_id.clone()).or_default();
+        stats.rejected += 1;
+        stats.rejected_cost -= quote.price_improvement();
+    }
+
+    pub fn get(&self, market_id: &MarketId) -> Option<&ImprovementStats> {
+        self.markets.get(market_id)
+    }
+
+    /// Totals across markets
+    pub fn overall(&self) -> ImprovementStats {
+        self.markets.values().fold(ImprovementStats::default(), |mut acc, s| {
+            acc.executions += s.executions;
+            acc.improved += s.improved;
+            acc.worsened += s.worsened;
+            acc.rejected += s.rejected;
+            acc.improvement += s.improvement;
+            acc.rejected_cost += s.rejected_cost;
+            acc
+        })
+    }
+}
 
 /// Execution simulator
 #[derive(Debug)]
 pub struct ExecutionEngine {
-    pub fee_model: FeeModel,
+    pub fees: SharedFeeSchedule,
+    pub sell_policy: SellPolicy,
 }
 
 impl ExecutionEngine {
-    pub fn new(fee_model: FeeModel) -> Self {
-        Self { fee_model }
+    pub fn new(fees: SharedFeeSchedule) -> Self {
+        Self { fees, sell_policy: SellPolicy::Clamp }
     }
 
-    /// Simulate order execution
-    pub fn execute(
-        &self,
-        book: &OrderBook,
-        size: f64,
-        side: Side,
-        wallet: &mut Wallet,
-    ) -> Option<ExecutionResult> {
+    /// Size we may actually sell of `book.token_id` under the sell policy
+    pub fn sellable_size(&self, book: &OrderBook, size: f64, wallet: &Wallet) -> Option<f64> {
+        let held = wallet.held(&book.token_id);
+        match self.sell_policy {
+            _ if held <= 0.0 => None,
+            SellPolicy::Reject if size > held => None,
+            _ => Some(size.min(held)),
+        }
+    }
+
+    /// What-if: price and cost an order against the book without touching any wallet.
+    /// With a `limit_price` it behaves like a limit order: only levels at or better than
+    /// the limit are taken, and the order fills partially when they run out.
+    pub fn simulate(&self, book: &OrderBook, size: Size, side: Side, limit_price: Option<f64>) -> Option<ExecutionResult> {
         // 1. Check fill ratio
+        let size = match limit_price {
+            Some(limit) => size.value().min(book.depth_within(limit, side)),
+            None => size.value(),
+        };
         let filled_size = FillModel::filled_size(book, size, side);
         if filled_size <= 0.0 {
             return None;
@@ -35,18 +242,9 @@ impl ExecutionEngine {
 
         // 3. Calculate costs
         let notional = exec_price * filled_size;
-        let fee = self.fee_model.calculate(notional, false); // Taker
+        let fee = self.fees.fee(exec_price, filled_size, false); // Taker
         let total_cost = notional + fee;
 
-        // 4. Check if affordable
-        if !wallet.can_afford(total_cost) {
-            return None;
-        }
-
-        // 5. Execute
-        wallet.deduct(total_cost);
-        wallet.record_fee(fee);
-
         Some(ExecutionResult {
             filed_size: filled_size,
             execution_price: exec_price,
@@ -56,4 +254,160 @@ impl ExecutionEngine {
             success: true,
         })
     }
-}
\ No newline at end of file
+
+    /// Simulate order execution, paying the cost (fees included) out of the wallet.
+    /// Sells go through `sell`, which credits the proceeds instead.
+    pub fn execute(
+        &self,
+        book: &OrderBook,
+        size: Size,
+        side: Side,
+        limit_price: Option<f64>,
+        wallet: &mut Wallet,
+    ) -> Option<ExecutionResult> {
+        let result = self.simulate(book, size, side, limit_price)?;
+
+        // 4. Check if affordable, 5. Execute
+        if !wallet.deduct(Usdc(result.total_cost), "buy fill", Some(&book.token_id)) {
+            return None;
+        }
+        wallet.record_fee(Usdc(result.fee_paid), Some(&book.token_id));
+
+        Some(result)
+    }
+
+    /// Sell held inventory into the bids, crediting the proceeds net of fees.
+    /// Polymarket has no margin: the size is clamped (or refused) under the sell policy.
+    pub fn sell(&self, book: &OrderBook, size: Size, limit_price: Option<f64>, wallet: &mut Wallet) -> Option<ExecutionResult> {
+        let size = Size::new(self.sellable_size(book, size.value(), wallet)?)?;
+        let result = self.simulate(book, size, Side::Sell, limit_price)?;
+        wallet.credit(Usdc(result.proceeds()), "sell fill", Some(&book.token_id));
+        wallet.record_fee(Usdc(result.fee_paid), Some(&book.token_id));
+        Some(result)
+    }
+
+    /// `execute` for buys, `sell` for sells
+    fn fill(&self, book: &OrderBook, size: Size, side: Side, limit_price: Option<f64>, wallet: &mut Wallet) -> Option<ExecutionResult> {
+        match side {
+            Side::Buy => self.execute(book, size, side, limit_price, wallet),
+            Side::Sell => self.sell(book, size, limit_price, wallet),
+        }
+    }
+
+    /// What-if for every leg of a multi-leg trade
+    pub fn simulate_legs(&self, market_id: &MarketId, legs: &[LegPlan]) -> MultiLegExecutionReport {
+        let fills = legs.iter().map(|leg| self.leg_fill(leg, self.simulate(leg.book, leg.size, leg.side, None))).collect();
+        MultiLegExecutionReport::new(market_id.clone(), fills)
+    }
+
+    /// Execute every leg against the wallet, in order
+    pub fn execute_legs(&self, market_id: &MarketId, legs: &[LegPlan], wallet: &mut Wallet) -> MultiLegExecutionReport {
+        let fills = legs.iter().map(|leg| self.leg_fill(leg, self.fill(leg.book, leg.size, leg.side, None, wallet))).collect();
+        MultiLegExecutionReport::new(market_id.clone(), fills)
+    }
+
+    /// Re-price a multi-leg trade at whatever the books offer now, not what the detector saw.
+    /// Better prices are simply taken; if the books moved against us, the trade must still
+    /// clear `min_profit`, valuing each matched unit at `unit_payout` (e.g. $1 per complete
+    /// set), or it is rejected and counted in `stats`. Returns the quote to submit at.
+    pub fn reprice(
+        &self,
+        market_id: &MarketId,
+        legs: &[LegPlan],
+        unit_payout: f64,
+        min_profit: f64,
+        stats: &mut PriceImprovement,
+    ) -> Result<MultiLegExecutionReport, String> {
+        let quote = self.simulate_legs(market_id, legs);
+        let profit = unit_payout * quote.matched_size() - quote.net_cost();
+        if quote.price_improvement() < -1e-9 && profit < min_profit {
+            stats.record_rejection(&quote);
+            return Err(format!(
+                "{}: books worsened by ${:.4} since detection, profit {:.4} below {:.4}",
+                market_id,
+                -quote.price_improvement(),
+                profit,
+                min_profit,
+            ));
+        }
+        Ok(quote)
+    }
+
+    fn leg_fill(&self, leg: &LegPlan, result: Option<ExecutionResult>) -> LegFill {
+        let result = result.unwrap_or(ExecutionResult {
+            filed_size: 0.0,
+            execution_price: 0.0,
+            fee_paid: 0.0,
+            slippage: 0.0,
+            total_cost: 0.0,
+            success: false,
+        });
+        LegFill {
+            token_id: leg.book.token_id.clone(),
+            side: leg.side,
+            requested: leg.size.value(),
+            filled: result.filed_size,
+            predicted_price: leg.predicted_price,
+            predicted_fee: self.fees.fee(leg.predicted_price, result.filed_size, false),
+            price: result.execution_price,
+            fee: result.fee_paid,
+            slippage: result.slippage,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fees::FeeModel;
+    use crate::types::PriceLevel;
+    use std::sync::Arc;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    fn engine() -> ExecutionEngine {
+        ExecutionEngine::new(Arc::new(FeeModel { maker_fee_bps: 0, taker_fee_bps: 0 }))
+    }
+
+    /// Bids 0.48 x 100, 0.46 x 100; asks 0.50 x 100, 0.52 x 100
+    fn book() -> OrderBook {
+        let level = |price, size| PriceLevel { price, size };
+        OrderBook {
+            token_id: TokenId::from("yes"),
+            bids: vec![level(0.48, 100.0), level(0.46, 100.0)],
+            asks: vec![level(0.50, 100.0), level(0.52, 100.0)],
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn buy_stops_at_the_limit() {
+        let size = Size::new(150.0).unwrap();
+        let result = engine().simulate(&book(), size, Side::Buy, Some(0.51)).unwrap();
+        assert!(close(result.filed_size, 100.0));
+        assert!(close(result.execution_price, 0.50));
+        // Without a limit it walks into the second level
+        let result = engine().simulate(&book(), size, Side::Buy, None).unwrap();
+        assert!(close(result.filed_size, 150.0));
+        assert!(close(result.execution_price, (100.0 * 0.50 + 50.0 * 0.52) / 150.0));
+    }
+
+    #[test]
+    fn limit_below_the_ask_fills_nothing() {
+        let size = Size::new(10.0).unwrap();
+        assert!(engine().simulate(&book(), size, Side::Buy, Some(0.49)).is_none());
+    }
+
+    #[test]
+    fn sell_limit_mirrors_the_buy_side() {
+        let size = Size::new(150.0).unwrap();
+        let result = engine().simulate(&book(), size, Side::Sell, Some(0.47)).unwrap();
+        assert!(close(result.filed_size, 100.0));
+        assert!(close(result.execution_price, 0.48));
+        let result = engine().simulate(&book(), size, Side::Sell, Some(0.46)).unwrap();
+        assert!(close(result.filed_size, 150.0));
+        assert!(engine().simulate(&book(), size, Side::Sell, Some(0.49)).is_none());
+    }
+}
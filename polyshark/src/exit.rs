@@ -0,0 +1,242 @@
+use crate::fees::SharedFeeSchedule;
+use crate::orders::{OrderRequest, TimeInForce};
+use crate::types::{Market, MarketId, OrderBook, Price, Side, Size, TokenId, SECONDS_PER_YEAR};
+use crate::wallet::Wallet;
+use serde::Deserialize;
+
+/// Tag on journaled fills of an early exit
+pub const EXIT_TAG: &str = "early-exit";
+
+/// When the bot sells held sets back into the books instead of waiting for resolution
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExitConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_min_gain")]
+    pub min_gain: f64,          // Required edge over holding (USDC)
+    #[serde(default = "default_annual_hold_rate")]
+    pub annual_hold_rate: f64,  // Opportunity cost of locked capital
+}
+
+impl Default for ExitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_gain: default_min_gain(),
+            annual_hold_rate: default_annual_hold_rate(),
+        }
+    }
+}
+
+fn default_min_gain() -> f64 {
+    0.05
+}
+
+fn default_annual_hold_rate() -> f64 {
+    0.05
+}
+
+/// Early exit opportunity for a held YES+NO set
+#[derive(Debug, Clone)]
+pub struct ExitDecision {
+    pub market_id: MarketId,
+    pub yes_token: TokenId,
+    pub no_token: TokenId,
+    pub size: f64,          // Complete sets to sell
+    pub yes_price: f64,     // VWAP when selling YES into the bids
+    pub no_price: f64,      // VWAP when selling NO into the bids
+    pub yes_limit: f64,     // Lowest YES bid the sale reaches
+    pub no_limit: f64,      // Lowest NO bid the sale reaches
+    pub fees: f64,
+    pub proceeds: f64,      // Cash received after fees
+    pub hold_value: f64,    // Resolution payout discounted to today
+}
+
+impl ExitDecision {
+    /// Extra value from exiting now instead of holding
+    pub fn gain(&self) -> f64 {
+        self.proceeds - self.hold_value
+    }
+}
+
+/// Sells both legs of a set when that beats holding to resolution
+#[derive(Debug, Clone)]
+pub struct EarlyExitStrategy {
+    pub fees: SharedFeeSchedule,
+    pub min_gain: f64,           // Required edge over holding (USDC)
+    pub annual_hold_rate: f64,   // Opportunity cost of locked capital (e.g., 0.05)
+}
+
+impl EarlyExitStrategy {
+    pub fn new(fees: SharedFeeSchedule, min_gain: f64, annual_hold_rate: f64) -> Self {
+        Self {
+            fees,
+            min_gain,
+            annual_hold_rate,
+        }
+    }
+
+    /// Value of holding `size` sets until they pay $1 each at resolution
+    pub fn hold_value(&self, market: &Market, size: f64, now: u64) -> f64 {
+        let years = market.seconds_to_resolution(now).unwrap_or(0) as f64 / SECONDS_PER_YEAR;
+        size / (1.0 + self.annual_hold_rate * years)
+    }
+
+    /// Check whether selling the held set into both books beats holding
+    pub fn evaluate(
+        &self,
+        market: &Market,
+        wallet: &Wallet,
+        yes_book: &OrderBook,
+        no_book: &OrderBook,
+        now: u64,
+    ) -> Option<ExitDecision> {
+        let yes_token = market.clob_token_ids.first()?;
+        let no_token = market.clob_token_ids.get(1)?;
+        let yes = wallet.positions.get(yes_token).filter(|p| p.side == Side::Buy)?;
+        let no = wallet.positions.get(no_token).filter(|p| p.side == Side::Buy)?;
+
+        let size = yes.size.min(no.size);
+        if size <= 0.0 {
+            return None;
+        }
+
+        let (yes_walk, no_walk) = (yes_book.walk(size, Side::Sell), no_book.walk(size, Side::Sell));
+        if !yes_walk.is_complete() || !no_walk.is_complete() {
+            return None;
+        }
+        let (yes_price, no_price) = (yes_walk.vwap()?, no_walk.vwap()?);
+        let gross = (yes_price + no_price) * size;
+        let fees = self.fees.fee(yes_price, size, false) + self.fees.fee(no_price, size, false);
+
+        let decision = ExitDecision {
+            market_id: market.id.clone(),
+            yes_token: yes_token.clone(),
+            no_token: no_token.clone(),
+            size,
+            yes_price,
+            no_price,
+            yes_limit: yes_walk.levels.last()?.price,
+            no_limit: no_walk.levels.last()?.price,
+            fees,
+            proceeds: gross - fees,
+            hold_value: self.hold_value(market, size, now),
+        };
+
+        if decision.gain() > self.min_gain {
+            Some(decision)
+        } else {
+            None
+        }
+    }
+
+    /// IOC sells of both legs, limited at the lowest bid each was priced against. Fills are
+    /// booked and journaled like any other.
+    pub fn orders(&self, decision: &ExitDecision) -> Vec<OrderRequest> {
+        [(&decision.yes_token, decision.yes_limit), (&decision.no_token, decision.no_limit)]
+            .into_iter()
+            .filter_map(|(token_id, limit)| {
+                Some(OrderRequest {
+                    token_id: token_id.clone(),
+                    side: Side::Sell,
+                    price: Price::new(limit)?,
+                    size: Size::new(decision.size)?,
+                    time_in_force: TimeInForce::Ioc,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attribution::EntryReason;
+    use crate::fees::FeeConfig;
+    use crate::types::PriceLevel;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    fn market() -> Market {
+        Market {
+            id: MarketId::from("m"),
+            question: String::new(),
+            slug: "m".to_string(),
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            outcome_prices: vec![0.5, 0.5],
+            clob_token_ids: vec![TokenId::from("yes"), TokenId::from("no")],
+            best_bid: None,
+            best_ask: None,
+            maker_base_fee: 0,
+            taker_base_fee: 0,
+            liquidity: 10_000.0,
+            volume_24hr: 0.0,
+            active: true,
+            accepting_orders: true,
+            condition_id: String::new(),
+            end_date: None,
+            resolution_source: None,
+            category: None,
+            tags: Vec::new(),
+            neg_risk: false,
+            event_id: None,
+            rewards_max_spread: None,
+            rewards_min_size: None,
+            restricted: false,
+            description: None,
+            uma_resolution_status: None,
+            closed: false,
+        }
+    }
+
+    fn bids(token: &str, levels: &[(f64, f64)]) -> OrderBook {
+        OrderBook {
+            token_id: TokenId::from(token),
+            bids: levels.iter().map(|&(price, size)| PriceLevel { price, size }).collect(),
+            asks: Vec::new(),
+            timestamp: 0,
+        }
+    }
+
+    fn wallet() -> Wallet {
+        let mut wallet = Wallet::new(100.0);
+        wallet.open_position("yes".into(), Side::Buy, 10.0, 0.4, 0, EntryReason::ArbLeg);
+        wallet.open_position("no".into(), Side::Buy, 10.0, 0.5, 0, EntryReason::ArbLeg);
+        wallet
+    }
+
+    fn strategy(min_gain: f64) -> EarlyExitStrategy {
+        let zero = FeeConfig::PriceCurve { maker_fee_bps: 0, taker_fee_bps: 0 };
+        EarlyExitStrategy::new(zero.build(), min_gain, 0.0)
+    }
+
+    #[test]
+    fn exits_only_when_the_gain_clears_min_gain() {
+        // Sets sell for 1.01 against a payout of 1.00: 0.10 over holding 10 of them
+        let (yes, no) = (bids("yes", &[(0.55, 10.0)]), bids("no", &[(0.46, 10.0)]));
+        let decision = strategy(0.05).evaluate(&market(), &wallet(), &yes, &no, 0).unwrap();
+        assert!(close(decision.size, 10.0));
+        assert!(close(decision.gain(), 0.1));
+        assert!(strategy(0.15).evaluate(&market(), &wallet(), &yes, &no, 0).is_none());
+    }
+
+    #[test]
+    fn thin_bids_are_held() {
+        let (yes, no) = (bids("yes", &[(0.60, 4.0)]), bids("no", &[(0.46, 10.0)]));
+        assert!(strategy(0.0).evaluate(&market(), &wallet(), &yes, &no, 0).is_none());
+    }
+
+    #[test]
+    fn orders_sell_both_legs_down_to_the_lowest_bid_reached() {
+        let (yes, no) = (bids("yes", &[(0.60, 5.0), (0.55, 10.0)]), bids("no", &[(0.46, 10.0)]));
+        let strategy = strategy(0.05);
+        let decision = strategy.evaluate(&market(), &wallet(), &yes, &no, 0).unwrap();
+        let orders = strategy.orders(&decision);
+        assert_eq!(orders.len(), 2);
+        assert!(orders.iter().all(|o| o.side == Side::Sell && o.time_in_force == TimeInForce::Ioc && close(o.size.value(), 10.0)));
+        assert!(close(orders[0].price.value(), 0.55));
+        assert!(close(orders[1].price.value(), 0.46));
+    }
+}
@@ -0,0 +1,255 @@
+use crate::fees::polymarket_fee;
+use crate::fills::FillModel;
+use crate::slippage::SlippageModel;
+use crate::types::{ArbitrageSignal, MarketId, OrderBook};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+pub const WHY_NOT_LOG_PATH: &str = "why_not.jsonl";
+
+/// Read back a why-not log
+pub fn load_rejections(path: &Path) -> std::io::Result<Vec<Rejection>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut rejections = Vec::new();
+    for line in reader.lines() {
+        if let Ok(rejection) = serde_json::from_str::<Rejection>(&line?) {
+            rejections.push(rejection);
+        }
+    }
+    Ok(rejections)
+}
+
+/// Cost that can turn a raw spread into a no-trade
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CostComponent {
+    Fees,
+    Slippage,
+    FillProbability,  // Expected edge lost to legs that don't fill
+    Staleness,        // Quotes too old to trust
+}
+
+impl CostComponent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Fees => "fees",
+            Self::Slippage => "slippage",
+            Self::FillProbability => "fill_probability",
+            Self::Staleness => "staleness",
+        }
+    }
+}
+
+/// Structured reason an opportunity near the threshold was not traded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rejection {
+    pub market_id: MarketId,
+    pub timestamp: u64,
+    pub size: f64,
+    pub gross_edge: f64,  // USDC before costs
+    pub fees: f64,
+    pub slippage: f64,
+    pub fill_risk: f64,
+    pub stale: bool,
+    pub net: f64,
+    pub threshold: f64,
+    pub killer: CostComponent,  // Largest cost, or staleness if the data was stale
+}
+
+/// Explains rejections whose gross edge came within `window` of clearing the threshold
+#[derive(Debug, Clone)]
+pub struct Explainer {
+    pub threshold: f64,  // Minimum net profit to trade
+    pub window: f64,     // Only explain signals whose gross edge >= threshold - window
+}
+
+impl Explainer {
+    pub fn new(threshold: f64, window: f64) -> Self {
+        Self { threshold, window }
+    }
+
+    /// Break down one signal; `None` if it would trade or is nowhere near the threshold
+    pub fn explain(
+        &self,
+        signal: &ArbitrageSignal,
+        size: f64,
+        fee_rate: f64,
+        books: (&OrderBook, &OrderBook),  // (YES, NO)
+        stale: bool,
+        now: u64,
+    ) -> Option<Rejection> {
+        let gross_edge = signal.edge * size;
+        if gross_edge < self.threshold - self.window {
+            return None;
+        }
+
+        let side = signal.recommended_side;
+        let (yes, no) = books;
+        let fees = polymarket_fee(fee_rate, signal.yes_price, size) + polymarket_fee(fee_rate, signal.no_price, size);
+        let slippage = [(yes, signal.yes_price), (no, signal.no_price)].iter()
+            .map(|(book, price)| SlippageModel::calculate(book, size, side).unwrap_or(0.0).max(0.0) * price * size)
+            .sum::<f64>();
+        // Arb edge only materialises if both legs fill
+        let fill = FillModel::estimate_fill_ratio(yes, size, side).min(FillModel::estimate_fill_ratio(no, size, side));
+        let fill_risk = gross_edge * (1.0 - fill);
+
+        let net = gross_edge - fees - slippage - fill_risk;
+        if net > self.threshold && !stale {
+            return None;
+        }
+
+        let killer = if stale {
+            CostComponent::Staleness
+        } else {
+            [(CostComponent::Fees, fees), (CostComponent::Slippage, slippage), (CostComponent::FillProbability, fill_risk)]
+                .into_iter()
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(c, _)| c)
+                .unwrap_or(CostComponent::Fees)
+        };
+
+        Some(Rejection {
+            market_id: signal.market_id.clone(),
+            timestamp: now,
+            size,
+            gross_edge,
+            fees,
+            slippage,
+            fill_risk,
+            stale,
+            net,
+            threshold: self.threshold,
+            killer,
+        })
+    }
+}
+
+/// Per-component totals for the "why we didn't trade" report
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ComponentTotals {
+    pub rejections: usize,
+    pub forgone_gross: f64,  // Gross edge of the opportunities it killed
+    pub avg_shortfall: f64,  // Mean of threshold - net
+}
+
+/// One row of the daily report
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WhyNotRow {
+    pub component: String,
+    pub rejections: usize,
+    pub forgone_gross: f64,
+    pub avg_shortfall: f64,
+}
+
+/// Daily aggregation of rejections
+#[derive(Debug, Clone, Default)]
+pub struct WhyNotReport {
+    rejections: Vec<Rejection>,
+    log: Option<PathBuf>,  // Every rejection is appended here as a JSON line
+}
+
+impl WhyNotReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_log(mut self, path: PathBuf) -> Self {
+        self.log = Some(path);
+        self
+    }
+
+    /// Report over rejections read back from a log
+    pub fn from_rejections(rejections: Vec<Rejection>) -> Self {
+        Self { rejections, log: None }
+    }
+
+    pub fn record(&mut self, rejection: Rejection) {
+        if let Some(path) = &self.log {
+            let line = serde_json::to_string(&rejection).unwrap_or_default();
+            let written = OpenOptions::new().create(true).append(true).open(path).and_then(|mut f| writeln!(f, "{}", line));
+            if let Err(err) = written {
+                eprintln!("warning: could not log why-not rejection: {}", err);
+            }
+        }
+        self.rejections.push(rejection);
+    }
+
+    /// Totals per killing component for rejections in `[since, until)`
+    pub fn summarize(&self, since: u64, until: u64) -> HashMap<CostComponent, ComponentTotals> {
+        let mut totals: HashMap<CostComponent, ComponentTotals> = HashMap::new();
+        for r in self.rejections.iter().filter(|r| r.timestamp >= since && r.timestamp < until) {
+            let t = totals.entry(r.killer).or_default();
+            t.rejections += 1;
+            t.forgone_gross += r.gross_edge;
+            t.avg_shortfall += r.threshold - r.net;
+        }
+        for t in totals.values_mut() {
+            t.avg_shortfall /= t.rejections as f64;
+        }
+        totals
+    }
+
+    /// Report rows for the UTC day starting at `day_start`, most forgone edge first
+    pub fn daily(&self, day_start: u64) -> Vec<WhyNotRow> {
+        let mut rows: Vec<WhyNotRow> = self.summarize(day_start, day_start + 86_400).into_iter()
+            .map(|(component, t)| WhyNotRow {
+                component: component.name().to_string(),
+                rejections: t.rejections,
+                forgone_gross: t.forgone_gross,
+                avg_shortfall: t.avg_shortfall,
+            })
+            .collect();
+        rows.sort_by(|a, b| b.forgone_gross.total_cmp(&a.forgone_gross));
+        rows
+    }
+
+    /// Drop rejections older than `before` once they've been reported
+    pub fn prune(&mut self, before: u64) {
+        self.rejections.retain(|r| r.timestamp >= before);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejection(timestamp: u64, gross_edge: f64, net: f64, killer: CostComponent) -> Rejection {
+        Rejection {
+            market_id: MarketId::from("m"),
+            timestamp,
+            size: 10.0,
+            gross_edge,
+            fees: 0.0,
+            slippage: 0.0,
+            fill_risk: 0.0,
+            stale: false,
+            net,
+            threshold: 0.05,
+            killer,
+        }
+    }
+
+    #[test]
+    fn the_daily_report_reads_back_the_log() {
+        let path = std::env::temp_dir().join(format!("polyshark-why-not-{}.jsonl", std::process::id()));
+        let mut report = WhyNotReport::new().with_log(path.clone());
+        report.record(rejection(86_400 + 10, 0.04, 0.01, CostComponent::Fees));
+        report.record(rejection(86_400 + 20, 0.06, 0.03, CostComponent::Fees));
+        report.record(rejection(86_400 + 30, 0.20, 0.04, CostComponent::Slippage));
+        report.record(rejection(10, 0.50, 0.0, CostComponent::Slippage));  // The day before
+
+        let logged = load_rejections(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(logged.len(), 4);
+
+        let rows = WhyNotReport::from_rejections(logged).daily(86_400);
+        assert_eq!(rows.len(), 2);
+        assert_eq!((rows[0].component.as_str(), rows[0].rejections), ("slippage", 1));
+        assert_eq!((rows[1].component.as_str(), rows[1].rejections), ("fees", 2));
+        assert!((rows[1].forgone_gross - 0.10).abs() < 1e-9);
+        assert!((rows[1].avg_shortfall - 0.03).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::instance::{InstanceLock, LockConfig};
+use crate::orders::{OrderError, OrderGateway, OrderManager};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Which side of a primary/standby pair this host is
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailoverRole {
+    Primary,
+    Standby,
+}
+
+/// Warm standby: the primary publishes a heartbeat next to the shared journal/lock, and
+/// the standby takes the instance lock once the heartbeat stops
+#[derive(Debug, Clone, Deserialize)]
+pub struct FailoverConfig {
+    pub role: FailoverRole,
+    pub heartbeat_path: PathBuf,     // On storage both hosts see
+    #[serde(default = "default_heartbeat_interval_ms")]
+    pub heartbeat_interval_ms: u64,
+    #[serde(default = "default_takeover_after_ms")]
+    pub takeover_after_ms: u64,      // Heartbeat silence before the standby steps in
+}
+
+fn default_heartbeat_interval_ms() -> u64 {
+    5_000
+}
+
+fn default_takeover_after_ms() -> u64 {
+    30_000
+}
+
+/// Last sign of life from the primary
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Heartbeat {
+    pub instance_id: String,
+    pub timestamp_ms: u64,
+    pub seq: u64,
+}
+
+impl Heartbeat {
+    pub fn read(path: &Path) -> std::io::Result<Option<Self>> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(serde_json::from_str(&text).ok()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Replace the file atomically so the standby never reads half a heartbeat
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string(self).map_err(std::io::Error::other)?)?;
+        fs::rename(tmp, path)
+    }
+}
+
+/// Publishes the primary's heartbeat. The trading loop beats only while its feed is
+/// delivering, so a primary that is up but blind lets the standby step in.
+#[derive(Debug, Clone)]
+pub struct HeartbeatWriter {
+    pub path: PathBuf,
+    pub instance_id: String,
+    seq: u64,
+}
+
+impl HeartbeatWriter {
+    pub fn new(path: &Path, instance_id: &str) -> Self {
+        Self { path: path.to_path_buf(), instance_id: instance_id.to_string(), seq: 0 }
+    }
+
+    pub fn beat(&mut self, now_ms: u64) -> std::io::Result<()> {
+        self.seq += 1;
+        Heartbeat { instance_id: self.instance_id.clone(), timestamp_ms: now_ms, seq: self.seq }.write(&self.path)
+    }
+}
+
+/// What the standby concluded from the latest heartbeat
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrimaryState {
+    Alive,
+    Silent { for_ms: u64 },  // Not yet long enough to take over
+    Dead { for_ms: u64 },
+}
+
+/// Watches the primary's heartbeat. Silence is measured on the standby's own clock from
+/// the last time the heartbeat changed, so clock skew between hosts doesn't matter.
+#[derive(Debug, Clone)]
+pub struct StandbyMonitor {
+    pub config: FailoverConfig,
+    last: Option<Heartbeat>,
+    changed_at: u64,
+}
+
+impl StandbyMonitor {
+    pub fn new(config: FailoverConfig, now_ms: u64) -> Self {
+        Self { config, last: None, changed_at: now_ms }
+    }
+
+    pub fn observe(&mut self, heartbeat: Option<Heartbeat>, now_ms: u64) -> PrimaryState {
+        if heartbeat.is_some() && heartbeat != self.last {
+            self.last = heartbeat;
+            self.changed_at = now_ms;
+        }
+        let silent = now_ms.saturating_sub(self.changed_at);
+        if silent < self.config.heartbeat_interval_ms * 2 {
+            PrimaryState::Alive
+        } else if silent < self.config.takeover_after_ms {
+            PrimaryState::Silent { for_ms: silent }
+        } else {
+            PrimaryState::Dead { for_ms: silent }
+        }
+    }
+
+    /// Block until the primary is dead and its instance lock is free, then hold the lock.
+    /// The lock is the real arbiter: a primary that is merely slow keeps it and we keep waiting.
+    pub async fn wait_for_takeover(&mut self, lock: &LockConfig, instance_id: &str) -> InstanceLock {
+        let mut ticker = tokio::time::interval(Duration::from_millis(self.config.heartbeat_interval_ms.max(100)));
+        loop {
+            ticker.tick().await;
+            let heartbeat = Heartbeat::read(&self.config.heartbeat_path).unwrap_or_else(|err| {
+                eprintln!("⚠️  standby: {}: {}", self.config.heartbeat_path.display(), err);
+                None
+            });
+            if let PrimaryState::Dead { for_ms } = self.observe(heartbeat, now_ms()) {
+                match lock.acquire(instance_id) {
+                    Ok(held) => {
+                        let primary = self.last.as_ref().map(|h| h.instance_id.as_str()).unwrap_or("unknown");
+                        println!("standby: primary {} silent for {} ms, taking over", primary, for_ms);
+                        return held;
+                    }
+                    Err(err) => eprintln!("standby: primary silent for {} ms but lock still held: {}", for_ms, err),
+                }
+            }
+        }
+    }
+}
+
+/// First thing a standby does after taking over: the gateway can't list the dead primary's
+/// resting orders, so they are all cancelled rather than adopted, leaving a clean slate.
+/// Cash and positions come from replaying the shared journal.
+pub fn take_over(gateway: &mut dyn OrderGateway, orders: &mut OrderManager) -> Result<(), OrderError> {
+    gateway.cancel_all()?;
+    orders.open.clear();
+    Ok(())
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
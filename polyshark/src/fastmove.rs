@@ -0,0 +1,164 @@
+use crate::bus::{EventBus, RiskEvent};
+use crate::types::MarketId;
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+
+/// What counts as a suspiciously fast move and how long a market must settle afterwards
+#[derive(Debug, Clone, Deserialize)]
+pub struct FastMoveConfig {
+    #[serde(default = "default_window_ms")]
+    pub window_ms: u64,    // Moves are measured over this span
+    #[serde(default = "default_max_move")]
+    pub max_move: f64,     // A mid range wider than this within the window pauses the market
+    #[serde(default = "default_calm_ms")]
+    pub calm_ms: u64,      // Paused until the mid has stayed within `calm_move` this long
+    #[serde(default = "default_calm_move")]
+    pub calm_move: f64,    // Largest mid range over `calm_ms` that still counts as settled
+}
+
+impl Default for FastMoveConfig {
+    fn default() -> Self {
+        Self {
+            window_ms: default_window_ms(),
+            max_move: default_max_move(),
+            calm_ms: default_calm_ms(),
+            calm_move: default_calm_move(),
+        }
+    }
+}
+
+fn default_window_ms() -> u64 {
+    5_000
+}
+
+fn default_max_move() -> f64 {
+    0.10
+}
+
+fn default_calm_ms() -> u64 {
+    120_000
+}
+
+fn default_calm_move() -> f64 {
+    0.02
+}
+
+/// Why and since when a market is paused
+#[derive(Debug, Clone, PartialEq)]
+pub struct FastMovePause {
+    pub from: f64,         // Mid at the start of the window that tripped the guard
+    pub to: f64,           // Furthest mid reached within it
+    pub since_ms: u64,
+    pub last_move_ms: u64, // Latest update that was itself too fast; calm is counted from here
+}
+
+#[derive(Debug, Clone, Default)]
+struct MarketMids {
+    mids: VecDeque<(u64, f64)>,  // (ms, mid), oldest first
+    pause: Option<FastMovePause>,
+}
+
+impl MarketMids {
+    /// Lowest and highest mid since `since`, with the first one in that span
+    fn range(&self, since: u64) -> Option<(f64, f64, f64)> {
+        let mut points = self.mids.iter().filter(|(ts, _)| *ts >= since).map(|(_, mid)| *mid);
+        let first = points.next()?;
+        let (low, high) = points.fold((first, first), |(lo, hi), mid| (lo.min(mid), hi.max(mid)));
+        Some((first, low, high))
+    }
+}
+
+/// Pauses a market whose mid moves very fast. A jump like that is usually news or a
+/// resolution leaking into the book, and the "arbitrage" it opens is the market repricing
+/// towards the outcome, not a mispricing. Trading resumes once the mid has held steady for
+/// `calm_ms`.
+#[derive(Debug, Clone, Default)]
+pub struct FastMoveGuard {
+    pub config: FastMoveConfig,
+    markets: HashMap<MarketId, MarketMids>,
+    bus: Option<EventBus>,
+}
+
+impl FastMoveGuard {
+    pub fn new(config: FastMoveConfig) -> Self {
+        Self { config, ..Default::default() }
+    }
+
+    pub fn with_bus(mut self, bus: EventBus) -> Self {
+        self.bus = Some(bus);
+        self
+    }
+
+    /// Record a market's mid at `now` (ms); returns true when this update paused it
+    pub fn observe(&mut self, market_id: &MarketId, mid: f64, now: u64) -> bool {
+        let keep = self.config.window_ms.max(self.config.calm_ms);
+        let entry = self.markets.entry(market_id.clone()).or_default();
+        entry.mids.push_back((now, mid));
+        while entry.mids.front().is_some_and(|(ts, _)| *ts + keep < now) {
+            entry.mids.pop_front();
+        }
+
+        let Some((first, low, high)) = entry.range(now.saturating_sub(self.config.window_ms)) else {
+            return false;
+        };
+        if high - low <= self.config.max_move {
+            return false;
+        }
+        if let Some(pause) = &mut entry.pause {
+            pause.last_move_ms = now;
+            return false;
+        }
+        // From where the window started to the furthest point reached
+        let (from, to) = (first, if high - first >= first - low { high } else { low });
+        entry.pause = Some(FastMovePause { from, to, since_ms: now, last_move_ms: now });
+        eprintln!(
+            "⚠️  {} moved {:.3} -> {:.3} within {}ms, pausing until it settles",
+            market_id, from, to, self.config.window_ms
+        );
+        if let Some(bus) = &self.bus {
+            bus.publish_risk(RiskEvent::FastMove { market_id: market_id.clone(), from, to, window_ms: self.config.window_ms });
+        }
+        true
+    }
+
+    /// Lift pauses on markets that have been calm for `calm_ms`; returns the resumed markets.
+    /// A market with no updates at all since its last fast move counts as calm.
+    pub fn refresh(&mut self, now: u64) -> Vec<MarketId> {
+        let mut resumed = Vec::new();
+        for (market_id, entry) in self.markets.iter_mut() {
+            let Some(pause) = &entry.pause else {
+                continue;
+            };
+            if now.saturating_sub(pause.last_move_ms) < self.config.calm_ms {
+                continue;
+            }
+            let calm = entry.range(now.saturating_sub(self.config.calm_ms))
+                .is_none_or(|(_, low, high)| high - low <= self.config.calm_move);
+            if !calm {
+                continue;
+            }
+            let paused_ms = now.saturating_sub(pause.since_ms);
+            entry.pause = None;
+            eprintln!("{} settled after {}ms, resuming", market_id, paused_ms);
+            if let Some(bus) = &self.bus {
+                bus.publish_risk(RiskEvent::FastMoveCleared { market_id: market_id.clone(), paused_ms });
+            }
+            resumed.push(market_id.clone());
+        }
+        resumed
+    }
+
+    /// Whether signals on the market should be dropped
+    pub fn is_paused(&self, market_id: &MarketId) -> bool {
+        self.markets.get(market_id).is_some_and(|m| m.pause.is_some())
+    }
+
+    pub fn pause(&self, market_id: &MarketId) -> Option<&FastMovePause> {
+        self.markets.get(market_id).and_then(|m| m.pause.as_ref())
+    }
+
+    /// Stop tracking markets that left the watchlist
+    pub fn retain(&mut self, keep: impl Fn(&MarketId) -> bool) {
+        self.markets.retain(|market_id, _| keep(market_id));
+    }
+}
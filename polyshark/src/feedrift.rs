@@ -0,0 +1,190 @@
+use crate::bus::{EventBus, Fill, RiskEvent};
+use crate::fees::FeeSchedule;
+use crate::types::TokenId;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// When charged fees diverge enough from the model to be worth an alert
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeeDriftConfig {
+    #[serde(default = "default_window")]
+    pub window: usize,          // Most recent fills compared
+    #[serde(default = "default_min_fills")]
+    pub min_fills: usize,       // Fewer than this and nothing is concluded
+    #[serde(default = "default_max_bias")]
+    pub max_bias: f64,          // Alert when charged/predicted - 1 exceeds this either way
+    #[serde(default = "default_min_consistency")]
+    pub min_consistency: f64,   // ...and this share of the off fills are off in the same direction
+    #[serde(default = "default_noise_usdc")]
+    pub noise_usdc: f64,        // Per-fill differences this small are rounding, not drift
+}
+
+impl Default for FeeDriftConfig {
+    fn default() -> Self {
+        Self {
+            window: default_window(),
+            min_fills: default_min_fills(),
+            max_bias: default_max_bias(),
+            min_consistency: default_min_consistency(),
+            noise_usdc: default_noise_usdc(),
+        }
+    }
+}
+
+fn default_window() -> usize {
+    100
+}
+
+fn default_min_fills() -> usize {
+    20
+}
+
+fn default_max_bias() -> f64 {
+    0.10
+}
+
+fn default_min_consistency() -> f64 {
+    0.75
+}
+
+fn default_noise_usdc() -> f64 {
+    0.001
+}
+
+/// Fee the exchange charged on one fill next to what the schedule predicted.
+/// A negative charge is a maker rebate.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeeCheck {
+    pub reference: String,  // Order id, or token id for simulated fills
+    pub token_id: TokenId,
+    pub price: f64,
+    pub size: f64,
+    pub is_maker: bool,
+    pub predicted: f64,
+    pub charged: f64,
+    pub timestamp: u64,     // ms
+}
+
+impl FeeCheck {
+    /// Overcharge (positive) or undercharge/rebate (negative) against the model
+    pub fn diff(&self) -> f64 {
+        self.charged - self.predicted
+    }
+}
+
+/// Fees charged over the window versus the model
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeeDrift {
+    pub fills: usize,
+    pub predicted: f64,
+    pub charged: f64,
+    pub over: usize,    // Fills charged more than predicted (beyond noise)
+    pub under: usize,   // Fills charged less, rebates included
+}
+
+impl FeeDrift {
+    /// charged / predicted - 1; `None` when the model predicted no fees at all
+    pub fn bias(&self) -> Option<f64> {
+        (self.predicted.abs() > 1e-9).then(|| self.charged / self.predicted - 1.0)
+    }
+
+    /// Share of off fills that are off in the dominant direction
+    pub fn consistency(&self) -> f64 {
+        let off = self.over + self.under;
+        if off == 0 { 0.0 } else { self.over.max(self.under) as f64 / off as f64 }
+    }
+
+    pub fn describe(&self) -> String {
+        let bias = self.bias().map(|b| format!("{:+.1}%", b * 100.0)).unwrap_or_else(|| "n/a".to_string());
+        format!(
+            "charged {:.4} vs predicted {:.4} USDC over {} fills ({}; {} over, {} under)",
+            self.charged, self.predicted, self.fills, bias, self.over, self.under
+        )
+    }
+}
+
+/// Pairs each fill's charged fee with the fee schedule's prediction and watches for the
+/// model drifting: a sustained, one-directional gap means the schedule or its parameters
+/// (base fee, volume tier, rebates) no longer match the exchange
+#[derive(Debug, Clone, Default)]
+pub struct FeeReconciler {
+    pub config: FeeDriftConfig,
+    pub checks: VecDeque<FeeCheck>,  // Most recent `config.window`, oldest first
+    alerted: bool,                   // Raised for the current episode; re-armed once back in line
+    bus: Option<EventBus>,
+}
+
+impl FeeReconciler {
+    pub fn new(config: FeeDriftConfig) -> Self {
+        Self { config, ..Default::default() }
+    }
+
+    pub fn with_bus(mut self, bus: EventBus) -> Self {
+        self.bus = Some(bus);
+        self
+    }
+
+    /// Record one fill; `reference` identifies it when the fill has no order id
+    pub fn record(&mut self, schedule: &dyn FeeSchedule, fill: &Fill, is_maker: bool, reference: &str) -> FeeCheck {
+        let check = FeeCheck {
+            reference: fill.order_id.clone().unwrap_or_else(|| reference.to_string()),
+            token_id: fill.token_id.clone(),
+            price: fill.price,
+            size: fill.size,
+            is_maker,
+            predicted: schedule.fee(fill.price, fill.size, is_maker),
+            charged: fill.fee,
+            timestamp: fill.timestamp,
+        };
+        self.checks.push_back(check.clone());
+        while self.checks.len() > self.config.window.max(1) {
+            self.checks.pop_front();
+        }
+        check
+    }
+
+    /// Totals over the window
+    pub fn drift(&self) -> FeeDrift {
+        let mut drift = FeeDrift { fills: self.checks.len(), predicted: 0.0, charged: 0.0, over: 0, under: 0 };
+        for check in &self.checks {
+            drift.predicted += check.predicted;
+            drift.charged += check.charged;
+            if check.diff() > self.config.noise_usdc {
+                drift.over += 1;
+            } else if check.diff() < -self.config.noise_usdc {
+                drift.under += 1;
+            }
+        }
+        drift
+    }
+
+    /// Whether the window shows systematic divergence rather than noise
+    pub fn is_drifting(&self, drift: &FeeDrift) -> bool {
+        if drift.fills < self.config.min_fills || drift.consistency() < self.config.min_consistency {
+            return false;
+        }
+        match drift.bias() {
+            Some(bias) => bias.abs() > self.config.max_bias,
+            // Charged fees where none were expected
+            None => drift.over + drift.under >= self.config.min_fills,
+        }
+    }
+
+    /// Check for drift, alerting once per episode; returns the drift when it is newly raised
+    pub fn check(&mut self) -> Option<FeeDrift> {
+        let drift = self.drift();
+        if !self.is_drifting(&drift) {
+            self.alerted = false;
+            return None;
+        }
+        if self.alerted {
+            return None;
+        }
+        self.alerted = true;
+        eprintln!("⚠️  fee model drift: {}", drift.describe());
+        if let Some(bus) = &self.bus {
+            bus.publish_risk(RiskEvent::FeeDrift { predicted: drift.predicted, charged: drift.charged, fills: drift.fills });
+        }
+        Some(drift)
+    }
+}
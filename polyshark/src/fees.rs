@@ -1,4 +1,24 @@
 use crate::types::Market;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Anything that can price the fee on a fill
+pub trait FeeSchedule: Debug + Send + Sync {
+    /// Fee in USDC for filling `size` shares at `price`
+    fn fee(&self, price: f64, size: f64, is_maker: bool) -> f64;
+
+    /// Effective taker fee as a fraction of notional at `price`
+    fn taker_rate_at(&self, price: f64) -> f64 {
+        if price <= 0.0 {
+            return 0.0;
+        }
+        self.fee(price, 1.0, false) / price
+    }
+}
+
+/// Fee schedule shared between engine, router and strategies
+pub type SharedFeeSchedule = Arc<dyn FeeSchedule>;
 
 /// Fee model based on Polymarket fee structure
 #[derive(Debug, Clone)]
@@ -26,4 +46,174 @@ impl FeeModel {
     pub fn taker_rate(&self) -> f64 {
         self.taker_fee_bps as f64 / 10000.0
     }
-}
\ No newline at end of file
+}
+
+/// Flat bps on notional
+impl FeeSchedule for FeeModel {
+    fn fee(&self, price: f64, size: f64, is_maker: bool) -> f64 {
+        self.calculate(price * size, is_maker)
+    }
+}
+
+/// Polymarket fee in USDC: `base_rate * min(price, 1 - price) * size`.
+/// Outcomes near 0 or 1 pay far less than flat notional would imply.
+pub fn polymarket_fee(base_rate: f64, price: f64, size: f64) -> f64 {
+    let p = price.clamp(0.0, 1.0);
+    base_rate * p.min(1.0 - p) * size
+}
+
+/// Fee proportional to the cheaper side of the outcome: bps * min(p, 1 - p) * size
+#[derive(Debug, Clone)]
+pub struct PriceCurveFee {
+    pub maker_fee_bps: u32,
+    pub taker_fee_bps: u32,
+}
+
+impl PriceCurveFee {
+    pub fn from_market(market: &Market) -> Self {
+        Self {
+            maker_fee_bps: market.maker_base_fee,
+            taker_fee_bps: market.taker_base_fee,
+        }
+    }
+}
+
+impl FeeSchedule for PriceCurveFee {
+    fn fee(&self, price: f64, size: f64, is_maker: bool) -> f64 {
+        let bps = if is_maker { self.maker_fee_bps } else { self.taker_fee_bps };
+        polymarket_fee(bps as f64 / 10000.0, price, size)
+    }
+}
+
+/// Discount applied once trailing volume reaches a threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeTier {
+    pub min_volume: f64,     // Trailing USDC volume to qualify
+    pub discount_bps: u32,   // Discount on the base fee (10000 = free)
+}
+
+/// Wraps another schedule and discounts it by volume tier
+#[derive(Debug)]
+pub struct VolumeTierFee {
+    pub base: SharedFeeSchedule,
+    pub tiers: Vec<VolumeTier>,
+    pub trailing_volume: f64,
+}
+
+impl VolumeTierFee {
+    pub fn new(base: SharedFeeSchedule, mut tiers: Vec<VolumeTier>, trailing_volume: f64) -> Self {
+        tiers.sort_by(|a, b| a.min_volume.total_cmp(&b.min_volume));
+        Self { base, tiers, trailing_volume }
+    }
+
+    /// Discount for the current trailing volume, as a fraction
+    pub fn discount(&self) -> f64 {
+        self.tiers.iter()
+            .rev()
+            .find(|t| self.trailing_volume >= t.min_volume)
+            .map(|t| (t.discount_bps.min(10000)) as f64 / 10000.0)
+            .unwrap_or(0.0)
+    }
+}
+
+impl FeeSchedule for VolumeTierFee {
+    fn fee(&self, price: f64, size: f64, is_maker: bool) -> f64 {
+        self.base.fee(price, size, is_maker) * (1.0 - self.discount())
+    }
+}
+
+/// Config selecting a fee schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FeeConfig {
+    Flat { maker_fee_bps: u32, taker_fee_bps: u32 },
+    PriceCurve { maker_fee_bps: u32, taker_fee_bps: u32 },
+    VolumeTier { base: Box<FeeConfig>, tiers: Vec<VolumeTier>, #[serde(default)] trailing_volume: f64 },
+}
+
+impl FeeConfig {
+    /// Build the configured schedule
+    pub fn build(&self) -> SharedFeeSchedule {
+        match self {
+            FeeConfig::Flat { maker_fee_bps, taker_fee_bps } => Arc::new(FeeModel {
+                maker_fee_bps: *maker_fee_bps,
+                taker_fee_bps: *taker_fee_bps,
+            }),
+            FeeConfig::PriceCurve { maker_fee_bps, taker_fee_bps } => Arc::new(PriceCurveFee {
+                maker_fee_bps: *maker_fee_bps,
+                taker_fee_bps: *taker_fee_bps,
+            }),
+            FeeConfig::VolumeTier { base, tiers, trailing_volume } => {
+                Arc::new(VolumeTierFee::new(base.build(), tiers.clone(), *trailing_volume))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn curve_fee_is_symmetric_around_half() {
+        for p in [0.01, 0.1, 0.25, 0.4, 0.49] {
+            assert!(close(polymarket_fee(0.02, p, 100.0), polymarket_fee(0.02, 1.0 - p, 100.0)));
+        }
+        assert!(close(polymarket_fee(0.02, 0.1, 100.0), 0.02 * 0.1 * 100.0));
+    }
+
+    #[test]
+    fn curve_fee_peaks_at_half() {
+        let peak = polymarket_fee(0.02, 0.5, 100.0);
+        assert!(close(peak, 1.0));
+        for p in [0.0, 0.05, 0.3, 0.49, 0.51, 0.7, 0.95, 1.0] {
+            assert!(polymarket_fee(0.02, p, 100.0) < peak);
+        }
+        // Certain outcomes and out-of-range prices pay nothing
+        assert_eq!(polymarket_fee(0.02, 0.0, 100.0), 0.0);
+        assert_eq!(polymarket_fee(0.02, 1.5, 100.0), 0.0);
+    }
+
+    #[test]
+    fn price_curve_schedule_uses_its_side_rate() {
+        let fees = PriceCurveFee { maker_fee_bps: 0, taker_fee_bps: 200 };
+        assert_eq!(fees.fee(0.3, 100.0, true), 0.0);
+        assert!(close(fees.fee(0.3, 100.0, false), 0.6));
+        assert!(close(fees.fee(0.7, 100.0, false), 0.6));
+        assert!(close(fees.taker_rate_at(0.3), 0.02));
+    }
+
+    fn tiered(trailing_volume: f64) -> VolumeTierFee {
+        let base: SharedFeeSchedule = Arc::new(FeeModel { maker_fee_bps: 0, taker_fee_bps: 200 });
+        // Out of order on purpose: `new` sorts them
+        let tiers = vec![
+            VolumeTier { min_volume: 100_000.0, discount_bps: 5_000 },
+            VolumeTier { min_volume: 10_000.0, discount_bps: 2_500 },
+        ];
+        VolumeTierFee::new(base, tiers, trailing_volume)
+    }
+
+    #[test]
+    fn volume_tiers_start_at_their_threshold() {
+        assert_eq!(tiered(0.0).discount(), 0.0);
+        assert_eq!(tiered(9_999.99).discount(), 0.0);
+        assert_eq!(tiered(10_000.0).discount(), 0.25);
+        assert_eq!(tiered(99_999.99).discount(), 0.25);
+        assert_eq!(tiered(100_000.0).discount(), 0.5);
+        assert_eq!(tiered(1e9).discount(), 0.5);
+    }
+
+    #[test]
+    fn volume_tier_discounts_the_base_fee() {
+        // 2% of 50 USDC notional is 1 USDC before the discount
+        assert!(close(tiered(0.0).fee(0.5, 100.0, false), 1.0));
+        assert!(close(tiered(10_000.0).fee(0.5, 100.0, false), 0.75));
+        assert!(close(tiered(100_000.0).fee(0.5, 100.0, false), 0.5));
+        let free = VolumeTierFee::new(Arc::new(FeeModel { maker_fee_bps: 0, taker_fee_bps: 200 }), vec![VolumeTier { min_volume: 0.0, discount_bps: 20_000 }], 0.0);
+        assert_eq!(free.fee(0.5, 100.0, false), 0.0);
+    }
+}
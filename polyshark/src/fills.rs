@@ -1,10 +1,24 @@
+use crate::tape::LevelFlow;
 use crate::types::{OrderBook, Side};
 
-/// Fill rate estimator
-#[derive(Debug, Clone)]
-pub struct FillModel;
+/// Fill rate estimator. Taker estimates only need the book; maker estimates also use the
+/// token's recorded taker flow.
+#[derive(Debug, Clone, Default)]
+pub struct FillModel {
+    pub flow: LevelFlow,  // Prints by distance from mid, e.g. from `TradeTape::level_flow`
+    pub base_rate: f64,   // Shares per second assumed through a price when the flow has no prints
+}
 
 impl FillModel {
+    pub fn new(flow: LevelFlow) -> Self {
+        Self { flow, base_rate: 0.0 }
+    }
+
+    pub fn with_base_rate(mut self, base_rate: f64) -> Self {
+        self.base_rate = base_rate;
+        self
+    }
+
     /// Estimate how much of the order can fill
     pub fn estimate_fill_ratio(book: &OrderBook, size: f64, side: Side) -> f64 {
         let available = match side {
@@ -19,9 +33,93 @@ impl FillModel {
         }
     }
 
+    /// `estimate_fill_ratio` when another taker may get there first: with probability
+    /// `competition` the liquidity we priced is gone before our order arrives
+    pub fn contested_fill_ratio(book: &OrderBook, size: f64, side: Side, competition: f64) -> f64 {
+        Self::estimate_fill_ratio(book, size, side) * (1.0 - competition.clamp(0.0, 1.0))
+    }
+
     /// Get filled size based on available liquidity
     pub fn filled_size(book: &OrderBook, requested_size: f64, side: Side) -> f64 {
         let ratio = Self::estimate_fill_ratio(book, requested_size, side);
         requested_size * ratio
     }
-}
\ No newline at end of file
+
+    /// Chance a resting order of `size` at `price` fills within `horizon_secs`, from the
+    /// recorded taker flow at that distance from mid. The order sits on the bids when
+    /// `price` is at or below the mid, on the asks above it, and joins the back of any
+    /// queue already at `price`. Without prints on that side the base rate is used.
+    pub fn maker_fill_probability(&self, book: &OrderBook, price: f64, size: f64, horizon_secs: f64) -> f64 {
+        let Some(mid) = book.midpoint() else {
+            return 0.0;
+        };
+        let (side, levels) = if price <= mid { (Side::Buy, &book.bids) } else { (Side::Sell, &book.asks) };
+        let printed = match side {
+            Side::Buy => !self.flow.below_mid.is_empty(),
+            Side::Sell => !self.flow.above_mid.is_empty(),
+        };
+        let rate = if printed { self.flow.rate_through(side, (price - mid).abs()) } else { self.base_rate };
+        if rate <= 0.0 || size <= 0.0 {
+            return 0.0;
+        }
+        let queue_ahead: f64 = levels.iter().filter(|l| (l.price - price).abs() < 1e-9).map(|l| l.size).sum();
+        // Exponential time until enough volume trades through the queue ahead plus our size
+        1.0 - (-rate * horizon_secs.max(0.0) / (queue_ahead + size)).exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PriceLevel, TokenId};
+
+    /// Bids 0.48 x 100, 0.47 x 300; asks 0.52 x 50; mid 0.50
+    fn book() -> OrderBook {
+        let level = |price, size| PriceLevel { price, size };
+        OrderBook {
+            token_id: TokenId::from("yes"),
+            bids: vec![level(0.48, 100.0), level(0.47, 300.0)],
+            asks: vec![level(0.52, 50.0)],
+            timestamp: 0,
+        }
+    }
+
+    /// 60 seconds of prints: 120 shares sold 0.02 under mid, 60 bought 0.02 over it
+    fn model() -> FillModel {
+        FillModel::new(LevelFlow {
+            window_ms: 60_000,
+            below_mid: vec![(0.02, 120.0)],
+            above_mid: vec![(0.02, 60.0)],
+        })
+    }
+
+    #[test]
+    fn deeper_queue_fills_less_often() {
+        let model = model();
+        let book = book();
+        // 2 shares/s through 0.48; 100 shares queued ahead there, none at 0.49
+        let behind_queue = model.maker_fill_probability(&book, 0.48, 10.0, 30.0);
+        let alone = model.maker_fill_probability(&book, 0.49, 10.0, 30.0);
+        assert!((behind_queue - (1.0 - (-2.0_f64 * 30.0 / 110.0).exp())).abs() < 1e-9);
+        assert!(alone > behind_queue);
+    }
+
+    #[test]
+    fn longer_horizon_fills_more_often() {
+        let model = model();
+        let book = book();
+        let short = model.maker_fill_probability(&book, 0.52, 10.0, 5.0);
+        let long = model.maker_fill_probability(&book, 0.52, 10.0, 60.0);
+        assert!(short > 0.0 && long > short && long < 1.0);
+        assert_eq!(model.maker_fill_probability(&book, 0.52, 10.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn prices_no_print_reached_never_fill_without_a_base_rate() {
+        let book = book();
+        assert_eq!(model().maker_fill_probability(&book, 0.47, 10.0, 60.0), 0.0);
+        // No prints on the side at all: the base rate stands in
+        let quiet = FillModel::default().with_base_rate(1.0);
+        assert!(quiet.maker_fill_probability(&book, 0.47, 10.0, 60.0) > 0.0);
+    }
+}
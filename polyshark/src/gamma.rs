@@ -0,0 +1,439 @@
+use crate::schema::{flag_field, text_field, Field, FieldKind, Schema, SchemaMode};
+#[cfg(feature = "network")]
+use crate::schema::SchemaDrift;
+use crate::types::{Event, EventMarket, Market, TokenId};
+use crate::websocket::num_field;
+use serde_json::Value;
+
+/// Market discovery and metadata
+pub const GAMMA_API_URL: &str = "https://gamma-api.polymarket.com";
+
+/// A Gamma market object, standalone or nested in an event
+pub const MARKET_SCHEMA: Schema = Schema {
+    name: "gamma market",
+    fields: &[
+        Field::required("id", FieldKind::Text),
+        Field::optional("question", FieldKind::Text, "empty"),
+        Field::optional("slug", FieldKind::Text, "empty"),
+        Field::optional("outcomes", FieldKind::List, "no outcomes"),
+        Field::optional("outcomePrices", FieldKind::List, "no prices; unparseable entries skipped"),
+        Field::optional("clobTokenIds", FieldKind::List, "no tokens, so never traded"),
+        Field::optional("bestBid", FieldKind::Number, "unknown"),
+        Field::optional("bestAsk", FieldKind::Number, "unknown"),
+        Field::optional("makerBaseFee", FieldKind::Number, "0 bps"),
+        Field::optional("takerBaseFee", FieldKind::Number, "0 bps"),
+        Field::optional("liquidityNum", FieldKind::Number, "liquidity"),
+        Field::optional("liquidity", FieldKind::Number, "0"),
+        Field::optional("volume24hr", FieldKind::Number, "0"),
+        Field::optional("active", FieldKind::Flag, "inactive"),
+        Field::optional("acceptingOrders", FieldKind::Flag, "not accepting orders"),
+        Field::optional("conditionId", FieldKind::Text, "empty"),
+        Field::optional("endDate", FieldKind::Text, "no end date"),
+        Field::optional("resolutionSource", FieldKind::Text, "none"),
+        Field::optional("category", FieldKind::Text, "none"),
+        Field::optional("tags", FieldKind::Array, "no tags"),
+        Field::optional("negRisk", FieldKind::Flag, "not neg-risk"),
+        Field::optional("events", FieldKind::Array, "no parent event"),
+        Field::optional("rewardsMaxSpread", FieldKind::Number, "no rewards"),
+        Field::optional("rewardsMinSize", FieldKind::Number, "no rewards"),
+        Field::optional("restricted", FieldKind::Flag, "unrestricted"),
+        Field::optional("description", FieldKind::Text, "none"),
+        Field::optional("umaResolutionStatus", FieldKind::Text, "none"),
+        Field::optional("groupItemTitle", FieldKind::Text, "no bucket label"),
+        Field::optional("closed", FieldKind::Flag, "open"),
+    ],
+    ignored: &[
+        "image", "icon", "startDate", "startDateIso", "endDateIso", "createdAt", "updatedAt",
+        "closedTime", "archived", "new", "featured", "approved", "ready", "funded", "cyom",
+        "marketMakerAddress", "resolvedBy", "submitted_by", "updatedBy", "questionID", "marketType",
+        "volume", "volumeNum", "volume1wk", "volume1mo", "volume1yr", "volumeClob", "volume24hrClob",
+        "volume1wkClob", "volume1moClob", "volume1yrClob", "liquidityClob", "competitive", "spread",
+        "lastTradePrice", "oneHourPriceChange", "oneDayPriceChange", "oneWeekPriceChange",
+        "oneMonthPriceChange", "orderPriceMinTickSize", "orderMinSize", "enableOrderBook",
+        "acceptingOrdersTimestamp", "clobRewards", "umaBond", "umaReward", "negRiskMarketID",
+        "negRiskRequestID", "negRiskOther", "hasReviewedDates", "readyForCron", "fpmmLive",
+        "pendingDeployment", "deploying", "deployingTimestamp", "automaticallyActive",
+        "manualActivation", "clearBookOnStart", "rfqEnabled", "holdingRewardsEnabled", "feesEnabled",
+        "pagerDutyNotificationEnabled", "groupItemThreshold", "seriesColor", "showGmpSeries",
+        "showGmpOutcome", "twitterCardImage", "mailchimpTag", "customLiveness", "sentDiscord",
+    ],
+};
+
+/// A Gamma event object; its markets are checked against `MARKET_SCHEMA`
+pub const EVENT_SCHEMA: Schema = Schema {
+    name: "gamma event",
+    fields: &[
+        Field::required("id", FieldKind::Text),
+        Field::optional("slug", FieldKind::Text, "empty"),
+        Field::optional("title", FieldKind::Text, "empty"),
+        Field::optional("category", FieldKind::Text, "none"),
+        Field::optional("endDate", FieldKind::Text, "no end date"),
+        Field::optional("negRisk", FieldKind::Flag, "not neg-risk"),
+        Field::optional("active", FieldKind::Flag, "inactive"),
+        Field::optional("closed", FieldKind::Flag, "open"),
+        Field::optional("markets", FieldKind::Array, "no markets"),
+    ],
+    ignored: &[
+        "ticker", "description", "resolutionSource", "startDate", "creationDate", "createdAt",
+        "updatedAt", "image", "icon", "archived", "new", "featured", "restricted", "cyom",
+        "liquidity", "liquidityClob", "volume", "volume24hr", "volume1wk", "volume1mo", "volume1yr",
+        "openInterest", "competitive", "commentCount", "enableOrderBook", "enableNegRisk",
+        "negRiskAugmented", "negRiskMarketID", "showAllOutcomes", "showMarketImages", "sortBy",
+        "tags", "series", "seriesSlug", "pendingDeployment", "deploying", "automaticallyActive",
+    ],
+};
+
+#[cfg(feature = "network")]
+/// GAMMA API client
+#[derive(Debug, Clone)]
+pub struct GammaClient {
+    pub base_url: String,
+    pub drift: SchemaDrift,  // Unknown response fields seen by this client
+    http: reqwest::Client,
+}
+
+#[cfg(feature = "network")]
+impl GammaClient {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            drift: SchemaDrift::new(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Fetch one page of active, open markets
+    pub async fn markets(&self, limit: usize, offset: usize) -> Result<Vec<Market>, reqwest::Error> {
+        let url = format!(
+            "{}/markets?active=true&closed=false&limit={}&offset={}",
+            self.base_url, limit, offset
+        );
+        let body: Value = self.http.get(url).send().await?.error_for_status()?.json().await?;
+        self.drift.observe_all(&MARKET_SCHEMA, &body);
+        Ok(parse_markets(&body))
+    }
+
+    /// Fetch one page of every market, closed and inactive ones included
+    pub async fn all_markets(&self, limit: usize, offset: usize) -> Result<Vec<Market>, reqwest::Error> {
+        let url = format!("{}/markets?limit={}&offset={}", self.base_url, limit, offset);
+        let body: Value = self.http.get(url).send().await?.error_for_status()?.json().await?;
+        self.drift.observe_all(&MARKET_SCHEMA, &body);
+        Ok(parse_markets(&body))
+    }
+
+    /// Look up a single market by id
+    pub async fn market_by_id(&self, id: &str) -> Result<Option<Market>, reqwest::Error> {
+        let url = format!("{}/markets/{}", self.base_url, id);
+        let response = self.http.get(url).send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let body: Value = response.error_for_status()?.json().await?;
+        self.drift.observe(&MARKET_SCHEMA, &body);
+        Ok(parse_market(&body))
+    }
+
+    /// Fetch one page of active, open events with their markets
+    pub async fn events(&self, limit: usize, offset: usize) -> Result<Vec<Event>, reqwest::Error> {
+        let url = format!(
+            "{}/events?active=true&closed=false&limit={}&offset={}",
+            self.base_url, limit, offset
+        );
+        let body: Value = self.http.get(url).send().await?.error_for_status()?.json().await?;
+        for event in body.as_array().into_iter().flatten() {
+            self.observe_event(event);
+        }
+        Ok(parse_events(&body))
+    }
+
+    /// Look up a single event, markets included
+    pub async fn event(&self, event_id: &str) -> Result<Option<Event>, reqwest::Error> {
+        let url = format!("{}/events/{}", self.base_url, event_id);
+        let response = self.http.get(url).send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let body: Value = response.error_for_status()?.json().await?;
+        self.observe_event(&body);
+        Ok(parse_event(&body))
+    }
+
+    fn observe_event(&self, event: &Value) {
+        self.drift.observe(&EVENT_SCHEMA, event);
+        self.drift.observe_all(&MARKET_SCHEMA, &event["markets"]);
+    }
+
+    /// Every market of one Gamma event (e.g., all buckets of a multi-outcome question)
+    pub async fn event_markets(&self, event_id: &str) -> Result<Vec<Market>, reqwest::Error> {
+        let event = self.event(event_id).await?;
+        Ok(event.map(|e| e.markets.into_iter().map(|m| m.market).collect()).unwrap_or_default())
+    }
+
+    /// Look up a single market by slug
+    pub async fn market_by_slug(&self, slug: &str) -> Result<Option<Market>, reqwest::Error> {
+        let url = format!("{}/markets?slug={}", self.base_url, slug);
+        let body: Value = self.http.get(url).send().await?.error_for_status()?.json().await?;
+        self.drift.observe_all(&MARKET_SCHEMA, &body);
+        Ok(parse_markets(&body).into_iter().next())
+    }
+}
+
+/// Parse a GAMMA markets response (array of market objects)
+pub fn parse_markets(body: &Value) -> Vec<Market> {
+    body.as_array()
+        .map(|items| items.iter().filter_map(parse_market).collect())
+        .unwrap_or_default()
+}
+
+/// `parse_markets`, failing in strict mode on any market that doesn't match `MARKET_SCHEMA`
+pub fn parse_markets_checked(body: &Value, mode: SchemaMode) -> Result<Vec<Market>, String> {
+    if mode == SchemaMode::Strict && !body.is_array() {
+        return Err("gamma markets: response is not an array".to_string());
+    }
+    for item in body.as_array().into_iter().flatten() {
+        mode.validate(&MARKET_SCHEMA, item)?;
+    }
+    Ok(parse_markets(body))
+}
+
+/// Convert one GAMMA market object into a `Market`. Fallbacks for absent or unreadable
+/// fields are listed in `MARKET_SCHEMA`; only a missing id drops the market.
+/// GAMMA encodes list fields as JSON strings (e.g., `"[\"Yes\", \"No\"]"`).
+pub fn parse_market(v: &Value) -> Option<Market> {
+    Some(Market {
+        id: text_field(v, "id")?.into(),
+        question: text_field(v, "question").unwrap_or_default(),
+        slug: text_field(v, "slug").unwrap_or_default(),
+        outcomes: string_list(&v["outcomes"]),
+        outcome_prices: string_list(&v["outcomePrices"]).iter().filter_map(|p| p.parse().ok()).collect(),
+        clob_token_ids: string_list(&v["clobTokenIds"]).into_iter().map(TokenId::from).collect(),
+        best_bid: num_field(v, "bestBid"),
+        best_ask: num_field(v, "bestAsk"),
+        maker_base_fee: num_field(v, "makerBaseFee").unwrap_or(0.0) as u32,
+        taker_base_fee: num_field(v, "takerBaseFee").unwrap_or(0.0) as u32,
+        liquidity: num_field(v, "liquidityNum").or_else(|| num_field(v, "liquidity")).unwrap_or(0.0),
+        volume_24hr: num_field(v, "volume24hr").unwrap_or(0.0),
+        active: flag_field(v, "active").unwrap_or(false),
+        accepting_orders: flag_field(v, "acceptingOrders").unwrap_or(false),
+        condition_id: text_field(v, "conditionId").unwrap_or_default(),
+        end_date: text_field(v, "endDate"),
+        resolution_source: text_field(v, "resolutionSource").filter(|s| !s.is_empty()),
+        category: text_field(v, "category"),
+        tags: parse_tags(&v["tags"]),
+        neg_risk: flag_field(v, "negRisk").unwrap_or(false),
+        event_id: text_field(&v["events"][0], "id"),
+        // Gamma quotes the reward spread in cents
+        rewards_max_spread: num_field(v, "rewardsMaxSpread").filter(|s| *s > 0.0).map(|s| s / 100.0),
+        rewards_min_size: num_field(v, "rewardsMinSize").filter(|s| *s > 0.0),
+        restricted: flag_field(v, "restricted").unwrap_or(false),
+        description: text_field(v, "description").filter(|s| !s.is_empty()),
+        uma_resolution_status: text_field(v, "umaResolutionStatus").filter(|s| !s.is_empty()),
+        closed: flag_field(v, "closed").unwrap_or(false),
+    })
+}
+
+/// Parse a GAMMA events response (array of event objects)
+pub fn parse_events(body: &Value) -> Vec<Event> {
+    body.as_array()
+        .map(|items| items.iter().filter_map(parse_event).collect())
+        .unwrap_or_default()
+}
+
+/// `parse_events`, failing in strict mode on any event or nested market that doesn't match
+/// its schema
+pub fn parse_events_checked(body: &Value, mode: SchemaMode) -> Result<Vec<Event>, String> {
+    if mode == SchemaMode::Strict && !body.is_array() {
+        return Err("gamma events: response is not an array".to_string());
+    }
+    for item in body.as_array().into_iter().flatten() {
+        mode.validate(&EVENT_SCHEMA, item)?;
+        for market in item["markets"].as_array().into_iter().flatten() {
+            mode.validate(&MARKET_SCHEMA, market)?;
+        }
+    }
+    Ok(parse_events(body))
+}
+
+/// Convert one GAMMA event object, nested markets included, into an `Event`.
+/// Nested markets don't repeat their parent, so it is filled in here.
+pub fn parse_event(v: &Value) -> Option<Event> {
+    let id = text_field(v, "id")?;
+    let neg_risk = flag_field(v, "negRisk").unwrap_or(false);
+    let markets = v["markets"].as_array()
+        .map(|items| {
+            items.iter()
+                .filter_map(|m| {
+                    let mut market = parse_market(m)?;
+                    market.event_id = Some(id.clone());
+                    market.neg_risk |= neg_risk;
+                    Some(EventMarket {
+                        group_item_title: text_field(m, "groupItemTitle").filter(|s| !s.is_empty()),
+                        market,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(Event {
+        slug: text_field(v, "slug").unwrap_or_default(),
+        title: text_field(v, "title").unwrap_or_default(),
+        category: text_field(v, "category"),
+        end_date: text_field(v, "endDate"),
+        neg_risk,
+        active: flag_field(v, "active").unwrap_or(false),
+        closed: flag_field(v, "closed").unwrap_or(false),
+        markets,
+        id,
+    })
+}
+
+/// Decode a list that may be a JSON array or a JSON-encoded string of one
+fn string_list(value: &Value) -> Vec<String> {
+    let decoded;
+    let list = match value {
+        Value::String(s) => {
+            decoded = serde_json::from_str::<Value>(s).unwrap_or(Value::Null);
+            &decoded
+        }
+        other => other,
+    };
+    list.as_array()
+        .map(|items| {
+            items.iter()
+                .map(|i| match i {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Tags arrive either as plain strings or as `{ "label": .., "slug": .. }` objects
+fn parse_tags(value: &Value) -> Vec<String> {
+    value.as_array()
+        .map(|items| {
+            items.iter()
+                .filter_map(|t| t.as_str().or_else(|| t["label"].as_str()).map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn market() -> Value {
+        json!({
+            "id": "512345",
+            "question": "Will it rain in London on Friday?",
+            "slug": "rain-london-friday",
+            "conditionId": "0xabc",
+            "outcomes": "[\"Yes\", \"No\"]",
+            "outcomePrices": "[\"0.41\", \"0.57\"]",
+            "clobTokenIds": "[\"111\", \"222\"]",
+            "bestBid": 0.40,
+            "bestAsk": "0.42",
+            "liquidityNum": 1520.5,
+            "volume24hr": 310,
+            "active": true,
+            "closed": "false",
+            "acceptingOrders": true,
+            "endDate": "2026-11-01T12:00:00Z",
+            "tags": [{ "label": "Weather" }],
+            "events": [{ "id": "9001" }],
+            "image": "https://example.com/rain.png",
+        })
+    }
+
+    #[test]
+    fn strict_accepts_a_market_matching_the_schema() {
+        let body = json!([market()]);
+        let strict = parse_markets_checked(&body, SchemaMode::Strict).unwrap();
+        assert_eq!(strict.len(), parse_markets(&body).len());
+        let m = &strict[0];
+        assert_eq!(m.id.as_str(), "512345");
+        assert_eq!(m.clob_token_ids, vec![TokenId::from("111"), TokenId::from("222")]);
+        assert_eq!(m.outcome_prices, vec![0.41, 0.57]);
+        assert_eq!(m.best_ask, Some(0.42));
+        assert!(m.active && !m.closed);
+        assert_eq!(m.event_id.as_deref(), Some("9001"));
+        assert_eq!(m.tags, vec!["Weather".to_string()]);
+    }
+
+    #[test]
+    fn text_fields_accept_numbers_as_the_schema_says() {
+        let mut v = market();
+        v["id"] = json!(512345);
+        v["conditionId"] = json!(42);
+        v["question"] = json!(7);
+        v["endDate"] = json!(1_800_000_000);
+        let body = json!([v]);
+        let m = &parse_markets_checked(&body, SchemaMode::Strict).unwrap()[0];
+        assert_eq!(m.id.as_str(), "512345");
+        assert_eq!(m.condition_id, "42");
+        assert_eq!(m.question, "7");
+        assert_eq!(m.end_date.as_deref(), Some("1800000000"));
+    }
+
+    #[test]
+    fn absent_optional_fields_pass_strict_mode() {
+        let body = json!([{ "id": "1" }]);
+        let markets = parse_markets_checked(&body, SchemaMode::Strict).unwrap();
+        assert_eq!(markets.len(), 1);
+        assert!(markets[0].clob_token_ids.is_empty());
+    }
+
+    #[test]
+    fn strict_rejects_what_lenient_falls_back_on() {
+        let mut unknown = market();
+        unknown["brandNewField"] = json!(1);
+        let mut malformed = market();
+        malformed["active"] = json!("yes");
+        let mut missing = market();
+        missing.as_object_mut().unwrap().remove("id");
+
+        let err = parse_markets_checked(&json!([unknown.clone()]), SchemaMode::Strict).unwrap_err();
+        assert!(err.contains("brandNewField unknown"), "{}", err);
+        let err = parse_markets_checked(&json!([malformed.clone()]), SchemaMode::Strict).unwrap_err();
+        assert!(err.contains("active not readable as Flag"), "{}", err);
+        let err = parse_markets_checked(&json!([missing.clone()]), SchemaMode::Strict).unwrap_err();
+        assert!(err.contains("id missing"), "{}", err);
+        assert!(parse_markets_checked(&json!({ "error": "rate limited" }), SchemaMode::Strict).is_err());
+
+        let lenient = parse_markets_checked(&json!([unknown, malformed, missing]), SchemaMode::Lenient).unwrap();
+        assert_eq!(lenient.len(), 2);
+        assert!(!lenient[1].active);
+    }
+
+    #[test]
+    fn strict_checks_markets_nested_in_events() {
+        let mut nested = market();
+        nested.as_object_mut().unwrap().remove("events");
+        nested["groupItemTitle"] = json!("Friday");
+        let event = json!({
+            "id": 9001,
+            "slug": "london-weather",
+            "title": "London weather this week",
+            "negRisk": true,
+            "active": true,
+            "closed": false,
+            "markets": [nested],
+            "ticker": "london-weather",
+        });
+        let events = parse_events_checked(&json!([event.clone()]), SchemaMode::Strict).unwrap();
+        assert_eq!(events.len(), 1);
+        let market = &events[0].markets[0];
+        assert_eq!(market.market.event_id.as_deref(), Some("9001"));
+        assert!(market.market.neg_risk);
+        assert_eq!(market.group_item_title.as_deref(), Some("Friday"));
+
+        let mut drifted = event;
+        drifted["markets"][0]["renamedPrices"] = json!("[]");
+        let err = parse_events_checked(&json!([drifted.clone()]), SchemaMode::Strict).unwrap_err();
+        assert!(err.starts_with("gamma market:"), "{}", err);
+        assert_eq!(parse_events_checked(&json!([drifted]), SchemaMode::Lenient).unwrap().len(), 1);
+    }
+}
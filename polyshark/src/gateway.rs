@@ -0,0 +1,243 @@
+// Most imports are only used by the CLOB gateway, which needs the `network` feature
+#![cfg_attr(not(feature = "network"), allow(unused_imports))]
+
+use crate::bus::Fill;
+#[cfg(feature = "network")]
+use crate::clob::{ClobClient, ClobError, PostOrderResponse};
+#[cfg(all(feature = "network", feature = "signing"))]
+use crate::clob::ClobCredentials;
+use crate::config::Profile;
+use crate::fees::polymarket_fee;
+use crate::money::Collateral;
+use crate::orders::{OrderError, OrderGateway, OrderRequest, OrderStatusReport, TimeInForce};
+use crate::schema::text_field;
+use crate::types::{Side, TokenId};
+use crate::units::{OrderAmounts, OrderPrecision};
+use crate::websocket::num_field;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What a live profile needs to place orders: a signer and the L2 API credentials.
+/// Secrets are read from the environment, never from the config file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LiveConfig {
+    pub signer_url: String,      // `RemoteSigner` endpoint holding the wallet key
+    pub address: String,         // Wallet (or proxy) address the API key belongs to
+    pub secret_env: String,      // Env var holding the base64url API secret
+    pub passphrase_env: String,  // Env var holding the API passphrase
+}
+
+/// Gateway for a live profile
+#[cfg(all(feature = "network", feature = "signing"))]
+pub fn live_gateway(profile: &Profile) -> Result<ClobGateway, String> {
+    let live = profile.live.as_ref().ok_or("live profile has no `live` section (signer_url, address, secret_env, passphrase_env)")?;
+    Ok(ClobGateway::new(live_client(profile)?, Box::new(RemoteSigner::new(&live.signer_url))))
+}
+
+/// CLOB client authenticated as the live profile's account
+#[cfg(all(feature = "network", feature = "signing"))]
+pub fn live_client(profile: &Profile) -> Result<ClobClient, String> {
+    let live = profile.live.as_ref().ok_or("live profile has no `live` section (signer_url, address, secret_env, passphrase_env)")?;
+    let env = |var: &str| std::env::var(var).map_err(|_| format!("environment variable {} is not set", var));
+    let credentials = ClobCredentials {
+        address: live.address.clone(),
+        api_key: profile.api_key().ok_or("live profile has no API key configured")?,
+        secret: env(&live.secret_env)?,
+        passphrase: env(&live.passphrase_env)?,
+    };
+    Ok(ClobClient::new(&profile.clob_url).with_credentials(credentials))
+}
+
+/// Gateway for a live profile
+#[cfg(all(feature = "network", not(feature = "signing")))]
+pub fn live_gateway(_profile: &Profile) -> Result<ClobGateway, String> {
+    Err("live trading requires the `signing` feature".to_string())
+}
+
+/// CLOB client authenticated as the live profile's account
+#[cfg(all(feature = "network", not(feature = "signing")))]
+pub fn live_client(_profile: &Profile) -> Result<ClobClient, String> {
+    Err("authenticated CLOB calls require the `signing` feature".to_string())
+}
+
+/// Signs orders for the CLOB exchange contract (EIP-712). The key stays with the signer;
+/// the bot only ever handles signed orders.
+pub trait OrderSigner: Send + Sync {
+    /// Signed order for `POST /order`, for the on-chain `amounts` of `request` (already at
+    /// the market's tick and lot precision). Its `expiration` must be `time_in_force.expiration_secs()`.
+    fn sign(&self, request: &OrderRequest, amounts: &OrderAmounts) -> Result<Value, String>;
+}
+
+#[cfg(feature = "network")]
+/// Signer running as its own service: the order goes to `POST <url>` and the signed order
+/// comes back as JSON
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    pub url: String,
+    http: reqwest::Client,
+}
+
+#[cfg(feature = "network")]
+impl RemoteSigner {
+    pub fn new(url: &str) -> Self {
+        Self { url: url.to_string(), http: reqwest::Client::new() }
+    }
+}
+
+#[cfg(feature = "network")]
+impl OrderSigner for RemoteSigner {
+    fn sign(&self, request: &OrderRequest, amounts: &OrderAmounts) -> Result<Value, String> {
+        // Base-unit integers as strings: the signer must not re-round floats
+        let body = json!({
+            "token_id": request.token_id,
+            "side": match request.side { Side::Buy => "BUY", Side::Sell => "SELL" },
+            "maker_amount": amounts.maker_amount.to_string(),
+            "taker_amount": amounts.taker_amount.to_string(),
+            "expiration": request.time_in_force.expiration_secs().to_string(),
+        });
+        block_on(async {
+            self.http.post(&self.url).json(&body).send().await?.error_for_status()?.json::<Value>().await
+        })
+        .map_err(|e| format!("signer {}: {}", self.url, e))
+    }
+}
+
+#[cfg(feature = "network")]
+/// Live orders on the Polymarket CLOB. Calls block the current worker thread, so this needs
+/// the multi-threaded runtime `main` runs on.
+pub struct ClobGateway {
+    pub client: ClobClient,
+    signer: Box<dyn OrderSigner>,
+    fills: Vec<Fill>,  // Matched on arrival, not yet taken
+    ticks: HashMap<TokenId, f64>,  // Tick size per token, fetched on its first order
+}
+
+#[cfg(feature = "network")]
+impl ClobGateway {
+    pub fn new(client: ClobClient, signer: Box<dyn OrderSigner>) -> Self {
+        Self { client, signer, fills: Vec::new(), ticks: HashMap::new() }
+    }
+
+    /// Precision the exchange accepts for a token's orders
+    fn precision(&mut self, token_id: &TokenId) -> Result<OrderPrecision, OrderError> {
+        if let Some(tick) = self.ticks.get(token_id) {
+            return Ok(OrderPrecision::for_tick(*tick));
+        }
+        let tick = block_on(self.client.tick_size(token_id))
+            .map_err(|e| OrderError::Network(e.to_string()))?
+            .ok_or_else(|| OrderError::Rejected(format!("no tick size for {}", token_id)))?;
+        self.ticks.insert(token_id.clone(), tick);
+        Ok(OrderPrecision::for_tick(tick))
+    }
+
+    /// Record what an order matched on arrival. The exchange charges the fee rate the order
+    /// was signed with, so that is what the fill carries. Later fills of resting orders
+    /// arrive on the user channel (or through `StatusPoller`).
+    fn record_match(&mut self, request: &OrderRequest, signed: &Value, response: &PostOrderResponse) {
+        let (shares, usdc) = match request.side {
+            Side::Buy => (response.taking_amount, response.making_amount),
+            Side::Sell => (response.making_amount, response.taking_amount),
+        };
+        if !response.status.eq_ignore_ascii_case("matched") || shares <= 0.0 {
+            return;
+        }
+        let price = usdc / shares;
+        let fee_rate = num_field(signed, "feeRateBps").unwrap_or(0.0) / 10_000.0;
+        self.fills.push(Fill {
+            order_id: Some(response.order_id.clone()),
+            token_id: request.token_id.clone(),
+            side: request.side,
+            price,
+            size: shares,
+            fee: polymarket_fee(fee_rate, price, shares),
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0),
+        });
+    }
+}
+
+#[cfg(feature = "network")]
+impl OrderGateway for ClobGateway {
+    fn place(&mut self, request: &OrderRequest) -> Result<String, OrderError> {
+        let precision = self.precision(&request.token_id)?;
+        let amounts = OrderAmounts::for_order(request.side, request.price.value(), request.size.value(), precision, Collateral::Usdc)
+            .ok_or_else(|| OrderError::Rejected(format!("{} @ {} rounds to nothing at the market's precision", request.size, request.price)))?;
+        let signed = self.signer.sign(request, &amounts).map_err(OrderError::Rejected)?;
+        // A GTD order the signer stamped with another expiration would rest for the wrong time
+        let expiration = request.time_in_force.expiration_secs().to_string();
+        let signed_expiration = text_field(&signed, "expiration").unwrap_or_else(|| "0".to_string());
+        if signed_expiration != expiration {
+            return Err(OrderError::Rejected(format!("signed expiration {} != {}", signed_expiration, expiration)));
+        }
+        let response = block_on(self.client.post_order(&signed, request.time_in_force)).map_err(|e| order_error(&e))?;
+        if response.success {
+            self.record_match(request, &signed, &response);
+            return Ok(response.order_id);
+        }
+        if request.time_in_force == TimeInForce::Fok && response.error.to_ascii_lowercase().contains("fully filled") {
+            return Err(OrderError::Killed);
+        }
+        Err(OrderError::Rejected(response.error))
+    }
+
+    fn cancel(&mut self, order_id: &str) -> Result<(), OrderError> {
+        self.cancel_batch(&[order_id.to_string()]).pop().unwrap_or(Ok(()))
+    }
+
+    fn cancel_all(&mut self) -> Result<(), OrderError> {
+        block_on(self.client.cancel_all()).map(|_| ()).map_err(|e| order_error(&e))
+    }
+
+    fn order_status(&mut self, order_id: &str) -> Result<OrderStatusReport, OrderError> {
+        block_on(self.client.order_status(order_id)).map_err(|e| order_error(&e))
+    }
+
+    fn cancel_batch(&mut self, order_ids: &[String]) -> Vec<Result<(), OrderError>> {
+        if order_ids.is_empty() {
+            return Vec::new();
+        }
+        let response = match block_on(self.client.cancel_orders(order_ids)) {
+            Ok(response) => response,
+            Err(err) => return order_ids.iter().map(|_| Err(order_error(&err))).collect(),
+        };
+        order_ids.iter()
+            .map(|id| {
+                if response.canceled.contains(id) {
+                    return Ok(());
+                }
+                match response.not_canceled.get(id) {
+                    Some(reason) if is_not_found(reason) => Err(OrderError::NotFound),
+                    Some(reason) => Err(OrderError::Rejected(reason.clone())),
+                    None => Err(OrderError::Network(format!("no cancel result for {}", id))),
+                }
+            })
+            .collect()
+    }
+
+    fn take_fills(&mut self) -> Vec<Fill> {
+        std::mem::take(&mut self.fills)
+    }
+}
+
+#[cfg(feature = "network")]
+/// The CLOB words unknown (already filled or cancelled) orders a few ways
+fn is_not_found(reason: &str) -> bool {
+    let reason = reason.to_ascii_lowercase();
+    reason.contains("not found") || reason.contains("can't be found") || reason.contains("already canceled") || reason.contains("matched")
+}
+
+#[cfg(feature = "network")]
+fn order_error(err: &ClobError) -> OrderError {
+    match err {
+        ClobError::Http(e) if e.status() == Some(reqwest::StatusCode::NOT_FOUND) => OrderError::NotFound,
+        ClobError::Http(e) => OrderError::Network(e.to_string()),
+        ClobError::Auth(reason) => OrderError::Rejected(reason.clone()),
+    }
+}
+
+/// Run an async call from a synchronous gateway
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(future))
+}
@@ -0,0 +1,169 @@
+use crate::booksync::SyncStats;
+use crate::capital::CapitalForecast;
+use crate::control::{respond, ControlState, REQUEST_TIMEOUT};
+use crate::orders::{OrderGateway, OrderManager};
+use serde::Serialize;
+use std::io::{BufRead, BufReader};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Liveness signals the trading loop keeps up to date
+#[derive(Debug, Default)]
+pub struct HealthState {
+    ws_connected: AtomicBool,
+    last_data_ms: AtomicU64,  // Last book or trade received
+    markets_loaded: AtomicBool,
+    book_divergences: AtomicU64,  // Hash mismatches and sequence gaps on the market feed
+    book_resyncs: AtomicU64,
+    capital: Mutex<Option<CapitalForecast>>,  // Forecast behind the last execution decision
+}
+
+/// Body of `/healthz` and `/readyz`
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub connected: bool,
+    pub markets_loaded: bool,
+    pub data_age_ms: Option<u64>,
+    pub data_fresh: bool,
+    pub book_divergences: u64,
+    pub book_resyncs: u64,
+    pub paused: bool,  // Risk state: new entries halted
+    pub capital: Option<CapitalForecast>,  // Free capital at the last execution decision
+    pub ready: bool,
+}
+
+impl HealthState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_connected(&self, connected: bool) {
+        self.ws_connected.store(connected, Ordering::SeqCst);
+    }
+
+    pub fn set_markets_loaded(&self, loaded: bool) {
+        self.markets_loaded.store(loaded, Ordering::SeqCst);
+    }
+
+    pub fn mark_data(&self, now_ms: u64) {
+        self.last_data_ms.store(now_ms, Ordering::SeqCst);
+    }
+
+    pub fn set_book_sync(&self, stats: SyncStats) {
+        self.book_divergences.store(stats.hash_mismatches + stats.sequence_gaps, Ordering::SeqCst);
+        self.book_resyncs.store(stats.resyncs, Ordering::SeqCst);
+    }
+
+    pub fn set_capital(&self, forecast: &CapitalForecast) {
+        if let Ok(mut capital) = self.capital.lock() {
+            *capital = Some(forecast.clone());
+        }
+    }
+
+    /// Ready = connected, markets loaded and data newer than `max_data_age_ms`
+    pub fn report(&self, control: &ControlState, now_ms: u64, max_data_age_ms: u64) -> HealthReport {
+        let connected = self.ws_connected.load(Ordering::SeqCst);
+        let markets_loaded = self.markets_loaded.load(Ordering::SeqCst);
+        let last = self.last_data_ms.load(Ordering::SeqCst);
+        let data_age_ms = (last > 0).then(|| now_ms.saturating_sub(last));
+        let data_fresh = data_age_ms.is_some_and(|age| age <= max_data_age_ms);
+        HealthReport {
+            connected,
+            markets_loaded,
+            data_age_ms,
+            data_fresh,
+            book_divergences: self.book_divergences.load(Ordering::SeqCst),
+            book_resyncs: self.book_resyncs.load(Ordering::SeqCst),
+            paused: control.is_paused(),
+            capital: self.capital.lock().ok().and_then(|c| c.clone()),
+            ready: connected && markets_loaded && data_fresh,
+        }
+    }
+}
+
+/// Unauthenticated probe endpoint for container orchestration.
+/// `/healthz` answers 200 while the process serves requests; `/readyz` is 503 until ready.
+#[derive(Debug, Clone)]
+pub struct HealthServer {
+    pub bind_addr: String,  // e.g., "0.0.0.0:8080"
+    pub max_data_age_ms: u64,
+    pub health: Arc<HealthState>,
+    pub control: Arc<ControlState>,
+}
+
+impl HealthServer {
+    pub fn new(bind_addr: &str, max_data_age_ms: u64, health: Arc<HealthState>, control: Arc<ControlState>) -> Self {
+        Self { bind_addr: bind_addr.to_string(), max_data_age_ms, health, control }
+    }
+
+    /// Bind and accept on a background thread; each probe is served on its own thread
+    pub fn spawn(self) -> std::io::Result<JoinHandle<()>> {
+        let listener = TcpListener::bind(&self.bind_addr)?;
+        let server = Arc::new(self);
+        Ok(thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let server = Arc::clone(&server);
+                thread::spawn(move || server.handle(stream));
+            }
+        }))
+    }
+
+    fn handle(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        let mut request_line = String::new();
+        BufReader::new(stream.try_clone()?).read_line(&mut request_line)?;
+        let path = request_line.split_whitespace().nth(1).unwrap_or("");
+
+        let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0);
+        let report = self.health.report(&self.control, now_ms, self.max_data_age_ms);
+        let body = serde_json::to_string(&report).unwrap_or_default();
+        let code = match path {
+            "/healthz" => "200 OK",
+            "/readyz" if report.ready => "200 OK",
+            "/readyz" => "503 Service Unavailable",
+            _ => return respond(&mut stream, "404 Not Found", r#"{"error":"not found"}"#),
+        };
+        respond(&mut stream, code, &body)
+    }
+}
+
+/// Resolve on SIGTERM (container stop) or Ctrl-C
+pub async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut term) => {
+                tokio::select! {
+                    _ = term.recv() => {}
+                    _ = tokio::signal::ctrl_c() => {}
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+/// Cancel everything resting before exit; returns how many orders were cancelled
+pub fn cancel_on_shutdown(gateway: &mut dyn OrderGateway, orders: &mut OrderManager) -> usize {
+    let count = orders.open.len();
+    if count == 0 {
+        return 0;
+    }
+    match orders.cancel_all(gateway) {
+        Ok(()) => count,
+        Err(err) => {
+            eprintln!("failed to cancel {} open orders on shutdown: {}", count, err);
+            0
+        }
+    }
+}
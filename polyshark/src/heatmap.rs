@@ -0,0 +1,144 @@
+use crate::types::MarketId;
+use std::collections::{BTreeMap, HashMap};
+
+/// Defaults for the control API's `/heatmap`: one-minute columns over the last hour
+pub const DEFAULT_BUCKET_MS: u64 = 60_000;
+pub const DEFAULT_WINDOW_MS: u64 = 3_600_000;
+pub const DEFAULT_FULL_SCALE_EDGE: f64 = 0.05;
+
+/// Shades from no violation to widest edge
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// One time bucket for one market
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeatCell {
+    pub max_edge: f64,  // Widest spread violation seen in the bucket
+    pub signals: u32,
+    pub captured: u32,  // Signals we actually traded
+}
+
+/// Rolling market × time grid of spread violations
+#[derive(Debug, Clone)]
+pub struct OpportunityHeatmap {
+    pub bucket_ms: u64,        // Width of one column
+    pub window_ms: u64,        // How far back the panel shows
+    pub full_scale_edge: f64,  // Edge drawn as the darkest shade
+    cells: HashMap<MarketId, BTreeMap<u64, HeatCell>>,  // market_id -> bucket start -> cell
+}
+
+impl Default for OpportunityHeatmap {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUCKET_MS, DEFAULT_WINDOW_MS, DEFAULT_FULL_SCALE_EDGE)
+    }
+}
+
+impl OpportunityHeatmap {
+    pub fn new(bucket_ms: u64, window_ms: u64, full_scale_edge: f64) -> Self {
+        Self { bucket_ms, window_ms, full_scale_edge, cells: HashMap::new() }
+    }
+
+    /// Record a detected violation; `captured` if the bot traded it
+    pub fn record(&mut self, market_id: &MarketId, edge: f64, captured: bool, now_ms: u64) {
+        let bucket = self.bucket(now_ms);
+        let cell = self.cells.entry(market_id.clone()).or_default().entry(bucket).or_default();
+        cell.max_edge = cell.max_edge.max(edge);
+        cell.signals += 1;
+        if captured {
+            cell.captured += 1;
+        }
+    }
+
+    /// Mark a signal traded after it was recorded (the execution report arrives later)
+    pub fn record_capture(&mut self, market_id: &MarketId, now_ms: u64) {
+        let bucket = self.bucket(now_ms);
+        let cell = self.cells.entry(market_id.clone()).or_default().entry(bucket).or_default();
+        cell.captured += 1;
+    }
+
+    /// Drop buckets that scrolled out of the window
+    pub fn prune(&mut self, now_ms: u64) {
+        let oldest = self.bucket(now_ms.saturating_sub(self.window_ms));
+        self.cells.retain(|_, row| {
+            row.retain(|start, _| *start >= oldest);
+            !row.is_empty()
+        });
+    }
+
+    /// Fraction of signals in the window the bot captured, per market
+    pub fn capture_rate(&self, market_id: &MarketId) -> Option<f64> {
+        let row = self.cells.get(market_id)?;
+        let (signals, captured) = row.values().fold((0, 0), |(s, c), cell| (s + cell.signals, c + cell.captured));
+        (signals > 0).then(|| captured as f64 / signals as f64)
+    }
+
+    /// Render as text rows (busiest market first), one character per bucket.
+    /// Buckets with signals but no capture are marked `!` in the capture column.
+    pub fn render(&self, now_ms: u64, label_width: usize) -> Vec<String> {
+        let newest = self.bucket(now_ms);
+        let columns = (self.window_ms / self.bucket_ms.max(1)).max(1);
+        let first = newest.saturating_sub((columns - 1) * self.bucket_ms);
+
+        let mut rows: Vec<(&MarketId, u32)> = self.cells.iter()
+            .map(|(id, row)| (id, row.values().map(|c| c.signals).sum()))
+            .collect();
+        rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        rows.into_iter()
+            .map(|(id, _)| {
+                let row = &self.cells[id];
+                let mut line: String = id.chars().take(label_width).collect();
+                line.push_str(&" ".repeat(label_width.saturating_sub(line.chars().count())));
+                line.push_str(" |");
+                let mut missed = false;
+                for i in 0..columns {
+                    let cell = row.get(&(first + i * self.bucket_ms)).copied().unwrap_or_default();
+                    missed |= cell.signals > 0 && cell.captured == 0;
+                    line.push(self.shade(cell.max_edge));
+                }
+                line.push('|');
+                if missed {
+                    line.push('!');
+                }
+                line
+            })
+            .collect()
+    }
+
+    fn shade(&self, edge: f64) -> char {
+        if edge <= 0.0 || self.full_scale_edge <= 0.0 {
+            return SHADES[0];
+        }
+        let level = ((edge / self.full_scale_edge) * (SHADES.len() - 1) as f64).ceil() as usize;
+        SHADES[level.clamp(1, SHADES.len() - 1)]
+    }
+
+    fn bucket(&self, ts_ms: u64) -> u64 {
+        let width = self.bucket_ms.max(1);
+        ts_ms - ts_ms % width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_reported_after_the_signal_count_toward_the_rate() {
+        let mut heatmap = OpportunityHeatmap::default();
+        let (a, b) = (MarketId::from("a"), MarketId::from("b"));
+        heatmap.record(&a, 0.03, false, 1_000);
+        heatmap.record(&a, 0.01, false, 2_000);
+        heatmap.record(&b, 0.05, false, 2_000);
+        heatmap.record_capture(&a, 3_000);
+
+        assert_eq!(heatmap.capture_rate(&a), Some(0.5));
+        assert_eq!(heatmap.capture_rate(&b), Some(0.0));
+        let rows = heatmap.render(3_000, 4);
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().any(|r| r.starts_with("b") && r.ends_with('!')));
+
+        // Everything scrolls out after an hour
+        heatmap.prune(3_000 + DEFAULT_WINDOW_MS + DEFAULT_BUCKET_MS);
+        assert_eq!(heatmap.capture_rate(&a), None);
+    }
+}
@@ -0,0 +1,90 @@
+use serde::Deserialize;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Where the single-instance lock lives
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LockConfig {
+    /// Advisory file lock; protects instances on one host
+    File { path: PathBuf },
+    /// Session advisory lock; protects instances across servers sharing a database
+    Postgres { url: String, key: i64 },
+}
+
+impl Default for LockConfig {
+    fn default() -> Self {
+        LockConfig::File { path: PathBuf::from("polyshark.lock") }
+    }
+}
+
+/// Held for as long as the process trades; released on drop
+#[allow(dead_code)]  // Never read: holding them is the point
+enum Guard {
+    File(File),
+    #[cfg(feature = "postgres")]
+    Postgres(Box<postgres::Client>),
+}
+
+/// Proof that this process is the only instance trading the wallet
+pub struct InstanceLock {
+    pub instance_id: String,
+    _guard: Guard,
+}
+
+impl std::fmt::Debug for InstanceLock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstanceLock").field("instance_id", &self.instance_id).finish()
+    }
+}
+
+/// `<host>-<pid>`, unique enough to tell instances apart in orders and journal rows
+pub fn instance_id() -> String {
+    let host = std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok().map(|h| h.trim().to_string()))
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "localhost".to_string());
+    format!("{}-{}", host, std::process::id())
+}
+
+impl LockConfig {
+    /// Take the lock or explain who holds it
+    pub fn acquire(&self, instance_id: &str) -> Result<InstanceLock, String> {
+        let guard = match self {
+            LockConfig::File { path } => {
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .truncate(false)
+                    .read(true)
+                    .write(true)
+                    .open(path)
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+                match file.try_lock() {
+                    Ok(()) => {}
+                    Err(TryLockError::WouldBlock) => {
+                        let holder = std::fs::read_to_string(path).unwrap_or_default();
+                        return Err(format!("another instance holds {} ({})", path.display(), holder.trim()));
+                    }
+                    Err(TryLockError::Error(e)) => return Err(format!("{}: {}", path.display(), e)),
+                }
+                // Record the holder for the error above; the lock itself is what counts
+                let _ = file.set_len(0).and_then(|_| writeln!(file, "{}", instance_id));
+                Guard::File(file)
+            }
+            #[cfg(feature = "postgres")]
+            LockConfig::Postgres { url, key } => {
+                let mut client = postgres::Client::connect(url, postgres::NoTls).map_err(|e| e.to_string())?;
+                let row = client.query_one("SELECT pg_try_advisory_lock($1)", &[key]).map_err(|e| e.to_string())?;
+                if !row.get::<_, bool>(0) {
+                    return Err(format!("another instance holds advisory lock {}", key));
+                }
+                Guard::Postgres(Box::new(client))
+            }
+            #[cfg(not(feature = "postgres"))]
+            LockConfig::Postgres { .. } => return Err("postgres lock requires the `postgres` feature".to_string()),
+        };
+        Ok(InstanceLock { instance_id: instance_id.to_string(), _guard: guard })
+    }
+}
@@ -0,0 +1,411 @@
+use crate::execution::MultiLegExecutionReport;
+use crate::storage::{Storage, StorageResult};
+use crate::types::{MarketId, Side, TokenId};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// One recorded fill
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: u64,
+    pub timestamp: u64,
+    pub market_id: MarketId,
+    pub token_id: TokenId,
+    pub side: Side,
+    pub size: f64,
+    pub price: f64,
+    pub fee: f64,
+    pub realized_pnl: Option<f64>,  // Set on closing fills
+    #[serde(default)]
+    pub tags: Vec<String>,          // e.g., "test", "hedge", "manual-exit"
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub mae: Option<f64>,           // Max adverse excursion of the closed position
+    #[serde(default)]
+    pub instance_id: Option<String>,  // Bot instance that made the fill
+    #[serde(default)]
+    pub group: Option<u64>,           // Multi-leg trade this fill belongs to (id of its first leg)
+}
+
+impl JournalEntry {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+}
+
+/// Selects journal entries for reports and exports
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JournalFilter {
+    pub any_tags: Vec<String>,      // Keep entries carrying at least one of these (empty = all)
+    pub exclude_tags: Vec<String>,  // Drop entries carrying any of these
+    pub market_id: Option<String>,
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+}
+
+impl JournalFilter {
+    pub fn matches(&self, entry: &JournalEntry) -> bool {
+        if !self.any_tags.is_empty() && !self.any_tags.iter().any(|t| entry.has_tag(t)) {
+            return false;
+        }
+        if self.exclude_tags.iter().any(|t| entry.has_tag(t)) {
+            return false;
+        }
+        if self.market_id.as_ref().is_some_and(|m| entry.market_id != *m) {
+            return false;
+        }
+        if self.since.is_some_and(|s| entry.timestamp < s) || self.until.is_some_and(|u| entry.timestamp > u) {
+            return false;
+        }
+        true
+    }
+}
+
+/// PnL totals over a filtered set of entries
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JournalSummary {
+    pub entries: usize,
+    pub volume: f64,
+    pub fees: f64,
+    pub realized_pnl: f64,
+    pub max_mae: f64,   // Worst drawdown any closed position saw
+    pub avg_mae: f64,   // Mean over closing fills that recorded one
+}
+
+/// `JournalSummary` of the entries carrying one tag
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TagSummary {
+    pub tag: String,
+    pub summary: JournalSummary,
+}
+
+/// Totals per tag, and overall, over the entries a filter selects
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JournalReport {
+    pub total: JournalSummary,
+    pub tags: Vec<TagSummary>,
+}
+
+/// Trade journal
+#[derive(Debug, Clone, Default)]
+pub struct Journal {
+    pub entries: Vec<JournalEntry>,
+    pub instance_id: Option<String>,  // Stamped on entries recorded without one
+    next_id: u64,
+}
+
+impl Journal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an entry (its id is assigned here) and return the id
+    pub fn record(&mut self, mut entry: JournalEntry) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        entry.id = id;
+        if entry.instance_id.is_none() {
+            entry.instance_id = self.instance_id.clone();
+        }
+        self.entries.push(entry);
+        id
+    }
+
+    /// Record every filled leg of a multi-leg trade, grouped under the first leg's id
+    pub fn record_execution(&mut self, report: &MultiLegExecutionReport, timestamp: u64, tags: &[String]) -> Vec<u64> {
+        let mut first = None;
+        let mut ids = Vec::new();
+        for leg in report.legs.iter().filter(|l| l.filled > 0.0) {
+            let id = self.record(JournalEntry {
+                id: 0,
+                timestamp,
+                market_id: report.market_id.clone(),
+                token_id: leg.token_id.clone(),
+                side: leg.side,
+                size: leg.filled,
+                price: leg.price,
+                fee: leg.fee,
+                realized_pnl: None,
+                tags: tags.to_vec(),
+                note: None,
+                mae: None,
+                instance_id: None,
+                group: None,
+            });
+            let group = *first.get_or_insert(id);
+            if let Some(entry) = self.entries.last_mut() {
+                entry.group = Some(group);
+            }
+            ids.push(id);
+        }
+        ids
+    }
+
+    /// Fills of one multi-leg trade
+    pub fn group(&self, group: u64) -> impl Iterator<Item = &JournalEntry> {
+        self.entries.iter().filter(move |e| e.group == Some(group))
+    }
+
+    pub fn get(&self, id: u64) -> Option<&JournalEntry> {
+        self.entries.iter().find(|e| e.id == id)
+    }
+
+    /// Add a tag to an entry (no-op if already present)
+    pub fn tag(&mut self, id: u64, tag: &str) -> bool {
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(entry) => {
+                if !entry.has_tag(tag) {
+                    entry.tags.push(tag.to_string());
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove a tag from an entry
+    pub fn untag(&mut self, id: u64, tag: &str) -> bool {
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(entry) => {
+                entry.tags.retain(|t| !t.eq_ignore_ascii_case(tag));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Attach or replace a free-form note
+    pub fn set_note(&mut self, id: u64, note: &str) -> bool {
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(entry) => {
+                entry.note = Some(note.to_string());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Entries matching a filter
+    pub fn filter<'a>(&'a self, filter: &'a JournalFilter) -> impl Iterator<Item = &'a JournalEntry> + 'a {
+        self.entries.iter().filter(move |e| filter.matches(e))
+    }
+
+    /// Totals over matching entries
+    pub fn summary(&self, filter: &JournalFilter) -> JournalSummary {
+        summarize(self.filter(filter))
+    }
+
+    /// Totals per tag over matching entries, by tag name. An entry counts toward each of
+    /// its tags, so the rows can add up to more than `summary`.
+    pub fn summary_by_tag(&self, filter: &JournalFilter) -> Vec<TagSummary> {
+        let mut tags: Vec<String> = self.filter(filter)
+            .flat_map(|e| e.tags.iter().map(|t| t.to_ascii_lowercase()))
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags.into_iter()
+            .map(|tag| {
+                let summary = summarize(self.filter(filter).filter(|e| e.has_tag(&tag)));
+                TagSummary { tag, summary }
+            })
+            .collect()
+    }
+
+    /// Export matching entries as CSV
+    pub fn export_csv(&self, path: &Path, filter: &JournalFilter) -> std::io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write_csv(&mut out, filter)?;
+        out.flush()
+    }
+
+    /// Write matching entries as CSV; returns the rows written
+    pub fn write_csv(&self, out: &mut impl Write, filter: &JournalFilter) -> std::io::Result<usize> {
+        writeln!(out, "id,timestamp,market_id,token_id,side,size,price,fee,realized_pnl,mae,tags,note")?;
+        let mut rows = 0;
+        for e in self.filter(filter) {
+            writeln!(
+                out,
+                "{},{},{},{},{:?},{},{},{},{},{},{},{}",
+                e.id,
+                e.timestamp,
+                e.market_id,
+                e.token_id,
+                e.side,
+                e.size,
+                e.price,
+                e.fee,
+                e.realized_pnl.map(|p| p.to_string()).unwrap_or_default(),
+                e.mae.map(|m| m.to_string()).unwrap_or_default(),
+                csv_escape(&e.tags.join(";")),
+                csv_escape(e.note.as_deref().unwrap_or("")),
+            )?;
+            rows += 1;
+        }
+        Ok(rows)
+    }
+
+    /// Load every entry from a storage backend
+    pub fn load(storage: &mut dyn Storage) -> StorageResult<Self> {
+        let entries = storage.load_entries()?;
+        let next_id = entries.iter().map(|e| e.id + 1).max().unwrap_or(0);
+        Ok(Self { entries, instance_id: None, next_id })
+    }
+
+    /// Write entries recorded since `from` (an index into `entries`) to storage
+    pub fn append_to(&self, storage: &mut dyn Storage, from: usize) -> StorageResult<()> {
+        for entry in self.entries.iter().skip(from) {
+            storage.append_entry(entry)?;
+        }
+        Ok(())
+    }
+
+    /// Save as JSON lines
+    pub fn save_jsonl(&self, path: &Path) -> std::io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        for e in &self.entries {
+            writeln!(out, "{}", serde_json::to_string(e)?)?;
+        }
+        out.flush()
+    }
+
+    /// Load from JSON lines, skipping unreadable rows
+    pub fn load_jsonl(path: &Path) -> std::io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut journal = Self::new();
+        for line in reader.lines() {
+            if let Ok(entry) = serde_json::from_str::<JournalEntry>(&line?) {
+                journal.next_id = journal.next_id.max(entry.id + 1);
+                journal.entries.push(entry);
+            }
+        }
+        Ok(journal)
+    }
+}
+
+/// Quote a field containing a delimiter, quote or line break, doubling embedded quotes
+/// Totals over `entries`
+fn summarize<'a>(entries: impl Iterator<Item = &'a JournalEntry>) -> JournalSummary {
+    let mut mae_count = 0;
+    let mut summary = entries.fold(JournalSummary::default(), |mut s, e| {
+        s.entries += 1;
+        s.volume += e.size * e.price;
+        s.fees += e.fee;
+        s.realized_pnl += e.realized_pnl.unwrap_or(0.0);
+        if let Some(mae) = e.mae {
+            s.max_mae = s.max_mae.max(mae);
+            s.avg_mae += mae;
+            mae_count += 1;
+        }
+        s
+    });
+    if mae_count > 0 {
+        summary.avg_mae /= mae_count as f64;
+    }
+    summary
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    fn entry(market: &str, timestamp: u64, tags: &[&str], realized_pnl: Option<f64>) -> JournalEntry {
+        JournalEntry {
+            id: 0,
+            timestamp,
+            market_id: MarketId::from(market),
+            token_id: TokenId::from("t"),
+            side: if realized_pnl.is_some() { Side::Sell } else { Side::Buy },
+            size: 10.0,
+            price: 0.5,
+            fee: 0.01,
+            realized_pnl,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            note: None,
+            mae: None,
+            instance_id: None,
+            group: None,
+        }
+    }
+
+    fn journal() -> Journal {
+        let mut journal = Journal::new();
+        journal.record(entry("a", 100, &["manual", "thesis"], None));
+        journal.record(entry("a", 200, &["manual", "thesis"], Some(1.5)));
+        journal.record(entry("b", 300, &["arb-leg"], None));
+        journal.record(entry("b", 400, &["arb-leg", "test"], Some(-0.5)));
+        journal
+    }
+
+    fn ids(journal: &Journal, filter: &JournalFilter) -> Vec<u64> {
+        journal.filter(filter).map(|e| e.id).collect()
+    }
+
+    #[test]
+    fn empty_filter_matches_everything() {
+        assert_eq!(ids(&journal(), &JournalFilter::default()), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn any_tag_keeps_entries_carrying_one_of_them_case_insensitively() {
+        let filter = JournalFilter { any_tags: vec!["THESIS".into(), "test".into()], ..Default::default() };
+        assert_eq!(ids(&journal(), &filter), vec![0, 1, 3]);
+    }
+
+    #[test]
+    fn excluded_tags_win_over_included_ones() {
+        let filter = JournalFilter { any_tags: vec!["arb-leg".into()], exclude_tags: vec!["test".into()], ..Default::default() };
+        assert_eq!(ids(&journal(), &filter), vec![2]);
+    }
+
+    #[test]
+    fn market_and_time_bounds_are_inclusive() {
+        let journal = journal();
+        let filter = JournalFilter { market_id: Some("b".into()), ..Default::default() };
+        assert_eq!(ids(&journal, &filter), vec![2, 3]);
+        let filter = JournalFilter { since: Some(200), until: Some(300), ..Default::default() };
+        assert_eq!(ids(&journal, &filter), vec![1, 2]);
+    }
+
+    #[test]
+    fn mae_summary_is_the_worst_and_mean_over_fills_that_recorded_one() {
+        let mut journal = Journal::new();
+        for mae in [Some(2.0), Some(1.0), None] {
+            let mut e = entry("a", 0, &[], Some(0.0));
+            e.mae = mae;
+            journal.record(e);
+        }
+        let summary = journal.summary(&JournalFilter::default());
+        assert!(close(summary.max_mae, 2.0));
+        assert!(close(summary.avg_mae, 1.5));
+    }
+
+    #[test]
+    fn summaries_total_the_filtered_entries_per_tag() {
+        let journal = journal();
+        let total = journal.summary(&JournalFilter::default());
+        assert_eq!(total.entries, 4);
+        assert!(close(total.volume, 20.0));
+        assert!(close(total.realized_pnl, 1.0));
+
+        let tags = journal.summary_by_tag(&JournalFilter { exclude_tags: vec!["test".into()], ..Default::default() });
+        let names: Vec<&str> = tags.iter().map(|t| t.tag.as_str()).collect();
+        assert_eq!(names, vec!["arb-leg", "manual", "thesis"]);
+        assert_eq!(tags[0].summary.entries, 1);
+        assert!(close(tags[1].summary.realized_pnl, 1.5));
+    }
+}
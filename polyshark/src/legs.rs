@@ -0,0 +1,42 @@
+use crate::orders::{OrderError, OrderGateway, OrderManager, OrderRequest, TimeInForce};
+
+/// Result of sending both legs of an arbitrage
+#[derive(Debug, Clone, PartialEq)]
+pub enum PairOutcome {
+    /// Both legs filled in full
+    Filled { first_id: String, second_id: String },
+    /// First leg was killed or rejected; nothing executed, no exposure
+    Missed { reason: OrderError },
+    /// First leg filled, second didn't: one-sided exposure to unwind
+    Exposed { filled_id: String, failed: OrderRequest, reason: OrderError },
+    /// Gateway can't do FOK; legs went out as IOC and may be partial, so the hedger must reconcile
+    NeedsHedge { first: Result<String, OrderError>, second: Result<String, OrderError> },
+}
+
+/// Sends arbitrage legs as fill-or-kill so a leg either fills in full or not at all.
+/// Pass the thinner (more likely to be killed) leg first: if it dies, the pair is a clean miss.
+pub fn execute_pair(
+    gateway: &mut dyn OrderGateway,
+    orders: &mut OrderManager,
+    first: &OrderRequest,
+    second: &OrderRequest,
+    now: u64,
+) -> PairOutcome {
+    if !gateway.supports_fok() {
+        let ioc = |r: &OrderRequest| OrderRequest { time_in_force: TimeInForce::Ioc, ..r.clone() };
+        return PairOutcome::NeedsHedge {
+            first: orders.submit(gateway, &ioc(first), now),
+            second: orders.submit(gateway, &ioc(second), now),
+        };
+    }
+
+    let fok = |r: &OrderRequest| OrderRequest { time_in_force: TimeInForce::Fok, ..r.clone() };
+    let first_id = match orders.submit(gateway, &fok(first), now) {
+        Ok(id) => id,
+        Err(reason) => return PairOutcome::Missed { reason },
+    };
+    match orders.submit(gateway, &fok(second), now) {
+        Ok(second_id) => PairOutcome::Filled { first_id, second_id },
+        Err(reason) => PairOutcome::Exposed { filled_id: first_id, failed: fok(second), reason },
+    }
+}
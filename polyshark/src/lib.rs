@@ -0,0 +1,86 @@
+// PolyShark - Arbitrage bot for Polymarket
+
+pub mod types;
+pub mod wallet;
+pub mod fees;
+pub mod slippage;
+pub mod fills;
+pub mod constraint;
+pub mod arb;
+pub mod execution;
+pub mod router;
+pub mod control;
+pub mod reconcile;
+pub mod exit;
+pub mod scheduler;
+pub mod journal;
+pub mod tape;
+pub mod storage;
+pub mod backtest;
+pub mod lifecycle;
+pub mod orders;
+pub mod cli;
+pub mod manual;
+pub mod gamma;
+pub mod clob;
+pub mod websocket;
+pub mod config;
+pub mod commands;
+pub mod reports;
+pub mod shadow;
+pub mod matcher;
+pub mod bus;
+pub mod heatmap;
+pub mod capital;
+pub mod risk;
+#[cfg(feature = "archive")]
+pub mod archive;
+pub mod explain;
+pub mod legs;
+#[cfg(feature = "health")]
+pub mod health;
+pub mod instance;
+pub mod replay;
+pub mod provider;
+pub mod money;
+pub mod depth;
+#[cfg(feature = "network")]
+pub mod selftest;
+pub mod webhook;
+pub mod merge;
+pub mod capture;
+pub mod activity;
+pub mod booksync;
+pub mod rewards;
+pub mod lots;
+pub mod pruning;
+pub mod outlier;
+pub mod volatility;
+pub mod compliance;
+pub mod paper;
+pub mod polling;
+pub mod clusters;
+pub mod failover;
+pub mod approval;
+pub mod bench;
+pub mod checkpoint;
+pub mod units;
+pub mod quality;
+pub mod resolution;
+pub mod feedrift;
+pub mod snapshot;
+pub mod priority;
+pub mod sparkline;
+pub mod backoff;
+pub mod costcurve;
+pub mod output;
+pub mod rebalance;
+pub mod fastmove;
+pub mod schema;
+pub mod attribution;
+pub mod datacheck;
+pub mod peg;
+pub mod gateway;
+pub mod bot;
+#[cfg(feature = "bookstore")]
+pub mod bookstore;
@@ -0,0 +1,252 @@
+use crate::types::{Market, MarketId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Lifecycle state of a tracked market
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MarketState {
+    Discovered,   // Known from GAMMA, no data flowing yet
+    Subscribed,   // Receiving books, not yet allowed to trade
+    Tradable,     // Detector may emit signals
+    WindingDown,  // Close to resolution: exits only, no new entries
+    Resolved,     // Final; awaiting or done with settlement
+}
+
+impl MarketState {
+    /// Check whether a transition is allowed
+    pub fn can_transition(self, to: MarketState) -> bool {
+        use MarketState::*;
+        matches!(
+            (self, to),
+            (Discovered, Subscribed)
+                | (Subscribed, Tradable)
+                | (Subscribed, Discovered)   // Unsubscribed (e.g., pruned)
+                | (Tradable, Subscribed)     // Suspended
+                | (Tradable, WindingDown)
+                | (WindingDown, Tradable)   // Orders reopened or the end date moved out
+                | (Subscribed, WindingDown)
+                | (Discovered, Resolved)
+                | (Subscribed, Resolved)
+                | (Tradable, Resolved)
+                | (WindingDown, Resolved)
+        )
+    }
+}
+
+/// Rejected state change
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidTransition {
+    pub market_id: MarketId,
+    pub from: Option<MarketState>,
+    pub to: MarketState,
+}
+
+impl fmt::Display for InvalidTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "market {}: invalid transition {:?} -> {:?}", self.market_id, self.from, self.to)
+    }
+}
+
+impl std::error::Error for InvalidTransition {}
+
+/// Data seen for a market still warming up
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Warmup {
+    pub since: u64,   // When the market was subscribed
+    pub books: u32,   // Book updates observed
+    pub trades: u32,  // Trade prints observed
+}
+
+/// States and warmup progress, as saved across restarts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LifecycleState {
+    pub states: HashMap<MarketId, MarketState>,
+    pub warmups: HashMap<MarketId, Warmup>,
+}
+
+/// Per-market warmup progress for display
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WarmupStatus {
+    pub elapsed_secs: u64,
+    pub remaining_secs: u64,
+    pub books: u32,
+    pub trades: u32,
+    pub ready: bool,  // Will be promoted on the next `promote_warmed`
+}
+
+/// Callback fired after every transition: (market_id, from, to)
+pub type TransitionHook = Box<dyn Fn(&str, MarketState, MarketState) + Send + Sync>;
+
+/// Tracks every market's lifecycle state and runs hooks on transitions
+#[derive(Default)]
+pub struct MarketLifecycle {
+    states: HashMap<MarketId, MarketState>,
+    hooks: Vec<TransitionHook>,
+    warmups: HashMap<MarketId, Warmup>,
+    pub wind_down_secs: u64,    // Stop new entries this long before the end date
+    pub warmup_secs: u64,       // Observe-only period after subscribing
+    pub min_warmup_books: u32,  // Book updates required before trading
+}
+
+impl fmt::Debug for MarketLifecycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MarketLifecycle")
+            .field("states", &self.states)
+            .field("hooks", &self.hooks.len())
+            .field("wind_down_secs", &self.wind_down_secs)
+            .field("warmup_secs", &self.warmup_secs)
+            .field("min_warmup_books", &self.min_warmup_books)
+            .finish()
+    }
+}
+
+impl MarketLifecycle {
+    pub fn new(wind_down_secs: u64) -> Self {
+        Self {
+            wind_down_secs,
+            ..Default::default()
+        }
+    }
+
+    /// Require `warmup_secs` of observation and `min_books` book updates before trading
+    pub fn with_warmup(mut self, warmup_secs: u64, min_books: u32) -> Self {
+        self.warmup_secs = warmup_secs;
+        self.min_warmup_books = min_books;
+        self
+    }
+
+    /// Subscribe a discovered market and start its warmup (no orders until promoted)
+    pub fn subscribe(&mut self, market_id: &MarketId, now: u64) -> Result<(), InvalidTransition> {
+        self.discover(market_id);
+        self.transition(market_id, MarketState::Subscribed)?;
+        self.warmups.entry(market_id.clone()).or_insert(Warmup { since: now, ..Default::default() });
+        Ok(())
+    }
+
+    /// Count a book update towards warmup
+    pub fn observe_book(&mut self, market_id: &MarketId) {
+        if let Some(w) = self.warmups.get_mut(market_id) {
+            w.books += 1;
+        }
+    }
+
+    /// Count a trade print towards warmup
+    pub fn observe_trade(&mut self, market_id: &MarketId) {
+        if let Some(w) = self.warmups.get_mut(market_id) {
+            w.trades += 1;
+        }
+    }
+
+    /// Warmup progress, or `None` if the market isn't warming up
+    pub fn warmup_status(&self, market_id: &MarketId, now: u64) -> Option<WarmupStatus> {
+        let w = self.warmups.get(market_id)?;
+        let elapsed_secs = now.saturating_sub(w.since);
+        Some(WarmupStatus {
+            elapsed_secs,
+            remaining_secs: self.warmup_secs.saturating_sub(elapsed_secs),
+            books: w.books,
+            trades: w.trades,
+            ready: elapsed_secs >= self.warmup_secs && w.books >= self.min_warmup_books,
+        })
+    }
+
+    /// Promote every warmed-up subscribed market to `Tradable`; returns the promoted ids
+    pub fn promote_warmed(&mut self, now: u64) -> Vec<MarketId> {
+        let mut ready: Vec<MarketId> = self.warmups.keys()
+            .filter(|id| self.state(id) == Some(MarketState::Subscribed))
+            .filter(|id| self.warmup_status(id, now).is_some_and(|s| s.ready))
+            .cloned()
+            .collect();
+        // A refused transition leaves the market warming
+        ready.retain(|id| self.transition(id, MarketState::Tradable).is_ok());
+        for id in &ready {
+            self.warmups.remove(id);
+        }
+        ready
+    }
+
+    /// Register a transition hook
+    pub fn on_transition(&mut self, hook: TransitionHook) {
+        self.hooks.push(hook);
+    }
+
+    pub fn state(&self, market_id: &MarketId) -> Option<MarketState> {
+        self.states.get(market_id).copied()
+    }
+
+    /// Check if the detector may emit signals for a market
+    pub fn is_tradable(&self, market_id: &MarketId) -> bool {
+        self.state(market_id) == Some(MarketState::Tradable)
+    }
+
+    /// Start tracking a market (no-op if already tracked)
+    pub fn discover(&mut self, market_id: &MarketId) {
+        self.states.entry(market_id.clone()).or_insert(MarketState::Discovered);
+    }
+
+    /// Move a market to a new state, running hooks
+    pub fn transition(&mut self, market_id: &MarketId, to: MarketState) -> Result<(), InvalidTransition> {
+        let from = self.state(market_id);
+        match from {
+            Some(from) if from == to => Ok(()),
+            Some(from) if from.can_transition(to) => {
+                self.states.insert(market_id.clone(), to);
+                if to == MarketState::Discovered {
+                    // Unsubscribed: a later subscribe warms up from scratch
+                    self.warmups.remove(market_id);
+                }
+                for hook in &self.hooks {
+                    hook(market_id, from, to);
+                }
+                Ok(())
+            }
+            _ => Err(InvalidTransition { market_id: market_id.clone(), from, to }),
+        }
+    }
+
+    /// Apply transitions implied by fresh GAMMA metadata. A wound-down market that is
+    /// accepting orders again with its end date outside the wind-down window (a paused
+    /// book reopened, or the end date was pushed back) becomes tradable again.
+    pub fn sync(&mut self, market: &Market, now: u64) {
+        self.discover(&market.id);
+        let current = self.state(&market.id);
+
+        if !market.active {
+            let _ = self.transition(&market.id, MarketState::Resolved);
+            return;
+        }
+        let near_end = market.seconds_to_resolution(now).is_some_and(|s| s <= self.wind_down_secs);
+        if (near_end || !market.accepting_orders) && current != Some(MarketState::Discovered) {
+            let _ = self.transition(&market.id, MarketState::WindingDown);
+        } else if current == Some(MarketState::WindingDown) {
+            let _ = self.transition(&market.id, MarketState::Tradable);
+        }
+    }
+
+    /// Markets currently in `state`
+    pub fn in_state(&self, state: MarketState) -> Vec<&str> {
+        self.states.iter()
+            .filter(|(_, s)| **s == state)
+            .map(|(id, _)| id.as_str())
+            .collect()
+    }
+
+    /// Everything needed to resume where this run left off
+    pub fn export_state(&self) -> LifecycleState {
+        LifecycleState { states: self.states.clone(), warmups: self.warmups.clone() }
+    }
+
+    /// Resume a saved run. Hooks don't fire: nothing transitioned, it was already there.
+    /// Call before `sync` so fresh Gamma metadata still applies on top.
+    pub fn restore_state(&mut self, state: LifecycleState) {
+        self.states.extend(state.states);
+        self.warmups.extend(state.warmups);
+    }
+
+    /// Stop tracking a market entirely
+    pub fn forget(&mut self, market_id: &MarketId) {
+        self.states.remove(market_id);
+        self.warmups.remove(market_id);
+    }
+}
@@ -0,0 +1,229 @@
+use crate::attribution::EntryReason;
+use crate::journal::Journal;
+use crate::types::{format_date, Side, TokenId};
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Write};
+
+/// Which open lots a sale consumes first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LotMethod {
+    #[default]
+    Fifo,  // Oldest first
+    Lifo,  // Newest first
+}
+
+impl LotMethod {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "fifo" => Some(LotMethod::Fifo),
+            "lifo" => Some(LotMethod::Lifo),
+            _ => None,
+        }
+    }
+}
+
+/// Shares bought in one fill, not yet sold
+#[derive(Debug, Clone)]
+pub struct TaxLot {
+    pub opened_at: u64,  // Seconds
+    pub size: f64,
+    pub cost: f64,       // Per share, including the buy fee
+    pub reason: EntryReason,
+}
+
+/// Part (or all) of a lot disposed of in one sale
+#[derive(Debug, Clone)]
+pub struct ClosedLot {
+    pub token_id: TokenId,
+    pub opened_at: u64,
+    pub closed_at: u64,
+    pub size: f64,
+    pub cost_basis: f64,  // Including the buy fee share
+    pub proceeds: f64,    // Net of the sell fee share
+    pub reason: EntryReason,  // Why the lot was opened
+}
+
+impl ClosedLot {
+    pub fn gain(&self) -> f64 {
+        self.proceeds - self.cost_basis
+    }
+
+    /// Held over a year (long-term for US tax purposes)
+    pub fn long_term(&self) -> bool {
+        self.closed_at.saturating_sub(self.opened_at) > 365 * 86_400
+    }
+}
+
+/// Open lots per token plus every lot closed so far
+#[derive(Debug, Clone, Default)]
+pub struct LotBook {
+    pub method: LotMethod,
+    pub open: HashMap<TokenId, VecDeque<TaxLot>>,  // Oldest lot at the front
+    pub closed: Vec<ClosedLot>,
+}
+
+impl LotBook {
+    pub fn new(method: LotMethod) -> Self {
+        Self { method, ..Default::default() }
+    }
+
+    /// Rebuild lots from journaled fills
+    pub fn from_journal(journal: &Journal, method: LotMethod) -> Self {
+        let mut lots = Self::new(method);
+        for e in &journal.entries {
+            match e.side {
+                Side::Buy => lots.buy(&e.token_id, e.size, e.price, e.fee, e.timestamp, EntryReason::from_tags(&e.tags)),
+                Side::Sell => {
+                    lots.sell(&e.token_id, e.size, e.price, e.fee, e.timestamp);
+                }
+            }
+        }
+        lots
+    }
+
+    /// Open a lot; the fee is folded into its cost basis
+    pub fn buy(&mut self, token_id: &TokenId, size: f64, price: f64, fee: f64, timestamp: u64, reason: EntryReason) {
+        if size <= 0.0 {
+            return;
+        }
+        let lot = TaxLot { opened_at: timestamp, size, cost: price + fee / size, reason };
+        self.open.entry(token_id.clone()).or_default().push_back(lot);
+    }
+
+    /// Dispose of `size` shares, returning the lots (or lot slices) closed.
+    /// Shares beyond what the open lots hold are ignored.
+    pub fn sell(&mut self, token_id: &TokenId, size: f64, price: f64, fee: f64, timestamp: u64) -> Vec<ClosedLot> {
+        let mut closed = Vec::new();
+        let Some(lots) = self.open.get_mut(token_id) else {
+            return closed;
+        };
+        let net_price = if size > 0.0 { price - fee / size } else { price };
+        let mut remaining = size;
+        while remaining > 1e-9 {
+            let lot = match self.method {
+                LotMethod::Fifo => lots.front_mut(),
+                LotMethod::Lifo => lots.back_mut(),
+            };
+            let Some(lot) = lot else {
+                break;
+            };
+            let take = remaining.min(lot.size);
+            closed.push(ClosedLot {
+                token_id: token_id.clone(),
+                opened_at: lot.opened_at,
+                closed_at: timestamp,
+                size: take,
+                cost_basis: take * lot.cost,
+                proceeds: take * net_price,
+                reason: lot.reason,
+            });
+            lot.size -= take;
+            remaining -= take;
+            if lot.size <= 1e-9 {
+                match self.method {
+                    LotMethod::Fifo => lots.pop_front(),
+                    LotMethod::Lifo => lots.pop_back(),
+                };
+            }
+        }
+        if lots.is_empty() {
+            self.open.remove(token_id);
+        }
+        self.closed.extend(closed.iter().cloned());
+        closed
+    }
+
+    /// Realized gain over all closed lots
+    pub fn realized(&self) -> f64 {
+        self.closed.iter().map(ClosedLot::gain).sum()
+    }
+
+    /// One row per closed lot, in the column layout of Form 8949 imports
+    pub fn write_csv(&self, out: &mut impl Write) -> io::Result<usize> {
+        writeln!(out, "description,quantity,date_acquired,date_sold,proceeds,cost_basis,gain_or_loss,term")?;
+        for lot in &self.closed {
+            writeln!(
+                out,
+                "{},{:.6},{},{},{:.2},{:.2},{:.2},{}",
+                lot.token_id,
+                lot.size,
+                format_date(lot.opened_at),
+                format_date(lot.closed_at),
+                lot.proceeds,
+                lot.cost_basis,
+                lot.gain(),
+                if lot.long_term() { "long" } else { "short" },
+            )?;
+        }
+        Ok(self.closed.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    /// 10 shares at 0.40 on day 1, then 10 at 0.60 on day 2 (no fees)
+    fn two_lots(method: LotMethod) -> (LotBook, TokenId) {
+        let token = TokenId::from("yes");
+        let mut lots = LotBook::new(method);
+        lots.buy(&token, 10.0, 0.40, 0.0, 86_400, EntryReason::ArbLeg);
+        lots.buy(&token, 10.0, 0.60, 0.0, 2 * 86_400, EntryReason::Hedge);
+        (lots, token)
+    }
+
+    #[test]
+    fn partial_sale_leaves_the_rest_of_the_lot_open() {
+        let (mut lots, token) = two_lots(LotMethod::Fifo);
+        let closed = lots.sell(&token, 4.0, 0.50, 0.0, 3 * 86_400);
+        assert_eq!(closed.len(), 1);
+        assert!(close(closed[0].size, 4.0));
+        assert!(close(closed[0].cost_basis, 1.6));
+        assert_eq!(closed[0].opened_at, 86_400);
+        let open = &lots.open[&token];
+        assert_eq!(open.len(), 2);
+        assert!(close(open[0].size, 6.0));
+        assert!(close(open[1].size, 10.0));
+    }
+
+    #[test]
+    fn fifo_and_lifo_realize_different_gains() {
+        let (mut fifo, token) = two_lots(LotMethod::Fifo);
+        let (mut lifo, _) = two_lots(LotMethod::Lifo);
+        // 15 shares at 0.50: FIFO closes the cheap lot whole, LIFO the dear one
+        let fifo_closed = fifo.sell(&token, 15.0, 0.50, 0.0, 3 * 86_400);
+        let lifo_closed = lifo.sell(&token, 15.0, 0.50, 0.0, 3 * 86_400);
+        assert!(close(fifo.realized(), 10.0 * 0.10 - 5.0 * 0.10));
+        assert!(close(lifo.realized(), -10.0 * 0.10 + 5.0 * 0.10));
+        assert_eq!(fifo_closed[0].reason, EntryReason::ArbLeg);
+        assert_eq!(lifo_closed[0].reason, EntryReason::Hedge);
+        assert!(close(fifo.open[&token][0].cost, 0.60));
+        assert!(close(lifo.open[&token][0].cost, 0.40));
+    }
+
+    #[test]
+    fn fees_fold_into_basis_and_proceeds() {
+        let token = TokenId::from("yes");
+        let mut lots = LotBook::new(LotMethod::Fifo);
+        lots.buy(&token, 10.0, 0.40, 0.10, 0, EntryReason::ArbLeg);
+        let closed = lots.sell(&token, 10.0, 0.50, 0.05, 60);
+        assert!(close(closed[0].cost_basis, 4.10));
+        assert!(close(closed[0].proceeds, 4.95));
+        assert!(close(lots.realized(), 0.85));
+    }
+
+    #[test]
+    fn selling_more_than_held_closes_only_what_is_open() {
+        let (mut lots, token) = two_lots(LotMethod::Fifo);
+        let closed = lots.sell(&token, 25.0, 0.50, 0.0, 3 * 86_400);
+        assert!(close(closed.iter().map(|l| l.size).sum::<f64>(), 20.0));
+        assert!(!lots.open.contains_key(&token));
+        assert!(lots.sell(&token, 1.0, 0.50, 0.0, 4 * 86_400).is_empty());
+    }
+}
@@ -1,21 +1,362 @@
 // PolyShark - Arbitrage bot for Polymarket
 
-mod types;
-mod wallet;
-mod fees;
-mod slippage;
-mod fills;
-mod constraint;
-mod arb;
-mod execution;
-// mod gamma;     // TODO: Implement GAMMA API client
-// mod clob;      // TODO: Implement CLOB API client
-// mod websocket; // TODO: Implement WebSocket streaming
-
-fn main() {
-    println!("🦈 PolyShark starting...");
-    
-    // TODO: Initialize wallet
-    // TODO: Connect to Polymarket API
-    // TODO: Start trading loop
+use polyshark::{bench, cli, commands, config};
+#[cfg(feature = "network")]
+use polyshark::{bot, clob, control, failover, gamma, gateway, instance, orders, paper, provider, webhook};
+#[cfg(feature = "health")]
+use polyshark::health;
+#[cfg(feature = "network")]
+use polyshark::selftest;
+use cli::Command;
+use config::Config;
+#[cfg(feature = "network")]
+use config::TradingMode;
+#[cfg(feature = "network")]
+use failover::{FailoverRole, HeartbeatWriter, StandbyMonitor};
+#[cfg(feature = "network")]
+use control::{BotView, ControlServer, ControlState};
+#[cfg(all(feature = "network", feature = "health"))]
+use health::{HealthServer, HealthState};
+use std::path::Path;
+#[cfg(feature = "network")]
+use std::sync::Arc;
+
+/// Config file picked up from the working directory when `--config` is not given
+const DEFAULT_CONFIG_PATH: &str = "polyshark.json";
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cli = match cli::parse_args(&args) {
+        Ok(cli) => cli,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let config = match cli.global.config.as_deref() {
+        Some(path) => Config::load(Path::new(path)),
+        None if Path::new(DEFAULT_CONFIG_PATH).exists() => Config::load(Path::new(DEFAULT_CONFIG_PATH)),
+        None => Ok(Config::default()),
+    };
+    let config = config.unwrap_or_else(|err| fail(&err, 2));
+    #[cfg_attr(not(feature = "network"), allow(unused_variables))]
+    let (profile_name, profile) = config
+        .select(cli.global.profile.as_deref(), cli.global.live_acknowledged)
+        .unwrap_or_else(|err| fail(&err, 2));
+
+    match cli.command {
+        #[cfg(feature = "network")]
+        Command::Run { headless, health_addr, markets } => {
+            println!("🦈 PolyShark starting... (profile: {}, {:?})", profile_name, profile.mode);
+
+            // Two instances on one wallet would double-trade
+            let instance_id = instance::instance_id();
+            let (_lock, took_over) = match (&profile.failover, profile.mode) {
+                (Some(failover), TradingMode::Live) if failover.role == FailoverRole::Standby => {
+                    println!("standby: watching {}", failover.heartbeat_path.display());
+                    let mut monitor = StandbyMonitor::new(failover.clone(), commands::now() * 1000);
+                    (monitor.wait_for_takeover(&profile.lock, &instance_id).await, true)
+                }
+                _ => (trading_lock(profile, &instance_id), false),
+            };
+
+            let gateway: Box<dyn orders::OrderGateway> = match profile.mode {
+                TradingMode::Paper => Box::new(paper::PaperGateway::new(
+                    paper::PaperVenue::new(profile.paper_latency.clone()),
+                    clob::ClobClient::new(&profile.clob_url),
+                )),
+                TradingMode::Live => Box::new(gateway::live_gateway(profile).unwrap_or_else(|err| fail(&err, 2))),
+            };
+            let mut bot = bot::Bot::new(profile, gateway).unwrap_or_else(|err| fail(&err, 1));
+            if took_over {
+                // The dead primary's resting orders can't be adopted; start from a clean slate
+                bot.take_over().unwrap_or_else(|err| fail(&format!("standby takeover: {}", err), 1));
+                println!("standby: cancelled the primary's resting orders");
+            }
+            if let Some(failover) = &profile.failover
+                && profile.mode == TradingMode::Live
+            {
+                let writer = HeartbeatWriter::new(&failover.heartbeat_path, &instance_id);
+                bot = bot.with_heartbeat(writer, failover.clone());
+            }
+            if profile.reconcile.enabled {
+                match profile.mode {
+                    TradingMode::Live => {
+                        let client = gateway::live_client(profile).unwrap_or_else(|err| fail(&format!("reconcile: {}", err), 2));
+                        bot = bot.with_reconciler(client);
+                        println!("reconciling balances every {}s ({:?} on drift)", profile.reconcile.interval_secs, profile.reconcile.action);
+                    }
+                    TradingMode::Paper => eprintln!("⚠️  reconcile is for live profiles, a paper wallet has no exchange balance"),
+                }
+            }
+
+            // One set of switches for the control API, the health report and the trading loop
+            let control = Arc::new(ControlState::new());
+            let view = profile.control.as_ref().map(|config| {
+                let token = std::env::var(&config.token_env)
+                    .unwrap_or_else(|_| fail(&format!("control API: set {} to its bearer token", config.token_env), 2));
+                let view = Arc::new(BotView::new());
+                view.follow_signals(&bot.bus);
+                let server = ControlServer::new(&config.bind_addr, &token, Arc::clone(&control))
+                    .with_view(Arc::clone(&view))
+                    .with_approvals(Arc::clone(&bot.approvals));
+                if let Err(err) = server.spawn() {
+                    fail(&format!("control endpoint {}: {}", config.bind_addr, err), 1);
+                }
+                println!("control API on {} (/status, /positions, /orders, /signals, /intents)", config.bind_addr);
+                view
+            });
+            if profile.supervised.is_some() && view.is_none() {
+                fail("supervised mode needs the control API to answer intents", 2);
+            }
+            let bot = bot.with_control(Arc::clone(&control), view);
+            if webhook::spawn_signal_webhooks(&bot.bus, profile.webhooks.clone(), Some(Arc::clone(&bot.history))).is_some() {
+                println!("pushing signals to {} webhooks", profile.webhooks.urls.len());
+            }
+
+            #[cfg(not(feature = "health"))]
+            if headless {
+                let _ = health_addr;
+                fail("headless mode requires the `health` feature", 2);
+            }
+            #[cfg(feature = "health")]
+            let health = Arc::new(HealthState::new());
+            #[cfg(feature = "health")]
+            if headless {
+                if let Err(err) = HealthServer::new(&health_addr, 30_000, Arc::clone(&health), control).spawn() {
+                    fail(&format!("health endpoint {}: {}", health_addr, err), 1);
+                }
+                println!("health endpoints on {} (/healthz, /readyz)", health_addr);
+            }
+            #[cfg(feature = "health")]
+            let mut bot = bot.with_health(health);
+            #[cfg(not(feature = "health"))]
+            let mut bot = bot;
+
+            // Without the `health` feature there is no signal handling: Ctrl-C just ends the process
+            #[cfg(feature = "health")]
+            let shutdown = health::shutdown_signal();
+            #[cfg(not(feature = "health"))]
+            let shutdown = std::future::pending::<()>();
+            let result = match markets {
+                Some(path) => {
+                    let source = provider::FileMarketSource::load(Path::new(&path))
+                        .unwrap_or_else(|err| fail(&format!("{}: {}", path, err), 2));
+                    // Books shipped with the file stand in until the feed sends its own
+                    bot.books.extend(source.books.clone());
+                    bot.run(&source, shutdown).await
+                }
+                None => bot.run(&gamma::GammaClient::new(&profile.gamma_url), shutdown).await,
+            };
+            println!("shutting down");
+            #[cfg(feature = "health")]
+            {
+                let cancelled = bot.cancel_open_orders();
+                if cancelled > 0 {
+                    println!("cancelled {} open orders", cancelled);
+                }
+            }
+            if let Err(err) = result {
+                fail(&err, 1);
+            }
+        }
+        #[cfg(feature = "network")]
+        Command::Trade(order) => {
+            // A dry run only reads; a real order writes the journal a running bot also writes
+            let _lock = (!order.dry_run).then(|| trading_lock(profile, &instance::instance_id()));
+            if let Err(err) = commands::trade(profile, &order).await {
+                fail(&err, 1);
+            }
+        }
+        Command::Compare { baseline, candidate, tolerance, expect_identical } => {
+            match commands::compare(&baseline, &candidate, tolerance, expect_identical) {
+                Ok(true) => std::process::exit(1),
+                Ok(false) => {}
+                Err(err) => fail(&err, 2),
+            }
+        }
+        #[cfg(feature = "network")]
+        Command::Flatten { max_slippage } => {
+            let _lock = trading_lock(profile, &instance::instance_id());
+            match commands::flatten(profile, max_slippage).await {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(err) => fail(&err, 2),
+            }
+        }
+        #[cfg(feature = "network")]
+        Command::Aging { hurdle, output } => {
+            if let Err(err) = commands::aging(profile, hurdle, output).await {
+                fail(&err, 1);
+            }
+        }
+        #[cfg(feature = "network")]
+        Command::Rebalance { output } => {
+            if let Err(err) = commands::rebalance(profile, output).await {
+                fail(&err, 1);
+            }
+        }
+        #[cfg(feature = "network")]
+        Command::SelfTest { ws_secs } => {
+            let report = selftest::run(profile, ws_secs).await;
+            if !report.passed() {
+                std::process::exit(1);
+            }
+        }
+        #[cfg(feature = "network")]
+        Command::Pairs { propose: Some(limit), output } => {
+            if let Err(err) = commands::propose_pairs(profile, limit).await {
+                fail(&err, 1);
+            }
+            if let Err(err) = commands::pairs(profile, output) {
+                fail(&err, 1);
+            }
+        }
+        #[cfg(not(feature = "network"))]
+        Command::Pairs { propose: Some(_), .. } => fail("pairs --propose talks to Polymarket and requires the `network` feature", 2),
+        Command::WhyNot { day, output } => {
+            if let Err(err) = commands::why_not(day, output) {
+                fail(&err, 1);
+            }
+        }
+        Command::Pairs { propose: None, output } => {
+            if let Err(err) = commands::pairs(profile, output) {
+                fail(&err, 1);
+            }
+        }
+        Command::ReviewPair { market_a, market_b, status } => {
+            if let Err(err) = commands::review_pair(profile, &market_a, &market_b, status) {
+                fail(&err, 1);
+            }
+        }
+        #[cfg(not(feature = "network"))]
+        Command::Run { .. }
+        | Command::Trade(_)
+        | Command::Flatten { .. }
+        | Command::Aging { .. }
+        | Command::Rebalance { .. }
+        | Command::SelfTest { .. } => fail("this command talks to Polymarket and requires the `network` feature", 2),
+        Command::ExportDepth { tokens, from, to, format, out } => {
+            if let Err(err) = commands::export_depth(profile, &tokens, from, to, format, out.as_deref()) {
+                fail(&err, 1);
+            }
+        }
+        Command::ExportLots { method, out } => {
+            if let Err(err) = commands::export_lots(profile, method, out.as_deref()) {
+                fail(&err, 1);
+            }
+        }
+        Command::Report { filter, output } => {
+            if let Err(err) = commands::report(profile, &filter, output) {
+                fail(&err, 1);
+            }
+        }
+        Command::Export { filter, out } => {
+            if let Err(err) = commands::export_journal(profile, &filter, out.as_deref()) {
+                fail(&err, 1);
+            }
+        }
+        Command::Annotate { id, tag, untag, note } => {
+            if let Err(err) = commands::annotate(profile, id, tag.as_deref(), untag.as_deref(), note.as_deref()) {
+                fail(&err, 1);
+            }
+        }
+        Command::Attribution { method, output } => {
+            if let Err(err) = commands::attribution(profile, method, output) {
+                fail(&err, 1);
+            }
+        }
+        Command::Clusters { min_trades, output } => {
+            if let Err(err) = commands::clusters(profile, min_trades, output) {
+                fail(&err, 1);
+            }
+        }
+        Command::Diff { from, to, output } => {
+            if let Err(err) = commands::diff(profile, from, to, output) {
+                fail(&err, 1);
+            }
+        }
+        Command::Balance { output } => {
+            if let Err(err) = commands::balance(profile, output) {
+                fail(&err, 1);
+            }
+        }
+        Command::Orders { output } => {
+            if let Err(err) = commands::orders(profile, output) {
+                fail(&err, 1);
+            }
+        }
+        Command::Sensitivity { result, max_bps, step_bps } => {
+            if let Err(err) = commands::sensitivity(&result, max_bps, step_bps) {
+                fail(&err, 1);
+            }
+        }
+        Command::Bench { markets, updates } => {
+            if markets == 0 {
+                fail("--markets must be at least 1", 2);
+            }
+            let report = bench::run_update_bench(markets, updates, 42);
+            println!(
+                "{} updates over {} markets in {:.2?}: {:.0} ns/update, {:.0} updates/s ({} tradable signals)",
+                report.updates,
+                report.markets,
+                report.elapsed,
+                report.ns_per_update(),
+                report.updates_per_sec(),
+                report.signals,
+            );
+        }
+        Command::ReplayTrade { trade_id } => {
+            if let Err(err) = commands::replay_trade(profile, trade_id) {
+                fail(&err, 1);
+            }
+        }
+        #[cfg(feature = "bookstore")]
+        Command::Lookahead { results, dir, delay_ms, output } => {
+            if let Err(err) = commands::lookahead(&results, &dir, delay_ms, output) {
+                fail(&err, 1);
+            }
+        }
+        #[cfg(not(feature = "bookstore"))]
+        Command::Lookahead { .. } => fail("lookahead requires the `bookstore` feature", 2),
+        #[cfg(feature = "bookstore")]
+        Command::CheckData { dir, max_gap_ms, output } => {
+            match commands::check_data(&dir, max_gap_ms, output) {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(err) => fail(&err, 2),
+            }
+        }
+        #[cfg(not(feature = "bookstore"))]
+        Command::CheckData { .. } => fail("check-data requires the `bookstore` feature", 2),
+        #[cfg(all(feature = "archive", feature = "network"))]
+        Command::Archive { dir } => {
+            if let Err(err) = commands::archive(profile, &dir).await {
+                fail(&err, 1);
+            }
+        }
+        #[cfg(not(all(feature = "archive", feature = "network")))]
+        Command::Archive { .. } => fail("archive requires the `archive` and `network` features", 2),
+        #[cfg(feature = "archive")]
+        Command::Quality { dir, top, output } => {
+            if let Err(err) = commands::quality(profile, &dir, top, output) {
+                fail(&err, 1);
+            }
+        }
+        #[cfg(not(feature = "archive"))]
+        Command::Quality { .. } => fail("quality requires the `archive` feature", 2),
+    }
+}
+
+fn fail(err: &str, code: i32) -> ! {
+    eprintln!("error: {}", err);
+    std::process::exit(code);
+}
+
+/// Single-instance lock for commands that trade or write the journal
+#[cfg(feature = "network")]
+fn trading_lock(profile: &config::Profile, instance_id: &str) -> instance::InstanceLock {
+    profile.lock.acquire(instance_id).unwrap_or_else(|err| fail(&err, 1))
 }
@@ -0,0 +1,259 @@
+use crate::attribution::EntryReason;
+use crate::bus::Fill;
+use crate::cli::ManualOrder;
+use crate::execution::ExecutionEngine;
+use crate::journal::{Journal, JournalEntry};
+use crate::types::{ExecutionResult, Market, MarketId, OrderBook, Side, TokenId};
+use crate::money::Usdc;
+use crate::reconcile::CASH_ASSET;
+use crate::risk::RiskMonitor;
+use crate::wallet::Wallet;
+
+/// Tag stamped on every manually placed fill
+pub const MANUAL_TAG: &str = "manual";
+
+/// Extra tag on fills placed by an emergency flatten
+pub const FLATTEN_TAG: &str = "flatten";
+
+/// Largest sell of at most `held` shares that never fills more than `max_slippage` below the
+/// mid, with the price floor that enforces it. `None` when no bid is within the cap.
+pub fn flatten_size(book: &OrderBook, held: f64, max_slippage: f64) -> Option<(f64, f64)> {
+    let mid = book.midpoint().or(book.best_bid())?;
+    let floor = mid * (1.0 - max_slippage);
+    let available: f64 = book.bids.iter().take_while(|l| l.price >= floor - 1e-9).map(|l| l.size).sum();
+    let size = held.min(available);
+    (size > 0.0).then_some((size, floor))
+}
+
+/// `flatten_size` for a short: the largest buy-back of at most `short` shares that never
+/// pays more than `max_slippage` above the mid, with the price ceiling that enforces it
+pub fn cover_size(book: &OrderBook, short: f64, max_slippage: f64) -> Option<(f64, f64)> {
+    let mid = book.midpoint().or(book.best_ask())?;
+    let ceiling = mid * (1.0 + max_slippage);
+    let available: f64 = book.asks.iter().take_while(|l| l.price <= ceiling + 1e-9).map(|l| l.size).sum();
+    let size = short.min(available);
+    (size > 0.0).then_some((size, ceiling))
+}
+
+/// Book a fill the exchange reported for a manual order and journal it; returns the entry id
+pub fn record_fill(fill: &Fill, market_id: &MarketId, wallet: &mut Wallet, journal: &mut Journal) -> u64 {
+    let now = fill.timestamp / 1000;
+    let reference = fill.order_id.as_deref().unwrap_or(fill.token_id.as_str());
+    let (realized_pnl, mae) = match fill.side {
+        Side::Buy => {
+            // Already executed on the venue: record the cash even if it overdraws
+            wallet.debit_unchecked(Usdc(fill.price * fill.size + fill.fee), "buy fill", Some(reference));
+            wallet.add_to_position(&fill.token_id, Side::Buy, fill.size, fill.price, fill.fee, now, EntryReason::Manual);
+            (None, None)
+        }
+        Side::Sell => {
+            wallet.credit(Usdc(fill.price * fill.size - fill.fee), "sell fill", Some(reference));
+            wallet.mark_excursion(&fill.token_id, fill.price);
+            let mae = wallet.positions.get(fill.token_id.as_str()).map(|p| p.max_adverse_excursion);
+            let closed = wallet.sell_from_position(&fill.token_id, fill.size, fill.price, fill.fee, now);
+            (Some(closed.iter().map(|l| l.gain()).sum()), mae)
+        }
+    };
+    wallet.record_fee(Usdc(fill.fee), Some(reference));
+    journal.record(JournalEntry {
+        id: 0,
+        timestamp: now,
+        market_id: market_id.clone(),
+        token_id: fill.token_id.clone(),
+        side: fill.side,
+        size: fill.size,
+        price: fill.price,
+        fee: fill.fee,
+        realized_pnl,
+        tags: vec![MANUAL_TAG.to_string()],
+        note: None,
+        mae,
+        instance_id: None,
+        group: None,
+    })
+}
+
+/// Resolve an outcome name (case insensitive) to its token id
+pub fn outcome_token(market: &Market, outcome: &str) -> Option<TokenId> {
+    let idx = market.outcomes.iter().position(|o| o.eq_ignore_ascii_case(outcome))?;
+    market.clob_token_ids.get(idx).cloned()
+}
+
+/// Run a manual order through the same checks and execution path as the bot
+#[allow(clippy::too_many_arguments)]
+pub fn execute_manual(
+    order: &ManualOrder,
+    market: &Market,
+    book: &OrderBook,
+    engine: &ExecutionEngine,
+    wallet: &mut Wallet,
+    journal: &mut Journal,
+    risk: &mut RiskMonitor,
+    now: u64,
+) -> Result<ExecutionResult, String> {
+    if !market.active || !market.accepting_orders {
+        return Err(format!("market {} is not accepting orders", market.slug));
+    }
+    let token_id = outcome_token(market, &order.outcome)
+        .ok_or_else(|| format!("market {} has no outcome `{}`", market.slug, order.outcome))?;
+
+    // No naked shorts: sells must come out of held inventory
+    if order.side == Side::Sell {
+        risk.check_sell(wallet, &token_id, order.size.value()).map_err(|violation| violation.to_string())?;
+    }
+
+    // Never trade through the limit: like a real limit order, take what the book offers at
+    // or better than it and leave the rest unfilled
+    let result = match order.side {
+        Side::Buy => engine.execute(book, order.size, order.side, Some(order.limit.value()), wallet),
+        Side::Sell => engine.sell(book, order.size, Some(order.limit.value()), wallet),
+    };
+    let result = result
+        .ok_or_else(|| format!("execution rejected (no liquidity at or better than {:.4}, or insufficient funds)", order.limit))?;
+
+    let (realized_pnl, mae) = match order.side {
+        Side::Buy => {
+            wallet.add_to_position(&token_id, Side::Buy, result.filed_size, result.execution_price, result.fee_paid, now, EntryReason::Manual);
+            (None, None)
+        }
+        Side::Sell => {
+            // The exit price is the last mark the position sees
+            wallet.mark_excursion(&token_id, result.execution_price);
+            let mae = wallet.positions.get(token_id.as_str()).ok_or("position vanished")?.max_adverse_excursion;
+            // Realized gain follows the wallet's lot method (FIFO/LIFO), fees on both legs included
+            let closed = wallet.sell_from_position(&token_id, result.filed_size, result.execution_price, result.fee_paid, now);
+            (Some(closed.iter().map(|l| l.gain()).sum()), Some(mae))
+        }
+    };
+
+    journal.record(JournalEntry {
+        id: 0,
+        timestamp: now,
+        market_id: market.id.clone(),
+        token_id,
+        side: order.side,
+        size: result.filed_size,
+        price: result.execution_price,
+        fee: result.fee_paid,
+        realized_pnl,
+        tags: std::iter::once(MANUAL_TAG.to_string()).chain(order.tags.iter().cloned()).collect(),
+        note: order.note.clone(),
+        mae,
+        instance_id: None,
+        group: None,
+    });
+
+    Ok(result)
+}
+
+/// Rebuild cash and positions by replaying journaled fills onto a fresh wallet. Every
+/// fill's price also marks the position in its token, so excursions seen between fills
+/// survive the rebuild instead of restarting at zero.
+pub fn replay_journal(wallet: &mut Wallet, journal: &Journal) {
+    for e in &journal.entries {
+        apply_entry(wallet, e);
+    }
+}
+
+/// Apply one journaled fill to the wallet. Cash corrections from reconciliation move
+/// cash only.
+pub fn apply_entry(wallet: &mut Wallet, e: &JournalEntry) {
+    let reference = format!("journal:{}", e.id);
+    if e.token_id.as_str() == CASH_ASSET {
+        match e.side {
+            Side::Buy => wallet.debit_unchecked(Usdc(e.size * e.price), "reconciled cash", Some(&reference)),
+            Side::Sell => wallet.credit(Usdc(e.size * e.price), "reconciled cash", Some(&reference)),
+        }
+        return;
+    }
+    wallet.mark_excursion(&e.token_id, e.price);
+    let notional = e.size * e.price;
+    match e.side {
+        Side::Buy => {
+            wallet.debit_unchecked(Usdc(notional + e.fee), "replayed buy", Some(&reference));
+            wallet.add_to_position(&e.token_id, Side::Buy, e.size, e.price, e.fee, e.timestamp, EntryReason::from_tags(&e.tags));
+        }
+        Side::Sell => {
+            wallet.credit(Usdc(notional - e.fee), "replayed sell", Some(&reference));
+            wallet.sell_from_position(&e.token_id, e.size, e.price, e.fee, e.timestamp);
+        }
+    }
+    wallet.record_fee(Usdc(e.fee), Some(&reference));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fees::FeeConfig;
+    use crate::risk::RiskViolation;
+    use crate::types::{Price, PriceLevel, Size};
+
+    fn market() -> Market {
+        Market {
+            id: MarketId::from("m"),
+            question: String::new(),
+            slug: "m".to_string(),
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            outcome_prices: vec![0.5, 0.5],
+            clob_token_ids: vec![TokenId::from("yes"), TokenId::from("no")],
+            best_bid: None,
+            best_ask: None,
+            maker_base_fee: 0,
+            taker_base_fee: 0,
+            liquidity: 10_000.0,
+            volume_24hr: 0.0,
+            active: true,
+            accepting_orders: true,
+            condition_id: String::new(),
+            end_date: None,
+            resolution_source: None,
+            category: None,
+            tags: Vec::new(),
+            neg_risk: false,
+            event_id: None,
+            rewards_max_spread: None,
+            rewards_min_size: None,
+            restricted: false,
+            description: None,
+            uma_resolution_status: None,
+            closed: false,
+        }
+    }
+
+    fn sell(size: f64) -> ManualOrder {
+        ManualOrder {
+            market: "m".to_string(),
+            outcome: "yes".to_string(),
+            side: Side::Sell,
+            size: Size::new(size).unwrap(),
+            limit: Price::new(0.4).unwrap(),
+            dry_run: false,
+            tags: Vec::new(),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn an_oversell_is_refused_and_recorded_as_a_naked_sell() {
+        let book = OrderBook {
+            token_id: TokenId::from("yes"),
+            bids: vec![PriceLevel { price: 0.5, size: 100.0 }],
+            asks: vec![PriceLevel { price: 0.52, size: 100.0 }],
+            timestamp: 0,
+        };
+        let engine = ExecutionEngine::new(FeeConfig::PriceCurve { maker_fee_bps: 0, taker_fee_bps: 0 }.build());
+        let mut wallet = Wallet::new(100.0);
+        wallet.open_position("yes".into(), Side::Buy, 10.0, 0.4, 0, EntryReason::Manual);
+        let (mut journal, mut risk) = (Journal::new(), RiskMonitor::new());
+
+        let err = execute_manual(&sell(15.0), &market(), &book, &engine, &mut wallet, &mut journal, &mut risk, 0).unwrap_err();
+        assert!(err.contains("only 10 held"), "{}", err);
+        assert_eq!(risk.violations, vec![RiskViolation::NakedSell { token_id: TokenId::from("yes"), requested: 15.0, held: 10.0 }]);
+        assert!(journal.entries.is_empty());
+        assert_eq!(wallet.held(&TokenId::from("yes")), 10.0);
+
+        execute_manual(&sell(10.0), &market(), &book, &engine, &mut wallet, &mut journal, &mut risk, 0).unwrap();
+        assert_eq!(risk.violations.len(), 1);
+        assert_eq!(wallet.held(&TokenId::from("yes")), 0.0);
+    }
+}
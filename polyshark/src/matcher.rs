@@ -0,0 +1,295 @@
+use crate::storage::StorageResult;
+use crate::types::{Market, MarketId};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Where `polyshark pairs` keeps proposed pairs and review decisions
+pub const MATCHED_PAIRS_PATH: &str = "matched_pairs.json";
+
+/// Thresholds for proposing duplicate markets
+#[derive(Debug, Clone, Deserialize)]
+pub struct MatcherConfig {
+    #[serde(default = "default_min_similarity")]
+    pub min_similarity: f64,    // Question token overlap needed to propose a pair
+    #[serde(default = "default_max_end_gap_secs")]
+    pub max_end_gap_secs: u64,  // End dates further apart than this are different events
+}
+
+impl Default for MatcherConfig {
+    fn default() -> Self {
+        Self { min_similarity: default_min_similarity(), max_end_gap_secs: default_max_end_gap_secs() }
+    }
+}
+
+fn default_min_similarity() -> f64 {
+    0.7
+}
+
+fn default_max_end_gap_secs() -> u64 {
+    2 * 24 * 3600
+}
+
+/// Words that carry no meaning for matching questions
+const STOPWORDS: &[&str] = &[
+    "a", "an", "the", "will", "be", "by", "in", "on", "of", "to", "at", "for", "or", "and", "is", "before", "after",
+];
+
+/// Lowercased, punctuation-free tokens without stopwords
+pub fn normalize(question: &str) -> HashSet<String> {
+    question
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '.' && c != '%' && c != '$')
+        .map(|w| w.trim_matches('.'))
+        .filter(|w| !w.is_empty() && !STOPWORDS.contains(w))
+        .map(String::from)
+        .collect()
+}
+
+/// Jaccard overlap of two token sets
+pub fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Two markets that look like the same question
+#[derive(Debug, Clone)]
+pub struct CandidatePair {
+    pub market_a: String,
+    pub market_b: String,
+    pub similarity: f64,
+    pub end_gap_secs: Option<u64>,  // None when either side has no end date
+}
+
+/// Review status of a proposed pair
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PairStatus {
+    #[default]
+    Pending,    // Proposed, awaiting the user
+    Confirmed,  // Safe for cross-market arbitrage
+    Rejected,   // Never propose again
+}
+
+/// Heuristic duplicate-market matcher
+#[derive(Debug, Clone)]
+pub struct MarketMatcher {
+    pub min_similarity: f64,    // e.g., 0.7
+    pub max_end_gap_secs: u64,  // End dates further apart than this can't be the same event
+    pairs: HashMap<(String, String), (CandidatePair, PairStatus)>,
+}
+
+/// One pair and its review status, as saved to disk and listed by `polyshark pairs`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PairRow {
+    pub market_a: String,
+    pub market_b: String,
+    pub similarity: f64,
+    pub end_gap_secs: Option<u64>,
+    pub status: PairStatus,
+}
+
+impl MarketMatcher {
+    pub fn new(min_similarity: f64, max_end_gap_secs: u64) -> Self {
+        Self { min_similarity, max_end_gap_secs, pairs: HashMap::new() }
+    }
+
+    pub fn from_config(config: &MatcherConfig) -> Self {
+        Self::new(config.min_similarity, config.max_end_gap_secs)
+    }
+
+    /// Restore saved pairs and decisions; thresholds come from the config
+    pub fn load(path: &Path, config: &MatcherConfig) -> StorageResult<Self> {
+        let rows: Vec<PairRow> = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let mut matcher = Self::from_config(config);
+        for row in rows {
+            let key = Self::key(&row.market_a, &row.market_b);
+            let pair = CandidatePair { market_a: key.0.clone(), market_b: key.1.clone(), similarity: row.similarity, end_gap_secs: row.end_gap_secs };
+            matcher.pairs.insert(key, (pair, row.status));
+        }
+        Ok(matcher)
+    }
+
+    pub fn save(&self, path: &Path) -> StorageResult<()> {
+        fs::write(path, serde_json::to_string_pretty(&self.rows())?)?;
+        Ok(())
+    }
+
+    /// Every known pair, ordered by market ids
+    pub fn rows(&self) -> Vec<PairRow> {
+        let mut rows: Vec<PairRow> = self.pairs.values()
+            .map(|(pair, status)| PairRow {
+                market_a: pair.market_a.clone(),
+                market_b: pair.market_b.clone(),
+                similarity: pair.similarity,
+                end_gap_secs: pair.end_gap_secs,
+                status: *status,
+            })
+            .collect();
+        rows.sort_by(|x, y| (&x.market_a, &x.market_b).cmp(&(&y.market_a, &y.market_b)));
+        rows
+    }
+
+    /// Propose new candidate pairs; already reviewed pairs are not proposed again
+    pub fn propose(&mut self, markets: &[Market]) -> Vec<CandidatePair> {
+        let tokens: Vec<HashSet<String>> = markets.iter().map(|m| normalize(&m.question)).collect();
+        let mut proposed = Vec::new();
+
+        for i in 0..markets.len() {
+            for j in (i + 1)..markets.len() {
+                let (a, b) = (&markets[i], &markets[j]);
+                if a.id == b.id || (a.event_id.is_some() && a.event_id == b.event_id) {
+                    continue; // Same market, or buckets of one event
+                }
+                let key = Self::key(&a.id, &b.id);
+                if self.pairs.contains_key(&key) {
+                    continue;
+                }
+
+                let sim = similarity(&tokens[i], &tokens[j]);
+                if sim < self.min_similarity {
+                    continue;
+                }
+                let end_gap_secs = a.end_timestamp().zip(b.end_timestamp()).map(|(x, y)| x.abs_diff(y));
+                if end_gap_secs.is_some_and(|gap| gap > self.max_end_gap_secs) {
+                    continue;
+                }
+
+                let pair = CandidatePair { market_a: key.0.clone(), market_b: key.1.clone(), similarity: sim, end_gap_secs };
+                self.pairs.insert(key, (pair.clone(), PairStatus::Pending));
+                proposed.push(pair);
+            }
+        }
+        proposed.sort_by(|x, y| y.similarity.total_cmp(&x.similarity));
+        proposed
+    }
+
+    pub fn confirm(&mut self, a: &str, b: &str) -> bool {
+        self.set_status(a, b, PairStatus::Confirmed)
+    }
+
+    pub fn reject(&mut self, a: &str, b: &str) -> bool {
+        self.set_status(a, b, PairStatus::Rejected)
+    }
+
+    pub fn status(&self, a: &str, b: &str) -> Option<PairStatus> {
+        self.pairs.get(&Self::key(a, b)).map(|(_, s)| *s)
+    }
+
+    /// Pairs the cross-market module may trade
+    pub fn confirmed(&self) -> impl Iterator<Item = &CandidatePair> {
+        self.pairs.values().filter(|(_, s)| *s == PairStatus::Confirmed).map(|(p, _)| p)
+    }
+
+    /// Confirmed pairs as market ids, for the detector
+    pub fn confirmed_ids(&self) -> Vec<(MarketId, MarketId)> {
+        self.confirmed().map(|p| (MarketId::from(p.market_a.as_str()), MarketId::from(p.market_b.as_str()))).collect()
+    }
+
+    /// Pairs waiting for review
+    pub fn pending(&self) -> impl Iterator<Item = &CandidatePair> {
+        self.pairs.values().filter(|(_, s)| *s == PairStatus::Pending).map(|(p, _)| p)
+    }
+
+    fn set_status(&mut self, a: &str, b: &str, status: PairStatus) -> bool {
+        match self.pairs.get_mut(&Self::key(a, b)) {
+            Some(entry) => {
+                entry.1 = status;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Order-independent pair key
+    fn key(a: &str, b: &str) -> (String, String) {
+        if a <= b { (a.to_string(), b.to_string()) } else { (b.to_string(), a.to_string()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arb::ArbitrageDetector;
+    use crate::types::TokenId;
+
+    fn market(id: &str, question: &str, yes: f64, no: f64) -> Market {
+        Market {
+            id: MarketId::from(id),
+            question: question.to_string(),
+            slug: id.to_string(),
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            outcome_prices: vec![yes, no],
+            clob_token_ids: vec![TokenId::from(format!("{}-yes", id)), TokenId::from(format!("{}-no", id))],
+            best_bid: None,
+            best_ask: None,
+            maker_base_fee: 0,
+            taker_base_fee: 0,
+            liquidity: 10_000.0,
+            volume_24hr: 0.0,
+            active: true,
+            accepting_orders: true,
+            condition_id: String::new(),
+            end_date: None,
+            resolution_source: None,
+            category: None,
+            tags: Vec::new(),
+            neg_risk: false,
+            event_id: None,
+            rewards_max_spread: None,
+            rewards_min_size: None,
+            restricted: false,
+            description: None,
+            uma_resolution_status: None,
+            closed: false,
+        }
+    }
+
+    fn markets() -> Vec<Market> {
+        vec![
+            market("a", "Will the Fed cut rates in March 2025?", 0.40, 0.62),
+            market("b", "Fed cut rates in March 2025?", 0.47, 0.55),
+            market("c", "Will it snow in Miami?", 0.02, 0.98),
+        ]
+    }
+
+    #[test]
+    fn review_decisions_survive_a_reload() {
+        let path = std::env::temp_dir().join(format!("polyshark-pairs-{}.json", std::process::id()));
+        let config = MatcherConfig::default();
+        let mut matcher = MarketMatcher::from_config(&config);
+        assert_eq!(matcher.propose(&markets()).len(), 1);
+        assert!(matcher.confirm("b", "a"));
+        assert!(!matcher.reject("a", "c"));
+        matcher.save(&path).unwrap();
+
+        let mut reloaded = MarketMatcher::load(&path, &config).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(reloaded.status("a", "b"), Some(PairStatus::Confirmed));
+        assert_eq!(reloaded.confirmed_ids(), vec![(MarketId::from("a"), MarketId::from("b"))]);
+        // Reviewed pairs are not proposed again
+        assert!(reloaded.propose(&markets()).is_empty());
+    }
+
+    #[test]
+    fn confirmed_pairs_are_priced_against_each_other() {
+        let listed: HashMap<MarketId, Market> = markets().into_iter().map(|m| (m.id.clone(), m)).collect();
+        let detector = ArbitrageDetector::new(0.02, 0.0).with_pairs(vec![(MarketId::from("a"), MarketId::from("b"))]);
+
+        // YES(a) 0.40 + NO(b) 0.55 = 0.95, cheaper than YES(b) + NO(a) = 1.09
+        let signals = detector.scan_pairs(&listed);
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].yes_market, MarketId::from("a"));
+        assert_eq!(signals[0].no_market, MarketId::from("b"));
+        assert!((signals[0].spread - 0.05).abs() < 1e-9);
+
+        // Edge below the threshold, or a side not watched, yields nothing
+        assert!(ArbitrageDetector::new(0.05, 0.0).with_pairs(detector.pairs.clone()).scan_pairs(&listed).is_empty());
+        let unwatched = ArbitrageDetector::new(0.02, 0.0).with_pairs(vec![(MarketId::from("a"), MarketId::from("z"))]);
+        assert!(unwatched.scan_pairs(&listed).is_empty());
+    }
+}
@@ -0,0 +1,249 @@
+use crate::execution::ExecutionEngine;
+use crate::types::{Market, MarketId, OrderBook, Side, Size, TokenId};
+use crate::money::Usdc;
+use crate::wallet::Wallet;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Complete-set recycling in `run` (paper only until on-chain merges exist)
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecycleConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_min_size")]
+    pub min_size: f64,  // Ignore dust sets smaller than this
+    #[serde(default)]
+    pub merge_fee: f64, // USDC a paper merge is charged (gas, relayer)
+}
+
+impl Default for RecycleConfig {
+    fn default() -> Self {
+        Self { enabled: default_enabled(), min_size: default_min_size(), merge_fee: 0.0 }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_min_size() -> f64 {
+    1.0
+}
+
+/// Something that can merge YES+NO back into collateral (the CTF `mergePositions` call)
+pub trait SetMerger {
+    fn merge(&self, market: &Market, size: f64) -> Result<(), String>;
+
+    /// USDC a merge of `size` sets costs on top of the shares (gas, relayer)
+    fn fee(&self, market: &Market, size: f64) -> f64;
+}
+
+/// Paper trading: merges always succeed instantly at a flat fee
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PaperMerger {
+    pub fee: f64,
+}
+
+impl SetMerger for PaperMerger {
+    fn merge(&self, _market: &Market, _size: f64) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn fee(&self, _market: &Market, _size: f64) -> f64 {
+        self.fee
+    }
+}
+
+/// Equal YES and NO holdings in one binary market: worth exactly 1 USDC per set
+#[derive(Debug, Clone)]
+pub struct CompleteSet {
+    pub market_id: MarketId,
+    pub yes: TokenId,
+    pub no: TokenId,
+    pub size: f64,
+    pub yes_entry: f64,   // Entry price of the YES leg
+    pub no_entry: f64,
+    pub cost_basis: f64,  // What the sets cost at entry prices
+}
+
+impl CompleteSet {
+    /// Share of a set's value attributed to the YES leg: its share of the entry cost
+    fn yes_weight(&self) -> f64 {
+        let total = self.yes_entry + self.no_entry;
+        if total > 0.0 { self.yes_entry / total } else { 0.5 }
+    }
+}
+
+/// How a set was turned back into cash
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecycleMethod {
+    Merged,
+    SoldBoth,  // Bids were worth more than the 1 USDC a merge returns
+}
+
+impl RecycleMethod {
+    /// Journal tag on the legs it closes
+    pub fn tag(self) -> &'static str {
+        match self {
+            RecycleMethod::Merged => "set-merged",
+            RecycleMethod::SoldBoth => "set-sold",
+        }
+    }
+}
+
+/// What one side of a recycled set booked against its tax lots
+#[derive(Debug, Clone)]
+pub struct RecycledLeg {
+    pub token_id: TokenId,
+    pub price: f64,         // Gross proceeds per share credited to this leg
+    pub fee: f64,           // This leg's share of the sell or merge fees
+    pub realized_pnl: f64,
+}
+
+/// One recycled set
+#[derive(Debug, Clone)]
+pub struct Recycled {
+    pub set: CompleteSet,
+    pub method: RecycleMethod,
+    pub proceeds: f64,  // USDC returned to the wallet, net of fees
+    pub legs: Vec<RecycledLeg>,
+}
+
+impl Recycled {
+    pub fn realized_pnl(&self) -> f64 {
+        self.proceeds - self.set.cost_basis
+    }
+}
+
+/// Result of one scan
+#[derive(Debug, Clone, Default)]
+pub struct RecycleReport {
+    pub recycled: Vec<Recycled>,
+    pub failures: Vec<(MarketId, String)>,
+}
+
+impl RecycleReport {
+    /// Capital freed by this scan
+    pub fn capital_recycled(&self) -> f64 {
+        self.recycled.iter().map(|r| r.proceeds).sum()
+    }
+
+    pub fn realized_pnl(&self) -> f64 {
+        self.recycled.iter().map(Recycled::realized_pnl).sum()
+    }
+}
+
+/// Finds complete sets in the wallet and recycles them into USDC
+pub struct SetRecycler {
+    pub min_size: f64,  // Ignore dust sets smaller than this
+    pub merger: Arc<dyn SetMerger + Send + Sync>,
+    pub total_recycled: f64,      // Since start
+}
+
+impl SetRecycler {
+    pub fn new(min_size: f64, merger: Arc<dyn SetMerger + Send + Sync>) -> Self {
+        Self { min_size, merger, total_recycled: 0.0 }
+    }
+
+    /// Complete sets held across binary markets
+    pub fn find_sets(&self, wallet: &Wallet, markets: &[Market]) -> Vec<CompleteSet> {
+        markets.iter()
+            .filter_map(|m| {
+                let [yes, no] = m.clob_token_ids.as_slice() else {
+                    return None;
+                };
+                let (y, n) = (long(wallet, yes)?, long(wallet, no)?);
+                let size = y.0.min(n.0);
+                (size >= self.min_size && size > 0.0).then(|| CompleteSet {
+                    market_id: m.id.clone(),
+                    yes: yes.clone(),
+                    no: no.clone(),
+                    size,
+                    yes_entry: y.1,
+                    no_entry: n.1,
+                    cost_basis: size * (y.1 + n.1),
+                })
+            })
+            .collect()
+    }
+
+    /// Merge (or sell both sides of) every complete set, shrinking the positions.
+    /// `engine` prices the sell-both alternative with each market's fees.
+    pub fn recycle(
+        &mut self,
+        wallet: &mut Wallet,
+        markets: &[Market],
+        books: &HashMap<TokenId, OrderBook>,
+        now: u64,
+        engine: impl Fn(&Market) -> ExecutionEngine,
+    ) -> RecycleReport {
+        let mut report = RecycleReport::default();
+        for set in self.find_sets(wallet, markets) {
+            let Some(market) = markets.iter().find(|m| m.id == set.market_id) else {
+                continue;
+            };
+            let reference = format!("complete set {}", set.market_id);
+            let merge_fee = self.merger.fee(market, set.size);
+
+            if let Some(sold) = sell_both(&set, books, &engine(market)).filter(|legs| net(&set, legs) > set.size - merge_fee) {
+                let fees: f64 = sold.iter().map(|(_, fee)| fee).sum();
+                wallet.credit(Usdc(net(&set, &sold)), "complete set sold", Some(&reference));
+                wallet.record_fee(Usdc(fees), Some(&reference));
+                self.finish(wallet, set, RecycleMethod::SoldBoth, sold, now, &mut report);
+                continue;
+            }
+            match self.merger.merge(market, set.size) {
+                Ok(()) => {
+                    // A merge pays 1 USDC per set; each leg is credited in proportion to what it cost
+                    let yes = set.yes_weight();
+                    let legs = [(yes, merge_fee * yes), (1.0 - yes, merge_fee * (1.0 - yes))];
+                    wallet.credit(Usdc(set.size - merge_fee), "complete set merged", Some(&reference));
+                    wallet.record_fee(Usdc(merge_fee), Some(&reference));
+                    self.finish(wallet, set, RecycleMethod::Merged, legs, now, &mut report);
+                }
+                Err(err) => report.failures.push((set.market_id, err)),
+            }
+        }
+        report
+    }
+
+    /// Close both legs' tax lots at their `(price, fee)` and record the set
+    fn finish(&mut self, wallet: &mut Wallet, set: CompleteSet, method: RecycleMethod, sold: [(f64, f64); 2], now: u64, report: &mut RecycleReport) {
+        let proceeds = net(&set, &sold);
+        let mut legs = Vec::with_capacity(2);
+        for (token_id, (price, fee)) in [&set.yes, &set.no].into_iter().zip(sold) {
+            let closed = wallet.sell_from_position(token_id, set.size, price, fee, now);
+            legs.push(RecycledLeg { token_id: token_id.clone(), price, fee, realized_pnl: closed.iter().map(|l| l.gain()).sum() });
+        }
+        self.total_recycled += proceeds;
+        report.recycled.push(Recycled { set, method, proceeds, legs });
+    }
+}
+
+/// Average price and fee of selling `set.size` into each leg's bids (YES first), if both
+/// books can absorb it
+fn sell_both(set: &CompleteSet, books: &HashMap<TokenId, OrderBook>, engine: &ExecutionEngine) -> Option<[(f64, f64); 2]> {
+    let size = Size::new(set.size)?;
+    let mut legs = [(0.0, 0.0); 2];
+    for (leg, token) in legs.iter_mut().zip([&set.yes, &set.no]) {
+        let sim = engine.simulate(books.get(token)?, size, Side::Sell, None)?;
+        if sim.filed_size + 1e-9 < set.size {
+            return None;
+        }
+        *leg = (sim.execution_price, sim.fee_paid);
+    }
+    Some(legs)
+}
+
+/// Cash both legs return after fees
+fn net(set: &CompleteSet, legs: &[(f64, f64); 2]) -> f64 {
+    legs.iter().map(|(price, fee)| price * set.size - fee).sum()
+}
+
+/// Long size and entry price of a token
+fn long(wallet: &Wallet, token_id: &TokenId) -> Option<(f64, f64)> {
+    wallet.positions.get(token_id)
+        .filter(|p| p.side == Side::Buy && p.size > 0.0)
+        .map(|p| (p.size, p.entry_price))
+}
@@ -0,0 +1,122 @@
+use crate::units::{Decimals, Rounding};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+/// Collateral markets settle in (Polymarket only uses USDC today)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Collateral {
+    #[default]
+    Usdc,
+}
+
+impl Collateral {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Collateral::Usdc => "USDC",
+        }
+    }
+
+    /// On-chain decimals (amounts travel as integers of the smallest unit)
+    pub fn decimals(self) -> u32 {
+        match self {
+            Collateral::Usdc => 6,
+        }
+    }
+}
+
+/// An amount of USDC collateral (not dollars; see `UsdConversion`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Usdc(pub f64);
+
+impl Usdc {
+    pub const ZERO: Usdc = Usdc(0.0);
+
+    pub fn value(self) -> f64 {
+        self.0
+    }
+
+    /// Integer base units (1 USDC = 1_000_000)
+    pub fn to_units(self) -> u64 {
+        Decimals::of(Collateral::Usdc).to_base(self.0.max(0.0), Rounding::Nearest).unwrap_or(0)
+    }
+
+    pub fn from_units(units: u64) -> Self {
+        Usdc(Decimals::of(Collateral::Usdc).amount(units))
+    }
+}
+
+impl fmt::Display for Usdc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2} {}", self.0, Collateral::Usdc.symbol())
+    }
+}
+
+impl Add for Usdc {
+    type Output = Usdc;
+    fn add(self, rhs: Usdc) -> Usdc {
+        Usdc(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Usdc {
+    type Output = Usdc;
+    fn sub(self, rhs: Usdc) -> Usdc {
+        Usdc(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Usdc {
+    type Output = Usdc;
+    fn neg(self) -> Usdc {
+        Usdc(-self.0)
+    }
+}
+
+impl AddAssign for Usdc {
+    fn add_assign(&mut self, rhs: Usdc) {
+        self.0 += rhs.0;
+    }
+}
+
+impl SubAssign for Usdc {
+    fn sub_assign(&mut self, rhs: Usdc) {
+        self.0 -= rhs.0;
+    }
+}
+
+/// Optional USD display layer for reports and alerts
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct UsdConversion {
+    #[serde(default = "default_usd_per_usdc")]
+    pub usd_per_usdc: f64,  // Peg rate; update from a price feed if it matters
+    #[serde(default)]
+    pub show_usd: bool,     // Print USD next to USDC amounts
+}
+
+impl Default for UsdConversion {
+    fn default() -> Self {
+        Self { usd_per_usdc: default_usd_per_usdc(), show_usd: false }
+    }
+}
+
+fn default_usd_per_usdc() -> f64 {
+    1.0
+}
+
+impl UsdConversion {
+    pub fn to_usd(self, amount: Usdc) -> f64 {
+        amount.0 * self.usd_per_usdc
+    }
+
+    /// "12.34 USDC" or "12.34 USDC ($12.33)"
+    pub fn format(self, amount: Usdc) -> String {
+        if self.show_usd {
+            format!("{} (${:.2})", amount, self.to_usd(amount))
+        } else {
+            amount.to_string()
+        }
+    }
+}
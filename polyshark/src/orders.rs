@@ -0,0 +1,791 @@
+use crate::bus::Fill;
+use crate::fees::SharedFeeSchedule;
+use crate::types::{OrderBook, Price, Side, Size, TokenId};
+use crate::money::Usdc;
+use crate::wallet::Wallet;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Exchange rejects GTD orders expiring sooner than this
+pub const MIN_GTD_LIFETIME_MS: u64 = 60_000;
+
+/// How long an order may live
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TimeInForce {
+    #[default]
+    Gtc,                        // Rests until cancelled
+    Fok,                        // Fill entirely now or not at all (arb taker legs)
+    Ioc,                        // Fill what's available now, cancel the rest (FAK on the CLOB)
+    Gtd { expires_at: u64 },    // Rests until `expires_at` (ms) unless cancelled
+}
+
+impl TimeInForce {
+    /// Order type string expected by the CLOB
+    pub fn wire_name(self) -> &'static str {
+        match self {
+            TimeInForce::Gtc => "GTC",
+            TimeInForce::Fok => "FOK",
+            TimeInForce::Ioc => "FAK",
+            TimeInForce::Gtd { .. } => "GTD",
+        }
+    }
+
+    /// Orders that never rest on the book
+    pub fn is_immediate(self) -> bool {
+        matches!(self, TimeInForce::Fok | TimeInForce::Ioc)
+    }
+
+    /// Expiration in unix seconds as the CLOB wants it (0 = none)
+    pub fn expiration_secs(self) -> u64 {
+        match self {
+            TimeInForce::Gtd { expires_at } => expires_at / 1000,
+            _ => 0,
+        }
+    }
+}
+
+/// Order to send to the exchange
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderRequest {
+    pub token_id: TokenId,
+    pub side: Side,
+    pub price: Price,
+    pub size: Size,
+    pub time_in_force: TimeInForce,
+}
+
+impl OrderRequest {
+    /// Local checks before anything goes to the exchange
+    pub fn validate(&self, now: u64) -> Result<(), OrderError> {
+        if let TimeInForce::Gtd { expires_at } = self.time_in_force
+            && expires_at < now + MIN_GTD_LIFETIME_MS
+        {
+            return Err(OrderError::Rejected(format!(
+                "GTD expiration must be at least {}s in the future",
+                MIN_GTD_LIFETIME_MS / 1000
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Order resting on the exchange
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenOrder {
+    pub order_id: String,
+    pub token_id: TokenId,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+    pub filled: f64,
+    pub created_at: u64,  // ms
+    pub expires_at: Option<u64>,  // ms, GTD only
+    pub instance_id: Option<String>,  // Bot instance that placed it
+}
+
+impl OpenOrder {
+    pub fn remaining(&self) -> f64 {
+        (self.size - self.filled).max(0.0)
+    }
+
+    /// Cash a resting buy keeps locked in the wallet (sells lock shares, not cash)
+    pub fn reserved(&self) -> Usdc {
+        match self.side {
+            Side::Buy => Usdc(self.price * self.remaining()),
+            Side::Sell => Usdc::ZERO,
+        }
+    }
+}
+
+/// Why an exchange call failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderError {
+    Rejected(String),  // Exchange refused it (tick size, balance, auth...)
+    Network(String),   // Never got an answer
+    NotFound,          // Unknown order id
+    Killed,            // FOK order could not fill in full; nothing executed
+    SelfTrade(String), // Would have matched one of our own resting orders; nothing sent
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderError::Rejected(reason) => write!(f, "rejected: {}", reason),
+            OrderError::Network(reason) => write!(f, "network error: {}", reason),
+            OrderError::NotFound => write!(f, "order not found"),
+            OrderError::Killed => write!(f, "fill-or-kill order killed"),
+            OrderError::SelfTrade(reason) => write!(f, "self-trade prevented: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for OrderError {}
+
+/// Exchange view of one order
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderStatusReport {
+    pub order_id: String,
+    pub filled: f64,   // Cumulative matched size
+    pub live: bool,    // Still resting on the book
+}
+
+/// Exchange side of order management (live CLOB or paper simulator)
+pub trait OrderGateway {
+    /// Submit an order, returning the exchange order id
+    fn place(&mut self, request: &OrderRequest) -> Result<String, OrderError>;
+
+    fn cancel(&mut self, order_id: &str) -> Result<(), OrderError>;
+
+    /// Cancel every open order for this account
+    fn cancel_all(&mut self) -> Result<(), OrderError>;
+
+    /// Current state of one order, for when fill events can't be streamed
+    fn order_status(&mut self, order_id: &str) -> Result<OrderStatusReport, OrderError>;
+
+    /// Cancel several orders in one round trip; one result per id, in order
+    fn cancel_batch(&mut self, order_ids: &[String]) -> Vec<Result<(), OrderError>> {
+        order_ids.iter().map(|id| self.cancel(id)).collect()
+    }
+
+    /// Place several orders in one round trip; one result per request, in order
+    fn place_batch(&mut self, requests: &[OrderRequest]) -> Vec<Result<String, OrderError>> {
+        requests.iter().map(|r| self.place(r)).collect()
+    }
+
+    /// True if FOK orders are honoured (all-or-nothing fills)
+    fn supports_fok(&self) -> bool {
+        true
+    }
+
+    /// True if the exchange itself cancels our orders when our session drops
+    fn supports_cancel_on_disconnect(&self) -> bool {
+        false
+    }
+
+    /// Latest book and fee schedule of a token about to be traded. A simulated venue fills
+    /// against them; a real exchange has its own.
+    fn observe(&mut self, _book: &OrderBook, _fees: &SharedFeeSchedule) {}
+
+    /// Fills the venue reported since the last call, oldest first
+    fn take_fills(&mut self) -> Vec<Fill> {
+        Vec::new()
+    }
+}
+
+/// Local book of our open orders
+#[derive(Debug, Clone, Default)]
+pub struct OrderManager {
+    pub open: HashMap<String, OpenOrder>,  // order_id -> order
+    pub instance_id: Option<String>,       // Stamped on every tracked order
+    pub min_resting_ms: u64,               // Orders can't be cancelled or replaced younger than this
+    locks: HashMap<String, Usdc>,          // order_id -> cash locked in the wallet for it
+}
+
+impl OrderManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_instance(instance_id: &str) -> Self {
+        Self { instance_id: Some(instance_id.to_string()), ..Self::default() }
+    }
+
+    pub fn with_min_resting(mut self, min_resting_ms: u64) -> Self {
+        self.min_resting_ms = min_resting_ms;
+        self
+    }
+
+    /// Place through the gateway and track the result (FOK/IOC orders never rest, so aren't tracked).
+    /// An order that would match our own resting orders is never sent as is: taker orders
+    /// cancel those orders first, resting orders are refused (see `uncrossed_quote`).
+    pub fn submit(&mut self, gateway: &mut dyn OrderGateway, request: &OrderRequest, now: u64) -> Result<String, OrderError> {
+        request.validate(now)?;
+        self.prevent_self_trade(gateway, request, now)?;
+        let order_id = gateway.place(request)?;
+        self.track(&order_id, request, now);
+        Ok(order_id)
+    }
+
+    /// Our resting orders on `token_id` that an order on `side` at `price` would match
+    pub fn crossing(&self, token_id: &TokenId, side: Side, price: f64) -> Vec<&OpenOrder> {
+        self.orders_for(token_id)
+            .filter(|o| match side {
+                Side::Buy => o.side == Side::Sell && o.price <= price + 1e-9,
+                Side::Sell => o.side == Side::Buy && o.price >= price - 1e-9,
+            })
+            .collect()
+    }
+
+    /// Closest price to `price` a resting quote can use without matching our own opposite
+    /// orders: one tick behind the nearest of them. `None` if that leaves no valid price.
+    pub fn uncrossed_quote(&self, token_id: &TokenId, side: Side, price: f64, tick: f64) -> Option<f64> {
+        let price = match side {
+            Side::Buy => self.crossing(token_id, side, price).iter().map(|o| o.price - tick).fold(price, f64::min),
+            Side::Sell => self.crossing(token_id, side, price).iter().map(|o| o.price + tick).fold(price, f64::max),
+        };
+        (price > 1e-9 && price < 1.0 - 1e-9).then_some(price)
+    }
+
+    fn prevent_self_trade(&mut self, gateway: &mut dyn OrderGateway, request: &OrderRequest, now: u64) -> Result<(), OrderError> {
+        let crossing: Vec<String> = self.crossing(&request.token_id, request.side, request.price.value())
+            .iter()
+            .map(|o| o.order_id.clone())
+            .collect();
+        if crossing.is_empty() {
+            return Ok(());
+        }
+        if !request.time_in_force.is_immediate() {
+            return Err(OrderError::SelfTrade(format!("would match our resting order {}", crossing[0])));
+        }
+        match self.cancel_batch(gateway, &crossing, now).into_iter().next() {
+            Some((id, err)) => Err(OrderError::SelfTrade(format!("could not cancel our resting order {}: {}", id, err))),
+            None => Ok(()),
+        }
+    }
+
+    /// Refuse to cancel an order that hasn't rested long enough
+    fn check_resting(&self, order_id: &str, now: u64) -> Result<(), OrderError> {
+        match self.open.get(order_id) {
+            Some(order) if now.saturating_sub(order.created_at) < self.min_resting_ms => Err(OrderError::Rejected(format!(
+                "order {} has rested {} ms, minimum is {} ms",
+                order_id,
+                now.saturating_sub(order.created_at),
+                self.min_resting_ms
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Cancel one order; an order the exchange no longer knows is dropped locally too
+    pub fn cancel(&mut self, gateway: &mut dyn OrderGateway, order_id: &str, now: u64) -> Result<(), OrderError> {
+        self.check_resting(order_id, now)?;
+        match gateway.cancel(order_id) {
+            Ok(()) | Err(OrderError::NotFound) => {
+                self.open.remove(order_id);
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Cancel everything we have resting. Ignores the minimum resting time: this is the
+    /// emergency path (flatten, disconnect, failover).
+    pub fn cancel_all(&mut self, gateway: &mut dyn OrderGateway) -> Result<(), OrderError> {
+        gateway.cancel_all()?;
+        self.open.clear();
+        Ok(())
+    }
+
+    /// Cancel a batch, dropping every order the exchange confirmed (or no longer knows).
+    /// Returns the failures; other orders in the batch are unaffected by them. Orders that
+    /// haven't rested long enough fail without being sent.
+    pub fn cancel_batch(&mut self, gateway: &mut dyn OrderGateway, order_ids: &[String], now: u64) -> Vec<(String, OrderError)> {
+        let (failures, vanished) = self.send_cancels(gateway, order_ids, now);
+        for id in &vanished {
+            self.open.remove(id);
+        }
+        failures
+    }
+
+    /// Cancel a batch, dropping confirmed cancels. Returns the failures and the ids the
+    /// exchange no longer knows, which are left tracked for the caller to settle.
+    fn send_cancels(&mut self, gateway: &mut dyn OrderGateway, order_ids: &[String], now: u64) -> (Vec<(String, OrderError)>, Vec<String>) {
+        let mut failures = Vec::new();
+        let mut vanished = Vec::new();
+        let mut ready = Vec::new();
+        for id in order_ids {
+            match self.check_resting(id, now) {
+                Ok(()) => ready.push(id.clone()),
+                Err(e) => failures.push((id.clone(), e)),
+            }
+        }
+        let results = gateway.cancel_batch(&ready);
+        for (id, result) in ready.iter().zip(results) {
+            match result {
+                Ok(()) => {
+                    self.open.remove(id);
+                }
+                Err(OrderError::NotFound) => vanished.push(id.clone()),
+                Err(e) => failures.push((id.clone(), e)),
+            }
+        }
+        (failures, vanished)
+    }
+
+    /// Settle an order the exchange no longer lists: it filled or was cancelled behind our
+    /// back. Applies whatever filled since we last knew and stops tracking it; returns that size.
+    fn settle_vanished(&mut self, gateway: &mut dyn OrderGateway, order_id: &str) -> f64 {
+        let Some(order) = self.open.remove(order_id) else {
+            return 0.0;
+        };
+        match gateway.order_status(order_id) {
+            Ok(report) => (report.filled - order.filled).max(0.0),
+            Err(_) => 0.0,
+        }
+    }
+
+    /// Cancel/replace many orders: cancels go out as one batch, and an order is only
+    /// replaced once the exchange has confirmed its cancel and reported how much of it
+    /// matched. The replacement covers what was still unfilled, so a failed cancel never
+    /// leaves two orders resting and a leg that filled since we last heard is never bought
+    /// (or sold) twice. Orders that filled in full, or that the exchange no longer knows,
+    /// are settled and not replaced.
+    pub fn replace_batch(
+        &mut self,
+        gateway: &mut dyn OrderGateway,
+        replacements: &[(String, OrderRequest)],
+        now: u64,
+    ) -> Vec<ReplaceOutcome> {
+        // Invalid replacements leave their old order alone
+        let mut failed: HashMap<String, OrderError> = replacements.iter()
+            .filter_map(|(id, r)| r.validate(now).err().map(|e| (id.clone(), e)))
+            .collect();
+        let ids: Vec<String> = replacements.iter()
+            .map(|(id, _)| id.clone())
+            .filter(|id| !failed.contains_key(id))
+            .collect();
+        // What we knew of each order before a confirmed cancel stops tracking it
+        let known: HashMap<String, (f64, f64)> = ids.iter()
+            .filter_map(|id| self.open.get(id).map(|o| (id.clone(), (o.size, o.filled))))
+            .collect();
+        let (failures, vanished) = self.send_cancels(gateway, &ids, now);
+        failed.extend(failures);
+        let mut gone: HashMap<String, f64> = vanished.iter()
+            .map(|id| (id.clone(), self.settle_vanished(gateway, id)))
+            .collect();
+
+        // Cancelled: the final matched size decides what is left to place again
+        let mut unsettled: HashMap<String, OrderError> = HashMap::new();
+        let mut to_place: Vec<(&String, OrderRequest, f64)> = Vec::new();
+        for (old_id, request) in replacements {
+            if failed.contains_key(old_id) || gone.contains_key(old_id) {
+                continue;
+            }
+            let (size, known_filled) = known.get(old_id).copied().unwrap_or((request.size.value(), 0.0));
+            match gateway.order_status(old_id) {
+                Ok(report) => {
+                    let filled = (report.filled - known_filled).max(0.0);
+                    match Size::new(request.size.value().min(size - report.filled)) {
+                        Some(left) if left.value() > 1e-9 => to_place.push((old_id, OrderRequest { size: left, ..request.clone() }, filled)),
+                        _ => {
+                            gone.insert(old_id.clone(), filled);
+                        }
+                    }
+                }
+                Err(error) => {
+                    unsettled.insert(old_id.clone(), error);
+                }
+            }
+        }
+        let requests: Vec<OrderRequest> = to_place.iter().map(|(_, r, _)| r.clone()).collect();
+        let mut results = gateway.place_batch(&requests).into_iter();
+        let mut placed: HashMap<String, (OrderRequest, f64, Result<String, OrderError>)> = HashMap::new();
+        for (old_id, request, filled) in to_place {
+            let result = results.next().unwrap_or_else(|| Err(OrderError::Network("no result for order".to_string())));
+            placed.insert(old_id.clone(), (request, filled, result));
+        }
+
+        replacements.iter()
+            .map(|(old_id, _)| {
+                let old_id = old_id.clone();
+                if let Some(error) = failed.remove(&old_id) {
+                    return ReplaceOutcome::CancelFailed { old_id, error };
+                }
+                if let Some(error) = unsettled.remove(&old_id) {
+                    return ReplaceOutcome::Unsettled { old_id, error };
+                }
+                if let Some(filled) = gone.remove(&old_id) {
+                    return ReplaceOutcome::Gone { old_id, filled };
+                }
+                match placed.remove(&old_id) {
+                    Some((request, filled, Ok(new_id))) => {
+                        self.track(&new_id, &request, now);
+                        ReplaceOutcome::Replaced { old_id, new_id, filled }
+                    }
+                    Some((_, filled, Err(error))) => ReplaceOutcome::PlaceFailed { old_id, error, filled },
+                    None => ReplaceOutcome::PlaceFailed {
+                        old_id,
+                        error: OrderError::Network("no result for order".to_string()),
+                        filled: 0.0,
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Drop GTD orders past their expiration (the exchange has already removed them),
+    /// releasing the cash they had locked in the wallet
+    pub fn expire(&mut self, now: u64, wallet: &mut Wallet) -> Vec<OpenOrder> {
+        let expired: Vec<String> = self.open.values()
+            .filter(|o| o.expires_at.is_some_and(|t| t <= now))
+            .map(|o| o.order_id.clone())
+            .collect();
+        let expired: Vec<OpenOrder> = expired.iter().filter_map(|id| self.open.remove(id)).collect();
+        for order in &expired {
+            if let Some(amount) = self.locks.remove(&order.order_id) {
+                wallet.unlock(amount, "order expired", Some(&order.order_id));
+            }
+        }
+        expired
+    }
+
+    /// Bring the wallet's locked cash in line with our resting buys: lock for new orders,
+    /// unlock what fills, cancels and vanished orders no longer need. Call after anything
+    /// that places, fills or drops orders.
+    pub fn sync_locks(&mut self, wallet: &mut Wallet) {
+        let mut released: Vec<(String, Usdc)> = self.locks.iter()
+            .filter_map(|(id, &locked)| {
+                let needed = self.open.get(id).map(OpenOrder::reserved).unwrap_or(Usdc::ZERO);
+                (needed.value() < locked.value() - 1e-9).then(|| (id.clone(), locked - needed))
+            })
+            .collect();
+        released.sort_by(|a, b| a.0.cmp(&b.0));
+        for (id, amount) in released {
+            let reason = if self.open.contains_key(&id) { "order filled" } else { "order closed" };
+            wallet.unlock(amount, reason, Some(&id));
+            match self.locks.get_mut(&id) {
+                Some(locked) if (*locked - amount).value() > 1e-9 => *locked -= amount,
+                _ => {
+                    self.locks.remove(&id);
+                }
+            }
+        }
+
+        let mut new: Vec<(String, Usdc)> = self.open.iter()
+            .filter(|(id, _)| !self.locks.contains_key(*id))
+            .map(|(id, o)| (id.clone(), o.reserved()))
+            .filter(|(_, amount)| amount.value() > 0.0)
+            .collect();
+        new.sort_by(|a, b| a.0.cmp(&b.0));
+        for (id, amount) in new {
+            // The order is already resting: remember it even when the wallet can't cover it
+            let locked = if wallet.lock(amount, Some(&id)) {
+                amount
+            } else {
+                eprintln!("⚠️  order {} rests {} beyond the available cash", id, amount);
+                Usdc::ZERO
+            };
+            self.locks.insert(id, locked);
+        }
+    }
+
+    fn track(&mut self, order_id: &str, request: &OrderRequest, now: u64) {
+        if request.time_in_force.is_immediate() {
+            return;
+        }
+        let expires_at = match request.time_in_force {
+            TimeInForce::Gtd { expires_at } => Some(expires_at),
+            _ => None,
+        };
+        self.open.insert(order_id.to_string(), OpenOrder {
+            order_id: order_id.to_string(),
+            token_id: request.token_id.clone(),
+            side: request.side,
+            price: request.price.value(),
+            size: request.size.value(),
+            filled: 0.0,
+            created_at: now,
+            expires_at,
+            instance_id: self.instance_id.clone(),
+        });
+    }
+
+    /// Apply a fill; fully filled orders stop being tracked
+    pub fn record_fill(&mut self, order_id: &str, size: f64) -> Option<OpenOrder> {
+        let order = self.open.get_mut(order_id)?;
+        order.filled += size;
+        let snapshot = order.clone();
+        if snapshot.remaining() <= 1e-9 {
+            self.open.remove(order_id);
+        }
+        Some(snapshot)
+    }
+
+    pub fn orders_for(&self, token_id: &TokenId) -> impl Iterator<Item = &OpenOrder> {
+        self.open.values().filter(move |o| o.token_id == *token_id)
+    }
+}
+
+/// Fill discovered by polling rather than the user channel
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolledFill {
+    pub order_id: String,
+    pub token_id: TokenId,
+    pub size: f64,  // Newly matched since we last knew
+}
+
+/// Fallback fill detection for when the user WebSocket channel is down.
+/// Fill sizes are derived from cumulative `filled`, so fills already applied
+/// from WebSocket events are never counted twice.
+#[derive(Debug, Clone)]
+pub struct StatusPoller {
+    pub min_interval_ms: u64,  // While fills are arriving
+    pub max_interval_ms: u64,  // While quiet, or backing off after errors
+    pub interval_ms: u64,
+    pub consecutive_errors: u32,
+    pub ws_connected: bool,    // Polling is skipped while the user channel is healthy
+    last_poll: u64,
+}
+
+impl StatusPoller {
+    pub fn new(min_interval_ms: u64, max_interval_ms: u64) -> Self {
+        Self {
+            min_interval_ms,
+            max_interval_ms,
+            interval_ms: min_interval_ms,
+            consecutive_errors: 0,
+            ws_connected: false,
+            last_poll: 0,
+        }
+    }
+
+    pub fn set_ws_connected(&mut self, connected: bool) {
+        if self.ws_connected && !connected {
+            // Channel just dropped; fills may have been missed, so look soon
+            self.interval_ms = self.min_interval_ms;
+        }
+        self.ws_connected = connected;
+    }
+
+    pub fn is_due(&self, now: u64, orders: &OrderManager) -> bool {
+        !self.ws_connected && !orders.open.is_empty() && now.saturating_sub(self.last_poll) >= self.interval_ms
+    }
+
+    /// Query every open order and apply any fills we haven't seen yet
+    pub fn poll(&mut self, now: u64, gateway: &mut dyn OrderGateway, orders: &mut OrderManager) -> Vec<PolledFill> {
+        if !self.is_due(now, orders) {
+            return Vec::new();
+        }
+        self.last_poll = now;
+
+        let mut fills = Vec::new();
+        let mut errored = false;
+        let ids: Vec<String> = orders.open.keys().cloned().collect();
+        for id in ids {
+            match gateway.order_status(&id) {
+                Ok(report) => {
+                    let Some(order) = orders.open.get(&id) else { continue };
+                    let (token_id, known) = (order.token_id.clone(), order.filled);
+                    let new_size = report.filled - known;
+                    if new_size > 1e-9 {
+                        orders.record_fill(&id, new_size);
+                        fills.push(PolledFill { order_id: id.clone(), token_id, size: new_size });
+                    }
+                    if !report.live {
+                        // Cancelled or expired on the exchange side
+                        orders.open.remove(&id);
+                    }
+                }
+                Err(OrderError::NotFound) => {
+                    orders.open.remove(&id);
+                }
+                Err(_) => errored = true,
+            }
+        }
+
+        if errored {
+            self.consecutive_errors += 1;
+            self.interval_ms = (self.interval_ms * 2).min(self.max_interval_ms);
+        } else {
+            self.consecutive_errors = 0;
+            self.interval_ms = if fills.is_empty() {
+                // Quiet: drift towards the slow interval
+                (self.interval_ms + self.interval_ms / 2).min(self.max_interval_ms)
+            } else {
+                self.min_interval_ms
+            };
+        }
+        fills
+    }
+}
+
+/// Result of one cancel/replace. `filled` is what the old order matched since we last
+/// knew, for the caller to book.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplaceOutcome {
+    Replaced { old_id: String, new_id: String, filled: f64 },      // New order covers what the old one left unfilled
+    CancelFailed { old_id: String, error: OrderError },             // Old order still resting, nothing placed
+    Unsettled { old_id: String, error: OrderError },                // Old order cancelled but its fills couldn't be read, nothing placed
+    PlaceFailed { old_id: String, error: OrderError, filled: f64 }, // Old order gone, new one not resting
+    Gone { old_id: String, filled: f64 },                           // Old order filled in full or was unknown to the exchange, nothing placed
+}
+
+/// Cancel-on-disconnect settings
+#[derive(Debug, Clone)]
+pub struct CancelOnDisconnectConfig {
+    pub enabled: bool,
+    pub heartbeat_timeout_ms: u64,  // Silence longer than this counts as a disconnect
+}
+
+/// Connectivity as seen by the supervisor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LinkState {
+    Connected,
+    Disconnected,  // Orders are unmanaged until we cancel them
+}
+
+/// Local supervisor that cancels open orders once connectivity is lost.
+/// If the exchange cancels on disconnect itself, we only clear local state.
+#[derive(Debug, Clone)]
+pub struct DisconnectSupervisor {
+    pub config: CancelOnDisconnectConfig,
+    pub state: LinkState,
+    last_heartbeat: u64,
+    cancel_pending: bool,  // Disconnected with open orders that still need cancelling
+}
+
+impl DisconnectSupervisor {
+    pub fn new(config: CancelOnDisconnectConfig, now: u64) -> Self {
+        Self {
+            config,
+            state: LinkState::Connected,
+            last_heartbeat: now,
+            cancel_pending: false,
+        }
+    }
+
+    /// Any message from the exchange counts as a heartbeat
+    pub fn on_heartbeat(&mut self, now: u64) {
+        self.last_heartbeat = now;
+    }
+
+    /// Explicit disconnect (socket closed, request failures)
+    pub fn on_disconnect(&mut self, orders: &OrderManager) {
+        self.state = LinkState::Disconnected;
+        if self.config.enabled && !orders.open.is_empty() {
+            self.cancel_pending = true;
+        }
+    }
+
+    /// Connectivity restored: cancel everything left resting, as soon as possible
+    pub fn on_reconnect(&mut self, now: u64, gateway: &mut dyn OrderGateway, orders: &mut OrderManager) -> Result<usize, OrderError> {
+        self.state = LinkState::Connected;
+        self.last_heartbeat = now;
+        self.flush(gateway, orders)
+    }
+
+    /// Periodic check; treats a heartbeat timeout as a disconnect and tries to cancel
+    pub fn check(&mut self, now: u64, gateway: &mut dyn OrderGateway, orders: &mut OrderManager) -> Result<usize, OrderError> {
+        if self.state == LinkState::Connected && now.saturating_sub(self.last_heartbeat) > self.config.heartbeat_timeout_ms {
+            self.on_disconnect(orders);
+        }
+        if self.cancel_pending {
+            // Best effort even while disconnected; retried on every check until it succeeds
+            return self.flush(gateway, orders);
+        }
+        Ok(0)
+    }
+
+    /// Orders still waiting to be cancelled
+    pub fn is_cancel_pending(&self) -> bool {
+        self.cancel_pending
+    }
+
+    fn flush(&mut self, gateway: &mut dyn OrderGateway, orders: &mut OrderManager) -> Result<usize, OrderError> {
+        if !self.cancel_pending {
+            return Ok(0);
+        }
+        let count = orders.open.len();
+        if gateway.supports_cancel_on_disconnect() {
+            // Exchange already killed them; just forget them locally
+            orders.open.clear();
+        } else {
+            orders.cancel_all(gateway)?;
+        }
+        self.cancel_pending = false;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Exchange that knows each order's matched size and keeps cancelled orders listed
+    #[derive(Default)]
+    struct StubGateway {
+        orders: HashMap<String, (f64, bool)>,  // order_id -> (filled, live)
+        placed: Vec<OrderRequest>,
+        status_down: bool,
+    }
+
+    impl OrderGateway for StubGateway {
+        fn place(&mut self, request: &OrderRequest) -> Result<String, OrderError> {
+            self.placed.push(request.clone());
+            let order_id = format!("new-{}", self.placed.len());
+            self.orders.insert(order_id.clone(), (0.0, true));
+            Ok(order_id)
+        }
+
+        fn cancel(&mut self, order_id: &str) -> Result<(), OrderError> {
+            match self.orders.get_mut(order_id) {
+                Some((_, live)) if *live => {
+                    *live = false;
+                    Ok(())
+                }
+                _ => Err(OrderError::NotFound),
+            }
+        }
+
+        fn cancel_all(&mut self) -> Result<(), OrderError> {
+            Ok(())
+        }
+
+        fn order_status(&mut self, order_id: &str) -> Result<OrderStatusReport, OrderError> {
+            if self.status_down {
+                return Err(OrderError::Network("timed out".to_string()));
+            }
+            let &(filled, live) = self.orders.get(order_id).ok_or(OrderError::NotFound)?;
+            Ok(OrderStatusReport { order_id: order_id.to_string(), filled, live })
+        }
+    }
+
+    fn buy(size: f64, price: f64) -> OrderRequest {
+        OrderRequest {
+            token_id: TokenId::from("yes"),
+            side: Side::Buy,
+            price: Price::new(price).unwrap(),
+            size: Size::new(size).unwrap(),
+            time_in_force: TimeInForce::Gtc,
+        }
+    }
+
+    /// One resting 100-share buy the exchange has matched `filled` of without telling us
+    fn resting(filled: f64) -> (OrderManager, StubGateway) {
+        let mut orders = OrderManager::new();
+        let mut gateway = StubGateway::default();
+        let order_id = orders.submit(&mut gateway, &buy(100.0, 0.40), 0).unwrap();
+        gateway.orders.insert(order_id, (filled, true));
+        (orders, gateway)
+    }
+
+    #[test]
+    fn replacement_covers_only_what_is_left() {
+        let (mut orders, mut gateway) = resting(30.0);
+        // The pegger still thinks all 100 are resting
+        let outcomes = orders.replace_batch(&mut gateway, &[("new-1".to_string(), buy(100.0, 0.41))], 10);
+        assert_eq!(outcomes, vec![ReplaceOutcome::Replaced { old_id: "new-1".to_string(), new_id: "new-2".to_string(), filled: 30.0 }]);
+        assert_eq!(gateway.placed[1].size.value(), 70.0);
+        assert_eq!(orders.open["new-2"].size, 70.0);
+        assert!(!orders.open.contains_key("new-1"));
+    }
+
+    #[test]
+    fn filled_order_is_not_replaced() {
+        let (mut orders, mut gateway) = resting(100.0);
+        let outcomes = orders.replace_batch(&mut gateway, &[("new-1".to_string(), buy(100.0, 0.41))], 10);
+        assert_eq!(outcomes, vec![ReplaceOutcome::Gone { old_id: "new-1".to_string(), filled: 100.0 }]);
+        assert_eq!(gateway.placed.len(), 1);
+        assert!(orders.open.is_empty());
+    }
+
+    #[test]
+    fn unreadable_fills_place_nothing() {
+        let (mut orders, mut gateway) = resting(0.0);
+        gateway.status_down = true;
+        let outcomes = orders.replace_batch(&mut gateway, &[("new-1".to_string(), buy(100.0, 0.41))], 10);
+        assert!(matches!(&outcomes[..], [ReplaceOutcome::Unsettled { .. }]));
+        assert_eq!(gateway.placed.len(), 1);
+    }
+}
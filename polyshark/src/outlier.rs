@@ -0,0 +1,234 @@
+use crate::bus::{EventBus, RiskEvent};
+use crate::types::{OrderBook, TokenId};
+use crate::websocket::MarketEvent;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Limits beyond which a streamed update is treated as corrupt rather than market news
+#[derive(Debug, Clone, Deserialize)]
+pub struct OutlierConfig {
+    #[serde(default = "default_max_jump")]
+    pub max_jump: f64,         // Largest midpoint move in one update, as a fraction of the old mid
+    #[serde(default = "default_max_size")]
+    pub max_size: f64,         // Largest believable size on one level or print, in shares
+    #[serde(default = "default_sane_updates")]
+    pub sane_updates: u32,     // Clean updates needed after a resync before trading resumes
+}
+
+impl Default for OutlierConfig {
+    fn default() -> Self {
+        Self {
+            max_jump: default_max_jump(),
+            max_size: default_max_size(),
+            sane_updates: default_sane_updates(),
+        }
+    }
+}
+
+fn default_max_jump() -> f64 {
+    0.5
+}
+
+fn default_max_size() -> f64 {
+    10_000_000.0
+}
+
+fn default_sane_updates() -> u32 {
+    5
+}
+
+/// What was wrong with a quarantined update
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outlier {
+    PriceOutOfRange { price: f64 },
+    AbsurdSize { size: f64 },
+    Crossed { bid: f64, ask: f64 },
+    Jump { from: f64, to: f64 },
+}
+
+impl Outlier {
+    pub fn describe(&self) -> String {
+        match self {
+            Outlier::PriceOutOfRange { price } => format!("price {} outside [0, 1]", price),
+            Outlier::AbsurdSize { size } => format!("size {}", size),
+            Outlier::Crossed { bid, ask } => format!("crossed book: bid {} >= ask {}", bid, ask),
+            Outlier::Jump { from, to } => format!("mid jumped {:.4} -> {:.4}", from, to),
+        }
+    }
+}
+
+/// Counters for the metrics endpoint
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OutlierStats {
+    pub checked: u64,
+    pub quarantined: u64,
+    pub recovered: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Quarantine {
+    reason: Outlier,
+    resynced: bool,  // A fresh snapshot has replaced the suspect book
+    clean: u32,      // Clean updates since the resync
+}
+
+/// Screens streamed book updates and prints before they are applied. An update that fails
+/// a sanity check is dropped, its token is flagged for a snapshot resync, and trading on
+/// the token stays suppressed until the resynced book has produced a run of clean updates.
+#[derive(Debug, Clone, Default)]
+pub struct OutlierGuard {
+    pub config: OutlierConfig,
+    pub stats: OutlierStats,
+    last_mid: HashMap<TokenId, f64>,
+    quarantined: HashMap<TokenId, Quarantine>,
+    bus: Option<EventBus>,  // Quarantines go out as risk events
+}
+
+impl OutlierGuard {
+    pub fn new(config: OutlierConfig) -> Self {
+        Self { config, ..Default::default() }
+    }
+
+    pub fn with_bus(mut self, bus: EventBus) -> Self {
+        self.bus = Some(bus);
+        self
+    }
+
+    /// Check an event against the token's current book before applying it.
+    /// `None` means apply it; `Some` means drop it (the token is now quarantined).
+    pub fn check(&mut self, event: &MarketEvent, current: Option<&OrderBook>) -> Option<Outlier> {
+        self.stats.checked += 1;
+        // Token ids are only allocated on the rare paths; clean updates touch existing entries
+        let token_id = event.token_id();
+        let mut next_mid = None;
+        let outlier = match event {
+            MarketEvent::Heartbeat { .. } => return None,
+            MarketEvent::Book { book, .. } => {
+                // A snapshot is authoritative for jumps (it may be the resync we asked for),
+                // but it still has to be internally sane
+                if let Some(outlier) = self.check_book(book) {
+                    return self.quarantine(token_id.clone(), outlier);
+                }
+                self.accept_snapshot(&book.token_id, book);
+                return None;
+            }
+            MarketEvent::PriceChange { side, price, size, .. } => {
+                self.check_level(*price, *size).or_else(|| {
+                    // Existing levels were checked when they arrived, so only the touch can go wrong
+                    let (bid, ask) = current?.touch_after(*side, *price, *size);
+                    if let (Some(bid), Some(ask)) = (bid, ask) {
+                        if bid >= ask {
+                            return Some(Outlier::Crossed { bid, ask });
+                        }
+                        next_mid = Some((bid + ask) / 2.0);
+                    }
+                    self.check_jump(token_id, next_mid?)
+                })
+            }
+            MarketEvent::LastTrade(trade) => self.check_level(trade.price, trade.size),
+        };
+        match outlier {
+            Some(outlier) => self.quarantine(token_id.clone(), outlier),
+            None => {
+                if let Some(mid) = next_mid {
+                    match self.last_mid.get_mut(token_id) {
+                        Some(last) => *last = mid,
+                        None => {
+                            self.last_mid.insert(token_id.clone(), mid);
+                        }
+                    }
+                }
+                self.count_clean(token_id);
+                None
+            }
+        }
+    }
+
+    /// Whether trading on a token is suppressed
+    pub fn is_suppressed(&self, token_id: &TokenId) -> bool {
+        self.quarantined.contains_key(token_id)
+    }
+
+    /// Quarantined tokens still waiting for a fresh snapshot
+    pub fn needs_resync(&self) -> Vec<TokenId> {
+        self.quarantined.iter().filter(|(_, q)| !q.resynced).map(|(t, _)| t.clone()).collect()
+    }
+
+    /// Why a token is quarantined
+    pub fn reason(&self, token_id: &TokenId) -> Option<&Outlier> {
+        self.quarantined.get(token_id).map(|q| &q.reason)
+    }
+
+    /// Feed a REST snapshot fetched for a quarantined token
+    pub fn resynced(&mut self, book: &OrderBook) -> Option<Outlier> {
+        match self.check_book(book) {
+            Some(outlier) => self.quarantine(book.token_id.clone(), outlier),
+            None => {
+                self.accept_snapshot(&book.token_id, book);
+                None
+            }
+        }
+    }
+
+    fn check_level(&self, price: f64, size: f64) -> Option<Outlier> {
+        if !price.is_finite() || !(0.0..=1.0).contains(&price) {
+            return Some(Outlier::PriceOutOfRange { price });
+        }
+        if !size.is_finite() || size < 0.0 || size > self.config.max_size {
+            return Some(Outlier::AbsurdSize { size });
+        }
+        None
+    }
+
+    fn check_book(&self, book: &OrderBook) -> Option<Outlier> {
+        if let Some(outlier) = book.bids.iter().chain(&book.asks).find_map(|l| self.check_level(l.price, l.size)) {
+            return Some(outlier);
+        }
+        match (book.best_bid(), book.best_ask()) {
+            (Some(bid), Some(ask)) if bid >= ask => Some(Outlier::Crossed { bid, ask }),
+            _ => None,
+        }
+    }
+
+    fn check_jump(&self, token_id: &TokenId, mid: f64) -> Option<Outlier> {
+        let from = *self.last_mid.get(token_id)?;
+        (from > 0.0 && ((mid - from) / from).abs() > self.config.max_jump).then_some(Outlier::Jump { from, to: mid })
+    }
+
+    fn accept_snapshot(&mut self, token_id: &TokenId, book: &OrderBook) {
+        match book.midpoint() {
+            Some(mid) => self.last_mid.insert(token_id.clone(), mid),
+            None => self.last_mid.remove(token_id),
+        };
+        if let Some(q) = self.quarantined.get_mut(token_id) {
+            q.resynced = true;
+            q.clean = 0;
+        }
+        self.count_clean(token_id);
+    }
+
+    fn count_clean(&mut self, token_id: &TokenId) {
+        let Some(q) = self.quarantined.get_mut(token_id) else {
+            return;
+        };
+        if !q.resynced {
+            return;
+        }
+        q.clean += 1;
+        if q.clean > self.config.sane_updates {
+            self.quarantined.remove(token_id);
+            self.stats.recovered += 1;
+            eprintln!("outlier guard: {} released", token_id);
+        }
+    }
+
+    fn quarantine(&mut self, token_id: TokenId, outlier: Outlier) -> Option<Outlier> {
+        self.stats.quarantined += 1;
+        eprintln!("outlier guard: {} quarantined, {}", token_id, outlier.describe());
+        if let Some(bus) = &self.bus {
+            bus.publish_risk(RiskEvent::FeedQuarantined { token_id: token_id.clone(), reason: outlier.describe() });
+        }
+        self.quarantined.insert(token_id, Quarantine { reason: outlier.clone(), resynced: false, clean: 0 });
+        Some(outlier)
+    }
+}
@@ -0,0 +1,101 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::io::{self, Write};
+
+/// How a query command prints its results
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum OutputFormat {
+    #[default]
+    Table,  // Aligned columns for people
+    Json,   // One pretty-printed document: the command's report struct as serialized
+    Csv,    // Header plus one line per row of the command's main table
+}
+
+impl OutputFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "table" => Some(OutputFormat::Table),
+            "json" => Some(OutputFormat::Json),
+            "csv" => Some(OutputFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// Print a report as pretty JSON on stdout
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    let mut out = io::stdout().lock();
+    piped(writeln!(out, "{}", text).and_then(|_| out.flush()))
+}
+
+/// Print rows as CSV on stdout
+pub fn print_csv<T: Serialize + Default>(rows: &[T]) -> Result<(), String> {
+    let mut out = io::stdout().lock();
+    piped(write_csv(rows, &mut out).and_then(|_| out.flush()))
+}
+
+/// A reader that stopped early (`| head`) is not an error
+fn piped(result: io::Result<()>) -> Result<(), String> {
+    match result {
+        Err(err) if err.kind() != io::ErrorKind::BrokenPipe => Err(err.to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// Write serializable rows as CSV. Columns are the row's fields in declaration order;
+/// nested structs become `outer.inner` columns and lists are joined with `;`, so the
+/// header is the same schema the JSON output documents. The header is written even with
+/// no rows (taken from `T::default()`), so pipelines always see the columns. Returns the
+/// rows written.
+pub fn write_csv<T: Serialize + Default>(rows: &[T], out: &mut impl Write) -> io::Result<usize> {
+    let cells = |row: &T| -> io::Result<Vec<(String, String)>> {
+        let value = serde_json::to_value(row).map_err(io::Error::other)?;
+        let mut cells = Vec::new();
+        flatten("", &value, &mut cells);
+        Ok(cells)
+    };
+    let header = match rows.first() {
+        Some(row) => cells(row)?,
+        None => cells(&T::default())?,
+    };
+    let names: Vec<String> = header.iter().map(|(name, _)| escape(name)).collect();
+    writeln!(out, "{}", names.join(","))?;
+    for row in rows {
+        let line: Vec<String> = cells(row)?.iter().map(|(_, cell)| escape(cell)).collect();
+        writeln!(out, "{}", line.join(","))?;
+    }
+    Ok(rows.len())
+}
+
+fn flatten(prefix: &str, value: &Value, cells: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => flatten_object(prefix, map, cells),
+        other => cells.push((prefix.to_string(), cell(other))),
+    }
+}
+
+fn flatten_object(prefix: &str, map: &Map<String, Value>, cells: &mut Vec<(String, String)>) {
+    for (key, value) in map {
+        let name = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        flatten(&name, value, cells);
+    }
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(cell).collect::<Vec<_>>().join(";"),
+        other => other.to_string(),
+    }
+}
+
+/// Quote a field when it holds a separator, quote or line break
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
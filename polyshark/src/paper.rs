@@ -0,0 +1,240 @@
+// Most imports are only used by the paper gateway, which needs the `network` feature
+#![cfg_attr(not(feature = "network"), allow(unused_imports))]
+
+use crate::backtest::SeededRng;
+use crate::bus::Fill;
+#[cfg(feature = "network")]
+use crate::clob::ClobClient;
+use crate::execution::ExecutionEngine;
+use crate::fees::SharedFeeSchedule;
+#[cfg(feature = "network")]
+use crate::gateway::block_on;
+use crate::orders::{OrderError, OrderGateway, OrderRequest, OrderStatusReport, TimeInForce};
+use crate::types::{OrderBook, TokenId};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Submission delay and rejections injected into paper fills, so paper capture rates
+/// reflect the books having moved by the time a live order would have arrived
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PaperLatencyConfig {
+    #[serde(default)]
+    pub latency_ms: u64,     // Fixed submission delay
+    #[serde(default)]
+    pub jitter_ms: u64,      // Uniform extra delay on top, per order
+    #[serde(default)]
+    pub reject_rate: f64,    // Chance an order is rejected outright (rate limit, stale nonce, ...)
+    #[serde(default)]
+    pub seed: Option<u64>,   // Fixed seed for reproducible runs; clock-seeded otherwise
+}
+
+impl PaperLatencyConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.latency_ms > 0 || self.jitter_ms > 0 || self.reject_rate > 0.0
+    }
+}
+
+/// What happened to one simulated submission
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Submission {
+    /// Reaches the book after this delay; fill it against the book as of then
+    Accepted { delay: Duration },
+    /// Never reaches the book
+    Rejected { delay: Duration },
+}
+
+impl Submission {
+    pub fn delay(&self) -> Duration {
+        match self {
+            Submission::Accepted { delay } | Submission::Rejected { delay } => *delay,
+        }
+    }
+}
+
+/// Counters for comparing paper and live capture
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PaperVenueStats {
+    pub submitted: u64,
+    pub rejected: u64,
+    pub total_delay_ms: u64,
+}
+
+impl PaperVenueStats {
+    pub fn avg_delay_ms(&self) -> f64 {
+        if self.submitted == 0 { 0.0 } else { self.total_delay_ms as f64 / self.submitted as f64 }
+    }
+}
+
+/// Draws latency and rejections for paper orders
+#[derive(Debug, Clone)]
+pub struct PaperVenue {
+    pub config: PaperLatencyConfig,
+    pub stats: PaperVenueStats,
+    rng: SeededRng,
+}
+
+impl PaperVenue {
+    pub fn new(config: PaperLatencyConfig) -> Self {
+        let seed = config.seed.unwrap_or_else(|| {
+            SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(0)
+        });
+        Self { config, stats: PaperVenueStats::default(), rng: SeededRng::new(seed) }
+    }
+
+    /// Venue for a single order of a one-shot command. The order's nonce is mixed into a
+    /// fixed seed, so reproducible runs still draw differently from one order to the next.
+    pub fn for_order(mut config: PaperLatencyConfig, nonce: u64) -> Self {
+        config.seed = config.seed.map(|seed| SeededRng::new(seed ^ nonce).next_u64());
+        Self::new(config)
+    }
+
+    /// Draw the fate of one order
+    pub fn submit(&mut self) -> Submission {
+        let jitter = if self.config.jitter_ms > 0 {
+            (self.rng.next_f64() * (self.config.jitter_ms + 1) as f64) as u64
+        } else {
+            0
+        };
+        let delay_ms = self.config.latency_ms + jitter;
+        self.stats.submitted += 1;
+        self.stats.total_delay_ms += delay_ms;
+        let delay = Duration::from_millis(delay_ms);
+        if self.rng.chance(self.config.reject_rate) {
+            self.stats.rejected += 1;
+            Submission::Rejected { delay }
+        } else {
+            Submission::Accepted { delay }
+        }
+    }
+
+    /// For replays over recorded books: the millisecond timestamp whose book the order
+    /// fills against, or `None` if it was rejected
+    pub fn fill_time(&mut self, submitted_ms: u64) -> Option<u64> {
+        match self.submit() {
+            Submission::Accepted { delay } => Some(submitted_ms + delay.as_millis() as u64),
+            Submission::Rejected { .. } => None,
+        }
+    }
+}
+
+#[cfg(feature = "network")]
+/// Paper order that rested: only its crossing part filled on arrival. Cancelled orders
+/// stay listed, as on the exchange, so what they matched can still be read.
+#[derive(Debug, Clone)]
+struct PaperOrder {
+    filled: f64,
+    live: bool,
+}
+
+#[cfg(feature = "network")]
+/// Simulated exchange for the paper trading loop. Orders fill against the last book the
+/// bot observed, or, with latency configured, against a fresh REST book fetched once the
+/// drawn delay has passed. One venue lives for the whole run, so its seeded draws differ
+/// from order to order.
+#[derive(Debug)]
+pub struct PaperGateway {
+    pub venue: PaperVenue,
+    clob: ClobClient,
+    books: HashMap<TokenId, OrderBook>,
+    fees: HashMap<TokenId, SharedFeeSchedule>,
+    orders: HashMap<String, PaperOrder>,
+    fills: Vec<Fill>,
+    next_id: u64,
+}
+
+#[cfg(feature = "network")]
+impl PaperGateway {
+    pub fn new(venue: PaperVenue, clob: ClobClient) -> Self {
+        Self {
+            venue,
+            clob,
+            books: HashMap::new(),
+            fees: HashMap::new(),
+            orders: HashMap::new(),
+            fills: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Book the order meets: the observed one, or what the exchange shows after the delay
+    fn arrival_book(&mut self, token_id: &TokenId) -> Result<OrderBook, OrderError> {
+        if !self.venue.config.is_enabled() {
+            return self.books.get(token_id).cloned().ok_or_else(|| OrderError::Rejected(format!("no book for {}", token_id)));
+        }
+        let submission = self.venue.submit();
+        block_on(tokio::time::sleep(submission.delay()));
+        if let Submission::Rejected { delay } = submission {
+            return Err(OrderError::Rejected(format!("simulated rejection after {} ms", delay.as_millis())));
+        }
+        block_on(self.clob.order_book(token_id)).map_err(|e| OrderError::Network(e.to_string()))
+    }
+}
+
+#[cfg(feature = "network")]
+impl OrderGateway for PaperGateway {
+    fn place(&mut self, request: &OrderRequest) -> Result<String, OrderError> {
+        let fees = self.fees.get(&request.token_id).cloned()
+            .ok_or_else(|| OrderError::Rejected(format!("no fee schedule for {}", request.token_id)))?;
+        let book = self.arrival_book(&request.token_id)?;
+        let result = ExecutionEngine::new(fees).simulate(&book, request.size, request.side, Some(request.price.value()));
+        let filled = result.as_ref().map(|r| r.filed_size).unwrap_or(0.0);
+        match request.time_in_force {
+            TimeInForce::Fok if filled + 1e-9 < request.size.value() => return Err(OrderError::Killed),
+            TimeInForce::Ioc if filled <= 0.0 => return Err(OrderError::Killed),
+            _ => {}
+        }
+
+        self.next_id += 1;
+        let order_id = format!("paper-{}", self.next_id);
+        if let Some(result) = result {
+            self.fills.push(Fill {
+                order_id: Some(order_id.clone()),
+                token_id: request.token_id.clone(),
+                side: request.side,
+                price: result.execution_price,
+                size: result.filed_size,
+                fee: result.fee_paid,
+                timestamp: now_ms(),
+            });
+        }
+        if !request.time_in_force.is_immediate() && filled + 1e-9 < request.size.value() {
+            self.orders.insert(order_id.clone(), PaperOrder { filled, live: true });
+        }
+        Ok(order_id)
+    }
+
+    fn cancel(&mut self, order_id: &str) -> Result<(), OrderError> {
+        match self.orders.get_mut(order_id) {
+            Some(order) if order.live => {
+                order.live = false;
+                Ok(())
+            }
+            _ => Err(OrderError::NotFound),
+        }
+    }
+
+    fn cancel_all(&mut self) -> Result<(), OrderError> {
+        self.orders.values_mut().for_each(|order| order.live = false);
+        Ok(())
+    }
+
+    fn order_status(&mut self, order_id: &str) -> Result<OrderStatusReport, OrderError> {
+        let order = self.orders.get(order_id).ok_or(OrderError::NotFound)?;
+        Ok(OrderStatusReport { order_id: order_id.to_string(), filled: order.filled, live: order.live })
+    }
+
+    fn observe(&mut self, book: &OrderBook, fees: &SharedFeeSchedule) {
+        self.books.insert(book.token_id.clone(), book.clone());
+        self.fees.insert(book.token_id.clone(), fees.clone());
+    }
+
+    fn take_fills(&mut self) -> Vec<Fill> {
+        std::mem::take(&mut self.fills)
+    }
+}
+
+#[cfg(feature = "network")]
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
@@ -0,0 +1,235 @@
+use crate::orders::{OrderGateway, OrderManager, OrderRequest, ReplaceOutcome, TimeInForce};
+use crate::types::{OrderBook, Price, Side, Size, TokenId};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Price a pegged order follows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PegReference {
+    #[default]
+    Touch,  // Best price on our side, not counting our own order
+    Mid,    // Midpoint, rounded away from the opposite side
+}
+
+/// How passive legs are kept pegged
+#[derive(Debug, Clone, Deserialize)]
+pub struct PegConfig {
+    #[serde(default)]
+    pub reference: PegReference,
+    #[serde(default)]
+    pub offset_ticks: i32,        // Ticks more aggressive than the reference (negative: further back)
+    #[serde(default = "default_min_reprice_ms")]
+    pub min_reprice_ms: u64,      // At most one reprice per order this often
+    #[serde(default = "default_min_move_ticks")]
+    pub min_move_ticks: u32,      // Smaller target changes keep the order (and its queue spot)
+}
+
+impl Default for PegConfig {
+    fn default() -> Self {
+        Self {
+            reference: PegReference::default(),
+            offset_ticks: 0,
+            min_reprice_ms: default_min_reprice_ms(),
+            min_move_ticks: default_min_move_ticks(),
+        }
+    }
+}
+
+fn default_min_reprice_ms() -> u64 {
+    1_000
+}
+
+fn default_min_move_ticks() -> u32 {
+    1
+}
+
+/// Price step pegged orders move in (Polymarket's standard tick)
+pub const PEG_TICK: f64 = 0.01;
+
+/// Worst price a passive leg may rest at and keep the pair profitable: a ceiling for buys,
+/// a floor for sells. `other_leg` is the other leg's per-share cost (buys) or proceeds
+/// (sells), fees included, and `min_edge` what the pair must still clear per share.
+pub fn arb_limit(side: Side, other_leg: f64, min_edge: f64) -> f64 {
+    match side {
+        Side::Buy => 1.0 - other_leg - min_edge,
+        Side::Sell => 1.0 + min_edge - other_leg,
+    }
+}
+
+/// One resting order kept at its peg
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeggedOrder {
+    pub token_id: TokenId,
+    pub side: Side,
+    pub tick: f64,
+    pub limit: f64,          // From `arb_limit`; the peg never goes past it
+    pub last_reprice: u64,   // ms
+    pub reprices: u32,
+}
+
+/// Reprices passive legs as their reference moves: joins the touch (or the mid) as it
+/// shifts, never crossing the spread, never past the arbitrage limit, and no more often
+/// than `min_reprice_ms` per order. Orders go through `OrderManager::replace_batch`: an
+/// order is only placed again once its cancel is confirmed, for what it left unfilled.
+#[derive(Debug, Clone, Default)]
+pub struct Pegger {
+    pub config: PegConfig,
+    pegs: HashMap<String, PeggedOrder>,  // order_id -> peg
+}
+
+impl Pegger {
+    pub fn new(config: PegConfig) -> Self {
+        Self { config, pegs: HashMap::new() }
+    }
+
+    /// Start pegging an order already placed through the manager
+    pub fn track(&mut self, order_id: &str, token_id: TokenId, side: Side, tick: f64, limit: f64, now: u64) {
+        self.pegs.insert(order_id.to_string(), PeggedOrder { token_id, side, tick, limit, last_reprice: now, reprices: 0 });
+    }
+
+    /// Move the limit, e.g. after the other leg filled at a different price
+    pub fn set_limit(&mut self, order_id: &str, limit: f64) {
+        if let Some(peg) = self.pegs.get_mut(order_id) {
+            peg.limit = limit;
+        }
+    }
+
+    pub fn untrack(&mut self, order_id: &str) -> Option<PeggedOrder> {
+        self.pegs.remove(order_id)
+    }
+
+    pub fn get(&self, order_id: &str) -> Option<&PeggedOrder> {
+        self.pegs.get(order_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pegs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pegs.is_empty()
+    }
+
+    /// Where an order on `side` should rest in `book`, before rate limiting. `ours` is the
+    /// order's own (price, remaining) so it doesn't peg to itself. `None` when no passive
+    /// price within the limit exists.
+    pub fn target(&self, book: &OrderBook, side: Side, tick: f64, limit: f64, ours: Option<(f64, f64)>) -> Option<f64> {
+        let on_tick = |price: f64, up: bool| {
+            let ticks = price / tick;
+            let ticks = if up { (ticks - 1e-9).ceil() } else { (ticks + 1e-9).floor() };
+            ticks * tick
+        };
+        let reference = match self.config.reference {
+            PegReference::Touch => touch(book, side, ours),
+            PegReference::Mid => book.midpoint().map(|mid| on_tick(mid, side == Side::Sell)),
+        }?;
+        let offset = self.config.offset_ticks as f64 * tick;
+        let mut price = match side {
+            Side::Buy => reference + offset,
+            Side::Sell => reference - offset,
+        };
+        // Stay passive: one tick off the opposite touch at most
+        match side {
+            Side::Buy => {
+                if let Some(ask) = book.best_ask() {
+                    price = price.min(ask - tick);
+                }
+                price = on_tick(price.min(limit), false);
+            }
+            Side::Sell => {
+                if let Some(bid) = book.best_bid() {
+                    price = price.max(bid + tick);
+                }
+                price = on_tick(price.max(limit), true);
+            }
+        }
+        (price >= tick - 1e-9 && price <= 1.0 - tick + 1e-9).then_some(price)
+    }
+
+    /// Replacements due for pegged orders on this book's token
+    pub fn reprices(&self, orders: &OrderManager, book: &OrderBook, now: u64) -> Vec<(String, OrderRequest)> {
+        let mut due = Vec::new();
+        for (order_id, peg) in self.pegs.iter().filter(|(_, p)| p.token_id == book.token_id) {
+            let Some(order) = orders.open.get(order_id) else {
+                continue;
+            };
+            if now.saturating_sub(peg.last_reprice) < self.config.min_reprice_ms
+                || now.saturating_sub(order.created_at) < orders.min_resting_ms
+            {
+                continue;
+            }
+            let Some(price) = self.target(book, peg.side, peg.tick, peg.limit, Some((order.price, order.remaining()))) else {
+                continue;
+            };
+            if (price - order.price).abs() < self.config.min_move_ticks.max(1) as f64 * peg.tick - 1e-9 {
+                continue;
+            }
+            let (Some(price), Some(size)) = (Price::new(price), Size::new(order.remaining())) else {
+                continue;
+            };
+            let time_in_force = match order.expires_at {
+                Some(expires_at) => TimeInForce::Gtd { expires_at },
+                None => TimeInForce::Gtc,
+            };
+            due.push((order_id.clone(), OrderRequest { token_id: peg.token_id.clone(), side: peg.side, price, size, time_in_force }));
+        }
+        due.sort_by(|a, b| a.0.cmp(&b.0));
+        due
+    }
+
+    /// Follow replaced orders to their new ids. An order that filled, was already gone
+    /// from the exchange, or whose replacement wasn't placed is no longer resting and stops
+    /// being pegged; one whose cancel failed keeps its peg and is retried after
+    /// `min_reprice_ms`.
+    pub fn apply(&mut self, outcomes: &[ReplaceOutcome], now: u64) {
+        for outcome in outcomes {
+            match outcome {
+                ReplaceOutcome::Replaced { old_id, new_id, .. } => {
+                    if let Some(mut peg) = self.pegs.remove(old_id) {
+                        peg.last_reprice = now;
+                        peg.reprices += 1;
+                        self.pegs.insert(new_id.clone(), peg);
+                    }
+                }
+                ReplaceOutcome::CancelFailed { old_id, .. } => {
+                    if let Some(peg) = self.pegs.get_mut(old_id) {
+                        peg.last_reprice = now;
+                    }
+                }
+                ReplaceOutcome::PlaceFailed { old_id, .. }
+                | ReplaceOutcome::Unsettled { old_id, .. }
+                | ReplaceOutcome::Gone { old_id, .. } => {
+                    self.pegs.remove(old_id);
+                }
+            }
+        }
+    }
+
+    /// Reprice every due order on the book's token; call after each book update
+    pub fn update(&mut self, orders: &mut OrderManager, gateway: &mut dyn OrderGateway, book: &OrderBook, now: u64) -> Vec<ReplaceOutcome> {
+        // Orders filled or cancelled elsewhere
+        self.pegs.retain(|id, _| orders.open.contains_key(id));
+        let due = self.reprices(orders, book, now);
+        if due.is_empty() {
+            return Vec::new();
+        }
+        let outcomes = orders.replace_batch(gateway, &due, now);
+        self.apply(&outcomes, now);
+        outcomes
+    }
+}
+
+/// Best price on `side` once our own order's size is taken out of its level
+fn touch(book: &OrderBook, side: Side, ours: Option<(f64, f64)>) -> Option<f64> {
+    let levels = match side {
+        Side::Buy => &book.bids,
+        Side::Sell => &book.asks,
+    };
+    levels.iter()
+        .find(|l| match ours {
+            Some((price, size)) if (l.price - price).abs() < 1e-9 => l.size - size > 1e-9,
+            _ => l.size > 0.0,
+        })
+        .map(|l| l.price)
+}
@@ -0,0 +1,154 @@
+use crate::types::{Market, MarketId};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// Request budget for REST book polling when WebSocket data is unavailable
+#[derive(Debug, Clone, Deserialize)]
+pub struct PollConfig {
+    #[serde(default = "default_requests_per_sec")]
+    pub requests_per_sec: f64,   // Book requests across the whole watchlist
+    #[serde(default = "default_min_interval_ms")]
+    pub min_interval_ms: u64,    // No market is polled faster than this
+    #[serde(default = "default_max_interval_ms")]
+    pub max_interval_ms: u64,    // ...or slower than this
+    #[serde(default = "default_proximity_scale")]
+    pub proximity_scale: f64,    // Spread shortfall (price units) at which a market's weight halves
+    #[serde(default = "default_fallback_after_ms")]
+    pub fallback_after_ms: u64,  // Stream silent this long and books are polled over REST instead
+}
+
+impl Default for PollConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_sec: default_requests_per_sec(),
+            min_interval_ms: default_min_interval_ms(),
+            max_interval_ms: default_max_interval_ms(),
+            proximity_scale: default_proximity_scale(),
+            fallback_after_ms: default_fallback_after_ms(),
+        }
+    }
+}
+
+fn default_requests_per_sec() -> f64 {
+    5.0
+}
+
+fn default_min_interval_ms() -> u64 {
+    1_000
+}
+
+fn default_max_interval_ms() -> u64 {
+    120_000
+}
+
+fn default_proximity_scale() -> f64 {
+    0.01
+}
+
+fn default_fallback_after_ms() -> u64 {
+    15_000
+}
+
+/// Splits a fixed REST budget across the watchlist by expected opportunity value:
+/// markets whose YES + NO spread sits just under the violation threshold are polled
+/// often, markets nowhere near it (or with no depth to trade) rarely. Markets a strategy
+/// has prioritized are always polled at the fastest interval.
+#[derive(Debug, Clone, Default)]
+pub struct AdaptivePoller {
+    pub config: PollConfig,
+    pub hot: HashSet<MarketId>,         // Prioritized by a strategy, see `DataPriorities`
+    intervals: HashMap<MarketId, u64>,  // ms, from the last allocation
+    last_polled: HashMap<MarketId, u64>,
+}
+
+impl AdaptivePoller {
+    pub fn new(config: PollConfig) -> Self {
+        Self { config, ..Default::default() }
+    }
+
+    /// Expected opportunity value of polling a market: closeness of its spread to the
+    /// threshold, scaled by the depth an arb there could use
+    pub fn opportunity_value(&self, market: &Market, threshold: f64) -> f64 {
+        let shortfall = (threshold - market.get_spread()).max(0.0);
+        let proximity = 1.0 / (1.0 + shortfall / self.config.proximity_scale.max(1e-9));
+        proximity * (1.0 + market.liquidity.max(0.0)).ln()
+    }
+
+    /// Markets to poll at the fastest interval regardless of opportunity value; applied
+    /// on the next `allocate`
+    pub fn set_hot(&mut self, hot: HashSet<MarketId>) {
+        self.hot = hot;
+    }
+
+    /// Recompute every market's polling interval. Hot markets take the fastest rate first;
+    /// the rest are proportional to opportunity value, clamped to the interval bounds, with
+    /// budget freed by clamped markets handed to the others. Each poll costs one request per token.
+    pub fn allocate(&mut self, markets: &[Market], threshold: f64) {
+        let c = &self.config;
+        let max_rate = 1000.0 / c.min_interval_ms.max(1) as f64;  // Polls per second
+        let min_rate = 1000.0 / c.max_interval_ms.max(c.min_interval_ms).max(1) as f64;
+        let cost = |m: &Market| m.clob_token_ids.len().max(1) as f64;
+
+        let weights: Vec<f64> = markets.iter().map(|m| self.opportunity_value(m, threshold)).collect();
+        let mut rates: Vec<Option<f64>> = markets.iter()
+            .map(|m| self.hot.contains(&m.id).then_some(max_rate))
+            .collect();
+        // Water-filling: fix clamped markets, re-split what's left among the others
+        loop {
+            let spent: f64 = markets.iter().zip(&rates).filter_map(|(m, r)| r.map(|r| r * cost(m))).sum();
+            let budget = (c.requests_per_sec - spent).max(0.0);
+            let free_weight: f64 = markets.iter().zip(&weights).zip(&rates)
+                .filter(|(_, r)| r.is_none())
+                .map(|((m, w), _)| w * cost(m))
+                .sum();
+            let mut clamped = false;
+            for (i, w) in weights.iter().enumerate() {
+                if rates[i].is_some() {
+                    continue;
+                }
+                let share = if free_weight > 0.0 { budget * w / free_weight } else { 0.0 };
+                if share >= max_rate {
+                    rates[i] = Some(max_rate);
+                    clamped = true;
+                } else if share <= min_rate {
+                    rates[i] = Some(min_rate);
+                    clamped = true;
+                }
+            }
+            if !clamped {
+                for (i, w) in weights.iter().enumerate() {
+                    if rates[i].is_none() {
+                        rates[i] = Some(if free_weight > 0.0 { budget * w / free_weight } else { min_rate });
+                    }
+                }
+                break;
+            }
+        }
+
+        self.intervals = markets.iter().zip(&rates)
+            .map(|(m, r)| (m.id.clone(), (1000.0 / r.unwrap_or(min_rate).max(1e-9)).round() as u64))
+            .collect();
+        self.last_polled.retain(|id, _| self.intervals.contains_key(id));
+    }
+
+    pub fn interval(&self, market_id: &MarketId) -> Option<Duration> {
+        self.intervals.get(market_id).map(|ms| Duration::from_millis(*ms))
+    }
+
+    /// Markets due for a poll at `now_ms`, most overdue first
+    pub fn due(&self, now_ms: u64) -> Vec<MarketId> {
+        let mut due: Vec<(&MarketId, u64)> = self.intervals.iter()
+            .filter_map(|(id, interval)| {
+                let next = self.last_polled.get(id).map(|t| t + interval).unwrap_or(0);
+                (next <= now_ms).then(|| (id, now_ms - next))
+            })
+            .collect();
+        due.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        due.into_iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    pub fn mark_polled(&mut self, market_id: &MarketId, now_ms: u64) {
+        self.last_polled.insert(market_id.clone(), now_ms);
+    }
+}
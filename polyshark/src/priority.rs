@@ -0,0 +1,115 @@
+use crate::types::{Market, MarketId, TokenId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Mutex;
+
+/// Limits on how much fresher data strategies can claim
+#[derive(Debug, Clone, Deserialize)]
+pub struct PriorityConfig {
+    #[serde(default = "default_lease_ms")]
+    pub lease_ms: u64,           // A request lapses unless renewed within this
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: usize,       // Prioritized tokens across all strategies; oldest requests win
+    #[serde(default = "default_tokens_per_shard")]
+    pub tokens_per_shard: usize, // Dedicated WebSocket shards stay this small
+}
+
+impl Default for PriorityConfig {
+    fn default() -> Self {
+        Self {
+            lease_ms: default_lease_ms(),
+            max_tokens: default_max_tokens(),
+            tokens_per_shard: default_tokens_per_shard(),
+        }
+    }
+}
+
+fn default_lease_ms() -> u64 {
+    30_000
+}
+
+fn default_max_tokens() -> usize {
+    20
+}
+
+fn default_tokens_per_shard() -> usize {
+    10
+}
+
+/// One strategy's claim on a token
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PriorityLease {
+    pub owner: String,       // Strategy name
+    pub token_id: TokenId,
+    pub since_ms: u64,
+    pub expires_ms: u64,
+}
+
+/// Data-service API for strategies: a strategy working an order on a token asks for it
+/// to be prioritized (polled at the fastest allowed interval, streamed on a dedicated
+/// shard) and renews the request while it cares. Shared between strategies and the
+/// feed, so state sits behind a lock.
+#[derive(Debug, Default)]
+pub struct DataPriorities {
+    pub config: PriorityConfig,
+    leases: Mutex<BTreeMap<(TokenId, String), PriorityLease>>,  // (token, owner) -> lease
+}
+
+impl DataPriorities {
+    pub fn new(config: PriorityConfig) -> Self {
+        Self { config, leases: Mutex::new(BTreeMap::new()) }
+    }
+
+    /// Ask for (or renew) priority on a token; returns when the lease lapses
+    pub fn request(&self, owner: &str, token_id: &TokenId, now_ms: u64) -> u64 {
+        let expires_ms = now_ms + self.config.lease_ms;
+        let mut leases = self.leases.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        leases.entry((token_id.clone(), owner.to_string()))
+            .and_modify(|l| l.expires_ms = expires_ms)
+            .or_insert_with(|| PriorityLease { owner: owner.to_string(), token_id: token_id.clone(), since_ms: now_ms, expires_ms });
+        expires_ms
+    }
+
+    /// Give up priority on a token (the order filled or was cancelled)
+    pub fn release(&self, owner: &str, token_id: &TokenId) {
+        if let Ok(mut leases) = self.leases.lock() {
+            leases.remove(&(token_id.clone(), owner.to_string()));
+        }
+    }
+
+    /// Give up everything a strategy holds
+    pub fn release_all(&self, owner: &str) {
+        if let Ok(mut leases) = self.leases.lock() {
+            leases.retain(|(_, o), _| o != owner);
+        }
+    }
+
+    /// Live leases, dropping lapsed ones
+    pub fn leases(&self, now_ms: u64) -> Vec<PriorityLease> {
+        let mut leases = self.leases.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        leases.retain(|_, l| l.expires_ms > now_ms);
+        leases.values().cloned().collect()
+    }
+
+    /// Tokens to prioritize now: any strategy's live request counts, capped at
+    /// `max_tokens` with the longest-standing requests first
+    pub fn hot_tokens(&self, now_ms: u64) -> Vec<TokenId> {
+        let mut leases = self.leases(now_ms);
+        leases.sort_by(|a, b| a.since_ms.cmp(&b.since_ms).then_with(|| a.token_id.cmp(&b.token_id)));
+        let mut seen = HashSet::new();
+        leases.into_iter()
+            .filter(|l| seen.insert(l.token_id.clone()))
+            .take(self.config.max_tokens)
+            .map(|l| l.token_id)
+            .collect()
+    }
+
+    /// Markets with at least one prioritized token, for the REST poller
+    pub fn hot_markets(&self, markets: &[Market], now_ms: u64) -> HashSet<MarketId> {
+        let hot: HashSet<TokenId> = self.hot_tokens(now_ms).into_iter().collect();
+        markets.iter()
+            .filter(|m| m.clob_token_ids.iter().any(|t| hot.contains(t)))
+            .map(|m| m.id.clone())
+            .collect()
+    }
+}
@@ -0,0 +1,267 @@
+#[cfg(feature = "network")]
+use crate::gamma::GammaClient;
+use crate::gamma::parse_markets;
+use crate::storage::StorageResult;
+use crate::types::{Event, Market, OrderBook, TokenId};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::future::Future;
+use std::path::Path;
+
+/// Anything that can list and look up markets (Gamma API, local files)
+pub trait MarketProvider {
+    /// One page of active, open markets
+    fn markets(&self, limit: usize, offset: usize) -> impl Future<Output = StorageResult<Vec<Market>>> + Send;
+
+    /// One page of every market, closed and inactive ones included
+    fn all_markets(&self, limit: usize, offset: usize) -> impl Future<Output = StorageResult<Vec<Market>>> + Send;
+
+    /// Look up a single market by id
+    fn market_by_id(&self, id: &str) -> impl Future<Output = StorageResult<Option<Market>>> + Send;
+
+    /// Look up a single market by slug
+    fn market_by_slug(&self, slug: &str) -> impl Future<Output = StorageResult<Option<Market>>> + Send;
+
+    /// One page of active, open events, each with its full market list
+    fn events(&self, limit: usize, offset: usize) -> impl Future<Output = StorageResult<Vec<Event>>> + Send;
+}
+
+#[cfg(feature = "network")]
+impl MarketProvider for GammaClient {
+    async fn markets(&self, limit: usize, offset: usize) -> StorageResult<Vec<Market>> {
+        Ok(GammaClient::markets(self, limit, offset).await?)
+    }
+
+    async fn all_markets(&self, limit: usize, offset: usize) -> StorageResult<Vec<Market>> {
+        Ok(GammaClient::all_markets(self, limit, offset).await?)
+    }
+
+    async fn market_by_id(&self, id: &str) -> StorageResult<Option<Market>> {
+        Ok(GammaClient::market_by_id(self, id).await?)
+    }
+
+    async fn market_by_slug(&self, slug: &str) -> StorageResult<Option<Market>> {
+        Ok(GammaClient::market_by_slug(self, slug).await?)
+    }
+
+    async fn events(&self, limit: usize, offset: usize) -> StorageResult<Vec<Event>> {
+        Ok(GammaClient::events(self, limit, offset).await?)
+    }
+}
+
+/// Offline market source read from a local file, for tests, demos and classrooms.
+///
+/// Accepted formats:
+/// - `.json`: an array of markets (our own serialization or raw Gamma objects), or
+///   `{ "markets": [..], "books": [..] }` to ship order books alongside
+/// - `.csv`: one market per row with a header; list columns are `|`-separated
+#[derive(Debug, Clone, Default)]
+pub struct FileMarketSource {
+    pub markets: Vec<Market>,
+    pub books: HashMap<TokenId, OrderBook>,
+}
+
+impl FileMarketSource {
+    /// Load by extension (`.csv`, anything else is treated as JSON)
+    pub fn load(path: &Path) -> StorageResult<Self> {
+        let text = fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => Self::from_csv(&text),
+            _ => Self::from_json(&text),
+        }
+    }
+
+    pub fn from_json(text: &str) -> StorageResult<Self> {
+        let body: Value = serde_json::from_str(text)?;
+        let (markets, books) = match &body {
+            Value::Object(obj) => (obj.get("markets").cloned().unwrap_or(Value::Null), obj.get("books").cloned()),
+            other => (other.clone(), None),
+        };
+        let markets = match serde_json::from_value::<Vec<Market>>(markets.clone()) {
+            Ok(markets) => markets,
+            Err(_) => parse_markets(&markets),
+        };
+        let books: Vec<OrderBook> = match books {
+            Some(books) => serde_json::from_value(books)?,
+            None => Vec::new(),
+        };
+        Ok(Self {
+            markets,
+            books: books.into_iter().map(|b| (b.token_id.clone(), b)).collect(),
+        })
+    }
+
+    /// Rows with an unparsable outcome price are rejected. Columns: `id, question, slug, outcomes, outcome_prices, clob_token_ids` are used
+    /// when present, plus optional `liquidity, volume_24hr, end_date, category,
+    /// maker_base_fee, taker_base_fee, active, accepting_orders, condition_id,
+    /// rewards_max_spread, rewards_min_size, restricted, description, closed`
+    pub fn from_csv(text: &str) -> StorageResult<Self> {
+        let mut lines = text.lines().filter(|l| !l.trim().is_empty());
+        let header = split_csv_line(lines.next().ok_or("empty CSV file")?);
+        let mut markets = Vec::new();
+        for (n, line) in lines.enumerate() {
+            let cells = split_csv_line(line);
+            let get = |name: &str| {
+                header.iter()
+                    .position(|h| h.trim().eq_ignore_ascii_case(name))
+                    .and_then(|i| cells.get(i))
+                    .map(|c| c.trim())
+                    .filter(|c| !c.is_empty())
+            };
+            let list = |name: &str| get(name).map(|v| v.split('|').map(|s| s.trim().to_string()).collect()).unwrap_or_default();
+            let num = |name: &str| get(name).and_then(|v| v.parse::<f64>().ok());
+            let flag = |name: &str| get(name).map(|v| v.eq_ignore_ascii_case("true") || v == "1").unwrap_or(true);
+
+            let id = get("id").ok_or_else(|| format!("row {}: missing id", n + 2))?;
+            // A skipped price would shift the rest onto the wrong outcomes
+            let outcome_prices: Vec<String> = list("outcome_prices");
+            let outcome_prices = outcome_prices.iter()
+                .map(|p| p.parse::<f64>().map_err(|_| format!("row {}: invalid outcome price `{}`", n + 2, p)))
+                .collect::<Result<Vec<f64>, String>>()?;
+            markets.push(Market {
+                id: id.into(),
+                question: get("question").unwrap_or_default().to_string(),
+                slug: get("slug").unwrap_or(id).to_string(),
+                outcomes: list("outcomes"),
+                outcome_prices,
+                clob_token_ids: list("clob_token_ids").into_iter().map(TokenId::from).collect(),
+                best_bid: num("best_bid"),
+                best_ask: num("best_ask"),
+                maker_base_fee: num("maker_base_fee").unwrap_or(0.0) as u32,
+                taker_base_fee: num("taker_base_fee").unwrap_or(0.0) as u32,
+                liquidity: num("liquidity").unwrap_or(0.0),
+                volume_24hr: num("volume_24hr").unwrap_or(0.0),
+                active: flag("active"),
+                accepting_orders: flag("accepting_orders"),
+                condition_id: get("condition_id").unwrap_or_default().to_string(),
+                end_date: get("end_date").map(String::from),
+                resolution_source: None,
+                category: get("category").map(String::from),
+                tags: list("tags"),
+                neg_risk: false,
+                event_id: None,
+                rewards_max_spread: num("rewards_max_spread"),
+                rewards_min_size: num("rewards_min_size"),
+                restricted: get("restricted").is_some_and(|v| v.eq_ignore_ascii_case("true") || v == "1"),
+                description: get("description").map(String::from),
+                uma_resolution_status: None,
+                closed: get("closed").is_some_and(|v| v.eq_ignore_ascii_case("true") || v == "1"),
+            });
+        }
+        Ok(Self { markets, books: HashMap::new() })
+    }
+
+    /// Recorded book for a token, if the file shipped one
+    pub fn order_book(&self, token_id: &TokenId) -> Option<&OrderBook> {
+        self.books.get(token_id)
+    }
+
+    fn open(&self) -> impl Iterator<Item = &Market> {
+        self.markets.iter().filter(|m| m.active && !m.closed)
+    }
+}
+
+impl MarketProvider for FileMarketSource {
+    async fn markets(&self, limit: usize, offset: usize) -> StorageResult<Vec<Market>> {
+        Ok(self.open().skip(offset).take(limit).cloned().collect())
+    }
+
+    async fn all_markets(&self, limit: usize, offset: usize) -> StorageResult<Vec<Market>> {
+        Ok(self.markets.iter().skip(offset).take(limit).cloned().collect())
+    }
+
+    async fn market_by_id(&self, id: &str) -> StorageResult<Option<Market>> {
+        Ok(self.markets.iter().find(|m| m.id == id).cloned())
+    }
+
+    async fn market_by_slug(&self, slug: &str) -> StorageResult<Option<Market>> {
+        Ok(self.markets.iter().find(|m| m.slug == slug).cloned())
+    }
+
+    /// Files carry no event membership, and a set guessed from the listed markets could
+    /// miss a bucket, so there are none
+    async fn events(&self, _limit: usize, _offset: usize) -> StorageResult<Vec<Event>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Split one CSV row, honouring double quotes (`""` inside quotes is a literal quote)
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => cells.push(std::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+    cells.push(cell);
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "\
+id,question,outcomes,outcome_prices,clob_token_ids,closed
+1,\"Will it rain, today?\",Yes|No,0.45|0.52,t1|t2,false
+2,Done,Yes|No,1|0,t3|t4,true
+";
+
+    #[test]
+    fn csv_rows_become_markets() {
+        let source = FileMarketSource::from_csv(CSV).unwrap();
+        assert_eq!(source.markets.len(), 2);
+        let market = &source.markets[0];
+        assert_eq!(market.question, "Will it rain, today?");
+        assert_eq!(market.slug, "1");
+        assert_eq!(market.outcome_prices, vec![0.45, 0.52]);
+        assert_eq!(market.clob_token_ids, vec![TokenId::from("t1"), TokenId::from("t2")]);
+        assert!(source.markets[1].closed);
+    }
+
+    #[test]
+    fn csv_rejects_unparsable_outcome_price() {
+        let csv = "id,outcomes,outcome_prices\n1,Yes|No,n/a|0.5\n";
+        let err = FileMarketSource::from_csv(csv).unwrap_err().to_string();
+        assert!(err.contains("row 2"), "{}", err);
+    }
+
+    #[test]
+    fn csv_requires_an_id() {
+        assert!(FileMarketSource::from_csv("id,question\n,No id\n").is_err());
+    }
+
+    #[test]
+    fn json_round_trips_markets_and_books() {
+        let csv = FileMarketSource::from_csv(CSV).unwrap();
+        let book = OrderBook { token_id: "t1".into(), bids: Vec::new(), asks: Vec::new(), timestamp: 7 };
+        let text = serde_json::json!({ "markets": csv.markets, "books": [book] }).to_string();
+        let source = FileMarketSource::from_json(&text).unwrap();
+        assert_eq!(source.markets.len(), 2);
+        assert_eq!(source.order_book(&TokenId::from("t1")).map(|b| b.timestamp), Some(7));
+    }
+
+    #[tokio::test]
+    async fn provider_lists_only_open_markets() {
+        let source = FileMarketSource::from_csv(CSV).unwrap();
+        let open = source.markets(10, 0).await.unwrap();
+        assert_eq!(open.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(), vec!["1"]);
+        assert_eq!(source.all_markets(10, 0).await.unwrap().len(), 2);
+        assert!(source.market_by_slug("2").await.unwrap().is_some());
+    }
+
+    #[test]
+    fn csv_line_honours_quotes() {
+        assert_eq!(split_csv_line(r#"a,"b,c","d ""e""""#), vec!["a", "b,c", r#"d "e""#]);
+    }
+}
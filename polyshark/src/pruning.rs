@@ -0,0 +1,153 @@
+use crate::lifecycle::{MarketLifecycle, MarketState};
+use crate::types::{Market, MarketId};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// When a dead market is dropped from subscriptions and scanning, and when it comes back
+#[derive(Debug, Clone, Deserialize)]
+pub struct PruneConfig {
+    #[serde(default = "default_min_liquidity")]
+    pub min_liquidity: f64,
+    #[serde(default = "default_min_volume")]
+    pub min_volume_24hr: f64,
+    #[serde(default = "default_prune_after_hours")]
+    pub prune_after_hours: u64,    // Below either threshold this long before pruning
+    #[serde(default = "default_restore_factor")]
+    pub restore_factor: f64,       // Must clear both thresholds by this multiple to come back
+    #[serde(default = "default_restore_after_hours")]
+    pub restore_after_hours: u64,  // ...for this long
+}
+
+impl Default for PruneConfig {
+    fn default() -> Self {
+        Self {
+            min_liquidity: default_min_liquidity(),
+            min_volume_24hr: default_min_volume(),
+            prune_after_hours: default_prune_after_hours(),
+            restore_factor: default_restore_factor(),
+            restore_after_hours: default_restore_after_hours(),
+        }
+    }
+}
+
+fn default_min_liquidity() -> f64 {
+    500.0
+}
+
+fn default_min_volume() -> f64 {
+    100.0
+}
+
+fn default_prune_after_hours() -> u64 {
+    6
+}
+
+fn default_restore_factor() -> f64 {
+    1.5
+}
+
+fn default_restore_after_hours() -> u64 {
+    1
+}
+
+/// Markets pruned or restored by one update
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PruneChanges {
+    pub pruned: Vec<MarketId>,
+    pub restored: Vec<MarketId>,
+}
+
+/// Dwell timers and pruned set, as saved across restarts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrunerState {
+    pub below_since: HashMap<MarketId, u64>,
+    pub above_since: HashMap<MarketId, u64>,
+    pub pruned: HashSet<MarketId>,
+}
+
+/// Drops markets that stayed illiquid from the watchlist and brings them back once
+/// activity clearly recovers. Separate enter/exit thresholds and dwell times keep a
+/// market hovering around the limit from flapping in and out.
+#[derive(Debug, Clone, Default)]
+pub struct StalePruner {
+    pub config: PruneConfig,
+    below_since: HashMap<MarketId, u64>,  // Active market under a threshold since (secs)
+    above_since: HashMap<MarketId, u64>,  // Pruned market over the restore thresholds since (secs)
+    pruned: HashSet<MarketId>,
+}
+
+impl StalePruner {
+    pub fn new(config: PruneConfig) -> Self {
+        Self { config, ..Default::default() }
+    }
+
+    pub fn export_state(&self) -> PrunerState {
+        PrunerState { below_since: self.below_since.clone(), above_since: self.above_since.clone(), pruned: self.pruned.clone() }
+    }
+
+    pub fn restore_state(&mut self, state: PrunerState) {
+        self.below_since = state.below_since;
+        self.above_since = state.above_since;
+        self.pruned = state.pruned;
+    }
+
+    pub fn is_pruned(&self, market_id: &MarketId) -> bool {
+        self.pruned.contains(market_id)
+    }
+
+    /// Re-evaluate every market against fresh Gamma stats, moving pruned markets back to
+    /// `Discovered` and restored ones to `Subscribed` (where they warm up again)
+    pub fn update(&mut self, markets: &[Market], lifecycle: &mut MarketLifecycle, now: u64) -> PruneChanges {
+        let c = &self.config;
+        let mut changes = PruneChanges::default();
+        for m in markets {
+            if self.pruned.contains(&m.id) {
+                let recovered = m.liquidity >= c.min_liquidity * c.restore_factor && m.volume_24hr >= c.min_volume_24hr * c.restore_factor;
+                if !recovered {
+                    self.above_since.remove(&m.id);
+                    continue;
+                }
+                let since = *self.above_since.entry(m.id.clone()).or_insert(now);
+                if now.saturating_sub(since) >= c.restore_after_hours * 3600 && lifecycle.subscribe(&m.id, now).is_ok() {
+                    self.pruned.remove(&m.id);
+                    self.above_since.remove(&m.id);
+                    changes.restored.push(m.id.clone());
+                }
+            } else {
+                let stale = m.liquidity < c.min_liquidity || m.volume_24hr < c.min_volume_24hr;
+                if !stale {
+                    self.below_since.remove(&m.id);
+                    continue;
+                }
+                let since = *self.below_since.entry(m.id.clone()).or_insert(now);
+                if now.saturating_sub(since) >= c.prune_after_hours * 3600 && Self::unsubscribe(lifecycle, &m.id) {
+                    self.pruned.insert(m.id.clone());
+                    self.below_since.remove(&m.id);
+                    changes.pruned.push(m.id.clone());
+                }
+            }
+        }
+        changes
+    }
+
+    /// Tokens to stay subscribed to: everything not pruned
+    pub fn watchlist(&self, markets: &[Market]) -> Vec<String> {
+        markets.iter()
+            .filter(|m| !self.pruned.contains(&m.id))
+            .flat_map(|m| m.clob_token_ids.iter().map(|t| t.to_string()))
+            .collect()
+    }
+
+    /// Step a live market back to `Discovered`; markets winding down or resolved are left alone
+    fn unsubscribe(lifecycle: &mut MarketLifecycle, market_id: &MarketId) -> bool {
+        match lifecycle.state(market_id) {
+            Some(MarketState::Tradable) => {
+                lifecycle.transition(market_id, MarketState::Subscribed).is_ok()
+                    && lifecycle.transition(market_id, MarketState::Discovered).is_ok()
+            }
+            Some(MarketState::Subscribed) => lifecycle.transition(market_id, MarketState::Discovered).is_ok(),
+            Some(MarketState::Discovered) | None => true,
+            Some(MarketState::WindingDown) | Some(MarketState::Resolved) => false,
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use crate::resolution::ResolutionStatus;
+use crate::storage::StorageResult;
+use crate::types::{Market, MarketId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Where `polyshark quality` writes its table
+pub const QUALITY_TABLE_PATH: &str = "quality.json";
+
+/// Heuristics for markets whose "guaranteed" edge is really resolution risk
+#[derive(Debug, Clone, Deserialize)]
+pub struct QualityConfig {
+    #[serde(default = "default_min_score")]
+    pub min_score: f64,               // Markets scoring below this are never signalled
+    #[serde(default = "default_vague_patterns")]
+    pub vague_patterns: Vec<String>,  // Wording that leaves the outcome to interpretation (case insensitive)
+    #[serde(default = "default_slow_resolution_hours")]
+    pub slow_resolution_hours: u64,   // Past the end date by this much counts as a slow resolution
+    #[serde(default = "default_min_category_markets")]
+    pub min_category_markets: usize,  // Archived markets a category needs before its history counts
+}
+
+impl Default for QualityConfig {
+    fn default() -> Self {
+        Self {
+            min_score: default_min_score(),
+            vague_patterns: default_vague_patterns(),
+            slow_resolution_hours: default_slow_resolution_hours(),
+            min_category_markets: default_min_category_markets(),
+        }
+    }
+}
+
+fn default_min_score() -> f64 {
+    0.5
+}
+
+fn default_vague_patterns() -> Vec<String> {
+    [
+        "sole discretion",
+        "credible reporting",
+        "widely reported",
+        "consensus of",
+        "substantially",
+        "approximately",
+        "in the spirit of",
+        "at least one major",
+        "officially announced",
+        "may be resolved",
+        "unclear",
+    ]
+    .iter()
+    .map(|p| p.to_string())
+    .collect()
+}
+
+fn default_slow_resolution_hours() -> u64 {
+    72
+}
+
+fn default_min_category_markets() -> usize {
+    10
+}
+
+/// UMA status Gamma reports while an outcome is being challenged
+pub fn is_disputed(market: &Market) -> bool {
+    ResolutionStatus::of(market) == ResolutionStatus::Disputed
+}
+
+/// How markets of one category have resolved in the archive
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CategoryHistory {
+    pub markets: usize,
+    pub disputed: usize,
+    pub resolved: usize,            // Markets whose resolution time could be measured
+    pub total_delay_hours: f64,     // Summed over `resolved`
+}
+
+impl CategoryHistory {
+    pub fn dispute_rate(&self) -> f64 {
+        if self.markets == 0 { 0.0 } else { self.disputed as f64 / self.markets as f64 }
+    }
+
+    pub fn avg_delay_hours(&self) -> Option<f64> {
+        (self.resolved > 0).then(|| self.total_delay_hours / self.resolved as f64)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MarketHistory {
+    category: Option<String>,
+    end: Option<u64>,
+    last_seen: u64,
+    settled_at: Option<u64>,  // First snapshot where its outcome was final
+    disputed: bool,
+}
+
+/// What the daily market archive says about past resolutions. Snapshots are fed oldest
+/// first; a market that stops appearing before the latest snapshot is taken as settled
+/// on the last day it was seen.
+#[derive(Debug, Clone, Default)]
+pub struct QualityHistory {
+    markets: HashMap<MarketId, MarketHistory>,
+    latest: u64,
+}
+
+impl QualityHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one snapshot taken at `seen_at` (seconds)
+    pub fn observe(&mut self, seen_at: u64, markets: &[Market]) {
+        self.latest = self.latest.max(seen_at);
+        for market in markets {
+            let entry = self.markets.entry(market.id.clone()).or_insert_with(|| MarketHistory {
+                category: None,
+                end: None,
+                last_seen: seen_at,
+                settled_at: None,
+                disputed: false,
+            });
+            entry.category = market.category.clone().or(entry.category.take());
+            entry.end = market.end_timestamp().or(entry.end);
+            entry.last_seen = entry.last_seen.max(seen_at);
+            entry.disputed |= is_disputed(market);
+            // Closed markets stay in the archive until they resolve, so closing alone isn't settling
+            if ResolutionStatus::of(market) == ResolutionStatus::Final && entry.settled_at.is_none() {
+                entry.settled_at = Some(seen_at);
+            }
+        }
+    }
+
+    /// Whether the market was ever seen disputed
+    pub fn was_disputed(&self, market_id: &MarketId) -> bool {
+        self.markets.get(market_id).is_some_and(|m| m.disputed)
+    }
+
+    /// Dispute counts and resolution delays by category (markets without one are skipped)
+    pub fn categories(&self) -> HashMap<String, CategoryHistory> {
+        let mut out: HashMap<String, CategoryHistory> = HashMap::new();
+        for m in self.markets.values() {
+            let Some(category) = m.category.as_deref() else {
+                continue;
+            };
+            let stats = out.entry(category.to_lowercase()).or_default();
+            stats.markets += 1;
+            if m.disputed {
+                stats.disputed += 1;
+            }
+            let settled = m.settled_at.or((m.last_seen < self.latest).then_some(m.last_seen));
+            if let (Some(end), Some(settled)) = (m.end, settled) {
+                stats.resolved += 1;
+                stats.total_delay_hours += settled.saturating_sub(end) as f64 / 3600.0;
+            }
+        }
+        out
+    }
+}
+
+/// One market's score in [0, 1] and what pulled it down
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityScore {
+    pub score: f64,
+    pub reasons: Vec<String>,
+}
+
+/// Score a market from its own wording and status plus its category's archived record.
+/// Each finding multiplies the score down, so several weak signals add up.
+pub fn score_market(
+    market: &Market,
+    history: &QualityHistory,
+    categories: &HashMap<String, CategoryHistory>,
+    config: &QualityConfig,
+    now: u64,
+) -> QualityScore {
+    let mut score = 1.0;
+    let mut reasons = Vec::new();
+
+    if is_disputed(market) {
+        score *= 0.2;
+        reasons.push("resolution currently disputed".to_string());
+    } else if history.was_disputed(&market.id) {
+        score *= 0.4;
+        reasons.push("resolution was disputed before".to_string());
+    }
+
+    let text = format!("{} {}", market.question, market.description.as_deref().unwrap_or("")).to_lowercase();
+    for pattern in config.vague_patterns.iter().filter(|p| text.contains(&p.to_lowercase())) {
+        score *= 0.85;
+        reasons.push(format!("vague wording: \"{}\"", pattern));
+    }
+
+    if market.resolution_source.as_deref().is_none_or(|s| s.trim().is_empty()) {
+        score *= 0.9;
+        reasons.push("no resolution source".to_string());
+    }
+
+    let overdue_hours = market.end_timestamp().map(|end| now.saturating_sub(end) / 3600).unwrap_or(0);
+    if overdue_hours >= config.slow_resolution_hours {
+        score *= 0.5;
+        reasons.push(format!("unresolved {}h past its end date", overdue_hours));
+    }
+
+    if let Some(stats) = market.category.as_deref()
+        .and_then(|c| categories.get(&c.to_lowercase()))
+        .filter(|s| s.markets >= config.min_category_markets)
+    {
+        let rate = stats.dispute_rate();
+        if rate > 0.0 {
+            score *= 1.0 - rate.min(0.9);
+            reasons.push(format!("category disputes {:.1}% of markets", rate * 100.0));
+        }
+        if let Some(delay) = stats.avg_delay_hours().filter(|d| *d >= config.slow_resolution_hours as f64) {
+            score *= 0.8;
+            reasons.push(format!("category resolves {:.0}h after end date on average", delay));
+        }
+    }
+
+    QualityScore { score, reasons }
+}
+
+/// One scored market as `polyshark quality` lists it
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct QualityRow {
+    pub market_id: MarketId,
+    pub slug: String,
+    pub score: f64,
+    pub reasons: Vec<String>,
+}
+
+/// Per-market quality scores computed from the archive
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QualityTable {
+    pub computed_at: u64,  // Seconds
+    pub scores: HashMap<MarketId, QualityScore>,
+}
+
+impl QualityTable {
+    pub fn compute(markets: &[Market], history: &QualityHistory, config: &QualityConfig, now: u64) -> Self {
+        let categories = history.categories();
+        let scores = markets.iter()
+            .map(|m| (m.id.clone(), score_market(m, history, &categories, config, now)))
+            .collect();
+        Self { computed_at: now, scores }
+    }
+
+    pub fn load(path: &Path) -> StorageResult<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> StorageResult<()> {
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn score(&self, market_id: &MarketId) -> Option<&QualityScore> {
+        self.scores.get(market_id)
+    }
+
+    /// Whether a market clears the configured floor (unscored markets pass)
+    pub fn passes(&self, config: &QualityConfig, market_id: &MarketId) -> bool {
+        self.score(market_id).is_none_or(|s| s.score >= config.min_score)
+    }
+}
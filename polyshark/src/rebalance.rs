@@ -0,0 +1,284 @@
+use crate::fees::{FeeSchedule, SharedFeeSchedule};
+use crate::orders::{OrderRequest, TimeInForce};
+use crate::types::{Market, MarketId, OrderBook, Price, Side, Size, TokenId};
+use crate::wallet::Wallet;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Journal tag of trims sold by a running bot
+pub const REBALANCE_TAG: &str = "rebalance";
+
+/// What the rebalancer does with the trades it finds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RebalanceMode {
+    Off,
+    #[default]
+    Propose,  // Report the trades only
+    Execute,  // Sell into the bids
+}
+
+/// When one-sided inventory gets trimmed and how much it may cost
+#[derive(Debug, Clone, Deserialize)]
+pub struct RebalanceConfig {
+    #[serde(default)]
+    pub mode: RebalanceMode,
+    #[serde(default = "default_max_skew")]
+    pub max_skew: f64,             // Unmatched YES vs NO value imbalance tolerated, in [0, 1]
+    #[serde(default = "default_max_loss_per_share")]
+    pub max_loss_per_share: f64,   // "Near break-even": net exit may sit this far below entry
+    #[serde(default = "default_min_size")]
+    pub min_size: f64,             // Smaller trims aren't worth the fees and book impact
+}
+
+impl Default for RebalanceConfig {
+    fn default() -> Self {
+        Self {
+            mode: RebalanceMode::default(),
+            max_skew: default_max_skew(),
+            max_loss_per_share: default_max_loss_per_share(),
+            min_size: default_min_size(),
+        }
+    }
+}
+
+fn default_max_skew() -> f64 {
+    0.5
+}
+
+fn default_max_loss_per_share() -> f64 {
+    0.005
+}
+
+fn default_min_size() -> f64 {
+    5.0
+}
+
+/// Which side of a binary market a leg is on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub enum Outcome {
+    #[default]
+    Yes,
+    No,
+}
+
+/// A leg held without its opposite: directional exposure rather than a locked-in set
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnmatchedLeg {
+    pub market_id: MarketId,
+    pub token_id: TokenId,
+    pub outcome: Outcome,
+    pub size: f64,          // Held beyond the matched sets
+    pub entry_price: f64,
+    pub mark: f64,          // Mid, entry price when there is no book
+}
+
+impl UnmatchedLeg {
+    pub fn value(&self) -> f64 {
+        self.size * self.mark
+    }
+}
+
+/// Unmatched inventory on each side across markets
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct InventorySkew {
+    pub yes_value: f64,
+    pub no_value: f64,
+    pub legs: Vec<UnmatchedLeg>,
+}
+
+impl InventorySkew {
+    /// (YES - NO) / (YES + NO): +1 all YES, -1 all NO, 0 balanced
+    pub fn skew(&self) -> f64 {
+        let total = self.yes_value + self.no_value;
+        if total <= 0.0 { 0.0 } else { (self.yes_value - self.no_value) / total }
+    }
+
+    /// The side carrying too much
+    pub fn heavy(&self) -> Outcome {
+        if self.yes_value >= self.no_value { Outcome::Yes } else { Outcome::No }
+    }
+}
+
+/// One proposed trim: sell part of an unmatched leg into the bids
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RebalanceTrade {
+    pub market_id: MarketId,
+    pub token_id: TokenId,
+    pub outcome: Outcome,
+    pub size: f64,
+    pub price: f64,         // VWAP into the bids
+    pub limit: f64,         // Lowest bid the sale reaches
+    pub fees: f64,
+    pub proceeds: f64,      // Cash freed, after fees
+    pub pnl: f64,           // Versus entry, after fees (near zero by construction)
+}
+
+/// Everything one scan found
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RebalancePlan {
+    pub skew_before: f64,
+    pub skew_after: f64,    // If every trade goes through
+    pub trades: Vec<RebalanceTrade>,
+}
+
+impl RebalancePlan {
+    pub fn freed(&self) -> f64 {
+        self.trades.iter().map(|t| t.proceeds).sum()
+    }
+}
+
+/// Looks for unmatched legs on the over-weighted side that can be sold at about what they
+/// cost, so a run of one-sided fills (e.g. failed second legs) doesn't leave the book
+/// betting on one direction with capital tied up in it
+#[derive(Debug, Clone)]
+pub struct Rebalancer {
+    pub config: RebalanceConfig,
+}
+
+impl Rebalancer {
+    pub fn new(config: RebalanceConfig) -> Self {
+        Self { config }
+    }
+
+    /// Unmatched YES and NO inventory across binary markets
+    pub fn inventory(wallet: &Wallet, markets: &[Market], books: &HashMap<TokenId, OrderBook>) -> InventorySkew {
+        let mut skew = InventorySkew::default();
+        for market in markets {
+            let [yes, no] = market.clob_token_ids.as_slice() else {
+                continue;
+            };
+            let matched = wallet.held(yes).min(wallet.held(no));
+            for (token_id, outcome) in [(yes, Outcome::Yes), (no, Outcome::No)] {
+                let Some(position) = wallet.positions.get(token_id).filter(|p| p.side == Side::Buy) else {
+                    continue;
+                };
+                let size = position.size - matched;
+                if size <= 1e-9 {
+                    continue;
+                }
+                let mark = books.get(token_id).and_then(OrderBook::midpoint).unwrap_or(position.entry_price);
+                let leg = UnmatchedLeg {
+                    market_id: market.id.clone(),
+                    token_id: token_id.clone(),
+                    outcome,
+                    size,
+                    entry_price: position.entry_price,
+                    mark,
+                };
+                match outcome {
+                    Outcome::Yes => skew.yes_value += leg.value(),
+                    Outcome::No => skew.no_value += leg.value(),
+                }
+                skew.legs.push(leg);
+            }
+        }
+        skew
+    }
+
+    /// Trims that bring the skew back within `max_skew`, cheapest first; each leg is charged
+    /// the fees of its own market
+    pub fn plan(
+        &self,
+        wallet: &Wallet,
+        markets: &[Market],
+        books: &HashMap<TokenId, OrderBook>,
+        fees: impl Fn(&Market) -> SharedFeeSchedule,
+    ) -> RebalancePlan {
+        let fees: HashMap<&MarketId, SharedFeeSchedule> = markets.iter().map(|m| (&m.id, fees(m))).collect();
+        let inventory = Self::inventory(wallet, markets, books);
+        let skew_before = inventory.skew();
+        let mut plan = RebalancePlan { skew_before, skew_after: skew_before, trades: Vec::new() };
+        if self.config.mode == RebalanceMode::Off || skew_before.abs() <= self.config.max_skew {
+            return plan;
+        }
+
+        // Value to move off the heavy side so that (heavy - light) / (heavy + light) = max_skew
+        let heavy = inventory.heavy();
+        let (mut heavy_value, light_value) = match heavy {
+            Outcome::Yes => (inventory.yes_value, inventory.no_value),
+            Outcome::No => (inventory.no_value, inventory.yes_value),
+        };
+        let target = light_value * (1.0 + self.config.max_skew) / (1.0 - self.config.max_skew).max(1e-9);
+
+        let mut candidates: Vec<RebalanceTrade> = inventory.legs.iter()
+            .filter(|leg| leg.outcome == heavy)
+            .filter_map(|leg| self.trim(leg, leg.size, books, fees.get(&leg.market_id)?.as_ref()))
+            .collect();
+        // Best exit relative to entry first
+        candidates.sort_by(|a, b| (b.pnl / b.size).total_cmp(&(a.pnl / a.size)));
+
+        for candidate in candidates {
+            let excess = heavy_value - target;
+            if excess <= 0.0 {
+                break;
+            }
+            let Some(leg) = inventory.legs.iter().find(|l| l.token_id == candidate.token_id) else {
+                continue;
+            };
+            let size = if leg.value() > excess { excess / leg.mark.max(1e-9) } else { leg.size };
+            let trade = match fees.get(&leg.market_id) {
+                Some(schedule) if size + 1e-9 < candidate.size => self.trim(leg, size, books, schedule.as_ref()),
+                _ => Some(candidate),
+            };
+            let Some(trade) = trade else {
+                continue;
+            };
+            heavy_value -= trade.size * leg.mark;
+            plan.trades.push(trade);
+        }
+
+        let (yes, no) = match heavy {
+            Outcome::Yes => (heavy_value, light_value),
+            Outcome::No => (light_value, heavy_value),
+        };
+        plan.skew_after = if yes + no <= 0.0 { 0.0 } else { (yes - no) / (yes + no) };
+        plan
+    }
+
+    /// Selling `size` of the leg, if it is big enough and clears the break-even tolerance
+    fn trim(&self, leg: &UnmatchedLeg, size: f64, books: &HashMap<TokenId, OrderBook>, schedule: &dyn FeeSchedule) -> Option<RebalanceTrade> {
+        if size < self.config.min_size {
+            return None;
+        }
+        let walk = books.get(&leg.token_id)?.walk(size, Side::Sell);
+        if !walk.is_complete() {
+            return None;
+        }
+        let (price, limit) = (walk.vwap()?, walk.levels.last()?.price);
+        let fees = schedule.fee(price, size, false);
+        let proceeds = price * size - fees;
+        let pnl = proceeds - leg.entry_price * size;
+        (pnl >= -self.config.max_loss_per_share * size).then(|| RebalanceTrade {
+            market_id: leg.market_id.clone(),
+            token_id: leg.token_id.clone(),
+            outcome: leg.outcome,
+            size,
+            price,
+            limit,
+            fees,
+            proceeds,
+            pnl,
+        })
+    }
+
+    /// IOC sells for every planned trim, limited at the lowest bid each was priced
+    /// against (execute mode only). Fills are booked and journaled like any other.
+    pub fn orders(&self, plan: &RebalancePlan) -> Vec<OrderRequest> {
+        if self.config.mode != RebalanceMode::Execute {
+            return Vec::new();
+        }
+        plan.trades.iter()
+            .filter_map(|trade| {
+                Some(OrderRequest {
+                    token_id: trade.token_id.clone(),
+                    side: Side::Sell,
+                    price: Price::new(trade.limit)?,
+                    size: Size::new(trade.size)?,
+                    time_in_force: TimeInForce::Ioc,
+                })
+            })
+            .collect()
+    }
+}
+
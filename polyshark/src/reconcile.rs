@@ -0,0 +1,278 @@
+use crate::attribution::FOUND_TAG;
+use crate::control::{ControlCommand, ControlState};
+use crate::journal::{Journal, JournalEntry};
+use crate::manual;
+use crate::types::{MarketId, Side, TokenId};
+use crate::wallet::Wallet;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::future::Future;
+
+/// Tag on journal entries that correct the wallet to exchange balances
+pub const RECONCILE_TAG: &str = "reconciled";
+
+/// Asset name of the cash balance in drifts and correction entries
+pub const CASH_ASSET: &str = "USDC";
+
+/// Periodic comparison of the wallet with the exchange (live profiles only)
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReconcileConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,   // Time between balance checks
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,       // Absolute difference ignored (USDC or shares)
+    #[serde(default)]
+    pub action: DriftAction,  // What a drift beyond the tolerance triggers
+}
+
+impl Default for ReconcileConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: default_interval_secs(),
+            tolerance: default_tolerance(),
+            action: DriftAction::default(),
+        }
+    }
+}
+
+fn default_interval_secs() -> u64 {
+    60
+}
+
+fn default_tolerance() -> f64 {
+    0.01
+}
+
+/// Balances as reported by the exchange / chain
+#[derive(Debug, Clone, Default)]
+pub struct ExchangeBalances {
+    pub usdc: f64,
+    pub tokens: HashMap<TokenId, f64>,  // token_id -> held size
+}
+
+/// Anything that can report real balances (CLOB balance endpoint, on-chain reads)
+pub trait BalanceSource {
+    /// Cash plus the balance of each of `tokens`
+    fn fetch_balances(&self, tokens: &[TokenId]) -> impl Future<Output = Result<ExchangeBalances, String>> + Send;
+}
+
+/// What to do when drift exceeds tolerance
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftAction {
+    #[default]
+    Report,       // Log only
+    AutoCorrect,  // Overwrite internal state with exchange values
+    Halt,         // Pause trading via the control state
+}
+
+/// One balance that disagrees
+#[derive(Debug, Clone)]
+pub struct BalanceDrift {
+    pub asset: String,  // `CASH_ASSET` or a token_id
+    pub internal: f64,
+    pub exchange: f64,
+}
+
+impl BalanceDrift {
+    pub fn diff(&self) -> f64 {
+        self.exchange - self.internal
+    }
+}
+
+/// Result of one reconciliation pass
+#[derive(Debug, Clone, Default)]
+pub struct ReconcileReport {
+    pub drifts: Vec<BalanceDrift>,
+    pub corrected: bool,
+    pub halted: bool,
+}
+
+impl ReconcileReport {
+    pub fn is_clean(&self) -> bool {
+        self.drifts.is_empty()
+    }
+}
+
+/// Compares the internal wallet with exchange balances
+#[derive(Debug, Clone)]
+pub struct Reconciler {
+    pub tolerance: f64,  // Absolute difference ignored (USDC or shares)
+    pub action: DriftAction,
+}
+
+impl Reconciler {
+    pub fn new(tolerance: f64, action: DriftAction) -> Self {
+        Self { tolerance, action }
+    }
+
+    /// Find balances that differ by more than the tolerance
+    pub fn compare(&self, wallet: &Wallet, balances: &ExchangeBalances) -> Vec<BalanceDrift> {
+        let mut drifts = Vec::new();
+
+        if (wallet.usdc.value() - balances.usdc).abs() > self.tolerance {
+            drifts.push(BalanceDrift { asset: CASH_ASSET.to_string(), internal: wallet.usdc.value(), exchange: balances.usdc });
+        }
+
+        // Only long positions correspond to held tokens
+        let held: HashMap<&str, f64> = wallet.positions.values()
+            .filter(|p| p.side == Side::Buy)
+            .map(|p| (p.token_id.as_str(), p.size))
+            .collect();
+
+        for (token_id, internal) in &held {
+            let exchange = balances.tokens.get(*token_id).copied().unwrap_or(0.0);
+            if (internal - exchange).abs() > self.tolerance {
+                drifts.push(BalanceDrift { asset: token_id.to_string(), internal: *internal, exchange });
+            }
+        }
+        for (token_id, exchange) in &balances.tokens {
+            if !held.contains_key(token_id.as_str()) && exchange.abs() > self.tolerance {
+                drifts.push(BalanceDrift { asset: token_id.to_string(), internal: 0.0, exchange: *exchange });
+            }
+        }
+
+        drifts
+    }
+
+    /// Compare and apply the configured action. Corrections are journaled and applied the way
+    /// a replay applies them, so a restart rebuilds the corrected wallet.
+    pub fn reconcile(&self, wallet: &mut Wallet, journal: &mut Journal, balances: &ExchangeBalances, control: &ControlState, now: u64) -> ReconcileReport {
+        let drifts = self.compare(wallet, balances);
+        let mut report = ReconcileReport { drifts, ..Default::default() };
+        if report.is_clean() {
+            return report;
+        }
+
+        match self.action {
+            DriftAction::Report => {}
+            DriftAction::AutoCorrect => {
+                for drift in &report.drifts {
+                    let entry = correction(drift, wallet.positions.contains_key(drift.asset.as_str()), now);
+                    let id = journal.record(entry);
+                    if let Some(entry) = journal.get(id) {
+                        manual::apply_entry(wallet, entry);
+                    }
+                }
+                report.corrected = true;
+            }
+            DriftAction::Halt => {
+                control.apply(ControlCommand::Pause);
+                report.halted = true;
+            }
+        }
+
+        report
+    }
+}
+
+/// Journal entry bringing one drifted balance to the exchange's value: cash at a price of 1,
+/// shares at a price of 0 (found shares cost nothing, missing ones realize their basis)
+fn correction(drift: &BalanceDrift, held: bool, now: u64) -> JournalEntry {
+    let diff = drift.diff();
+    let cash = drift.asset == CASH_ASSET;
+    let (tags, note) = if !cash && !held {
+        (vec![FOUND_TAG.to_string(), RECONCILE_TAG.to_string()], "held on the exchange, never bought by us")
+    } else {
+        (vec![RECONCILE_TAG.to_string()], "reconciled to the exchange balance")
+    };
+    JournalEntry {
+        id: 0,
+        timestamp: now,
+        market_id: MarketId::default(),
+        token_id: drift.asset.as_str().into(),
+        // Cash comes in on a sell; shares come in on a buy
+        side: match (cash, diff > 0.0) {
+            (true, true) | (false, false) => Side::Sell,
+            (true, false) | (false, true) => Side::Buy,
+        },
+        size: diff.abs(),
+        price: if cash { 1.0 } else { 0.0 },
+        fee: 0.0,
+        realized_pnl: None,
+        tags,
+        note: Some(note.to_string()),
+        mae: None,
+        instance_id: None,
+        group: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attribution::EntryReason;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    fn wallet() -> Wallet {
+        let mut wallet = Wallet::new(100.0);
+        wallet.open_position("yes".into(), Side::Buy, 10.0, 0.4, 0, EntryReason::ArbLeg);
+        wallet.open_position("no".into(), Side::Buy, 5.0, 0.5, 0, EntryReason::ArbLeg);
+        wallet
+    }
+
+    fn exchange() -> ExchangeBalances {
+        let tokens = [("yes", 12.0), ("no", 2.0), ("other", 3.0)];
+        ExchangeBalances { usdc: 90.0, tokens: tokens.iter().map(|(t, s)| (TokenId::from(*t), *s)).collect() }
+    }
+
+    #[test]
+    fn corrections_survive_a_journal_replay() {
+        // Replaying the journal must rebuild the same wallet: start from an empty journal of the positions
+        let mut journal = Journal::new();
+        let mut wallet = Wallet::new(100.0);
+        for (token, size, price) in [("yes", 10.0, 0.4), ("no", 5.0, 0.5)] {
+            let id = journal.record(JournalEntry {
+                id: 0, timestamp: 0, market_id: MarketId::default(), token_id: token.into(), side: Side::Buy, size, price,
+                fee: 0.0, realized_pnl: None, tags: Vec::new(), note: None, mae: None, instance_id: None, group: None,
+            });
+            manual::apply_entry(&mut wallet, journal.get(id).unwrap());
+        }
+
+        let report = Reconciler::new(0.01, DriftAction::AutoCorrect).reconcile(&mut wallet, &mut journal, &exchange(), &ControlState::new(), 1);
+        assert!(report.corrected);
+        assert_eq!(report.drifts.len(), 4);
+        assert!(close(wallet.usdc.value(), 90.0));
+        assert!(close(wallet.positions["yes"].size, 12.0));
+        assert!(close(wallet.positions["no"].size, 2.0));
+        assert!(close(wallet.positions["other"].size, 3.0));
+        assert!(journal.entries.iter().skip(2).all(|e| e.has_tag(RECONCILE_TAG)));
+
+        let mut replayed = Wallet::new(100.0);
+        manual::replay_journal(&mut replayed, &journal);
+        assert!(close(replayed.usdc.value(), wallet.usdc.value()));
+        for token in ["yes", "no", "other"] {
+            assert!(close(replayed.positions[token].size, wallet.positions[token].size), "{}", token);
+        }
+        assert!(Reconciler::new(0.01, DriftAction::Report).compare(&replayed, &exchange()).is_empty());
+    }
+
+    #[test]
+    fn report_and_halt_leave_the_wallet_alone() {
+        let control = ControlState::new();
+        let mut journal = Journal::new();
+        let mut wallet = wallet();
+
+        let report = Reconciler::new(0.01, DriftAction::Report).reconcile(&mut wallet, &mut journal, &exchange(), &control, 1);
+        assert!(!report.corrected && !report.halted && !control.is_paused());
+
+        let report = Reconciler::new(0.01, DriftAction::Halt).reconcile(&mut wallet, &mut journal, &exchange(), &control, 1);
+        assert!(report.halted && control.is_paused());
+        assert!(journal.entries.is_empty());
+        assert!(close(wallet.positions["yes"].size, 10.0));
+    }
+
+    #[test]
+    fn drift_within_tolerance_is_ignored() {
+        let mut balances = ExchangeBalances { usdc: 100.005, tokens: HashMap::new() };
+        balances.tokens.insert("yes".into(), 10.005);
+        balances.tokens.insert("no".into(), 5.0);
+        assert!(Reconciler::new(0.01, DriftAction::Report).compare(&wallet(), &balances).is_empty());
+    }
+}
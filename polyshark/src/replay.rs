@@ -0,0 +1,147 @@
+use crate::constraint::ConstraintChecker;
+use crate::execution::ExecutionEngine;
+use crate::fees::FeeConfig;
+use crate::journal::JournalEntry;
+use crate::money::Usdc;
+use crate::types::{BookWalk, Market, OrderBook, Price, Side, Size, TokenId};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+pub const TRADE_RECORDS_PATH: &str = "trades.jsonl";
+
+/// Everything needed to re-run a past trade: the opportunity as seen, the books, and the config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeRecord {
+    pub trade_id: u64,  // Journal entry id
+    pub timestamp: u64,
+    pub market: Market,
+    pub token_id: TokenId,
+    pub books: Vec<OrderBook>,  // Traded token first
+    pub side: Side,
+    pub size: Size,
+    pub limit: Option<Price>,
+    pub fees: FeeConfig,
+    pub cash_before: Usdc,
+    #[serde(default)]
+    pub walk: Option<BookWalk>,  // Levels the order was priced across when it was placed
+    #[serde(default)]
+    pub edge: Option<f64>,       // Signal edge per unit, for trades the detector opened
+}
+
+impl TradeRecord {
+    /// Append to a JSON lines file
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(self).map_err(std::io::Error::other)?)
+    }
+
+    /// Every record in the file, skipping lines that no longer parse
+    pub fn load_all(path: &Path) -> std::io::Result<Vec<Self>> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut records = Vec::new();
+        for line in reader.lines() {
+            if let Ok(record) = serde_json::from_str::<TradeRecord>(&line?) {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Find the record for a trade id
+    pub fn load(path: &Path, trade_id: u64) -> std::io::Result<Option<Self>> {
+        let reader = BufReader::new(File::open(path)?);
+        for line in reader.lines() {
+            if let Ok(record) = serde_json::from_str::<TradeRecord>(&line?)
+                && record.trade_id == trade_id
+            {
+                return Ok(Some(record));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Re-run detection and simulated execution for a recorded trade, narrating each step
+pub fn replay(record: &TradeRecord, actual: Option<&JournalEntry>, out: &mut dyn Write) -> std::io::Result<()> {
+    let m = &record.market;
+    writeln!(out, "trade #{} at {} — {}", record.trade_id, record.timestamp, m.question)?;
+    writeln!(out, "  {:?} {} of {} (limit {:?}), cash before {}", record.side, record.size, record.token_id, record.limit, record.cash_before)?;
+
+    writeln!(out, "\n[1] detection")?;
+    writeln!(out, "  yes {:.4} + no {:.4} = {:.4} (spread {:.4})", m.yes_price(), m.no_price(), m.yes_price() + m.no_price(), m.get_spread())?;
+    match ConstraintChecker::new(0.0).check_violation(m) {
+        Some(s) => writeln!(out, "  violation: edge {:.4}/unit, detector says {:?}", s.edge, s.recommended_side)?,
+        None => writeln!(out, "  no YES+NO violation; this was not an arbitrage entry")?,
+    }
+
+    writeln!(out, "\n[2] book")?;
+    let Some(book) = record.books.iter().find(|b| b.token_id == record.token_id) else {
+        return writeln!(out, "  no book recorded for {}", record.token_id);
+    };
+    writeln!(
+        out,
+        "  bid {:?} ask {:?} mid {:?} | depth bids {:.2} asks {:.2}",
+        book.best_bid(), book.best_ask(), book.midpoint(), book.total_bid_liquidity(), book.total_ask_liquidity()
+    )?;
+    let walk = book.walk(record.size.value(), record.side);
+    write_walk(out, &walk)?;
+    if let Some(recorded) = &record.walk
+        && *recorded != walk
+    {
+        writeln!(out, "  recorded walk differs: {} levels, vwap {:?}", recorded.levels.len(), recorded.vwap())?;
+    }
+
+    writeln!(out, "\n[3] simulated execution ({:?})", record.fees)?;
+    let engine = ExecutionEngine::new(record.fees.build());
+    let Some(sim) = engine.simulate(book, record.size, record.side, record.limit.map(|p| p.value())) else {
+        return writeln!(out, "  simulation failed: no liquidity");
+    };
+    writeln!(
+        out,
+        "  filled {:.2} @ {:.4}, slippage {:.2}%, fee {:.4}, total {:.4}",
+        sim.filed_size, sim.execution_price, sim.slippage * 100.0, sim.fee_paid, sim.total_cost
+    )?;
+    if let Some(limit) = record.limit {
+        let ok = match record.side {
+            Side::Buy => sim.execution_price <= limit.value(),
+            Side::Sell => sim.execution_price >= limit.value(),
+        };
+        writeln!(out, "  limit {}: {}", limit, if ok { "respected" } else { "VIOLATED" })?;
+    }
+
+    writeln!(out, "\n[4] outcome")?;
+    match actual {
+        Some(e) => {
+            writeln!(out, "  journal: {:.2} @ {:.4}, fee {:.4}, realized {:?}", e.size, e.price, e.fee, e.realized_pnl)?;
+            writeln!(
+                out,
+                "  price diff vs replay {:+.4}, fee diff {:+.4}",
+                e.price - sim.execution_price,
+                e.fee - sim.fee_paid
+            )?;
+        }
+        None => writeln!(out, "  journal entry not found")?,
+    }
+    Ok(())
+}
+
+/// Per-level fills of a book walk, one line per level
+pub fn write_walk(out: &mut dyn Write, walk: &BookWalk) -> std::io::Result<()> {
+    for level in &walk.levels {
+        writeln!(
+            out,
+            "  walk {:.4} x {:.2}  cum {:.2} for {:.4} (vwap {:.4})",
+            level.price,
+            level.size,
+            level.cumulative_size,
+            level.cumulative_cost,
+            level.cumulative_cost / level.cumulative_size.max(f64::MIN_POSITIVE)
+        )?;
+    }
+    if !walk.is_complete() {
+        writeln!(out, "  book exhausted with {:.2} unfilled", walk.unfilled)?;
+    }
+    Ok(())
+}
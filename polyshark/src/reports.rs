@@ -0,0 +1,335 @@
+use crate::constraint::ConstraintChecker;
+use crate::orders::OrderManager;
+use crate::resolution::ResolutionStatus;
+use crate::types::{Event, Market, MarketId, Side, TokenId, SECONDS_PER_YEAR};
+use crate::wallet::{Position, Wallet};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Capital lock-up for one market's held positions
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AgingRow {
+    pub market_id: MarketId,
+    pub question: String,
+    pub cost_basis: f64,            // USDC paid for the held legs
+    pub complete_sets: f64,         // Matched YES+NO pairs (pay $1 each at resolution)
+    pub expected_payoff: f64,       // Sets at $1 plus unmatched legs at mark
+    pub days_held: f64,
+    pub days_to_resolution: Option<f64>,
+    pub annualized_return: Option<f64>,  // Over entry -> resolution
+    pub recycle: bool,              // Capital better used elsewhere
+    pub disputed: bool,             // Outcome under dispute: payoff and timing uncertain
+}
+
+/// Everything `polyshark aging` reports; its JSON output
+#[derive(Debug, Clone, Serialize)]
+pub struct AgingReport {
+    pub positions: Vec<AgingRow>,
+    pub concentration: Vec<ConcentrationRow>,
+    pub carry: Vec<CarryRow>,
+    pub events: Vec<EventRow>,
+}
+
+/// Complete sets held and expected payoff: sets at $1 plus unmatched legs at mark
+fn expected_payoff(market: &Market, legs: &[&Position], prices: &HashMap<TokenId, f64>) -> (f64, f64) {
+    let complete_sets = if legs.len() == market.clob_token_ids.len() {
+        legs.iter().map(|p| p.size).fold(f64::INFINITY, f64::min)
+    } else {
+        0.0
+    };
+    let unmatched: f64 = legs.iter()
+        .map(|p| (p.size - complete_sets) * prices.get(&p.token_id).copied().unwrap_or(p.entry_price))
+        .sum();
+    (complete_sets, complete_sets + unmatched)
+}
+
+/// Long legs held in a market
+fn held_legs<'a>(wallet: &'a Wallet, market: &Market) -> Vec<&'a Position> {
+    market.clob_token_ids.iter()
+        .filter_map(|t| wallet.positions.get(t))
+        .filter(|p| p.side == Side::Buy)
+        .collect()
+}
+
+/// Build the aging report for every market with open long positions.
+/// `min_annualized` is the hurdle below which a position is flagged for recycling.
+pub fn aging_report(
+    wallet: &Wallet,
+    markets: &[Market],
+    prices: &HashMap<TokenId, f64>,
+    now: u64,
+    min_annualized: f64,
+) -> Vec<AgingRow> {
+    let mut rows = Vec::new();
+
+    for market in markets {
+        let legs = held_legs(wallet, market);
+        if legs.is_empty() {
+            continue;
+        }
+
+        let cost_basis: f64 = legs.iter().map(|p| p.size * p.entry_price).sum();
+        let (complete_sets, expected_payoff) = expected_payoff(market, &legs, prices);
+
+        let entry_time = legs.iter().map(|p| p.entry_time).min().unwrap_or(now);
+        let days_held = now.saturating_sub(entry_time) as f64 / 86400.0;
+        let secs_left = market.seconds_to_resolution(now);
+        let days_to_resolution = secs_left.map(|s| s as f64 / 86400.0);
+
+        // Return over the full holding period, annualized
+        let annualized_return = secs_left.and_then(|left| {
+            let years = (now.saturating_sub(entry_time) + left) as f64 / SECONDS_PER_YEAR;
+            (cost_basis > 0.0 && years > 0.0).then(|| (expected_payoff / cost_basis - 1.0) / years)
+        });
+
+        rows.push(AgingRow {
+            market_id: market.id.clone(),
+            question: market.question.clone(),
+            cost_basis,
+            complete_sets,
+            expected_payoff,
+            days_held,
+            days_to_resolution,
+            annualized_return,
+            recycle: annualized_return.is_some_and(|r| r < min_annualized),
+            disputed: ResolutionStatus::of(market) == ResolutionStatus::Disputed,
+        });
+    }
+
+    // Worst capital efficiency first
+    rows.sort_by(|a, b| {
+        a.annualized_return.unwrap_or(f64::INFINITY)
+            .total_cmp(&b.annualized_return.unwrap_or(f64::INFINITY))
+    });
+    rows
+}
+
+/// Return available elsewhere for the same capital
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alternative {
+    pub name: String,      // e.g., "t-bills"
+    pub annual_rate: f64,  // 0.045 = 4.5%
+}
+
+/// Alternatives that hold-to-resolution positions must beat
+#[derive(Debug, Clone, Deserialize)]
+pub struct CarryConfig {
+    pub alternatives: Vec<Alternative>,
+    #[serde(default)]
+    pub min_excess: f64,  // Required annualized margin over the best alternative
+}
+
+impl Default for CarryConfig {
+    fn default() -> Self {
+        Self {
+            alternatives: vec![Alternative { name: "risk-free".to_string(), annual_rate: 0.045 }],
+            min_excess: 0.0,
+        }
+    }
+}
+
+/// Forward carry of one market's held legs versus the best alternative
+#[derive(Debug, Clone, Serialize)]
+pub struct CarryRow {
+    pub market_id: MarketId,
+    pub question: String,
+    pub exit_value: f64,        // What selling into the bids would raise now
+    pub expected_payoff: f64,   // What holding to resolution pays: $1 per complete set, mark per unmatched share
+    pub days_to_resolution: f64,
+    pub carry: f64,             // Annualized return from holding on, starting from exit value
+    pub alternative: Alternative,
+    pub excess: f64,            // carry - alternative rate
+    pub exit_recommended: bool,
+}
+
+/// Compare holding each position to resolution against selling now and earning the best alternative.
+/// Unmatched legs are expected to pay their mark (the market's probability) but only raise
+/// the bid when sold, so their carry is the spread given up by exiting early. Legs without a
+/// bid are valued at mark. Markets without a known resolution date or mark prices are skipped.
+pub fn carry_report(
+    wallet: &Wallet,
+    markets: &[Market],
+    prices: &HashMap<TokenId, f64>,
+    bids: &HashMap<TokenId, f64>,
+    now: u64,
+    config: &CarryConfig,
+) -> Vec<CarryRow> {
+    let Some(best) = config.alternatives.iter().max_by(|a, b| a.annual_rate.total_cmp(&b.annual_rate)) else {
+        return Vec::new();
+    };
+
+    let mut rows: Vec<CarryRow> = markets.iter()
+        .filter_map(|market| {
+            let legs = held_legs(wallet, market);
+            if legs.is_empty() || legs.iter().any(|p| !prices.contains_key(&p.token_id)) {
+                return None;
+            }
+            let secs_left = market.seconds_to_resolution(now).filter(|s| *s > 0)?;
+            let exit_value: f64 = legs.iter()
+                .map(|p| p.size * bids.get(&p.token_id).copied().unwrap_or(prices[&p.token_id]))
+                .sum();
+            let (_, expected_payoff) = expected_payoff(market, &legs, prices);
+            if exit_value <= 0.0 {
+                return None;
+            }
+
+            let carry = (expected_payoff / exit_value - 1.0) / (secs_left as f64 / SECONDS_PER_YEAR);
+            let excess = carry - best.annual_rate;
+            Some(CarryRow {
+                market_id: market.id.clone(),
+                question: market.question.clone(),
+                exit_value,
+                expected_payoff,
+                days_to_resolution: secs_left as f64 / 86400.0,
+                carry,
+                alternative: best.clone(),
+                excess,
+                exit_recommended: excess < config.min_excess,
+            })
+        })
+        .collect();
+
+    // Poorest carry first
+    rows.sort_by(|a, b| a.excess.total_cmp(&b.excess));
+    rows
+}
+
+/// Exposure and pricing of one Gamma event, across all of its markets
+#[derive(Debug, Clone, Serialize)]
+pub struct EventRow {
+    pub event_id: String,          // The market id for markets without a parent event
+    pub title: String,             // Event title; first market's question when the event wasn't fetched
+    pub market_ids: Vec<MarketId>,
+    pub cost_basis: f64,           // USDC paid for held legs across the event
+    pub mark_value: f64,           // Held legs at mark
+    pub implied_sum: f64,          // Sum of YES prices; ~1 when outcomes are mutually exclusive
+    pub exclusive: bool,           // NegRisk event: exactly one market resolves YES
+    pub binary_arbs: usize,        // Markets whose own YES + NO misprice
+    pub set_spread: Option<f64>,   // Mispricing of the whole set (exclusive events only)
+}
+
+impl EventRow {
+    pub fn has_arb(&self) -> bool {
+        self.binary_arbs > 0 || self.set_spread.is_some()
+    }
+}
+
+/// Group markets by parent event with exposure, combined implied probability and arb status.
+/// `events` supplies titles and exclusivity; markets of events not in it fall back to
+/// their own `event_id` and `neg_risk` flags. Events are listed largest exposure first,
+/// then by mispricing.
+pub fn event_report(
+    wallet: &Wallet,
+    markets: &[Market],
+    events: &[Event],
+    prices: &HashMap<TokenId, f64>,
+    checker: &ConstraintChecker,
+) -> Vec<EventRow> {
+    let mut groups: Vec<(String, Vec<&Market>)> = Vec::new();
+    for market in markets {
+        let key = market.event_id.clone().unwrap_or_else(|| market.id.to_string());
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, group)) => group.push(market),
+            None => groups.push((key, vec![market])),
+        }
+    }
+
+    let mut rows: Vec<EventRow> = groups.into_iter()
+        .map(|(event_id, group)| {
+            let legs: Vec<&Position> = group.iter().flat_map(|m| held_legs(wallet, m)).collect();
+            let event = events.iter().find(|e| e.id == event_id);
+            let exclusive = match event {
+                Some(event) => event.is_exclusive(),
+                None => group.len() > 1 && group.iter().all(|m| m.neg_risk),
+            };
+            let set_spread = if exclusive {
+                checker.check_categorical(&event_id, &group).map(|s| s.spread)
+            } else {
+                None
+            };
+            EventRow {
+                title: event.map(|e| e.title.clone()).unwrap_or_else(|| group[0].question.clone()),
+                market_ids: group.iter().map(|m| m.id.clone()).collect(),
+                cost_basis: legs.iter().map(|p| p.size * p.entry_price).sum(),
+                mark_value: legs.iter().map(|p| p.size * prices.get(&p.token_id).copied().unwrap_or(p.entry_price)).sum(),
+                implied_sum: group.iter().map(|m| m.yes_price()).sum(),
+                exclusive,
+                binary_arbs: group.iter().filter(|m| checker.check_violation(m).is_some()).count(),
+                set_spread,
+                event_id,
+            }
+        })
+        .collect();
+
+    rows.sort_by(|a, b| {
+        b.cost_basis.total_cmp(&a.cost_basis)
+            .then_with(|| b.set_spread.unwrap_or(0.0).total_cmp(&a.set_spread.unwrap_or(0.0)))
+    });
+    rows
+}
+
+/// How much of total equity sits in one market
+#[derive(Debug, Clone, Serialize)]
+pub struct ConcentrationRow {
+    pub market_id: MarketId,
+    pub question: String,
+    pub position_value: f64,  // Held legs at mark
+    pub locked: f64,          // Resting buy orders on the market's tokens
+    pub share: f64,           // (position_value + locked) / total equity
+    pub alert: bool,          // Share at or above the warning level
+}
+
+impl ConcentrationRow {
+    pub fn exposure(&self) -> f64 {
+        self.position_value + self.locked
+    }
+}
+
+/// Total equity for concentration: cash (locked funds included), positions at mark and
+/// settlements still to be credited
+pub fn total_equity(wallet: &Wallet, prices: &HashMap<TokenId, f64>, pending_settlements: f64) -> f64 {
+    let positions: f64 = wallet.positions.values()
+        .map(|p| p.size * prices.get(&p.token_id).copied().unwrap_or(p.entry_price))
+        .sum();
+    wallet.usdc.value() + positions + pending_settlements
+}
+
+/// Each market's share of total equity, largest first. `alert_share` is the early-warning
+/// level (below the hard per-market limit in the risk module).
+pub fn concentration_report(
+    wallet: &Wallet,
+    orders: &OrderManager,
+    markets: &[Market],
+    prices: &HashMap<TokenId, f64>,
+    pending_settlements: f64,
+    alert_share: f64,
+) -> Vec<ConcentrationRow> {
+    let equity = total_equity(wallet, prices, pending_settlements);
+    let mut rows: Vec<ConcentrationRow> = markets.iter()
+        .filter_map(|market| {
+            let position_value: f64 = market.clob_token_ids.iter()
+                .filter_map(|t| wallet.positions.get(t))
+                .map(|p| p.size * prices.get(&p.token_id).copied().unwrap_or(p.entry_price))
+                .sum();
+            let locked: f64 = market.clob_token_ids.iter()
+                .flat_map(|t| orders.orders_for(t))
+                .filter(|o| o.side == Side::Buy)
+                .map(|o| o.remaining() * o.price)
+                .sum();
+            if position_value + locked <= 0.0 {
+                return None;
+            }
+            let share = if equity > 0.0 { (position_value + locked) / equity } else { 1.0 };
+            Some(ConcentrationRow {
+                market_id: market.id.clone(),
+                question: market.question.clone(),
+                position_value,
+                locked,
+                share,
+                alert: share >= alert_share,
+            })
+        })
+        .collect();
+    rows.sort_by(|a, b| b.share.total_cmp(&a.share));
+    rows
+}
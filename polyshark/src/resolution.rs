@@ -0,0 +1,158 @@
+use crate::types::{Market, MarketId, TokenId};
+use crate::wallet::Wallet;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Journal tag on the sells that redeem a resolved position
+pub const SETTLEMENT_TAG: &str = "settlement";
+
+/// Where a market is in UMA's optimistic resolution: an outcome is proposed, can be
+/// disputed during the challenge window, and only then becomes final
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionStatus {
+    Open,      // Still trading
+    Closed,    // Trading stopped, no outcome proposed yet
+    Proposed,  // Outcome proposed, challenge window running
+    Disputed,  // Proposal challenged: the outcome may still change
+    Final,     // Settled; payouts can be booked
+}
+
+impl ResolutionStatus {
+    /// Read from Gamma's `umaResolutionStatus`, falling back to the market's own flags
+    /// when the status is missing
+    pub fn of(market: &Market) -> Self {
+        let uma = market.uma_resolution_status.as_deref().unwrap_or("").to_ascii_lowercase();
+        if uma.contains("disput") {
+            ResolutionStatus::Disputed
+        } else if uma == "resolved" {
+            ResolutionStatus::Final
+        } else if uma.contains("propos") {
+            ResolutionStatus::Proposed
+        } else if market.active && !market.closed {
+            ResolutionStatus::Open
+        } else if uma.is_empty() && decided(market) {
+            // Old markets settled before Gamma reported UMA status
+            ResolutionStatus::Final
+        } else {
+            ResolutionStatus::Closed
+        }
+    }
+}
+
+/// Every outcome price is pinned to 0 or 1
+fn decided(market: &Market) -> bool {
+    !market.outcome_prices.is_empty()
+        && market.outcome_prices.iter().all(|p| *p <= 0.001 || *p >= 0.999)
+}
+
+/// A market whose resolution status moved
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolutionChange {
+    pub market_id: MarketId,
+    pub from: Option<ResolutionStatus>,
+    pub to: ResolutionStatus,
+}
+
+/// Cash that held positions will (or might) return once their markets settle
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SettlementOutlook {
+    pub incoming: f64,                  // Payouts of proposed, undisputed outcomes
+    pub disputed: f64,                  // Payouts held up by a dispute: not counted on
+    pub disputed_markets: Vec<MarketId>,
+}
+
+#[derive(Debug, Clone)]
+struct Tracked {
+    status: ResolutionStatus,
+    payouts: Vec<(TokenId, f64)>,  // USDC per share of each outcome token
+}
+
+/// Follows each held market through proposal, dispute and finality. Settlement credit is
+/// only booked once the outcome is final; a proposal can still be overturned.
+#[derive(Debug, Clone, Default)]
+pub struct ResolutionMonitor {
+    markets: HashMap<MarketId, Tracked>,
+    settled: HashSet<MarketId>,
+}
+
+impl ResolutionMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record fresh Gamma metadata; returns the change if the status moved
+    pub fn observe(&mut self, market: &Market) -> Option<ResolutionChange> {
+        let to = ResolutionStatus::of(market);
+        let payouts = market.clob_token_ids.iter().cloned().zip(market.outcome_prices.iter().copied()).collect();
+        let from = self.markets.insert(market.id.clone(), Tracked { status: to, payouts }).map(|t| t.status);
+        (from != Some(to)).then(|| ResolutionChange { market_id: market.id.clone(), from, to })
+    }
+
+    pub fn status(&self, market_id: &MarketId) -> Option<ResolutionStatus> {
+        self.markets.get(market_id).map(|t| t.status)
+    }
+
+    /// USDC per share of each outcome token at the last observed status
+    pub fn payouts(&self, market_id: &MarketId) -> &[(TokenId, f64)] {
+        self.markets.get(market_id).map_or(&[], |t| t.payouts.as_slice())
+    }
+
+    pub fn is_disputed(&self, market_id: &MarketId) -> bool {
+        self.status(market_id) == Some(ResolutionStatus::Disputed)
+    }
+
+    /// Markets currently under dispute
+    pub fn disputed(&self) -> Vec<&str> {
+        self.markets.iter()
+            .filter(|(_, t)| t.status == ResolutionStatus::Disputed)
+            .map(|(id, _)| id.as_str())
+            .collect()
+    }
+
+    /// Book payouts for every held outcome of a final market, once. Returns realized PnL,
+    /// or `None` if the market isn't final yet or was already settled.
+    pub fn settle(&mut self, wallet: &mut Wallet, market_id: &MarketId, now: u64) -> Option<f64> {
+        let tracked = self.markets.get(market_id)?;
+        if tracked.status != ResolutionStatus::Final || self.settled.contains(market_id) {
+            return None;
+        }
+        let pnl = tracked.payouts.iter()
+            .filter_map(|(token, payout)| wallet.settle_position(token, *payout, now))
+            .sum();
+        self.settled.insert(market_id.clone());
+        Some(pnl)
+    }
+
+    /// Settle every final market not yet settled; returns (market, realized PnL) pairs
+    pub fn settle_all(&mut self, wallet: &mut Wallet, now: u64) -> Vec<(MarketId, f64)> {
+        let ready: Vec<MarketId> = self.markets.iter()
+            .filter(|(id, t)| t.status == ResolutionStatus::Final && !self.settled.contains(*id))
+            .map(|(id, _)| id.clone())
+            .collect();
+        ready.into_iter()
+            .filter_map(|id| self.settle(wallet, &id, now).map(|pnl| (id, pnl)))
+            .collect()
+    }
+
+    /// What held positions in proposed and disputed markets would pay at the current
+    /// proposal. Only undisputed proposals count as incoming.
+    pub fn outlook(&self, wallet: &Wallet) -> SettlementOutlook {
+        let mut outlook = SettlementOutlook::default();
+        for (id, tracked) in &self.markets {
+            let payout: f64 = tracked.payouts.iter()
+                .filter_map(|(token, price)| wallet.positions.get(token).map(|p| p.size * price))
+                .sum();
+            match tracked.status {
+                ResolutionStatus::Proposed => outlook.incoming += payout,
+                ResolutionStatus::Disputed if tracked.payouts.iter().any(|(t, _)| wallet.positions.contains_key(t)) => {
+                    outlook.disputed += payout;
+                    outlook.disputed_markets.push(id.clone());
+                }
+                _ => {}
+            }
+        }
+        outlook.disputed_markets.sort();
+        outlook
+    }
+}
@@ -0,0 +1,125 @@
+use crate::orders::OpenOrder;
+use crate::types::{format_date, Market, MarketId, OrderBook, Side, TokenId};
+use std::collections::{BTreeMap, HashMap};
+
+/// Outside this midpoint range, only two-sided quoting earns rewards
+const SINGLE_SIDED_BAND: (f64, f64) = (0.10, 0.90);
+
+/// Single-sided quotes score at a third of two-sided ones
+const SINGLE_SIDED_FACTOR: f64 = 1.0 / 3.0;
+
+/// One day of samples for one market
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DayCompliance {
+    pub samples: u32,
+    pub qualified: u32,   // Samples earning anything (two-sided, or one-sided where allowed)
+    pub two_sided: u32,
+    pub score: f64,       // Sum of per-sample scores (Polymarket's quadratic spread scoring)
+}
+
+/// Reported qualification for one market on one UTC day
+#[derive(Debug, Clone)]
+pub struct ComplianceRow {
+    pub market_id: MarketId,
+    pub date: String,
+    pub samples: u32,
+    pub qualified_pct: f64,
+    pub two_sided_pct: f64,
+    pub avg_score: f64,
+}
+
+/// Samples our resting quotes against each market's reward band and estimates
+/// how much of each day we actually qualified for maker rewards
+#[derive(Debug, Clone, Default)]
+pub struct RewardTracker {
+    days: BTreeMap<(MarketId, u64), DayCompliance>,  // (market, UTC day number) -> totals
+}
+
+impl RewardTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take one sample for a market; markets without a reward band are ignored.
+    /// NO-token orders count on the opposite side of the YES book (a NO bid at p is a YES ask at 1 - p).
+    pub fn sample(&mut self, market: &Market, books: &HashMap<TokenId, OrderBook>, orders: &[OpenOrder], now_ms: u64) {
+        let (Some(max_spread), [yes, no]) = (market.rewards_max_spread, market.clob_token_ids.as_slice()) else {
+            return;
+        };
+        let Some(mid) = books.get(yes).and_then(OrderBook::midpoint) else {
+            return;
+        };
+        let min_size = market.rewards_min_size.unwrap_or(0.0);
+
+        let (mut bid_score, mut ask_score) = (0.0, 0.0);
+        for order in orders.iter().filter(|o| o.remaining() >= min_size && o.remaining() > 0.0) {
+            let (yes_side, yes_price) = if order.token_id == *yes {
+                (order.side, order.price)
+            } else if order.token_id == *no {
+                (opposite(order.side), 1.0 - order.price)
+            } else {
+                continue;
+            };
+            let distance = (yes_price - mid).abs();
+            if distance > max_spread {
+                continue;
+            }
+            let score = ((max_spread - distance) / max_spread).powi(2) * order.remaining();
+            match yes_side {
+                Side::Buy => bid_score += score,
+                Side::Sell => ask_score += score,
+            }
+        }
+
+        let two_sided = bid_score > 0.0 && ask_score > 0.0;
+        let one_sided_allowed = mid >= SINGLE_SIDED_BAND.0 && mid <= SINGLE_SIDED_BAND.1;
+        let score = if one_sided_allowed {
+            // Reward the balanced part fully, the lopsided remainder at the single-sided rate
+            bid_score.min(ask_score).max(bid_score.max(ask_score) * SINGLE_SIDED_FACTOR)
+        } else {
+            bid_score.min(ask_score)
+        };
+
+        let day = self.days.entry((market.id.clone(), now_ms / 1000 / 86_400)).or_default();
+        day.samples += 1;
+        if score > 0.0 {
+            day.qualified += 1;
+        }
+        if two_sided {
+            day.two_sided += 1;
+        }
+        day.score += score;
+    }
+
+    /// Per market per day, oldest day first
+    pub fn report(&self) -> Vec<ComplianceRow> {
+        let mut rows: Vec<ComplianceRow> = self.days.iter()
+            .map(|((market_id, day), d)| {
+                let pct = |n: u32| if d.samples == 0 { 0.0 } else { n as f64 / d.samples as f64 * 100.0 };
+                ComplianceRow {
+                    market_id: market_id.clone(),
+                    date: format_date(day * 86_400),
+                    samples: d.samples,
+                    qualified_pct: pct(d.qualified),
+                    two_sided_pct: pct(d.two_sided),
+                    avg_score: if d.samples == 0 { 0.0 } else { d.score / d.samples as f64 },
+                }
+            })
+            .collect();
+        rows.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.market_id.cmp(&b.market_id)));
+        rows
+    }
+
+    /// Drop days before `keep_from_ms`
+    pub fn prune(&mut self, keep_from_ms: u64) {
+        let first_day = keep_from_ms / 1000 / 86_400;
+        self.days.retain(|(_, day), _| *day >= first_day);
+    }
+}
+
+fn opposite(side: Side) -> Side {
+    match side {
+        Side::Buy => Side::Sell,
+        Side::Sell => Side::Buy,
+    }
+}
@@ -0,0 +1,477 @@
+use crate::bus::{EventBus, RiskEvent};
+use crate::config::RiskLimits;
+use crate::control::{ControlCommand, ControlState};
+use crate::journal::{Journal, JournalEntry, JournalFilter};
+use crate::reports::ConcentrationRow;
+use crate::types::{Market, MarketId, TokenId};
+use crate::wallet::Wallet;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+/// A trading policy that was broken (or would have been)
+#[derive(Debug, Clone, PartialEq)]
+pub enum RiskViolation {
+    /// Sell larger than held inventory; Polymarket has no margin shorting
+    NakedSell { token_id: TokenId, requested: f64, held: f64 },
+    /// Trade would push portfolio expected shortfall past its share of equity
+    ExpectedShortfall { market_id: MarketId, es: f64, max: f64 },
+    /// Today's fees already exceed the daily budget
+    FeeBudget { spent: f64, budget: f64 },
+}
+
+impl fmt::Display for RiskViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NakedSell { token_id, requested, held } => write!(f, "cannot sell {} shares of {}, only {} held", requested, token_id, held),
+            Self::ExpectedShortfall { market_id, es, max } => write!(f, "expected shortfall on {} would reach {:.2} (max {:.2})", market_id, es, max),
+            Self::FeeBudget { spent, budget } => write!(f, "fee budget spent: {:.2} of {:.2} USDC today", spent, budget),
+        }
+    }
+}
+
+/// Records and alerts on policy violations
+#[derive(Debug, Default)]
+pub struct RiskMonitor {
+    pub violations: Vec<RiskViolation>,
+    pub bus: Option<EventBus>,
+}
+
+impl RiskMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_bus(bus: EventBus) -> Self {
+        Self { violations: Vec::new(), bus: Some(bus) }
+    }
+
+    /// Check a sell against held inventory; an attempted naked sell is recorded and alerted
+    pub fn check_sell(&mut self, wallet: &Wallet, token_id: &TokenId, size: f64) -> Result<(), RiskViolation> {
+        let held = wallet.held(token_id);
+        if size <= held + 1e-9 {
+            return Ok(());
+        }
+        let violation = RiskViolation::NakedSell { token_id: token_id.clone(), requested: size, held };
+        self.record(violation.clone());
+        Err(violation)
+    }
+
+    pub fn record(&mut self, violation: RiskViolation) {
+        match &violation {
+            RiskViolation::NakedSell { token_id, requested, held } => {
+                eprintln!("⚠️  naked sell attempted on {}: {} requested, {} held", token_id, requested, held);
+                if let Some(bus) = &self.bus {
+                    bus.publish_risk(RiskEvent::NakedSell { token_id: token_id.clone(), requested: *requested, held: *held });
+                }
+            }
+            RiskViolation::ExpectedShortfall { market_id, es, max } => {
+                eprintln!("⚠️  trade on {} rejected: expected shortfall {:.2} > {:.2}", market_id, es, max);
+                if let Some(bus) = &self.bus {
+                    bus.publish_risk(RiskEvent::LimitBreached { limit: "expected_shortfall".to_string(), value: *es, max: *max });
+                }
+            }
+            RiskViolation::FeeBudget { spent, budget } => {
+                eprintln!("⚠️  fee budget spent: {:.2} of {:.2} USDC today", spent, budget);
+                if let Some(bus) = &self.bus {
+                    bus.publish_risk(RiskEvent::LimitBreached { limit: "daily_fee_budget".to_string(), value: *spent, max: *budget });
+                }
+            }
+        }
+        self.violations.push(violation);
+    }
+}
+
+/// Why the circuit breaker paused trading
+#[derive(Debug, Clone)]
+pub struct BreakerTrip {
+    pub timestamp: u64,
+    pub peak_pnl: f64,              // Highest PnL inside the window
+    pub current_pnl: f64,
+    pub drop: f64,                  // peak - current
+    pub trades: Vec<JournalEntry>,  // Fills inside the window
+}
+
+/// Pauses new entries when realized + unrealized PnL falls more than `max_drop`
+/// within `window_secs`. It never resumes on its own: an operator must POST /resume.
+#[derive(Debug, Clone)]
+pub struct PnlCircuitBreaker {
+    pub max_drop: f64,     // USDC
+    pub window_secs: u64,
+    samples: VecDeque<(u64, f64)>,  // (timestamp, total PnL)
+    tripped: bool,
+}
+
+impl PnlCircuitBreaker {
+    pub fn new(max_drop: f64, window_secs: u64) -> Self {
+        Self { max_drop, window_secs, samples: VecDeque::new(), tripped: false }
+    }
+
+    pub fn is_tripped(&self) -> bool {
+        self.tripped
+    }
+
+    /// Feed the current total PnL; pauses `control` and returns the trip when the swing is too large
+    pub fn observe(&mut self, now: u64, total_pnl: f64, journal: &Journal, control: &ControlState) -> Option<BreakerTrip> {
+        if self.tripped {
+            if control.is_paused() {
+                return None;
+            }
+            // Operator resumed: start a fresh window so the old drop doesn't re-trip us
+            self.tripped = false;
+            self.samples.clear();
+        }
+
+        self.samples.push_back((now, total_pnl));
+        let since = now.saturating_sub(self.window_secs);
+        while self.samples.front().is_some_and(|(t, _)| *t < since) {
+            self.samples.pop_front();
+        }
+
+        let peak_pnl = self.samples.iter().map(|(_, p)| *p).fold(f64::NEG_INFINITY, f64::max);
+        let drop = peak_pnl - total_pnl;
+        if drop <= self.max_drop {
+            return None;
+        }
+
+        self.tripped = true;
+        control.apply(ControlCommand::Pause);
+        let filter = JournalFilter { since: Some(since), ..Default::default() };
+        Some(BreakerTrip {
+            timestamp: now,
+            peak_pnl,
+            current_pnl: total_pnl,
+            drop,
+            trades: journal.filter(&filter).cloned().collect(),
+        })
+    }
+}
+
+impl RiskMonitor {
+    /// Alert the operator about a breaker trip, listing the trades involved
+    pub fn alert_breaker(&self, trip: &BreakerTrip) {
+        eprintln!(
+            "🛑 circuit breaker: PnL fell {:.2} ({:.2} -> {:.2}); new entries paused until POST /resume",
+            trip.drop, trip.peak_pnl, trip.current_pnl
+        );
+        for t in &trip.trades {
+            eprintln!("   #{} {} {:?} {:.2} @ {:.4} pnl {:?}", t.id, t.market_id, t.side, t.size, t.price, t.realized_pnl);
+        }
+        if let Some(bus) = &self.bus {
+            bus.publish_risk(RiskEvent::CircuitBreaker {
+                drop: trip.drop,
+                trade_ids: trip.trades.iter().map(|t| t.id).collect(),
+            });
+        }
+    }
+}
+
+/// Early warning when one market grows to too large a share of equity, well before the
+/// hard per-market limit stops new entries. A market alerts once, then again only after
+/// it has dropped back under the level.
+#[derive(Debug, Default)]
+pub struct ConcentrationMonitor {
+    pub alert_share: f64,
+    alerted: HashSet<MarketId>,
+    bus: Option<EventBus>,
+}
+
+impl ConcentrationMonitor {
+    pub fn new(alert_share: f64) -> Self {
+        Self { alert_share, ..Default::default() }
+    }
+
+    pub fn with_bus(mut self, bus: EventBus) -> Self {
+        self.bus = Some(bus);
+        self
+    }
+
+    /// Check a fresh concentration report; returns the markets newly over the level
+    pub fn check<'a>(&mut self, rows: &'a [ConcentrationRow]) -> Vec<&'a ConcentrationRow> {
+        let over: HashSet<&MarketId> = rows.iter().filter(|r| r.share >= self.alert_share).map(|r| &r.market_id).collect();
+        self.alerted.retain(|id| over.contains(id));
+        let mut raised = Vec::new();
+        for row in rows.iter().filter(|r| r.share >= self.alert_share) {
+            if !self.alerted.insert(row.market_id.clone()) {
+                continue;
+            }
+            eprintln!(
+                "⚠️  concentration: {} is {:.1}% of equity ({:.2} USDC), warning level {:.1}%",
+                row.market_id, row.share * 100.0, row.exposure(), self.alert_share * 100.0
+            );
+            if let Some(bus) = &self.bus {
+                bus.publish_risk(RiskEvent::Concentration { market_id: row.market_id.clone(), share: row.share, alert_share: self.alert_share });
+            }
+            raised.push(row);
+        }
+        raised
+    }
+}
+
+/// What is still allowed once the daily fee budget is spent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeBudgetMode {
+    #[default]
+    MakerOnly,  // Passive orders only (no taker fees)
+    Halt,       // No new trades at all
+}
+
+/// Caps fees paid per UTC day so a churning strategy can't burn capital into fees.
+/// Spend is summed from today's journal fees, so a restart doesn't reopen the budget.
+#[derive(Debug, Clone)]
+pub struct FeeBudget {
+    pub daily_budget: f64,
+    pub mode: FeeBudgetMode,
+    reported_day: Option<u64>,  // Day whose breach was already alerted
+}
+
+impl FeeBudget {
+    pub fn new(daily_budget: f64, mode: FeeBudgetMode) -> Self {
+        Self { daily_budget, mode, reported_day: None }
+    }
+
+    /// Budget from the profile limits, if one is configured
+    pub fn from_limits(limits: &RiskLimits) -> Option<Self> {
+        limits.daily_fee_budget.map(|budget| Self::new(budget, limits.fee_budget_mode))
+    }
+
+    /// Fees journaled since the last UTC midnight
+    pub fn spent_today(&self, journal: &Journal, now: u64) -> f64 {
+        let day = now / 86_400;
+        journal.entries.iter().filter(|e| e.timestamp / 86_400 == day).map(|e| e.fee).sum()
+    }
+
+    /// Check a new order; `maker` orders still pass in `MakerOnly` mode once the budget is spent
+    pub fn check(&self, journal: &Journal, now: u64, maker: bool) -> Result<(), RiskViolation> {
+        let spent = self.spent_today(journal, now);
+        if spent < self.daily_budget || (maker && self.mode == FeeBudgetMode::MakerOnly) {
+            return Ok(());
+        }
+        Err(RiskViolation::FeeBudget { spent, budget: self.daily_budget })
+    }
+
+    /// True the first time it's asked on a day, so a spent budget alerts once rather than
+    /// on every refused order
+    pub fn report_breach(&mut self, now: u64) -> bool {
+        let day = now / 86_400;
+        self.reported_day.replace(day) != Some(day)
+    }
+}
+
+/// Atoms kept per loss distribution before nearby losses are merged
+const MAX_ATOMS: usize = 2000;
+
+/// Expected shortfall of held outcome tokens over resolution scenarios.
+///
+/// Every market resolves to exactly one outcome with its market-implied probability.
+/// Markets in the same correlation group (configured, else the parent event) share one
+/// draw, so they resolve "in the same direction" together; groups are independent.
+#[derive(Debug, Clone)]
+pub struct ShortfallModel {
+    pub confidence: f64,
+    pub max_fraction: f64,
+    pub groups: HashMap<MarketId, String>,  // market_id -> group
+}
+
+impl ShortfallModel {
+    pub fn from_limits(limits: &RiskLimits) -> Self {
+        let groups = limits.correlation_groups.iter()
+            .flat_map(|(group, ids)| ids.iter().map(move |id| (MarketId::from(id.as_str()), group.clone())))
+            .collect();
+        Self { confidence: limits.es_confidence, max_fraction: limits.max_es_fraction, groups }
+    }
+
+    fn group_of<'a>(&'a self, market: &'a Market) -> &'a str {
+        self.groups.get(&market.id).map(String::as_str)
+            .or(market.event_id.as_deref())
+            .unwrap_or(&market.id)
+    }
+
+    /// Expected loss (versus marking at `prices`) in the worst `1 - confidence` of scenarios.
+    /// `extra` adds hypothetical holdings: (token_id, size). Holdings whose market isn't in
+    /// `markets` still count: each resolves on its own, winning with its mark (entry price
+    /// when unmarked) as probability.
+    pub fn expected_shortfall(&self, wallet: &Wallet, markets: &[Market], prices: &HashMap<TokenId, f64>, extra: &[(TokenId, f64)]) -> f64 {
+        let mut holdings: HashMap<&str, f64> = wallet.positions.keys()
+            .map(|token_id| (token_id.as_str(), wallet.held(token_id)))
+            .collect();
+        for (token_id, size) in extra {
+            *holdings.entry(token_id).or_default() += size;
+        }
+
+        let mut groups: HashMap<&str, Vec<&Market>> = HashMap::new();
+        for m in markets.iter().filter(|m| m.clob_token_ids.iter().any(|t| holdings.contains_key(t.as_str()))) {
+            groups.entry(self.group_of(m)).or_default().push(m);
+        }
+
+        let mut total = vec![(0.0, 1.0)];
+        for members in groups.values() {
+            let group = group_losses(members, &holdings, prices);
+            total = merge_atoms(convolve(&total, &group));
+        }
+        let covered: HashSet<&str> = markets.iter().flat_map(|m| m.clob_token_ids.iter().map(|t| t.as_str())).collect();
+        for (token_id, size) in holdings.iter().filter(|(t, size)| !covered.contains(*t) && **size > 0.0) {
+            let p = prices.get(*token_id).copied()
+                .or_else(|| wallet.positions.get(*token_id).map(|pos| pos.entry_price))
+                .unwrap_or(0.0)
+                .clamp(0.0, 1.0);
+            let mark = size * p;
+            total = merge_atoms(convolve(&total, &[(mark - size, p), (mark, 1.0 - p)]));
+        }
+        tail_mean(total, self.confidence)
+    }
+
+    /// Reject a purchase of `legs` (token_id, size) if it would push ES past the cap
+    pub fn check(&self, wallet: &Wallet, markets: &[Market], prices: &HashMap<TokenId, f64>, legs: &[(TokenId, f64)]) -> Result<f64, RiskViolation> {
+        let es = self.expected_shortfall(wallet, markets, prices, legs);
+        let max = self.max_fraction * wallet.equity(prices);
+        if es <= max {
+            return Ok(es);
+        }
+        let token_id = legs.first().map(|(t, _)| t.clone()).unwrap_or_default();
+        let market_id = markets.iter()
+            .find(|m| m.clob_token_ids.contains(&token_id))
+            .map(|m| m.id.clone())
+            .unwrap_or_else(|| token_id.as_str().into());
+        Err(RiskViolation::ExpectedShortfall { market_id, es, max })
+    }
+}
+
+/// Loss distribution of one group. A shared uniform draw `u` picks each market's winner
+/// by walking its outcomes' cumulative probabilities, so the loss is piecewise constant in `u`.
+fn group_losses(members: &[&Market], holdings: &HashMap<&str, f64>, prices: &HashMap<TokenId, f64>) -> Vec<(f64, f64)> {
+    // (held size, probability) per outcome, probabilities normalized per market
+    let outcomes: Vec<Vec<(f64, f64)>> = members.iter()
+        .map(|m| {
+            let raw: Vec<(f64, f64)> = m.clob_token_ids.iter().enumerate()
+                .map(|(i, t)| {
+                    let price = prices.get(t).copied()
+                        .or_else(|| m.outcome_prices.get(i).copied())
+                        .unwrap_or(0.0);
+                    (holdings.get(t.as_str()).copied().unwrap_or(0.0), price)
+                })
+                .collect();
+            let sum: f64 = raw.iter().map(|(_, p)| p).sum();
+            raw.into_iter().map(|(size, p)| (size, if sum > 0.0 { p / sum } else { 0.0 })).collect()
+        })
+        .collect();
+
+    let mut cuts: Vec<f64> = outcomes.iter()
+        .flat_map(|o| o.iter().scan(0.0, |acc, (_, p)| { *acc += p; Some(*acc) }))
+        .filter(|c| *c > 0.0 && *c < 1.0)
+        .collect();
+    cuts.push(0.0);
+    cuts.push(1.0);
+    cuts.sort_by(f64::total_cmp);
+    cuts.dedup_by(|a, b| (*a - *b).abs() < 1e-12);
+
+    let mark: f64 = outcomes.iter().flatten().map(|(size, p)| size * p).sum();
+    cuts.windows(2)
+        .map(|w| {
+            let u = (w[0] + w[1]) / 2.0;
+            let payout: f64 = outcomes.iter()
+                .map(|o| {
+                    let mut acc = 0.0;
+                    o.iter().find(|(_, p)| { acc += p; u < acc }).map(|(size, _)| *size).unwrap_or(0.0)
+                })
+                .sum();
+            (mark - payout, w[1] - w[0])
+        })
+        .collect()
+}
+
+/// Distribution of the sum of two independent losses
+fn convolve(a: &[(f64, f64)], b: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    a.iter().flat_map(|(la, pa)| b.iter().map(move |(lb, pb)| (la + lb, pa * pb))).collect()
+}
+
+/// Sort by loss and, past `MAX_ATOMS`, merge neighbours into equal-width buckets
+fn merge_atoms(mut atoms: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    atoms.sort_by(|a, b| a.0.total_cmp(&b.0));
+    if atoms.len() <= MAX_ATOMS {
+        return atoms;
+    }
+    let (lo, hi) = (atoms[0].0, atoms[atoms.len() - 1].0);
+    let width = (hi - lo) / MAX_ATOMS as f64;
+    let mut merged: Vec<(f64, f64)> = Vec::with_capacity(MAX_ATOMS + 1);
+    let mut bucket = usize::MAX;
+    for (loss, p) in atoms {
+        let b = ((loss - lo) / width) as usize;
+        match merged.last_mut() {
+            // Probability-weighted mean keeps the bucket's expected loss exact
+            Some(last) if b == bucket => {
+                let total = last.1 + p;
+                if total > 0.0 {
+                    last.0 = (last.0 * last.1 + loss * p) / total;
+                }
+                last.1 = total;
+            }
+            _ => {
+                merged.push((loss, p));
+                bucket = b;
+            }
+        }
+    }
+    merged
+}
+
+/// Mean of the worst `1 - confidence` probability mass (atoms sorted by loss ascending)
+fn tail_mean(atoms: Vec<(f64, f64)>, confidence: f64) -> f64 {
+    let tail = (1.0 - confidence).clamp(1e-9, 1.0);
+    let mut remaining = tail;
+    let mut sum = 0.0;
+    for (loss, p) in atoms.into_iter().rev() {
+        let take = p.min(remaining);
+        sum += loss * take;
+        remaining -= take;
+        if remaining <= 1e-12 {
+            break;
+        }
+    }
+    (sum / tail).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn breaker_trips_on_a_drop_inside_the_window_and_pauses() {
+        let (journal, control) = (Journal::new(), ControlState::new());
+        let mut breaker = PnlCircuitBreaker::new(5.0, 60);
+        assert!(breaker.observe(0, 10.0, &journal, &control).is_none());
+        assert!(breaker.observe(30, 6.0, &journal, &control).is_none());
+
+        let trip = breaker.observe(50, 4.0, &journal, &control).unwrap();
+        assert_eq!((trip.peak_pnl, trip.current_pnl, trip.drop), (10.0, 4.0, 6.0));
+        assert!(breaker.is_tripped() && control.is_paused());
+        // Tripped and still paused: further losses don't re-alert
+        assert!(breaker.observe(55, 0.0, &journal, &control).is_none());
+    }
+
+    #[test]
+    fn drops_spread_past_the_window_do_not_trip() {
+        let (journal, control) = (Journal::new(), ControlState::new());
+        let mut breaker = PnlCircuitBreaker::new(5.0, 60);
+        breaker.observe(0, 10.0, &journal, &control);
+        breaker.observe(40, 7.0, &journal, &control);
+        // The 10.0 peak is older than the window by now
+        assert!(breaker.observe(100, 4.0, &journal, &control).is_none());
+        assert!(!control.is_paused());
+    }
+
+    #[test]
+    fn a_resume_starts_a_fresh_window() {
+        let (journal, control) = (Journal::new(), ControlState::new());
+        let mut breaker = PnlCircuitBreaker::new(5.0, 60);
+        breaker.observe(0, 10.0, &journal, &control);
+        assert!(breaker.observe(10, 0.0, &journal, &control).is_some());
+
+        control.apply(ControlCommand::Resume);
+        // The old peak no longer counts, only drops from here on
+        assert!(breaker.observe(20, 0.0, &journal, &control).is_none());
+        assert!(!breaker.is_tripped());
+        assert!(breaker.observe(30, -6.0, &journal, &control).is_some());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::fees::SharedFeeSchedule;
+use crate::fills::FillModel;
+use crate::tape::LevelFlow;
+use crate::types::{OrderBook, Side, TokenId};
+use serde::Deserialize;
+
+/// Whether the bot works one leg of a pair passively when that is expected to cost less
+#[derive(Debug, Clone, Deserialize)]
+pub struct RouterConfig {
+    #[serde(default)]
+    pub enabled: bool,            // Off: both legs always take
+    #[serde(default = "default_passive_fill_rate")]
+    pub passive_fill_rate: f64,   // Fills per second at the touch when a token has no recorded prints
+    #[serde(default = "default_miss_penalty")]
+    pub miss_penalty: f64,        // Expected drift per share when a passive leg misses
+    #[serde(default = "default_ttl_secs")]
+    pub ttl_secs: f64,            // How long a signal is worth waiting on
+}
+
+impl Default for RouterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            passive_fill_rate: default_passive_fill_rate(),
+            miss_penalty: default_miss_penalty(),
+            ttl_secs: default_ttl_secs(),
+        }
+    }
+}
+
+fn default_passive_fill_rate() -> f64 {
+    0.05
+}
+
+fn default_miss_penalty() -> f64 {
+    0.01
+}
+
+fn default_ttl_secs() -> f64 {
+    30.0
+}
+
+/// How a leg should be worked
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RouteKind {
+    Taker,  // Cross the spread now
+    Maker,  // Post passively at the touch
+}
+
+/// Routing decision for a single leg
+#[derive(Debug, Clone)]
+pub struct LegRoute {
+    pub token_id: TokenId,
+    pub side: Side,
+    pub size: f64,
+    pub kind: RouteKind,
+    pub price: f64,             // Limit price (touch for maker, VWAP for taker)
+    pub fill_probability: f64,  // Chance the leg fills within the signal TTL
+    pub expected_cost: f64,     // Expected cost including fees and fallback
+}
+
+/// Smart order router choosing maker vs. taker per leg
+#[derive(Debug, Clone)]
+pub struct SmartOrderRouter {
+    pub fees: SharedFeeSchedule,
+    pub passive_fill_rate: f64,  // Fills per second at the touch with an empty queue
+    pub miss_penalty: f64,       // Expected price drift per unit when a passive leg misses
+}
+
+impl SmartOrderRouter {
+    pub fn new(fees: SharedFeeSchedule, passive_fill_rate: f64, miss_penalty: f64) -> Self {
+        Self {
+            fees,
+            passive_fill_rate,
+            miss_penalty,
+        }
+    }
+
+    /// Decide how to work one leg, minimizing expected cost
+    pub fn route_leg(&self, book: &OrderBook, size: f64, side: Side, ttl_secs: f64) -> Option<LegRoute> {
+        self.route_leg_with_flow(book, size, side, ttl_secs, None)
+    }
+
+    /// Same as `route_leg`, using recorded taker flow for the passive fill estimate when it
+    /// has prints; otherwise the configured `passive_fill_rate`
+    pub fn route_leg_with_flow(
+        &self,
+        book: &OrderBook,
+        size: f64,
+        side: Side,
+        ttl_secs: f64,
+        flow: Option<&LevelFlow>,
+    ) -> Option<LegRoute> {
+        // Taker: walk the book now, limited by available depth
+        let taker_fill = FillModel::estimate_fill_ratio(book, size, side);
+        let taker_price = book.execution_price(size, side);
+        let taker_cost = taker_price.map(|p| leg_cost(p * size, self.fees.fee(p, size, false), side));
+
+        // Maker: rest at our side's touch and fall back to taking on a miss
+        let touch = match side {
+            Side::Buy => book.best_bid(),
+            Side::Sell => book.best_ask(),
+        };
+        let maker = touch.map(|price| {
+            let maker_cost = leg_cost(price * size, self.fees.fee(price, size, true), side);
+            // The configured rate is per order with an empty queue, i.e. `size` shares at a time
+            let model = FillModel::new(flow.cloned().unwrap_or_default()).with_base_rate(self.passive_fill_rate * size);
+            let p = model.maker_fill_probability(book, price, size, ttl_secs);
+            let fallback = taker_cost.unwrap_or(maker_cost) + self.miss_penalty * size;
+            (price, p, p * maker_cost + (1.0 - p) * fallback)
+        });
+
+        let taker = taker_price.zip(taker_cost).map(|(price, cost)| LegRoute {
+            token_id: book.token_id.clone(),
+            side,
+            size,
+            kind: RouteKind::Taker,
+            price,
+            fill_probability: taker_fill,
+            expected_cost: cost,
+        });
+
+        let maker = maker.map(|(price, p, cost)| LegRoute {
+            token_id: book.token_id.clone(),
+            side,
+            size,
+            kind: RouteKind::Maker,
+            price,
+            fill_probability: p,
+            expected_cost: cost,
+        });
+
+        match (taker, maker) {
+            (Some(t), Some(m)) => Some(if m.expected_cost < t.expected_cost { m } else { t }),
+            (t, m) => t.or(m),
+        }
+    }
+
+    /// Route every leg of a multi-leg signal
+    pub fn route(&self, legs: &[(&OrderBook, Side)], size: f64, ttl_secs: f64) -> Option<Vec<LegRoute>> {
+        legs.iter()
+            .map(|(book, side)| self.route_leg(book, size, *side, ttl_secs))
+            .collect()
+    }
+}
+
+/// Net cash out for a leg; sells receive notional so they count negative
+fn leg_cost(notional: f64, fee: f64, side: Side) -> f64 {
+    match side {
+        Side::Buy => notional + fee,
+        Side::Sell => fee - notional,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fees::FeeModel;
+    use crate::types::PriceLevel;
+    use std::sync::Arc;
+
+    /// Bid 0.40, ask 0.50: a wide spread that pays to wait out
+    fn book() -> OrderBook {
+        OrderBook {
+            token_id: TokenId::from("yes"),
+            bids: vec![PriceLevel { price: 0.40, size: 10.0 }],
+            asks: vec![PriceLevel { price: 0.50, size: 100.0 }],
+            timestamp: 0,
+        }
+    }
+
+    fn router(passive_fill_rate: f64) -> SmartOrderRouter {
+        SmartOrderRouter::new(Arc::new(FeeModel { maker_fee_bps: 0, taker_fee_bps: 200 }), passive_fill_rate, 0.01)
+    }
+
+    #[test]
+    fn posts_when_flow_makes_a_fill_likely() {
+        let flow = LevelFlow { window_ms: 10_000, below_mid: vec![(0.05, 500.0)], above_mid: Vec::new() };
+        let route = router(0.0).route_leg_with_flow(&book(), 20.0, Side::Buy, 30.0, Some(&flow)).unwrap();
+        assert_eq!(route.kind, RouteKind::Maker);
+        assert_eq!(route.price, 0.40);
+    }
+
+    #[test]
+    fn takes_when_a_passive_fill_is_unlikely() {
+        let route = router(0.0).route_leg_with_flow(&book(), 20.0, Side::Buy, 30.0, None).unwrap();
+        assert_eq!(route.kind, RouteKind::Taker);
+        assert_eq!(route.price, 0.50);
+    }
+}
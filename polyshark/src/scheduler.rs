@@ -0,0 +1,199 @@
+use crate::types::{ArbitrageSignal, MarketId};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Signal waiting for execution
+#[derive(Debug, Clone)]
+pub struct PendingSignal {
+    pub signal: ArbitrageSignal,
+    pub size: f64,              // Pairs to buy
+    pub expected_profit: f64,   // After fees and slippage
+    pub capital_required: f64,  // USDC locked if executed
+    pub latency_risk: f64,      // Expected seconds until both legs are done
+}
+
+impl PendingSignal {
+    /// Expected profit per dollar of capital, discounted by latency risk
+    pub fn priority(&self) -> f64 {
+        if self.capital_required <= 0.0 {
+            return 0.0;
+        }
+        self.expected_profit / self.capital_required / (1.0 + self.latency_risk.max(0.0))
+    }
+}
+
+// Heap entry ordered by priority, ties broken by arrival (earlier first)
+#[derive(Debug, Clone)]
+struct Entry {
+    priority: f64,
+    seq: u64,
+    pending: PendingSignal,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority.total_cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// Priority queue executing the best risk-adjusted signals first
+#[derive(Debug, Clone)]
+pub struct ExecutionScheduler {
+    pub max_pending: usize,
+    heap: BinaryHeap<Entry>,
+    next_seq: u64,
+}
+
+impl ExecutionScheduler {
+    pub fn new(max_pending: usize) -> Self {
+        Self {
+            max_pending: max_pending.max(1),
+            heap: BinaryHeap::new(),
+            next_seq: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Queue a signal; when full, the lowest-priority entry is preempted.
+    /// Returns whichever signal was dropped, if any.
+    pub fn submit(&mut self, pending: PendingSignal) -> Option<PendingSignal> {
+        let entry = Entry {
+            priority: pending.priority(),
+            seq: self.next_seq,
+            pending,
+        };
+        self.next_seq += 1;
+        self.heap.push(entry);
+
+        if self.heap.len() <= self.max_pending {
+            return None;
+        }
+        // Evict the weakest entry (possibly the one just pushed)
+        let mut entries = std::mem::take(&mut self.heap).into_sorted_vec();
+        let evicted = entries.remove(0);
+        self.heap = entries.into();
+        Some(evicted.pending)
+    }
+
+    /// Pop signals in priority order that fit within available capital.
+    /// Signals too large for what's left stay queued.
+    pub fn next_batch(&mut self, available_capital: f64) -> Vec<PendingSignal> {
+        let mut remaining = available_capital;
+        let mut batch = Vec::new();
+        let mut deferred = Vec::new();
+
+        while let Some(entry) = self.heap.pop() {
+            if entry.pending.capital_required <= remaining {
+                remaining -= entry.pending.capital_required;
+                batch.push(entry.pending);
+            } else {
+                deferred.push(entry);
+            }
+        }
+
+        self.heap.extend(deferred);
+        batch
+    }
+
+    /// Drop pending work for a market (e.g., signal expired)
+    pub fn cancel_market(&mut self, market_id: &MarketId) -> usize {
+        let before = self.heap.len();
+        self.heap.retain(|e| &e.pending.signal.market_id != market_id);
+        before - self.heap.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Side;
+
+    fn pending(market: &str, expected_profit: f64, capital_required: f64) -> PendingSignal {
+        PendingSignal {
+            signal: ArbitrageSignal {
+                market_id: MarketId::from(market),
+                spread: 0.02,
+                edge: 0.02,
+                recommended_side: Side::Buy,
+                yes_price: 0.49,
+                no_price: 0.49,
+            },
+            size: capital_required,
+            expected_profit,
+            capital_required,
+            latency_risk: 0.0,
+        }
+    }
+
+    fn markets(batch: &[PendingSignal]) -> Vec<&str> {
+        batch.iter().map(|p| p.signal.market_id.as_str()).collect()
+    }
+
+    #[test]
+    fn a_full_queue_evicts_its_weakest_signal() {
+        let mut scheduler = ExecutionScheduler::new(2);
+        assert!(scheduler.submit(pending("a", 1.0, 100.0)).is_none());
+        assert!(scheduler.submit(pending("b", 3.0, 100.0)).is_none());
+        let evicted = scheduler.submit(pending("c", 2.0, 100.0)).unwrap();
+        assert_eq!(evicted.signal.market_id.as_str(), "a");
+        // A newcomer weaker than everything queued is the one dropped
+        let evicted = scheduler.submit(pending("d", 0.5, 100.0)).unwrap();
+        assert_eq!(evicted.signal.market_id.as_str(), "d");
+        assert_eq!(markets(&scheduler.next_batch(f64::INFINITY)), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn equal_priorities_keep_arrival_order() {
+        let mut scheduler = ExecutionScheduler::new(2);
+        scheduler.submit(pending("a", 1.0, 100.0));
+        scheduler.submit(pending("b", 1.0, 100.0));
+        assert_eq!(scheduler.submit(pending("c", 1.0, 100.0)).unwrap().signal.market_id.as_str(), "c");
+        assert_eq!(markets(&scheduler.next_batch(f64::INFINITY)), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn signals_larger_than_free_capital_are_deferred() {
+        let mut scheduler = ExecutionScheduler::new(8);
+        scheduler.submit(pending("big", 10.0, 300.0));   // 0.033 per dollar
+        scheduler.submit(pending("small", 2.0, 50.0));   // 0.04 per dollar
+        scheduler.submit(pending("medium", 3.0, 100.0)); // 0.03 per dollar
+
+        // The best first, then whatever still fits; the big one waits
+        assert_eq!(markets(&scheduler.next_batch(200.0)), vec!["small", "medium"]);
+        assert_eq!(scheduler.len(), 1);
+        assert!(scheduler.next_batch(100.0).is_empty());
+        assert_eq!(markets(&scheduler.next_batch(300.0)), vec!["big"]);
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn cancelling_a_market_drops_its_pending_work() {
+        let mut scheduler = ExecutionScheduler::new(8);
+        scheduler.submit(pending("a", 1.0, 100.0));
+        scheduler.submit(pending("b", 1.0, 100.0));
+        assert_eq!(scheduler.cancel_market(&MarketId::from("a")), 1);
+        assert_eq!(markets(&scheduler.next_batch(f64::INFINITY)), vec!["b"]);
+    }
+}
@@ -0,0 +1,192 @@
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// Shape a response field is read as. Each kind accepts the encodings upstream has been
+/// seen to use, so a field switching between them doesn't lose data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Text,    // String, or a number read as its decimal text
+    Number,  // Number, or a numeric string
+    Flag,    // Bool, or "true" / "false"
+    List,    // Array, or a JSON-encoded string of one (Gamma's outcomes, prices, token ids)
+    Array,
+    Object,
+}
+
+impl FieldKind {
+    /// Whether a present, non-null value can be read as this kind
+    pub fn accepts(self, value: &Value) -> bool {
+        match self {
+            FieldKind::Text => value.is_string() || value.is_number(),
+            FieldKind::Number => value.is_number() || value.as_str().is_some_and(|s| s.parse::<f64>().is_ok()),
+            FieldKind::Flag => value.is_boolean() || value.as_str().is_some_and(|s| s == "true" || s == "false"),
+            FieldKind::List => value.is_array() || value.as_str().is_some_and(|s| serde_json::from_str::<Value>(s).is_ok_and(|v| v.is_array())),
+            FieldKind::Array => value.is_array(),
+            FieldKind::Object => value.is_object(),
+        }
+    }
+}
+
+/// One field we read and what the parser does without it
+#[derive(Debug, Clone, Copy)]
+pub struct Field {
+    pub name: &'static str,
+    pub kind: FieldKind,
+    pub fallback: Option<&'static str>,  // None: the item is dropped without it
+}
+
+impl Field {
+    pub const fn required(name: &'static str, kind: FieldKind) -> Self {
+        Self { name, kind, fallback: None }
+    }
+
+    pub const fn optional(name: &'static str, kind: FieldKind, fallback: &'static str) -> Self {
+        Self { name, kind, fallback: Some(fallback) }
+    }
+}
+
+/// The fields of one upstream object: the ones parsed, and the ones known and deliberately
+/// not read. Anything else is drift.
+#[derive(Debug, Clone, Copy)]
+pub struct Schema {
+    pub name: &'static str,
+    pub fields: &'static [Field],
+    pub ignored: &'static [&'static str],
+}
+
+/// A way a response doesn't match its schema
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaIssue {
+    Missing { field: &'static str },                      // Required field absent or null
+    Malformed { field: &'static str, kind: FieldKind },   // Present but unreadable; the fallback applies
+    Unknown { field: String },                            // Not a field we know about
+}
+
+impl SchemaIssue {
+    pub fn describe(&self) -> String {
+        match self {
+            SchemaIssue::Missing { field } => format!("{} missing", field),
+            SchemaIssue::Malformed { field, kind } => format!("{} not readable as {:?}", field, kind),
+            SchemaIssue::Unknown { field } => format!("{} unknown", field),
+        }
+    }
+}
+
+impl Schema {
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    pub fn knows(&self, name: &str) -> bool {
+        self.field(name).is_some() || self.ignored.contains(&name)
+    }
+
+    /// Everything about `value` that doesn't match. A non-object is reported as every
+    /// required field missing.
+    pub fn check(&self, value: &Value) -> Vec<SchemaIssue> {
+        let empty = Map::new();
+        let object = value.as_object().unwrap_or(&empty);
+        let mut issues = Vec::new();
+        for field in self.fields {
+            match object.get(field.name).filter(|v| !v.is_null()) {
+                None if field.fallback.is_none() => issues.push(SchemaIssue::Missing { field: field.name }),
+                Some(v) if !field.kind.accepts(v) => issues.push(SchemaIssue::Malformed { field: field.name, kind: field.kind }),
+                _ => {}
+            }
+        }
+        issues.extend(self.unknown(value).into_iter().map(|field| SchemaIssue::Unknown { field }));
+        issues
+    }
+
+    /// Fields present on `value` that the schema doesn't know about
+    pub fn unknown(&self, value: &Value) -> Vec<String> {
+        value.as_object()
+            .map(|o| o.keys().filter(|k| !self.knows(k)).cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// How parsing treats a response that doesn't match its schema
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SchemaMode {
+    #[default]
+    Lenient,  // Fall back per field, drop items missing a required field, ignore unknown fields
+    Strict,   // A required field missing, a field unreadable as its kind or an unknown field is an
+              // error; absent optional fields still fall back. For tests and fixtures, not live trading
+}
+
+impl SchemaMode {
+    /// Err with every issue in strict mode, always Ok in lenient mode
+    pub fn validate(self, schema: &Schema, value: &Value) -> Result<(), String> {
+        if self == SchemaMode::Lenient {
+            return Ok(());
+        }
+        let issues = schema.check(value);
+        if issues.is_empty() {
+            return Ok(());
+        }
+        let parts: Vec<String> = issues.iter().map(SchemaIssue::describe).collect();
+        Err(format!("{}: {}", schema.name, parts.join(", ")))
+    }
+}
+
+/// Remembers the unknown fields seen per schema and logs each the first time it shows up,
+/// so upstream additions (often a renamed field we still read under its old name) are
+/// noticed without flooding the log. Clones share what has been seen.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaDrift {
+    seen: Arc<Mutex<HashMap<&'static str, BTreeSet<String>>>>,
+}
+
+impl SchemaDrift {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the unknown fields on `value`; returns the ones not seen before
+    pub fn observe(&self, schema: &Schema, value: &Value) -> Vec<String> {
+        let unknown = schema.unknown(value);
+        if unknown.is_empty() {
+            return unknown;
+        }
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        let known = seen.entry(schema.name).or_default();
+        let new: Vec<String> = unknown.into_iter().filter(|f| known.insert(f.clone())).collect();
+        if !new.is_empty() {
+            eprintln!("schema drift: {} has new fields we ignore: {}", schema.name, new.join(", "));
+        }
+        new
+    }
+
+    /// Record every item of an array response
+    pub fn observe_all(&self, schema: &Schema, body: &Value) -> Vec<String> {
+        body.as_array()
+            .map(|items| items.iter().flat_map(|i| self.observe(schema, i)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Unknown fields seen so far, per schema
+    pub fn ignored(&self) -> BTreeMap<String, Vec<String>> {
+        let seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        seen.iter().map(|(name, fields)| (name.to_string(), fields.iter().cloned().collect())).collect()
+    }
+}
+
+/// Text field: a string, or a number written out (ids have been both)
+pub fn text_field(value: &Value, key: &str) -> Option<String> {
+    match &value[key] {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Bool field, accepting "true" / "false"
+pub fn flag_field(value: &Value, key: &str) -> Option<bool> {
+    match &value[key] {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
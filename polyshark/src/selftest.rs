@@ -0,0 +1,223 @@
+use crate::clob::{parse_book, ClobClient, BOOK_SCHEMA};
+use crate::config::Profile;
+use crate::gamma::{parse_markets, MARKET_SCHEMA};
+use crate::schema::Schema;
+use crate::types::{OrderBook, TokenId};
+use crate::websocket::{parse_market_message, MarketEvent};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Fields trading depends on, per endpoint
+const MARKET_FIELDS: &[&str] = &["id", "question", "slug", "outcomes", "outcomePrices", "clobTokenIds", "active", "acceptingOrders"];
+const BOOK_FIELDS: &[&str] = &["asset_id", "bids", "asks", "timestamp"];
+const WS_EVENT_TYPES: &[&str] = &["book", "price_change", "last_trade_price", "tick_size_change", "best_bid_ask", "heartbeat", "book_hash"];
+
+/// Tokens subscribed on the WebSocket during the test
+const WS_TOKENS: usize = 10;
+
+/// Largest tolerated difference between our clock and the exchange's
+const MAX_CLOCK_SKEW_SECS: u64 = 5;
+
+/// Outcome of one selftest check
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: String,
+    pub ok: bool,
+    pub detail: String,
+}
+
+/// Everything the selftest found
+#[derive(Debug, Clone, Default)]
+pub struct SelfTestReport {
+    pub checks: Vec<Check>,
+}
+
+impl SelfTestReport {
+    fn check(&mut self, name: &str, ok: bool, detail: impl Into<String>) {
+        let detail = detail.into();
+        println!("{} {:<18} {}", if ok { "ok  " } else { "FAIL" }, name, detail);
+        self.checks.push(Check { name: name.to_string(), ok, detail });
+    }
+
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.ok)
+    }
+}
+
+/// Exercise the read-only live endpoints (markets, books, server time, market WebSocket)
+/// and report schema mismatches. Never authenticates and never places orders.
+pub async fn run(profile: &Profile, ws_secs: u64) -> SelfTestReport {
+    let mut report = SelfTestReport::default();
+    let http = reqwest::Client::new();
+
+    // Markets: raw JSON so missing fields show up instead of silently defaulting
+    let url = format!("{}/markets?active=true&closed=false&limit=20", profile.gamma_url.trim_end_matches('/'));
+    let raw = match fetch_json(&http, &url).await {
+        Ok(v) => v,
+        Err(err) => {
+            report.check("gamma markets", false, err);
+            return report;
+        }
+    };
+    let missing = missing_fields(raw.as_array().map(Vec::as_slice).unwrap_or_default(), MARKET_FIELDS);
+    report.check("gamma schema", missing.is_empty(), describe_missing(&missing));
+    // New fields aren't failures, but one may be a rename of a field we still read
+    report.check("gamma drift", true, describe_drift(&MARKET_SCHEMA, raw.as_array().map(Vec::as_slice).unwrap_or_default()));
+    let markets = parse_markets(&raw);
+    let tokens: Vec<TokenId> = markets.iter()
+        .filter(|m| m.accepting_orders)
+        .flat_map(|m| m.clob_token_ids.iter().cloned())
+        .take(WS_TOKENS)
+        .collect();
+    report.check("gamma markets", !markets.is_empty() && !tokens.is_empty(), format!("{} markets, {} tradable tokens", markets.len(), tokens.len()));
+
+    // Server time
+    let clob = ClobClient::new(&profile.clob_url);
+    match clob.server_time().await {
+        Ok(server) => {
+            let local = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+            let skew = local.abs_diff(server);
+            report.check("server time", server > 0 && skew <= MAX_CLOCK_SKEW_SECS, format!("skew {}s", skew));
+        }
+        Err(err) => report.check("server time", false, err.to_string()),
+    }
+
+    // Books
+    let mut books: HashMap<TokenId, OrderBook> = HashMap::new();
+    if let Some(token) = tokens.first() {
+        let url = format!("{}/book?token_id={}", profile.clob_url.trim_end_matches('/'), token);
+        match fetch_json(&http, &url).await {
+            Ok(raw) => {
+                let missing = missing_fields(std::slice::from_ref(&raw), BOOK_FIELDS);
+                report.check("book schema", missing.is_empty(), describe_missing(&missing));
+                report.check("book drift", true, describe_drift(&BOOK_SCHEMA, std::slice::from_ref(&raw)));
+                let book = parse_book(&raw);
+                let ok = book.token_id == *token && !crossed(&book);
+                report.check("book parse", ok, format!("{} bids, {} asks", book.bids.len(), book.asks.len()));
+                books.insert(token.clone(), book);
+            }
+            Err(err) => report.check("book", false, err),
+        }
+    }
+
+    // WebSocket: subscribe and maintain books for a while
+    if !tokens.is_empty() {
+        match watch_stream(&profile.ws_url, &tokens, Duration::from_secs(ws_secs), &mut books).await {
+            Ok(stats) => {
+                report.check("ws subscribe", stats.events > 0, format!("{} messages, {} events", stats.messages, stats.events));
+                report.check(
+                    "ws schema",
+                    stats.unknown.is_empty(),
+                    if stats.unknown.is_empty() { "all event types known".to_string() } else { format!("unknown: {}", stats.unknown.join(", ")) },
+                );
+                report.check(
+                    "book maintenance",
+                    stats.crossed == 0 && stats.orphan_changes == 0,
+                    format!("{} books, {} crossed, {} changes before snapshot", books.len(), stats.crossed, stats.orphan_changes),
+                );
+            }
+            Err(err) => report.check("ws subscribe", false, err),
+        }
+    }
+    report
+}
+
+#[derive(Debug, Default)]
+struct StreamStats {
+    messages: u64,
+    events: u64,
+    crossed: u64,         // Updates that left a book with bid >= ask
+    orphan_changes: u64,  // Price changes for a token with no snapshot yet
+    unknown: Vec<String>, // Event types we don't recognise
+}
+
+async fn watch_stream(url: &str, tokens: &[TokenId], duration: Duration, books: &mut HashMap<TokenId, OrderBook>) -> Result<StreamStats, String> {
+    let (mut ws, _) = connect_async(url).await.map_err(|e| e.to_string())?;
+    let subscribe = serde_json::json!({ "assets_ids": tokens, "type": "market" });
+    ws.send(Message::Text(subscribe.to_string().into())).await.map_err(|e| e.to_string())?;
+
+    let mut stats = StreamStats::default();
+    let deadline = Instant::now() + duration;
+    while let Ok(Some(msg)) = tokio::time::timeout_at(deadline.into(), ws.next()).await {
+        let text = match msg.map_err(|e| e.to_string())? {
+            Message::Text(text) => text,
+            Message::Ping(payload) => {
+                let _ = ws.send(Message::Pong(payload)).await;
+                continue;
+            }
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        stats.messages += 1;
+        for event_type in event_types(&text) {
+            if !WS_EVENT_TYPES.contains(&event_type.as_str()) && !stats.unknown.contains(&event_type) {
+                stats.unknown.push(event_type);
+            }
+        }
+        for event in parse_market_message(&text) {
+            stats.events += 1;
+            let token_id = event.token_id().clone();
+            match event {
+                MarketEvent::Book { book, .. } => {
+                    books.insert(token_id.clone(), book);
+                }
+                MarketEvent::PriceChange { side, price, size, timestamp, .. } => match books.get_mut(&token_id) {
+                    Some(book) => book.apply_level(side, price, size, timestamp),
+                    None => stats.orphan_changes += 1,
+                },
+                MarketEvent::LastTrade(_) | MarketEvent::Heartbeat { .. } => continue,
+            }
+            if books.get(&token_id).is_some_and(crossed) {
+                stats.crossed += 1;
+            }
+        }
+    }
+    Ok(stats)
+}
+
+async fn fetch_json(http: &reqwest::Client, url: &str) -> Result<Value, String> {
+    let response = http.get(url).send().await.map_err(|e| e.to_string())?;
+    let response = response.error_for_status().map_err(|e| e.to_string())?;
+    response.json().await.map_err(|e| format!("invalid JSON: {}", e))
+}
+
+/// Required fields absent from any of `items`, with how many items lacked each
+fn missing_fields(items: &[Value], fields: &[&str]) -> Vec<(String, usize)> {
+    fields.iter()
+        .map(|f| (f.to_string(), items.iter().filter(|i| i.get(*f).is_none_or(Value::is_null)).count()))
+        .filter(|(_, n)| *n > 0)
+        .collect()
+}
+
+fn describe_missing(missing: &[(String, usize)]) -> String {
+    if missing.is_empty() {
+        return "all required fields present".to_string();
+    }
+    let parts: Vec<String> = missing.iter().map(|(f, n)| format!("{} (missing in {})", f, n)).collect();
+    parts.join(", ")
+}
+
+/// Fields on `items` that `schema` doesn't know about
+fn describe_drift(schema: &Schema, items: &[Value]) -> String {
+    let mut unknown: Vec<String> = items.iter().flat_map(|i| schema.unknown(i)).collect();
+    unknown.sort();
+    unknown.dedup();
+    if unknown.is_empty() { "no unknown fields".to_string() } else { format!("ignoring: {}", unknown.join(", ")) }
+}
+
+fn event_types(text: &str) -> Vec<String> {
+    let items = match serde_json::from_str::<Value>(text) {
+        Ok(Value::Array(items)) => items,
+        Ok(other) => vec![other],
+        Err(_) => return vec!["<invalid json>".to_string()],
+    };
+    items.iter().filter_map(|i| i["event_type"].as_str().map(String::from)).collect()
+}
+
+fn crossed(book: &OrderBook) -> bool {
+    matches!((book.best_bid(), book.best_ask()), (Some(bid), Some(ask)) if bid >= ask)
+}
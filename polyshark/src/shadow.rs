@@ -0,0 +1,137 @@
+use crate::arb::ArbitrageDetector;
+use crate::backtest::{compare, BacktestComparison, BacktestResult, BacktestTrade};
+use crate::fees::polymarket_fee;
+use crate::slippage::pair_depth;
+use crate::types::{ArbitrageSignal, Market, OrderBook, TokenId};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// A candidate detector setting run next to the bot's own, trading nothing
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShadowConfig {
+    pub min_spread: f64,               // Candidate thresholds
+    pub min_profit: f64,
+    #[serde(default = "default_size")]
+    pub size: f64,                     // Pairs each would-be trade is valued at
+    #[serde(default)]
+    pub fee_rate: f64,                 // Taker fee rate charged to both detectors
+    #[serde(default)]
+    pub slippage: f64,                 // Per pair, on top of the liquidity tier buffers
+    #[serde(default = "default_sample_secs")]
+    pub sample_secs: u64,              // Both detectors scan the watchlist this often
+    #[serde(default)]
+    pub tolerance: f64,                // PnL drop of the shadow that counts as a regression
+    #[serde(default)]
+    pub results_dir: Option<PathBuf>,  // live.json / shadow.json written here on shutdown, for `compare`
+}
+
+fn default_size() -> f64 {
+    10.0
+}
+
+fn default_sample_secs() -> u64 {
+    60
+}
+
+/// Costs used to value each detector's would-be trades
+#[derive(Debug, Clone)]
+pub struct ShadowCosts {
+    pub size: f64,
+    pub fee_rate: f64,
+    pub slippage: f64,  // Per unit
+}
+
+/// Runs a live and a shadow detector on the same markets.
+/// Only the live detector's signals are returned for execution;
+/// both are logged as hypothetical trades for the performance report.
+#[derive(Debug)]
+pub struct ShadowRunner {
+    pub live: ArbitrageDetector,
+    pub shadow: ArbitrageDetector,
+    pub live_label: String,
+    pub shadow_label: String,
+    pub costs: ShadowCosts,
+    live_trades: Vec<BacktestTrade>,
+    shadow_trades: Vec<BacktestTrade>,
+}
+
+impl ShadowRunner {
+    pub fn new(live: ArbitrageDetector, shadow: ArbitrageDetector, costs: ShadowCosts) -> Self {
+        Self {
+            live,
+            shadow,
+            live_label: "live".to_string(),
+            shadow_label: "shadow".to_string(),
+            costs,
+            live_trades: Vec::new(),
+            shadow_trades: Vec::new(),
+        }
+    }
+
+    /// Scan one snapshot (taken at `now`, in ms) with both detectors; returns the live
+    /// signals worth trading
+    pub fn observe(&mut self, markets: &[Market], books: &HashMap<TokenId, OrderBook>, now: u64) -> Vec<ArbitrageSignal> {
+        let live = Self::tradable(&self.live, markets, books, &self.costs);
+        let shadow = Self::tradable(&self.shadow, markets, books, &self.costs);
+
+        let live_trades: Vec<_> = live.iter().map(|s| Self::hypothetical(&self.live, &self.costs, s, markets, books, now)).collect();
+        let shadow_trades: Vec<_> = shadow.iter().map(|s| Self::hypothetical(&self.shadow, &self.costs, s, markets, books, now)).collect();
+        self.live_trades.extend(live_trades);
+        self.shadow_trades.extend(shadow_trades);
+        live
+    }
+
+    /// What the shadow detector would have done differently so far
+    pub fn report(&self, tolerance: f64) -> BacktestComparison {
+        let (live, shadow) = self.results();
+        compare(&live, &shadow, tolerance)
+    }
+
+    /// Would-be trades of both detectors so far, as backtest results
+    pub fn results(&self) -> (BacktestResult, BacktestResult) {
+        (
+            BacktestResult::new(&self.live_label, self.live_trades.clone()),
+            BacktestResult::new(&self.shadow_label, self.shadow_trades.clone()),
+        )
+    }
+
+    fn tradable(detector: &ArbitrageDetector, markets: &[Market], books: &HashMap<TokenId, OrderBook>, costs: &ShadowCosts) -> Vec<ArbitrageSignal> {
+        detector.scan(markets).into_iter()
+            .filter(|s| detector.should_trade(s, costs.size, costs.fee_rate, costs.slippage, Self::depth(markets, books, s)))
+            .collect()
+    }
+
+    /// Depth of the pair's books; unknown (thinnest tier) until both have been seen
+    fn depth(markets: &[Market], books: &HashMap<TokenId, OrderBook>, signal: &ArbitrageSignal) -> Option<f64> {
+        let market = markets.iter().find(|m| m.id == signal.market_id)?;
+        let [yes, no] = market.clob_token_ids.as_slice() else { return None };
+        Some(pair_depth(books.get(yes)?, books.get(no)?))
+    }
+
+    /// A would-be trade valued with the cost assumptions (liquidity-tier buffers) of the
+    /// detector that took it
+    fn hypothetical(
+        detector: &ArbitrageDetector,
+        costs: &ShadowCosts,
+        signal: &ArbitrageSignal,
+        markets: &[Market],
+        books: &HashMap<TokenId, OrderBook>,
+        now: u64,
+    ) -> BacktestTrade {
+        let size = costs.size;
+        BacktestTrade {
+            trade_id: String::new(),  // Assigned by BacktestResult::new
+            market_id: signal.market_id.clone(),
+            timestamp: now / 1000,
+            size,
+            edge: signal.edge,
+            fees: polymarket_fee(costs.fee_rate, signal.yes_price, size)
+                + polymarket_fee(costs.fee_rate, signal.no_price, size),
+            pnl: detector.expected_profit(signal, size, costs.fee_rate, costs.slippage, Self::depth(markets, books, signal)),
+            prices: Some([signal.yes_price, signal.no_price]),
+            depth: Self::depth(markets, books, signal),
+            timestamp_ms: Some(now),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::types::{OrderBook, Side};
+use serde::Deserialize;
 
 /// Slippage calculator using order book
 #[derive(Debug, Clone)]
@@ -23,4 +24,34 @@ impl SlippageModel {
         let exec_price = book.execution_price(size, side)?;
         Some(exec_price * size)
     }
-}
\ No newline at end of file
+}
+
+/// Extra edge required per unit in markets of a given depth
+#[derive(Debug, Clone, Deserialize)]
+pub struct LiquidityTier {
+    pub name: String,    // e.g., "thin"
+    pub min_depth: f64,  // USDC of resting liquidity to qualify
+    pub buffer: f64,     // Added to estimated slippage, per unit
+}
+
+/// Thin books need more edge: 1c below $1k depth, 0.5c below $10k, nothing above
+pub fn default_liquidity_tiers() -> Vec<LiquidityTier> {
+    vec![
+        LiquidityTier { name: "thin".to_string(), min_depth: 0.0, buffer: 0.01 },
+        LiquidityTier { name: "medium".to_string(), min_depth: 1_000.0, buffer: 0.005 },
+        LiquidityTier { name: "deep".to_string(), min_depth: 10_000.0, buffer: 0.0 },
+    ]
+}
+
+/// Deepest tier the depth qualifies for
+pub fn classify(tiers: &[LiquidityTier], depth: f64) -> Option<&LiquidityTier> {
+    tiers.iter()
+        .filter(|t| depth >= t.min_depth)
+        .max_by(|a, b| a.min_depth.total_cmp(&b.min_depth))
+}
+
+/// Depth an arbitrage can draw on: the shallower of the two books (notional, both sides)
+pub fn pair_depth(yes: &OrderBook, no: &OrderBook) -> f64 {
+    let notional = |b: &OrderBook| b.bids.iter().chain(&b.asks).map(|l| l.price * l.size).sum::<f64>();
+    notional(yes).min(notional(no))
+}
@@ -0,0 +1,235 @@
+use crate::journal::Journal;
+use crate::lots::LotMethod;
+use crate::manual;
+use crate::orders::{OpenOrder, OrderManager};
+use crate::storage::StorageResult;
+use crate::types::{Side, TokenId};
+use crate::wallet::Wallet;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+/// Periodic portfolio snapshots, one JSON object per line
+pub const SNAPSHOTS_PATH: &str = "snapshots.jsonl";
+
+/// One held position at snapshot time
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PositionSnapshot {
+    pub token_id: TokenId,
+    pub side: Side,
+    pub size: f64,
+    pub entry_price: f64,
+    pub mark: f64,  // Mid at snapshot time, entry price if unknown
+}
+
+impl PositionSnapshot {
+    pub fn value(&self) -> f64 {
+        self.size * self.mark
+    }
+}
+
+/// One resting order at snapshot time
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrderSnapshot {
+    pub order_id: String,
+    pub token_id: TokenId,
+    pub side: Side,
+    pub price: f64,
+    pub remaining: f64,
+}
+
+impl From<&OpenOrder> for OrderSnapshot {
+    fn from(order: &OpenOrder) -> Self {
+        Self {
+            order_id: order.order_id.clone(),
+            token_id: order.token_id.clone(),
+            side: order.side,
+            price: order.price,
+            remaining: order.remaining(),
+        }
+    }
+}
+
+impl From<&OrderSnapshot> for OpenOrder {
+    /// The resting part only: fills and timestamps aren't snapshotted
+    fn from(order: &OrderSnapshot) -> Self {
+        Self {
+            order_id: order.order_id.clone(),
+            token_id: order.token_id.clone(),
+            side: order.side,
+            price: order.price,
+            size: order.remaining,
+            filled: 0.0,
+            created_at: 0,
+            expires_at: None,
+            instance_id: None,
+        }
+    }
+}
+
+/// Cash, positions and orders at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortfolioSnapshot {
+    pub timestamp: u64,  // Seconds
+    pub cash: f64,
+    pub locked: f64,     // Reserved for resting orders
+    pub equity: f64,     // Cash plus positions at mark
+    pub positions: Vec<PositionSnapshot>,
+    pub open_orders: Vec<OrderSnapshot>,
+    #[serde(default)]
+    pub from_journal: bool,  // Rebuilt from fills: no orders or locked funds, marks are last fill prices
+}
+
+impl PortfolioSnapshot {
+    /// Snapshot the live wallet and order book at `now`, marking positions at `prices`
+    pub fn capture(wallet: &Wallet, orders: &OrderManager, prices: &HashMap<TokenId, f64>, now: u64) -> Self {
+        let mut positions: Vec<PositionSnapshot> = wallet.positions.values()
+            .map(|p| PositionSnapshot {
+                token_id: p.token_id.clone(),
+                side: p.side,
+                size: p.size,
+                entry_price: p.entry_price,
+                mark: prices.get(&p.token_id).copied().unwrap_or(p.entry_price),
+            })
+            .collect();
+        positions.sort_by(|a, b| a.token_id.cmp(&b.token_id));
+        let mut open_orders: Vec<OrderSnapshot> = orders.open.values().map(OrderSnapshot::from).collect();
+        open_orders.sort_by(|a, b| a.order_id.cmp(&b.order_id));
+        Self {
+            timestamp: now,
+            cash: wallet.usdc.value(),
+            locked: wallet.locked.value(),
+            equity: wallet.usdc.value() + positions.iter().map(PositionSnapshot::value).sum::<f64>(),
+            positions,
+            open_orders,
+            from_journal: false,
+        }
+    }
+
+    /// Rebuild the portfolio at `at` by replaying journal fills up to it, consuming lots
+    /// with `lot_method`. Positions are marked at their token's last fill price.
+    pub fn from_journal(journal: &Journal, starting_balance: f64, lot_method: LotMethod, at: u64) -> Self {
+        let mut upto = Journal::new();
+        upto.entries = journal.entries.iter().filter(|e| e.timestamp <= at).cloned().collect();
+        let mut wallet = Wallet::new(starting_balance);
+        wallet.lots.method = lot_method;
+        manual::replay_journal(&mut wallet, &upto);
+        let marks: HashMap<TokenId, f64> = upto.entries.iter().map(|e| (e.token_id.clone(), e.price)).collect();
+        Self { from_journal: true, ..Self::capture(&wallet, &OrderManager::new(), &marks, at) }
+    }
+
+    pub fn append(&self, path: &Path) -> StorageResult<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Latest recorded snapshot at or before `at`
+    pub fn load_at(path: &Path, at: u64) -> StorageResult<Option<Self>> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let mut best: Option<Self> = None;
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let snapshot: Self = serde_json::from_str(&line)?;
+            if snapshot.timestamp <= at && best.as_ref().is_none_or(|b| snapshot.timestamp >= b.timestamp) {
+                best = Some(snapshot);
+            }
+        }
+        Ok(best)
+    }
+}
+
+/// One token's position on both sides of the diff (size 0 = not held)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PositionChange {
+    pub token_id: TokenId,
+    pub size_before: f64,
+    pub size_after: f64,
+    pub value_before: f64,
+    pub value_after: f64,
+}
+
+/// Fills booked between the two snapshots
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FillSummary {
+    pub fills: usize,
+    pub bought: f64,    // USDC spent on buys
+    pub sold: f64,      // USDC received from sells
+    pub fees: f64,
+    pub realized_pnl: f64,
+}
+
+/// Where the money went between two points in time
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PortfolioDiff {
+    pub from: PortfolioSnapshot,
+    pub to: PortfolioSnapshot,
+    pub cash: f64,      // Change, to - from
+    pub locked: f64,
+    pub equity: f64,
+    pub positions: Vec<PositionChange>,  // Tokens whose size changed
+    pub orders_added: Vec<OrderSnapshot>,
+    pub orders_removed: Vec<OrderSnapshot>,  // Filled, cancelled or expired
+    pub fills: FillSummary,
+}
+
+impl PortfolioDiff {
+    pub fn between(from: PortfolioSnapshot, to: PortfolioSnapshot, journal: &Journal) -> Self {
+        let mut sizes: BTreeMap<&TokenId, PositionChange> = BTreeMap::new();
+        let blank = |token_id: &TokenId| PositionChange {
+            token_id: token_id.clone(),
+            size_before: 0.0,
+            size_after: 0.0,
+            value_before: 0.0,
+            value_after: 0.0,
+        };
+        for p in &from.positions {
+            let change = sizes.entry(&p.token_id).or_insert_with(|| blank(&p.token_id));
+            change.size_before = p.size;
+            change.value_before = p.value();
+        }
+        for p in &to.positions {
+            let change = sizes.entry(&p.token_id).or_insert_with(|| blank(&p.token_id));
+            change.size_after = p.size;
+            change.value_after = p.value();
+        }
+        let positions = sizes.into_values().filter(|c| (c.size_after - c.size_before).abs() > 1e-9).collect();
+
+        let ids = |s: &PortfolioSnapshot| s.open_orders.iter().map(|o| o.order_id.clone()).collect::<Vec<_>>();
+        let (before, after) = (ids(&from), ids(&to));
+        let orders_added = to.open_orders.iter().filter(|o| !before.contains(&o.order_id)).cloned().collect();
+        let orders_removed = from.open_orders.iter().filter(|o| !after.contains(&o.order_id)).cloned().collect();
+
+        let mut fills = FillSummary::default();
+        for e in journal.entries.iter().filter(|e| e.timestamp > from.timestamp && e.timestamp <= to.timestamp) {
+            fills.fills += 1;
+            match e.side {
+                Side::Buy => fills.bought += e.size * e.price,
+                Side::Sell => fills.sold += e.size * e.price,
+            }
+            fills.fees += e.fee;
+            fills.realized_pnl += e.realized_pnl.unwrap_or(0.0);
+        }
+
+        Self {
+            cash: to.cash - from.cash,
+            locked: to.locked - from.locked,
+            equity: to.equity - from.equity,
+            positions,
+            orders_added,
+            orders_removed,
+            fills,
+            from,
+            to,
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use crate::storage::StorageResult;
+use crate::types::MarketId;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Block characters from lowest to highest
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// How much mid-price history is kept per market for quick context
+#[derive(Debug, Clone, Deserialize)]
+pub struct SparklineConfig {
+    #[serde(default = "default_resolution_ms")]
+    pub resolution_ms: u64,        // One point per bucket of this size (last mid wins)
+    #[serde(default = "default_points")]
+    pub points: usize,             // Ring size: 1440 one-minute points = 24h
+    #[serde(default)]
+    pub path: Option<PathBuf>,     // Saved here on shutdown and reloaded on start, if set
+}
+
+impl Default for SparklineConfig {
+    fn default() -> Self {
+        Self { resolution_ms: default_resolution_ms(), points: default_points(), path: None }
+    }
+}
+
+fn default_resolution_ms() -> u64 {
+    60_000
+}
+
+fn default_points() -> usize {
+    1_440
+}
+
+/// Mid prices of one market on a fixed time grid, oldest first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MidRing {
+    points: VecDeque<(u64, f64)>,  // (bucket start ms, mid)
+}
+
+impl MidRing {
+    fn record(&mut self, bucket: u64, mid: f64, resolution_ms: u64, capacity: usize) {
+        match self.points.back_mut() {
+            Some(last) if last.0 == bucket => last.1 = mid,
+            Some(last) if last.0 > bucket => return,  // Late update for a closed bucket
+            _ => self.points.push_back((bucket, mid)),
+        }
+        let horizon = bucket.saturating_sub(resolution_ms * capacity.saturating_sub(1) as u64);
+        while self.points.len() > capacity || self.points.front().is_some_and(|p| p.0 < horizon) {
+            self.points.pop_front();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn first(&self) -> Option<f64> {
+        self.points.front().map(|p| p.1)
+    }
+
+    pub fn last(&self) -> Option<f64> {
+        self.points.back().map(|p| p.1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u64, f64)> + '_ {
+        self.points.iter().copied()
+    }
+}
+
+/// Recent price movement of one market, small enough for a notification
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PriceContext {
+    pub sparkline: String,
+    pub first: f64,
+    pub last: f64,
+    pub low: f64,
+    pub high: f64,
+    pub span_ms: u64,  // Time covered by the points
+}
+
+impl PriceContext {
+    /// One line for alert text, e.g. "24h ▁▂▃▅▇ 0.420 -> 0.550 (+13.0c)"
+    pub fn describe(&self) -> String {
+        format!(
+            "{:.0}h {} {:.3} -> {:.3} ({:+.1}c)",
+            self.span_ms as f64 / 3_600_000.0,
+            self.sparkline,
+            self.first,
+            self.last,
+            (self.last - self.first) * 100.0
+        )
+    }
+}
+
+/// Render values as block characters scaled between their min and max, resampled to at
+/// most `width` characters (each the last value of its slice)
+pub fn render(values: &[f64], width: usize) -> String {
+    if values.is_empty() || width == 0 {
+        return String::new();
+    }
+    let step = values.len().div_ceil(width);
+    let sampled: Vec<f64> = values.chunks(step).filter_map(|c| c.last().copied()).collect();
+    let low = sampled.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = sampled.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let top = (SPARK_LEVELS.len() - 1) as f64;
+    sampled.iter()
+        .map(|v| {
+            let level = if high - low > 1e-12 { ((v - low) / (high - low) * top).round() as usize } else { 0 };
+            SPARK_LEVELS[level.min(SPARK_LEVELS.len() - 1)]
+        })
+        .collect()
+}
+
+/// Per-market mid-price rings in memory, for sparkline panels and alert messages
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PriceHistory {
+    #[serde(skip)]
+    pub config: SparklineConfig,
+    markets: HashMap<MarketId, MidRing>,
+}
+
+/// History shared between the feed that records it and whatever displays it
+pub type SharedPriceHistory = Arc<Mutex<PriceHistory>>;
+
+impl PriceHistory {
+    pub fn new(config: SparklineConfig) -> Self {
+        Self { config, markets: HashMap::new() }
+    }
+
+    /// Record a market's mid at `timestamp` (ms)
+    pub fn record(&mut self, market_id: &MarketId, timestamp: u64, mid: f64) {
+        let resolution = self.config.resolution_ms.max(1);
+        let bucket = timestamp / resolution * resolution;
+        let capacity = self.config.points.max(1);
+        self.markets.entry(market_id.clone()).or_default().record(bucket, mid, resolution, capacity);
+    }
+
+    pub fn ring(&self, market_id: &MarketId) -> Option<&MidRing> {
+        self.markets.get(market_id).filter(|r| !r.is_empty())
+    }
+
+    /// Sparkline of a market's history, at most `width` characters
+    pub fn sparkline(&self, market_id: &MarketId, width: usize) -> Option<String> {
+        let values: Vec<f64> = self.ring(market_id)?.iter().map(|p| p.1).collect();
+        Some(render(&values, width))
+    }
+
+    /// Summary of a market's recent movement for notifications
+    pub fn context(&self, market_id: &MarketId, width: usize) -> Option<PriceContext> {
+        let ring = self.ring(market_id)?;
+        let values: Vec<f64> = ring.iter().map(|p| p.1).collect();
+        let span_ms = match (ring.points.front(), ring.points.back()) {
+            (Some(first), Some(last)) => last.0 - first.0 + self.config.resolution_ms,
+            _ => 0,
+        };
+        Some(PriceContext {
+            sparkline: render(&values, width),
+            first: ring.first()?,
+            last: ring.last()?,
+            low: values.iter().copied().fold(f64::INFINITY, f64::min),
+            high: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            span_ms,
+        })
+    }
+
+    /// Stop keeping history for markets that left the watchlist
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+        self.markets.retain(|id, _| keep(id));
+    }
+
+    /// Load saved rings with the given config; an empty history if there is no file
+    pub fn load(config: SparklineConfig, path: &Path) -> StorageResult<Self> {
+        let mut history: Self = match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(err) => return Err(err.into()),
+        };
+        history.config = config;
+        Ok(history)
+    }
+
+    pub fn save(&self, path: &Path) -> StorageResult<()> {
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
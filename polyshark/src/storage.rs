@@ -0,0 +1,296 @@
+use crate::journal::JournalEntry;
+use crate::types::{OrderBook, TokenId};
+use serde::Deserialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+pub type StorageError = Box<dyn std::error::Error + Send + Sync>;
+pub type StorageResult<T> = Result<T, StorageError>;
+
+/// Backend for the journal and the book recorder. Journal entries are keyed by
+/// `(instance_id, id)`: ids are only unique per bot instance, so several instances can
+/// share one database without colliding.
+pub trait Storage: Send {
+    /// Append a new journal entry
+    fn append_entry(&mut self, entry: &JournalEntry) -> StorageResult<()>;
+
+    /// Replace an existing entry (tags, notes, late PnL)
+    fn update_entry(&mut self, entry: &JournalEntry) -> StorageResult<()>;
+
+    /// All journal entries, oldest first
+    fn load_entries(&mut self) -> StorageResult<Vec<JournalEntry>>;
+
+    /// Append a recorded book snapshot
+    fn append_book(&mut self, book: &OrderBook) -> StorageResult<()>;
+
+    /// Recorded books for a token in `[from, to]`, ordered by timestamp
+    fn load_books(&mut self, token_id: &TokenId, from: u64, to: u64) -> StorageResult<Vec<OrderBook>>;
+}
+
+/// Which backend to open
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StorageConfig {
+    FlatFile { dir: PathBuf },
+    Sqlite { path: PathBuf },
+    Postgres { url: String },
+}
+
+impl Default for StorageConfig {
+    #[cfg(feature = "sqlite")]
+    fn default() -> Self {
+        StorageConfig::Sqlite { path: PathBuf::from("polyshark.db") }
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    fn default() -> Self {
+        StorageConfig::FlatFile { dir: PathBuf::from("data") }
+    }
+}
+
+impl StorageConfig {
+    /// Open the configured backend
+    pub fn open(&self) -> StorageResult<Box<dyn Storage>> {
+        match self {
+            StorageConfig::FlatFile { dir } => Ok(Box::new(FlatFileStorage::open(dir.clone())?)),
+            #[cfg(feature = "sqlite")]
+            StorageConfig::Sqlite { path } => Ok(Box::new(SqliteStorage::open(path)?)),
+            #[cfg(feature = "postgres")]
+            StorageConfig::Postgres { url } => Ok(Box::new(PostgresStorage::connect(url)?)),
+            #[allow(unreachable_patterns)]
+            other => Err(format!("storage backend {:?} not compiled in", other).into()),
+        }
+    }
+}
+
+/// JSON lines files in a directory
+#[derive(Debug, Clone)]
+pub struct FlatFileStorage {
+    pub dir: PathBuf,
+}
+
+impl FlatFileStorage {
+    pub fn open(dir: PathBuf) -> StorageResult<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn journal_path(&self) -> PathBuf {
+        self.dir.join("journal.jsonl")
+    }
+
+    fn books_path(&self) -> PathBuf {
+        self.dir.join("books.jsonl")
+    }
+
+    fn append_line(path: &PathBuf, line: &str) -> StorageResult<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", line)?;
+        Ok(())
+    }
+
+    fn read_lines<T: for<'de> Deserialize<'de>>(path: &PathBuf) -> StorageResult<Vec<T>> {
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let reader = BufReader::new(File::open(path)?);
+        let mut items = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                items.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(items)
+    }
+}
+
+impl Storage for FlatFileStorage {
+    fn append_entry(&mut self, entry: &JournalEntry) -> StorageResult<()> {
+        Self::append_line(&self.journal_path(), &serde_json::to_string(entry)?)
+    }
+
+    fn update_entry(&mut self, entry: &JournalEntry) -> StorageResult<()> {
+        // Flat files have no in-place update; rewrite the journal
+        let mut entries: Vec<JournalEntry> = Self::read_lines(&self.journal_path())?;
+        match entries.iter_mut().find(|e| e.id == entry.id && e.instance_id == entry.instance_id) {
+            Some(existing) => *existing = entry.clone(),
+            None => entries.push(entry.clone()),
+        }
+        let tmp = self.dir.join("journal.jsonl.tmp");
+        let mut out = BufWriter::new(File::create(&tmp)?);
+        for e in &entries {
+            writeln!(out, "{}", serde_json::to_string(e)?)?;
+        }
+        out.flush()?;
+        drop(out);
+        fs::rename(tmp, self.journal_path())?;
+        Ok(())
+    }
+
+    fn load_entries(&mut self) -> StorageResult<Vec<JournalEntry>> {
+        let mut entries: Vec<JournalEntry> = Self::read_lines(&self.journal_path())?;
+        entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.id.cmp(&b.id)));
+        Ok(entries)
+    }
+
+    fn append_book(&mut self, book: &OrderBook) -> StorageResult<()> {
+        Self::append_line(&self.books_path(), &serde_json::to_string(book)?)
+    }
+
+    fn load_books(&mut self, token_id: &TokenId, from: u64, to: u64) -> StorageResult<Vec<OrderBook>> {
+        let mut books: Vec<OrderBook> = Self::read_lines::<OrderBook>(&self.books_path())?
+            .into_iter()
+            .filter(|b| b.token_id == *token_id && b.timestamp >= from && b.timestamp <= to)
+            .collect();
+        books.sort_by_key(|b| b.timestamp);
+        Ok(books)
+    }
+}
+
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS journal (
+    instance_id TEXT NOT NULL,
+    id BIGINT NOT NULL,
+    timestamp BIGINT NOT NULL,
+    body TEXT NOT NULL,
+    PRIMARY KEY (instance_id, id)
+);
+CREATE TABLE IF NOT EXISTS books (token_id TEXT NOT NULL, timestamp BIGINT NOT NULL, body TEXT NOT NULL);
+CREATE INDEX IF NOT EXISTS books_token_time ON books (token_id, timestamp);
+";
+
+/// Key column for an entry's instance (entries from before instance ids share "")
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+fn instance_key(entry: &JournalEntry) -> &str {
+    entry.instance_id.as_deref().unwrap_or("")
+}
+
+/// Single-file SQLite database (default)
+#[cfg(feature = "sqlite")]
+pub struct SqliteStorage {
+    conn: rusqlite::Connection,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteStorage {
+    pub fn open(path: &std::path::Path) -> StorageResult<Self> {
+        let conn = rusqlite::Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl Storage for SqliteStorage {
+    fn append_entry(&mut self, entry: &JournalEntry) -> StorageResult<()> {
+        self.conn.execute(
+            "INSERT INTO journal (instance_id, id, timestamp, body) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![instance_key(entry), entry.id as i64, entry.timestamp as i64, serde_json::to_string(entry)?],
+        )?;
+        Ok(())
+    }
+
+    fn update_entry(&mut self, entry: &JournalEntry) -> StorageResult<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO journal (instance_id, id, timestamp, body) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![instance_key(entry), entry.id as i64, entry.timestamp as i64, serde_json::to_string(entry)?],
+        )?;
+        Ok(())
+    }
+
+    fn load_entries(&mut self) -> StorageResult<Vec<JournalEntry>> {
+        let mut stmt = self.conn.prepare("SELECT body FROM journal ORDER BY timestamp, id")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        let mut entries = Vec::new();
+        for body in rows {
+            entries.push(serde_json::from_str(&body?)?);
+        }
+        Ok(entries)
+    }
+
+    fn append_book(&mut self, book: &OrderBook) -> StorageResult<()> {
+        self.conn.execute(
+            "INSERT INTO books (token_id, timestamp, body) VALUES (?1, ?2, ?3)",
+            rusqlite::params![book.token_id.as_str(), book.timestamp as i64, serde_json::to_string(book)?],
+        )?;
+        Ok(())
+    }
+
+    fn load_books(&mut self, token_id: &TokenId, from: u64, to: u64) -> StorageResult<Vec<OrderBook>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT body FROM books WHERE token_id = ?1 AND timestamp BETWEEN ?2 AND ?3 ORDER BY timestamp",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![token_id.as_str(), from as i64, to as i64], |row| row.get::<_, String>(0))?;
+        let mut books = Vec::new();
+        for body in rows {
+            books.push(serde_json::from_str(&body?)?);
+        }
+        Ok(books)
+    }
+}
+
+/// Shared Postgres database for multi-instance deployments
+#[cfg(feature = "postgres")]
+pub struct PostgresStorage {
+    client: postgres::Client,
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresStorage {
+    pub fn connect(url: &str) -> StorageResult<Self> {
+        let mut client = postgres::Client::connect(url, postgres::NoTls)?;
+        client.batch_execute(SCHEMA)?;
+        Ok(Self { client })
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl Storage for PostgresStorage {
+    fn append_entry(&mut self, entry: &JournalEntry) -> StorageResult<()> {
+        self.client.execute(
+            "INSERT INTO journal (instance_id, id, timestamp, body) VALUES ($1, $2, $3, $4)",
+            &[&instance_key(entry), &(entry.id as i64), &(entry.timestamp as i64), &serde_json::to_string(entry)?],
+        )?;
+        Ok(())
+    }
+
+    fn update_entry(&mut self, entry: &JournalEntry) -> StorageResult<()> {
+        self.client.execute(
+            "INSERT INTO journal (instance_id, id, timestamp, body) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (instance_id, id) DO UPDATE SET timestamp = EXCLUDED.timestamp, body = EXCLUDED.body",
+            &[&instance_key(entry), &(entry.id as i64), &(entry.timestamp as i64), &serde_json::to_string(entry)?],
+        )?;
+        Ok(())
+    }
+
+    fn load_entries(&mut self) -> StorageResult<Vec<JournalEntry>> {
+        let mut entries = Vec::new();
+        for row in self.client.query("SELECT body FROM journal ORDER BY timestamp, id", &[])? {
+            entries.push(serde_json::from_str(row.get::<_, &str>(0))?);
+        }
+        Ok(entries)
+    }
+
+    fn append_book(&mut self, book: &OrderBook) -> StorageResult<()> {
+        self.client.execute(
+            "INSERT INTO books (token_id, timestamp, body) VALUES ($1, $2, $3)",
+            &[&book.token_id.as_str(), &(book.timestamp as i64), &serde_json::to_string(book)?],
+        )?;
+        Ok(())
+    }
+
+    fn load_books(&mut self, token_id: &TokenId, from: u64, to: u64) -> StorageResult<Vec<OrderBook>> {
+        let rows = self.client.query(
+            "SELECT body FROM books WHERE token_id = $1 AND timestamp BETWEEN $2 AND $3 ORDER BY timestamp",
+            &[&token_id.as_str(), &(from as i64), &(to as i64)],
+        )?;
+        let mut books = Vec::new();
+        for row in rows {
+            books.push(serde_json::from_str(row.get::<_, &str>(0))?);
+        }
+        Ok(books)
+    }
+}
@@ -0,0 +1,287 @@
+use crate::types::{Market, MarketId, Side, TokenId, Trade};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+/// Windows `MarketFlowTracker` keeps by default: 1 minute, 5 minutes, 1 hour
+pub const DEFAULT_FLOW_WINDOWS_MS: [u64; 3] = [60_000, 300_000, 3_600_000];
+
+/// Rolling trade statistics for one token (timestamps in ms)
+#[derive(Debug, Clone, Default)]
+pub struct TradeStats {
+    pub last_price: Option<f64>,
+    pub last_timestamp: Option<u64>,
+    pub trade_count: usize,   // Trades inside the window
+    pub buy_volume: f64,      // Taker buys (lifted offers)
+    pub sell_volume: f64,     // Taker sells (hit bids)
+    pub window_ms: u64,
+}
+
+impl TradeStats {
+    pub fn volume(&self) -> f64 {
+        self.buy_volume + self.sell_volume
+    }
+
+    /// (buy - sell) / total, in [-1, 1]
+    pub fn imbalance(&self) -> f64 {
+        let total = self.volume();
+        if total <= 0.0 {
+            0.0
+        } else {
+            (self.buy_volume - self.sell_volume) / total
+        }
+    }
+}
+
+/// Taker flow by distance from mid for one token, used to estimate maker fills per price level
+#[derive(Debug, Clone, Default)]
+pub struct LevelFlow {
+    pub window_ms: u64,
+    pub below_mid: Vec<(f64, f64)>,  // (distance, size) of taker sells that hit bids
+    pub above_mid: Vec<(f64, f64)>,  // (distance, size) of taker buys that lifted asks
+}
+
+impl LevelFlow {
+    /// Shares per second that traded at least `distance` from mid on the side a resting
+    /// `side` order sits (bids for Buy, asks for Sell)
+    pub fn rate_through(&self, side: Side, distance: f64) -> f64 {
+        if self.window_ms == 0 {
+            return 0.0;
+        }
+        let prints = match side {
+            Side::Buy => &self.below_mid,
+            Side::Sell => &self.above_mid,
+        };
+        let volume: f64 = prints.iter().filter(|(d, _)| *d >= distance - 1e-9).map(|(_, size)| size).sum();
+        volume / (self.window_ms as f64 / 1000.0)
+    }
+
+    /// Mean print size on that side (0 when nothing traded)
+    pub fn avg_size(&self, side: Side) -> f64 {
+        let prints = match side {
+            Side::Buy => &self.below_mid,
+            Side::Sell => &self.above_mid,
+        };
+        if prints.is_empty() {
+            return 0.0;
+        }
+        prints.iter().map(|(_, s)| s).sum::<f64>() / prints.len() as f64
+    }
+}
+
+/// Per-token last sale, rolling volume and imbalance from the trade channel
+#[derive(Debug, Clone)]
+pub struct TradeTape {
+    pub window_ms: u64,
+    trades: HashMap<TokenId, VecDeque<Trade>>,
+    last: HashMap<TokenId, Trade>,  // Survives window expiry for staleness checks
+}
+
+impl TradeTape {
+    pub fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms,
+            trades: HashMap::new(),
+            last: HashMap::new(),
+        }
+    }
+
+    /// Add a trade print
+    pub fn record(&mut self, trade: Trade) {
+        let newer = self.last.get(&trade.token_id).is_none_or(|t| trade.timestamp >= t.timestamp);
+        if newer {
+            self.last.insert(trade.token_id.clone(), trade.clone());
+        }
+        self.trades.entry(trade.token_id.clone()).or_default().push_back(trade);
+    }
+
+    /// Drop trades older than the window
+    pub fn prune(&mut self, now_ms: u64) {
+        let cutoff = now_ms.saturating_sub(self.window_ms);
+        for queue in self.trades.values_mut() {
+            while queue.front().is_some_and(|t| t.timestamp < cutoff) {
+                queue.pop_front();
+            }
+        }
+        self.trades.retain(|_, q| !q.is_empty());
+    }
+
+    /// Most recent print for a token
+    pub fn last_trade(&self, token_id: &TokenId) -> Option<&Trade> {
+        self.last.get(token_id)
+    }
+
+    /// Rolling stats for a token as of `now_ms`
+    pub fn stats(&self, token_id: &TokenId, now_ms: u64) -> TradeStats {
+        let cutoff = now_ms.saturating_sub(self.window_ms);
+        let mut stats = TradeStats {
+            window_ms: self.window_ms,
+            ..Default::default()
+        };
+        if let Some(last) = self.last.get(token_id) {
+            stats.last_price = Some(last.price);
+            stats.last_timestamp = Some(last.timestamp);
+        }
+        for t in self.trades.get(token_id).into_iter().flatten().filter(|t| t.timestamp >= cutoff) {
+            stats.trade_count += 1;
+            match t.side {
+                Side::Buy => stats.buy_volume += t.size,
+                Side::Sell => stats.sell_volume += t.size,
+            }
+        }
+        stats
+    }
+
+    /// Windowed prints bucketed by distance from `mid`
+    pub fn level_flow(&self, token_id: &TokenId, mid: f64, now_ms: u64) -> LevelFlow {
+        let cutoff = now_ms.saturating_sub(self.window_ms);
+        let mut flow = LevelFlow { window_ms: self.window_ms, ..Default::default() };
+        for t in self.trades.get(token_id).into_iter().flatten().filter(|t| t.timestamp >= cutoff) {
+            match t.side {
+                Side::Sell => flow.below_mid.push(((mid - t.price).max(0.0), t.size)),
+                Side::Buy => flow.above_mid.push(((t.price - mid).max(0.0), t.size)),
+            }
+        }
+        flow
+    }
+
+    /// A market is stale when none of its tokens traded within `max_age_ms`
+    pub fn is_stale(&self, market: &Market, now_ms: u64, max_age_ms: u64) -> bool {
+        !market.clob_token_ids.iter().any(|token| {
+            self.last.get(token).is_some_and(|t| now_ms.saturating_sub(t.timestamp) <= max_age_ms)
+        })
+    }
+}
+
+/// Who is trading a market over one rolling window, from the public trade feed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MarketFlowStats {
+    pub window_ms: u64,
+    pub trade_count: usize,
+    pub buy_volume: f64,   // Taker buys across all outcome tokens
+    pub sell_volume: f64,  // Taker sells
+}
+
+impl MarketFlowStats {
+    pub fn volume(&self) -> f64 {
+        self.buy_volume + self.sell_volume
+    }
+
+    /// Taker buy volume per unit of taker sell volume (`None` when nobody sold)
+    pub fn taker_buy_sell_ratio(&self) -> Option<f64> {
+        (self.sell_volume > 0.0).then(|| self.buy_volume / self.sell_volume)
+    }
+
+    pub fn avg_trade_size(&self) -> f64 {
+        if self.trade_count == 0 { 0.0 } else { self.volume() / self.trade_count as f64 }
+    }
+
+    pub fn trades_per_minute(&self) -> f64 {
+        if self.window_ms == 0 { 0.0 } else { self.trade_count as f64 * 60_000.0 / self.window_ms as f64 }
+    }
+
+    /// Competition parameter for the fill model: chance another taker trades the market
+    /// within `reaction_ms` of an opportunity appearing, treating prints as a Poisson stream
+    pub fn competition(&self, reaction_ms: u64) -> f64 {
+        let per_ms = self.trades_per_minute() / 60_000.0;
+        1.0 - (-per_ms * reaction_ms as f64).exp()
+    }
+}
+
+/// One market's flow across every tracked window, for reports
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketFlowRow {
+    pub market_id: MarketId,
+    pub windows: Vec<MarketFlowStats>,  // Shortest window first
+}
+
+/// Aggregates the public trade stream per market (all outcome tokens together) over
+/// several rolling windows
+#[derive(Debug, Clone)]
+pub struct MarketFlowTracker {
+    pub windows_ms: Vec<u64>,
+    token_market: HashMap<TokenId, MarketId>,
+    trades: HashMap<MarketId, VecDeque<Trade>>,
+}
+
+impl Default for MarketFlowTracker {
+    fn default() -> Self {
+        Self::new(&DEFAULT_FLOW_WINDOWS_MS)
+    }
+}
+
+impl MarketFlowTracker {
+    pub fn new(windows_ms: &[u64]) -> Self {
+        let mut windows_ms = windows_ms.to_vec();
+        windows_ms.sort_unstable();
+        windows_ms.dedup();
+        Self { windows_ms, token_market: HashMap::new(), trades: HashMap::new() }
+    }
+
+    /// Map a market's tokens so their prints count towards it
+    pub fn watch(&mut self, market: &Market) {
+        for token in &market.clob_token_ids {
+            self.token_market.insert(token.clone(), market.id.clone());
+        }
+    }
+
+    /// Add a print; prints for tokens of unwatched markets are ignored
+    pub fn record(&mut self, trade: Trade) {
+        if let Some(market_id) = self.token_market.get(&trade.token_id) {
+            self.trades.entry(market_id.clone()).or_default().push_back(trade);
+        }
+    }
+
+    /// Drop prints older than the longest window
+    pub fn prune(&mut self, now_ms: u64) {
+        let cutoff = now_ms.saturating_sub(self.windows_ms.last().copied().unwrap_or(0));
+        for queue in self.trades.values_mut() {
+            while queue.front().is_some_and(|t| t.timestamp < cutoff) {
+                queue.pop_front();
+            }
+        }
+        self.trades.retain(|_, q| !q.is_empty());
+    }
+
+    /// Stats for one market over the trailing `window_ms`
+    pub fn stats(&self, market_id: &MarketId, window_ms: u64, now_ms: u64) -> MarketFlowStats {
+        let cutoff = now_ms.saturating_sub(window_ms);
+        let mut stats = MarketFlowStats { window_ms, ..Default::default() };
+        for t in self.trades.get(market_id).into_iter().flatten().filter(|t| t.timestamp >= cutoff) {
+            stats.trade_count += 1;
+            match t.side {
+                Side::Buy => stats.buy_volume += t.size,
+                Side::Sell => stats.sell_volume += t.size,
+            }
+        }
+        stats
+    }
+
+    /// One token's prints over the trailing `window_ms`, bucketed by distance from `mid`
+    pub fn level_flow(&self, token_id: &TokenId, mid: f64, window_ms: u64, now_ms: u64) -> LevelFlow {
+        let cutoff = now_ms.saturating_sub(window_ms);
+        let mut flow = LevelFlow { window_ms, ..Default::default() };
+        let Some(market_id) = self.token_market.get(token_id) else {
+            return flow;
+        };
+        for t in self.trades.get(market_id).into_iter().flatten().filter(|t| t.timestamp >= cutoff && t.token_id == *token_id) {
+            match t.side {
+                Side::Sell => flow.below_mid.push(((mid - t.price).max(0.0), t.size)),
+                Side::Buy => flow.above_mid.push(((t.price - mid).max(0.0), t.size)),
+            }
+        }
+        flow
+    }
+
+    /// Every market with prints, busiest (by trades in the shortest window) first
+    pub fn report(&self, now_ms: u64) -> Vec<MarketFlowRow> {
+        let mut rows: Vec<MarketFlowRow> = self.trades.keys()
+            .map(|id| MarketFlowRow {
+                market_id: id.clone(),
+                windows: self.windows_ms.iter().map(|w| self.stats(id, *w, now_ms)).collect(),
+            })
+            .collect();
+        let busiest = |r: &MarketFlowRow| r.windows.first().map(|w| w.trade_count).unwrap_or(0);
+        rows.sort_by(|a, b| busiest(b).cmp(&busiest(a)).then_with(|| a.market_id.cmp(&b.market_id)));
+        rows
+    }
+}
@@ -1,15 +1,173 @@
 use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::Deref;
+
+pub const SECONDS_PER_YEAR : f64 = 365.0 * 24.0 * 3600.0;
+
+// string ids that must not be mixed up -> a market id is never a token id
+// both deref to &str and borrow as str so HashMap<TokenId, _>.get("...") still works
+macro_rules! string_id {
+    ($name:ident, $what:literal) => {
+        #[doc = $what]
+        #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn new(id: impl Into<String>) -> Self {
+                Self(id.into())
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Deref for $name {
+            type Target = str;
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> Self {
+                Self(id.to_string())
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> String {
+                id.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<String> for $name {
+            fn eq(&self, other: &String) -> bool {
+                &self.0 == other
+            }
+        }
+    };
+}
+
+string_id!(TokenId, "CLOB outcome token id (one per outcome)");
+string_id!(MarketId, "GAMMA market id");
+
+// outcome token price -> always a probability in [0, 1]
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct Price(f64);
+
+impl Price {
+    pub fn new(price: f64) -> Option<Price> {
+        (price.is_finite() && (0.0..=1.0).contains(&price)).then_some(Price(price))
+    }
+
+    pub fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl TryFrom<f64> for Price {
+    type Error = String;
+    fn try_from(price: f64) -> Result<Self, String> {
+        Price::new(price).ok_or_else(|| format!("price {} outside [0, 1]", price))
+    }
+}
+
+impl From<Price> for f64 {
+    fn from(price: Price) -> f64 {
+        price.0
+    }
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.4}", self.0)
+    }
+}
+
+// number of outcome tokens -> finite and strictly positive
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct Size(f64);
+
+impl Size {
+    pub fn new(size: f64) -> Option<Size> {
+        (size.is_finite() && size > 0.0).then_some(Size(size))
+    }
+
+    pub fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl TryFrom<f64> for Size {
+    type Error = String;
+    fn try_from(size: f64) -> Result<Self, String> {
+        Size::new(size).ok_or_else(|| format!("size {} must be positive", size))
+    }
+}
+
+impl From<Size> for f64 {
+    fn from(size: Size) -> f64 {
+        size.0
+    }
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2}", self.0)
+    }
+}
 
 
 // represents a polymarket prediction market
 #[derive(Debug, Clone , Serialize , Deserialize)]
 pub struct Market {
-    pub id : String , // unique market ID 
+    pub id : MarketId , // unique market ID 
     pub question : String , // Human redabale question
     pub slug : String , // url friendly name
     pub outcomes : Vec<String> , // ["yes" , "no"]
     pub outcome_prices : Vec<f64> , // [0.5 , 0.5]
-    pub clob_token_ids : Vec<String> ,  // Token Ids for trading 
+    pub clob_token_ids : Vec<TokenId> ,  // Token Ids for trading 
     pub best_bid : Option<f64> , // highest by price across outcomes 
     pub best_ask : Option<f64> ,  // lowest sell price across outcomes
     pub maker_base_fee : u32 ,   // In basis points (eg : 0) -> fees if you add liquidity 
@@ -17,7 +175,60 @@ pub struct Market {
     pub liquidity : f64 ,  // Depth of the market 
     pub volume_24hr : f64 , // trading activity 
     pub active : bool ,  /// is market live ? 
-    pub accepting_orders : bool  // can you trade right now ? 
+    pub accepting_orders : bool ,  // can you trade right now ? 
+    #[serde(default)]
+    pub condition_id : String , // CTF condition this market settles against
+    #[serde(default)]
+    pub end_date : Option<String> , // ISO-8601 expected resolution time (eg : "2025-11-04T00:00:00Z")
+    #[serde(default)]
+    pub resolution_source : Option<String> , // where the outcome is decided
+    #[serde(default)]
+    pub category : Option<String> , // eg : "Politics"
+    #[serde(default)]
+    pub tags : Vec<String> , // free-form labels from gamma
+    #[serde(default)]
+    pub neg_risk : bool ,  // part of a NegRisk (multi-outcome) event
+    #[serde(default)]
+    pub event_id : Option<String> , // parent gamma event
+    #[serde(default)]
+    pub rewards_max_spread : Option<f64> , // liquidity rewards: max distance from mid that scores (price units, eg : 0.03)
+    #[serde(default)]
+    pub rewards_min_size : Option<f64> , // liquidity rewards: smallest qualifying order (shares)
+    #[serde(default)]
+    pub restricted : bool , // gamma marks it unavailable in some jurisdictions
+    #[serde(default)]
+    pub description : Option<String> , // resolution rules as written
+    #[serde(default)]
+    pub uma_resolution_status : Option<String> , // eg : "proposed" , "disputed" , "resolved"
+    #[serde(default)]
+    pub closed : bool , // trading over (resolved or finished) ; only archive listings include these
+}
+
+// represents a gamma event : the parent question that groups related markets
+// example -> "Fed decision in December?" -> markets "25 bps cut" , "50 bps cut" , "no change"
+#[derive(Debug, Clone , Serialize , Deserialize)]
+pub struct Event {
+    pub id : String , // gamma event ID
+    pub slug : String ,
+    pub title : String ,
+    #[serde(default)]
+    pub category : Option<String> ,
+    #[serde(default)]
+    pub end_date : Option<String> , // ISO-8601 , latest of its markets
+    #[serde(default)]
+    pub neg_risk : bool , // markets are mutually exclusive -> exactly one resolves YES
+    pub active : bool ,
+    #[serde(default)]
+    pub closed : bool ,
+    pub markets : Vec<EventMarket> , // children , in gamma's order
+}
+
+// one market inside an event
+#[derive(Debug, Clone , Serialize , Deserialize)]
+pub struct EventMarket {
+    #[serde(default)]
+    pub group_item_title : Option<String> , // short label of the bucket (eg : "25 bps") , the question otherwise
+    pub market : Market , // event_id always points back at the parent
 }
 
 // Single price level in order book 
@@ -38,6 +249,44 @@ pub struct PriceLevel {
     pub size : f64   // Changed to f64 to match execution_price logic
 }
 
+// One level consumed while walking the book
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LevelFill {
+    pub price : f64 ,
+    pub size : f64 ,             // taken at this level
+    pub cumulative_size : f64 ,  // taken up to and including this level
+    pub cumulative_cost : f64 ,
+}
+
+// Per-level breakdown of an order against a book -> what execution_price averages over
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookWalk {
+    pub side : Side ,
+    pub requested : f64 ,
+    pub levels : Vec<LevelFill> ,
+    pub unfilled : f64   // left over when the book ran out
+}
+
+impl BookWalk {
+    pub fn filled(&self) -> f64 {
+        self.levels.last().map(|l| l.cumulative_size).unwrap_or(0.0)
+    }
+
+    pub fn cost(&self) -> f64 {
+        self.levels.last().map(|l| l.cumulative_cost).unwrap_or(0.0)
+    }
+
+    // VWAP of what was filled
+    pub fn vwap(&self) -> Option<f64> {
+        let filled = self.filled();
+        (filled > 0.0).then(|| self.cost() / filled)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.unfilled <= 0.0
+    }
+}
+
 // Order book for a single token 
 // You trade YES and NO independently, each has its own order book.
 // BIDS:
@@ -49,7 +298,7 @@ pub struct PriceLevel {
 // 0.52 -> 700 tokens
 #[derive(Debug, Clone , Serialize , Deserialize)]
 pub struct OrderBook { 
-    pub token_id : String , 
+    pub token_id : TokenId , 
     pub bids : Vec<PriceLevel> , 
     pub asks : Vec<PriceLevel> ,  // Added missing comma
     pub timestamp : u64 
@@ -61,7 +310,7 @@ pub struct OrderBook {
 #[derive(Debug, Clone , Serialize , Deserialize)]
 pub struct Trade {
     pub id : String , 
-    pub token_id : String , 
+    pub token_id : TokenId , 
     pub price : f64 ,   // Added missing comma
     pub size : f64 , 
     pub side : Side , 
@@ -69,8 +318,9 @@ pub struct Trade {
 }
 
 // Order side 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 pub enum Side { 
+    #[default]
     Buy , 
     Sell 
 }
@@ -79,9 +329,9 @@ pub enum Side {
 // core invariant -> YES_price + NO_price ≈ 1
 // example arbitrage _> yes = 0.48 , no = 0.47 -> Sum = 0.95 -> one of them settles at $1
 // guarenteed profit = 0.05 - fees 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone , Serialize)]
 pub struct ArbitrageSignal {
-    pub market_id : String , 
+    pub market_id : MarketId , 
     pub spread : f64 ,  // how much the price deviates from 1 
     pub edge : f64 , // Expected profit per unit 
     pub recommended_side : Side , 
@@ -89,6 +339,31 @@ pub struct ArbitrageSignal {
     pub no_price : f64 
 }
 
+// Categorical (bucketed range / multi-outcome) signal
+// an event's mutually exclusive markets -> exactly one YES settles at $1
+// example -> CPI buckets : 0.30 + 0.45 + 0.20 = 0.95 -> buy every YES for 0.95 , receive 1
+#[derive(Debug, Clone , Serialize)]
+pub struct CategoricalSignal {
+    pub event_id : String ,
+    pub market_ids : Vec<MarketId> ,
+    pub yes_prices : Vec<f64> ,
+    pub sum : f64 ,  // sum of YES prices across buckets
+    pub spread : f64 , // |sum - 1|
+    pub recommended_side : Side  // Buy all YES when sum < 1 , Sell when > 1
+}
+
+// Cross-market signal
+// two confirmed duplicates of one question -> YES in one plus NO in the other settles at exactly $1
+// example -> YES(a) 0.40 + NO(b) 0.55 = 0.95 -> buy both for 0.95 , receive 1
+#[derive(Debug, Clone , Serialize)]
+pub struct CrossMarketSignal {
+    pub yes_market : MarketId , // market whose YES is bought
+    pub no_market : MarketId ,  // market whose NO is bought
+    pub yes_price : f64 ,
+    pub no_price : f64 ,
+    pub spread : f64 , // 1 - (yes_price + no_price)
+}
+
 // Execution resutl 
 #[derive(Debug, Clone)]
 pub struct ExecutionResult {
@@ -101,6 +376,13 @@ pub struct ExecutionResult {
 }
 
 
+impl ExecutionResult {
+    // Cash a sell brings in: notional less the fee
+    pub fn proceeds(&self) -> f64 {
+        self.execution_price * self.filed_size - self.fee_paid
+    }
+}
+
 // Implementaion for Market 
 
 impl Market {
@@ -119,7 +401,7 @@ impl Market {
 
     // get YES token price (assumes binary market)
     pub fn yes_price(&self) -> f64 {
-        self.outcome_prices.get(0).copied().unwrap_or(0.0)
+        self.outcome_prices.first().copied().unwrap_or(0.0)
     }
 
 
@@ -133,10 +415,161 @@ impl Market {
     pub fn taker_fee_rate(&self) -> f64 {
         self.taker_base_fee as f64 / 10000.0
     }
+
+    // get end date as unix seconds (None if missing or unparsable)
+    pub fn end_timestamp(&self) -> Option<u64> {
+        self.end_date.as_deref().and_then(parse_iso8601)
+    }
+
+    // seconds left until expected resolution (0 once the end date has passed)
+    pub fn seconds_to_resolution(&self, now: u64) -> Option<u64> {
+        self.end_timestamp().map(|end| end.saturating_sub(now))
+    }
+
+    // check if market carries a tag (case insensitive)
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+}
+
+
+// Implementaion for Event
+
+impl Event {
+
+    // child markets , in gamma's order
+    pub fn markets(&self) -> Vec<&Market> {
+        self.markets.iter().map(|m| &m.market).collect()
+    }
+
+    pub fn market_ids(&self) -> Vec<MarketId> {
+        self.markets.iter().map(|m| m.market.id.clone()).collect()
+    }
+
+    // check if the event owns the market
+    pub fn contains(&self, market_id: &MarketId) -> bool {
+        self.markets.iter().any(|m| m.market.id == *market_id)
+    }
+
+    // the markets form one categorical set (buying every YES pays exactly $1)
+    pub fn is_exclusive(&self) -> bool {
+        self.neg_risk && self.markets.len() > 1
+    }
+
+    // every child is live and accepting orders , so the whole set can be traded
+    pub fn is_tradable(&self) -> bool {
+        !self.markets.is_empty() && self.markets.iter().all(|m| m.market.active && m.market.accepting_orders)
+    }
+
+    // short label for a child market (bucket title , or its question)
+    pub fn label_of(&self, market_id: &MarketId) -> Option<&str> {
+        self.markets.iter()
+            .find(|m| m.market.id == *market_id)
+            .map(|m| m.group_item_title.as_deref().unwrap_or(&m.market.question))
+    }
+}
+
+// parse "YYYY-MM-DD" or "YYYY-MM-DDTHH:MM:SS[.fff]Z" into unix seconds
+pub fn parse_iso8601(s: &str) -> Option<u64> {
+    let date = s.get(0..10)?;
+    let mut parts = date.split('-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let (mut hour, mut minute, mut second) = (0, 0, 0);
+    if let Some(time) = s.get(11..19) {
+        let mut hms = time.split(':');
+        hour = hms.next()?.parse().ok()?;
+        minute = hms.next()?.parse().ok()?;
+        second = hms.next()?.parse().ok()?;
+    }
+
+    // days since epoch (Howard Hinnant's days_from_civil)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    let secs = days * 86400 + hour * 3600 + minute * 60 + second;
+    u64::try_from(secs).ok()
+}
+
+// unix seconds -> "YYYY-MM-DD" (UTC) , inverse of the date part above (civil_from_days)
+pub fn format_date(secs: u64) -> String {
+    let z = (secs / 86400) as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
 }
 
 // Implemtation of OrderBook 
 impl OrderBook {
+    // sort so that bids[0] / asks[0] are the best levels (APIs don't guarantee order)
+    pub fn sort_levels(&mut self) {
+        self.bids.sort_by(|a, b| b.price.total_cmp(&a.price));
+        self.asks.sort_by(|a, b| a.price.total_cmp(&b.price));
+    }
+
+    // replace one level from an incremental update (size 0 removes it), keeping sort order
+    // edits the level vec in place -> no re-sort and no allocation once the book has its depth
+    pub fn apply_level(&mut self, side: Side, price: f64, size: f64, timestamp: u64) {
+        let levels = match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        // bids descend , asks ascend -> position of `price` in the side's own order
+        let found = levels.binary_search_by(|l| {
+            if (l.price - price).abs() <= 1e-9 {
+                std::cmp::Ordering::Equal
+            } else if side == Side::Buy {
+                price.total_cmp(&l.price)
+            } else {
+                l.price.total_cmp(&price)
+            }
+        });
+        match (found, size > 0.0) {
+            (Ok(i), true) => levels[i].size = size,
+            (Ok(i), false) => {
+                levels.remove(i);
+            }
+            (Err(i), true) => levels.insert(i, PriceLevel { price, size }),
+            (Err(_), false) => {}
+        }
+        self.timestamp = self.timestamp.max(timestamp);
+    }
+
+    // best bid and ask as they would be after `apply_level(side, price, size)` , without touching the book
+    pub fn touch_after(&self, side: Side, price: f64, size: f64) -> (Option<f64>, Option<f64>) {
+        let levels = match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        };
+        let rest = levels.iter().map(|l| l.price).find(|p| (p - price).abs() > 1e-9);
+        let best = match (side, size > 0.0, rest) {
+            (_, false, rest) => rest,
+            (_, true, None) => Some(price),
+            (Side::Buy, true, Some(p)) => Some(p.max(price)),
+            (Side::Sell, true, Some(p)) => Some(p.min(price)),
+        };
+        match side {
+            Side::Buy => (best, self.best_ask()),
+            Side::Sell => (self.best_bid(), best),
+        }
+    }
+
     // get best bid price 
     pub fn best_bid(&self) -> Option<f64> {
         self.bids.first().map(|l| l.price)
@@ -176,32 +609,105 @@ impl OrderBook {
         self.asks.iter().map(|l| l.size).sum()
     }
 
-    // calculates given price for a give size (walks the book)
-    pub fn execution_price(&self, size: f64, side: Side) -> Option<f64> {
+    // walks the book for `size` , recording every level consumed on the way
+    pub fn walk(&self, size: f64, side: Side) -> BookWalk {
         let levels = match side {
             Side::Buy => &self.asks,
             Side::Sell => &self.bids,
         };
 
         let mut remaining = size;
-        let mut total_cost = 0.0;
+        let mut fills = Vec::new();
+        let (mut cumulative_size, mut cumulative_cost) = (0.0, 0.0);
 
         for level in levels {
             let fill = remaining.min(level.size);
-            total_cost += fill * level.price;
+            cumulative_size += fill;
+            cumulative_cost += fill * level.price;
+            fills.push(LevelFill { price: level.price, size: fill, cumulative_size, cumulative_cost });
             remaining -= fill;
             if remaining <= 0.0 {
                 break;
             }
         }
 
-        if remaining > 0.0 {
+        BookWalk { side, requested: size, levels: fills, unfilled: remaining.max(0.0) }
+    }
+
+    // Size resting at prices at or better than `limit` -> all a limit order can take
+    pub fn depth_within(&self, limit: f64, side: Side) -> f64 {
+        match side {
+            Side::Buy => self.asks.iter().take_while(|l| l.price <= limit + 1e-9).map(|l| l.size).sum(),
+            Side::Sell => self.bids.iter().take_while(|l| l.price >= limit - 1e-9).map(|l| l.size).sum(),
+        }
+    }
+
+    // calculates given price for a give size (walks the book)
+    pub fn execution_price(&self, size: f64, side: Side) -> Option<f64> {
+        let walk = self.walk(size, side);
+        if walk.unfilled > 0.0 {
             None // Not enough liquidity
         } else {
-            Some(total_cost / size) // Volume-weighted average price
+            Some(walk.cost() / size) // Volume-weighted average price
+        }
+    }
+
+
+
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book() -> OrderBook {
+        OrderBook {
+            token_id: TokenId::from("t1"),
+            bids: vec![PriceLevel { price: 0.48, size: 100.0 }, PriceLevel { price: 0.47, size: 200.0 }],
+            asks: vec![PriceLevel { price: 0.50, size: 100.0 }, PriceLevel { price: 0.52, size: 50.0 }, PriceLevel { price: 0.55, size: 100.0 }],
+            timestamp: 0,
         }
     }
 
+    #[test]
+    fn walk_consumes_levels_in_order_with_running_totals() {
+        let walk = book().walk(175.0, Side::Buy);
+        let levels: Vec<(f64, f64)> = walk.levels.iter().map(|l| (l.price, l.size)).collect();
+        assert_eq!(levels, vec![(0.50, 100.0), (0.52, 50.0), (0.55, 25.0)]);
+        let last = walk.levels.last().unwrap();
+        assert!((last.cumulative_size - 175.0).abs() < 1e-9);
+        assert!((last.cumulative_cost - (50.0 + 26.0 + 13.75)).abs() < 1e-9);
+        assert!(walk.is_complete());
+    }
+
+    #[test]
+    fn vwap_matches_execution_price() {
+        let book = book();
+        let walk = book.walk(175.0, Side::Buy);
+        let vwap = walk.vwap().unwrap();
+        assert!((vwap - 89.75 / 175.0).abs() < 1e-12);
+        assert_eq!(book.execution_price(175.0, Side::Buy), Some(vwap));
+    }
+
+    #[test]
+    fn sells_walk_the_bids() {
+        let walk = book().walk(150.0, Side::Sell);
+        assert_eq!(walk.levels.len(), 2);
+        assert!((walk.vwap().unwrap() - (48.0 + 23.5) / 150.0).abs() < 1e-12);
+    }
 
+    #[test]
+    fn exhausted_book_reports_unfilled_and_no_execution_price() {
+        let book = book();
+        let walk = book.walk(300.0, Side::Buy);
+        assert!(!walk.is_complete());
+        assert!((walk.unfilled - 50.0).abs() < 1e-9);
+        assert!((walk.filled() - 250.0).abs() < 1e-9);
+        assert_eq!(book.execution_price(300.0, Side::Buy), None);
+    }
 
-}
\ No newline at end of file
+    #[test]
+    fn empty_walk_has_no_vwap() {
+        let empty = OrderBook { token_id: TokenId::from("t1"), bids: Vec::new(), asks: Vec::new(), timestamp: 0 };
+        assert_eq!(empty.walk(10.0, Side::Buy).vwap(), None);
+    }
+}
@@ -0,0 +1,188 @@
+use crate::money::Collateral;
+use crate::types::Side;
+
+/// Outcome shares are CTF ERC-1155 tokens minted 1:1 against collateral, so they carry
+/// the collateral's decimals
+pub const SHARE_DECIMALS: u32 = 6;
+
+/// Slack for binary float noise before rounding (0.29 * 100 = 28.999999999999996)
+const ROUNDING_EPSILON: f64 = 1e-9;
+
+/// Which way an amount that doesn't fit the precision is pushed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rounding {
+    Down,     // Never promise more than we have (sizes, amounts we pay)
+    Up,       // Never ask for less than we need
+    Nearest,
+}
+
+/// A fixed decimal precision: conversions between human-readable amounts and integers of
+/// the smallest unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Decimals(pub u32);
+
+impl Decimals {
+    pub fn of(collateral: Collateral) -> Self {
+        Decimals(collateral.decimals())
+    }
+
+    pub fn shares() -> Self {
+        Decimals(SHARE_DECIMALS)
+    }
+
+    fn scale(self) -> f64 {
+        10f64.powi(self.0 as i32)
+    }
+
+    /// Integer base units, e.g. 1.5 USDC -> 1_500_000. `None` for negative or non-finite amounts.
+    pub fn to_base(self, amount: f64, rounding: Rounding) -> Option<u64> {
+        if !amount.is_finite() || amount < 0.0 {
+            return None;
+        }
+        let scaled = amount * self.scale();
+        let units = match rounding {
+            Rounding::Down => (scaled + ROUNDING_EPSILON).floor(),
+            Rounding::Up => (scaled - ROUNDING_EPSILON).ceil(),
+            Rounding::Nearest => scaled.round(),
+        };
+        (units <= u64::MAX as f64).then_some(units.max(0.0) as u64)
+    }
+
+    /// Human-readable amount of `units` base units
+    pub fn amount(self, units: u64) -> f64 {
+        units as f64 / self.scale()
+    }
+
+    /// `amount` rounded to this precision
+    pub fn round(self, amount: f64, rounding: Rounding) -> f64 {
+        match self.to_base(amount.abs(), rounding) {
+            Some(units) => self.amount(units).copysign(amount),
+            None => amount,
+        }
+    }
+
+    /// Exact decimal string for API payloads, with no float noise ("0.1", not "0.1000000001")
+    pub fn format(self, amount: f64, rounding: Rounding) -> String {
+        let units = self.to_base(amount.abs(), rounding).unwrap_or(0);
+        let scale = 10u64.pow(self.0);
+        let sign = if amount < 0.0 && units > 0 { "-" } else { "" };
+        if self.0 == 0 {
+            return format!("{}{}", sign, units);
+        }
+        let frac = format!("{:0width$}", units % scale, width = self.0 as usize);
+        let frac = frac.trim_end_matches('0');
+        if frac.is_empty() {
+            format!("{}{}", sign, units / scale)
+        } else {
+            format!("{}{}.{}", sign, units / scale, frac)
+        }
+    }
+}
+
+/// Precision the CLOB accepts for one market's orders, derived from its tick size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderPrecision {
+    pub price: Decimals,   // Tick: 0.01 -> 2
+    pub size: Decimals,    // Shares: always 2
+    pub amount: Decimals,  // Price x size: price decimals + size decimals
+}
+
+impl OrderPrecision {
+    /// `tick` is the market's minimum price increment (0.1, 0.01, 0.001 or 0.0001)
+    pub fn for_tick(tick: f64) -> Self {
+        let price = (-tick.log10()).round().clamp(0.0, SHARE_DECIMALS as f64) as u32;
+        Self { price: Decimals(price), size: Decimals(2), amount: Decimals(price + 2) }
+    }
+}
+
+/// On-chain maker/taker amounts of a limit order, in base units of what each side gives
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderAmounts {
+    pub maker_amount: u64,  // What we give: collateral for buys, shares for sells
+    pub taker_amount: u64,  // What we get
+}
+
+impl OrderAmounts {
+    /// Round price to the tick and size down to what the CLOB accepts, then express both
+    /// legs in base units. Rounding never leaves the order worse for us than `price` and
+    /// `size`: buys round cash down, sells round it up.
+    pub fn for_order(side: Side, price: f64, size: f64, precision: OrderPrecision, collateral: Collateral) -> Option<Self> {
+        let price = precision.price.round(price, match side {
+            Side::Buy => Rounding::Down,
+            Side::Sell => Rounding::Up,
+        });
+        let size = precision.size.round(size, Rounding::Down);
+        if price <= 0.0 || size <= 0.0 {
+            return None;
+        }
+        let cash = precision.amount.round(price * size, match side {
+            Side::Buy => Rounding::Down,
+            Side::Sell => Rounding::Up,
+        });
+        let shares = Decimals::shares().to_base(size, Rounding::Down)?;
+        let cash = Decimals::of(collateral).to_base(cash, Rounding::Nearest)?;
+        Some(match side {
+            Side::Buy => OrderAmounts { maker_amount: cash, taker_amount: shares },
+            Side::Sell => OrderAmounts { maker_amount: shares, taker_amount: cash },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn prices_on_a_tick_survive_every_rounding_mode() {
+        let price = OrderPrecision::for_tick(0.01).price;
+        // 0.29 * 100 = 28.999999999999996 in binary floats
+        for rounding in [Rounding::Down, Rounding::Up, Rounding::Nearest] {
+            assert!(close(price.round(0.29, rounding), 0.29));
+            assert_eq!(price.to_base(0.29, rounding), Some(29));
+        }
+        assert_eq!(OrderPrecision::for_tick(0.001).price.to_base(0.577, Rounding::Up), Some(577));
+    }
+
+    #[test]
+    fn prices_between_ticks_round_as_asked() {
+        let price = OrderPrecision::for_tick(0.01).price;
+        assert!(close(price.round(0.2901, Rounding::Down), 0.29));
+        assert!(close(price.round(0.2901, Rounding::Up), 0.30));
+        assert!(close(price.round(0.2901, Rounding::Nearest), 0.29));
+        assert!(close(price.round(0.2961, Rounding::Nearest), 0.30));
+
+        let fine = OrderPrecision::for_tick(0.0001).price;
+        assert!(close(fine.round(0.12345, Rounding::Down), 0.1234));
+        assert!(close(fine.round(0.12341, Rounding::Up), 0.1235));
+    }
+
+    #[test]
+    fn sizes_round_to_the_lot() {
+        let size = OrderPrecision::for_tick(0.01).size;
+        assert!(close(size.round(10.0, Rounding::Down), 10.0));
+        assert!(close(size.round(10.009, Rounding::Down), 10.0));
+        assert!(close(size.round(10.001, Rounding::Up), 10.01));
+        assert!(close(size.round(10.006, Rounding::Nearest), 10.01));
+        assert!(close(size.round(10.004, Rounding::Nearest), 10.0));
+    }
+
+    #[test]
+    fn order_amounts_never_round_against_us() {
+        let cents = OrderPrecision::for_tick(0.01);
+        // Buy: price and cash round down, size down to the lot
+        let buy = OrderAmounts::for_order(Side::Buy, 0.555, 10.009, cents, Collateral::Usdc).unwrap();
+        assert_eq!(buy, OrderAmounts { maker_amount: 5_500_000, taker_amount: 10_000_000 });
+        // Sell: price and cash round up, shares still down
+        let sell = OrderAmounts::for_order(Side::Sell, 0.551, 10.0, cents, Collateral::Usdc).unwrap();
+        assert_eq!(sell, OrderAmounts { maker_amount: 10_000_000, taker_amount: 5_600_000 });
+        // On-tick orders are unchanged
+        let exact = OrderAmounts::for_order(Side::Buy, 0.577, 3.0, OrderPrecision::for_tick(0.001), Collateral::Usdc).unwrap();
+        assert_eq!(exact, OrderAmounts { maker_amount: 1_731_000, taker_amount: 3_000_000 });
+        // Below one lot there is nothing to send
+        assert_eq!(OrderAmounts::for_order(Side::Buy, 0.5, 0.009, cents, Collateral::Usdc), None);
+    }
+}
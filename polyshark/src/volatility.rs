@@ -0,0 +1,113 @@
+use crate::storage::{Storage, StorageResult};
+use crate::types::{Market, MarketId, OrderBook};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// How much extra edge a market's recent price noise demands
+#[derive(Debug, Clone, Deserialize)]
+pub struct VolatilityConfig {
+    #[serde(default = "default_multiple")]
+    pub multiple: f64,         // Extra edge per unit of mid volatility
+    #[serde(default = "default_window_hours")]
+    pub window_hours: u64,     // Recorded history the volatility is measured over
+    #[serde(default = "default_sample_ms")]
+    pub sample_ms: u64,        // Mid is sampled on this grid before taking changes
+    #[serde(default = "default_max_extra_edge")]
+    pub max_extra_edge: f64,   // Cap on the volatility add-on
+}
+
+impl Default for VolatilityConfig {
+    fn default() -> Self {
+        Self {
+            multiple: default_multiple(),
+            window_hours: default_window_hours(),
+            sample_ms: default_sample_ms(),
+            max_extra_edge: default_max_extra_edge(),
+        }
+    }
+}
+
+fn default_multiple() -> f64 {
+    2.0
+}
+
+fn default_window_hours() -> u64 {
+    24
+}
+
+fn default_sample_ms() -> u64 {
+    60_000
+}
+
+fn default_max_extra_edge() -> f64 {
+    0.05
+}
+
+/// Standard deviation of YES mid changes between samples, in price units
+pub fn mid_volatility(books: &[OrderBook], sample_ms: u64) -> Option<f64> {
+    let sample_ms = sample_ms.max(1);
+    // Last mid seen in each sample bucket
+    let mut mids: Vec<(u64, f64)> = Vec::new();
+    for book in books {
+        let Some(mid) = book.midpoint() else {
+            continue;
+        };
+        let bucket = book.timestamp / sample_ms;
+        match mids.last_mut() {
+            Some(last) if last.0 == bucket => last.1 = mid,
+            _ => mids.push((bucket, mid)),
+        }
+    }
+    let changes: Vec<f64> = mids.windows(2).map(|w| w[1].1 - w[0].1).collect();
+    if changes.len() < 2 {
+        return None;
+    }
+    let mean = changes.iter().sum::<f64>() / changes.len() as f64;
+    let variance = changes.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / (changes.len() - 1) as f64;
+    Some(variance.sqrt())
+}
+
+/// Per-market mid volatility measured from recorded books
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VolatilityTable {
+    pub computed_at: u64,  // Seconds
+    pub markets: HashMap<MarketId, f64>,
+}
+
+impl VolatilityTable {
+    /// Measure every market over the configured window ending at `now` (seconds),
+    /// from the recorded books of its YES token
+    pub fn compute(storage: &mut dyn Storage, markets: &[Market], config: &VolatilityConfig, now: u64) -> StorageResult<Self> {
+        let to = now * 1000;
+        let from = to.saturating_sub(config.window_hours * 3_600_000);
+        let mut table = Self { computed_at: now, markets: HashMap::new() };
+        for market in markets {
+            let Some(yes) = market.clob_token_ids.first() else {
+                continue;
+            };
+            let books = storage.load_books(yes, from, to)?;
+            if let Some(vol) = mid_volatility(&books, config.sample_ms) {
+                table.markets.insert(market.id.clone(), vol);
+            }
+        }
+        Ok(table)
+    }
+
+    pub fn load(path: &Path) -> StorageResult<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> StorageResult<()> {
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Edge a market needs on top of the base threshold (0 for unmeasured markets)
+    pub fn extra_edge(&self, config: &VolatilityConfig, market_id: &MarketId) -> f64 {
+        self.markets.get(market_id)
+            .map(|vol| (vol * config.multiple).min(config.max_extra_edge))
+            .unwrap_or(0.0)
+    }
+}
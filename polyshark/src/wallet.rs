@@ -1,63 +1,187 @@
 use std::collections::HashMap;
-use crate::types::Side;
+use crate::attribution::EntryReason;
+use crate::lots::{ClosedLot, LotBook};
+use crate::money::{Collateral, Usdc};
+use crate::types::{Side, TokenId};
+use serde::Serialize;
 
+/// Wallet mutations kept in `history`; older ones are dropped (their cash effect stays)
+pub const HISTORY_LIMIT: usize = 10_000;
 
 #[derive(Debug, Clone)]
 // fake wallet just a variable
 pub struct Wallet {
-    pub usdc: f64,                              // cash balance
-    pub positions: HashMap<String, Position>,   // token_id -> Position
-    pub starting_balance: f64,
-    pub total_fees_paid: f64,
+    pub usdc: Usdc,                             // cash balance
+    pub collateral: Collateral,
+    pub positions: HashMap<TokenId, Position>,   // token_id -> Position
+    pub starting_balance: Usdc,
+    pub total_fees_paid: Usdc,
     pub total_trades: u32,
     pub winning_trades: u32,
+    pub locked: Usdc,                           // reserved for resting orders
+    pub history: Vec<WalletTx>,                 // recent mutations, oldest first (up to HISTORY_LIMIT)
+    pub lots: LotBook,                          // tax lots behind the long positions
+    last_seq: u64,                              // seq of the newest mutation, dropped ones included
 }
 
+/// Kind of wallet mutation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TxKind {
+    Deduct,
+    Credit,
+    Fee,         // Bookkeeping only: the cash already moved in the matching deduct/credit
+    Lock,
+    Unlock,
+    Adjustment,  // Cash overwritten (e.g., reconciliation against the exchange)
+}
+
+/// One audited wallet mutation
 #[derive(Debug, Clone)]
+pub struct WalletTx {
+    pub seq: u64,
+    pub kind: TxKind,
+    pub amount: Usdc,
+    pub usdc_after: Usdc,
+    pub locked_after: Usdc,
+    pub reason: String,             // e.g., "buy fill", "early exit"
+    pub reference: Option<String>,  // order id, journal id, token id...
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct Position {
-    pub token_id: String,
+    pub token_id: TokenId,
     pub side: Side,          // Changed from String to Side
     pub size: f64,
     pub entry_price: f64,
     pub entry_time: u64,
+    pub max_adverse_excursion: f64,  // Worst unrealized loss seen since entry (>= 0)
+    pub reason: EntryReason,         // Why it was first opened; lots keep the reason of each fill
+}
+
+impl Position {
+    /// Unrealized PnL at a given mark price
+    pub fn unrealized_pnl(&self, price: f64) -> f64 {
+        match self.side {
+            Side::Buy => (price - self.entry_price) * self.size,
+            Side::Sell => (self.entry_price - price) * self.size,
+        }
+    }
 }
 
 impl Wallet {
     /// Create new wallet with starting balance
     pub fn new(starting_balance: f64) -> Self {
         Self {
-            usdc: starting_balance,
+            usdc: Usdc(starting_balance),
+            collateral: Collateral::Usdc,
             positions: HashMap::new(),
-            starting_balance,
-            total_fees_paid: 0.0,
+            starting_balance: Usdc(starting_balance),
+            total_fees_paid: Usdc::ZERO,
             total_trades: 0,
             winning_trades: 0,
+            locked: Usdc::ZERO,
+            history: Vec::new(),
+            lots: LotBook::default(),
+            last_seq: 0,
         }
     }
 
+    /// Cash not reserved for resting orders
+    pub fn available(&self) -> Usdc {
+        self.usdc - self.locked
+    }
+
     /// Check if wallet can afford a purchase
-    pub fn can_afford(&self, amount: f64) -> bool {
-        self.usdc >= amount
+    pub fn can_afford(&self, amount: Usdc) -> bool {
+        self.available() >= amount
+    }
+
+    /// Tokens held long (what we are allowed to sell; there is no margin shorting)
+    pub fn held(&self, token_id: &TokenId) -> f64 {
+        self.positions.get(token_id)
+            .filter(|p| p.side == Side::Buy)
+            .map(|p| p.size)
+            .unwrap_or(0.0)
     }
 
     /// Deduct amount from wallet
-    pub fn deduct(&mut self, amount: f64) -> bool {
+    pub fn deduct(&mut self, amount: Usdc, reason: &str, reference: Option<&str>) -> bool {
         if self.can_afford(amount) {
-            self.usdc -= amount;
+            self.debit_unchecked(amount, reason, reference);
             true
         } else {
             false
         }
     }
 
+    /// Deduct without the affordability check (replaying history that already happened)
+    pub fn debit_unchecked(&mut self, amount: Usdc, reason: &str, reference: Option<&str>) {
+        self.usdc -= amount;
+        self.log(TxKind::Deduct, amount, reason, reference);
+    }
+
     /// Credit amount to wallet
-    pub fn credit(&mut self, amount: f64) {
+    pub fn credit(&mut self, amount: Usdc, reason: &str, reference: Option<&str>) {
         self.usdc += amount;
+        self.log(TxKind::Credit, amount, reason, reference);
     }
 
     /// Add fee to tracking
-    pub fn record_fee(&mut self, fee: f64) {
+    pub fn record_fee(&mut self, fee: Usdc, reference: Option<&str>) {
         self.total_fees_paid += fee;
+        self.log(TxKind::Fee, fee, "fee", reference);
+    }
+
+    /// Reserve cash for a resting order
+    pub fn lock(&mut self, amount: Usdc, reference: Option<&str>) -> bool {
+        if !self.can_afford(amount) {
+            return false;
+        }
+        self.locked += amount;
+        self.log(TxKind::Lock, amount, "order placed", reference);
+        true
+    }
+
+    /// Release a reservation (order filled, cancelled or expired)
+    pub fn unlock(&mut self, amount: Usdc, reason: &str, reference: Option<&str>) {
+        let amount = Usdc(amount.value().min(self.locked.value()));
+        self.locked -= amount;
+        self.log(TxKind::Unlock, amount, reason, reference);
+    }
+
+    /// Overwrite the cash balance, recording the difference
+    pub fn adjust_cash(&mut self, usdc: Usdc, reason: &str) {
+        let delta = usdc - self.usdc;
+        self.usdc = usdc;
+        self.log(TxKind::Adjustment, delta, reason, None);
+    }
+
+    /// Mutations mentioning `reference`
+    pub fn history_for<'a>(&'a self, reference: &'a str) -> impl Iterator<Item = &'a WalletTx> + 'a {
+        self.history.iter().filter(move |t| t.reference.as_deref() == Some(reference))
+    }
+
+    /// Mutations after sequence number `seq` that are still kept
+    pub fn history_since(&self, seq: u64) -> &[WalletTx] {
+        let start = self.history.partition_point(|t| t.seq <= seq);
+        &self.history[start..]
+    }
+
+    fn log(&mut self, kind: TxKind, amount: Usdc, reason: &str, reference: Option<&str>) {
+        // Trim in chunks so a long run doesn't shift the whole history on every mutation
+        if self.history.len() >= HISTORY_LIMIT + HISTORY_LIMIT / 10 {
+            self.history.drain(..self.history.len() - HISTORY_LIMIT);
+        }
+        self.last_seq += 1;
+        self.history.push(WalletTx {
+            seq: self.last_seq,
+            kind,
+            amount,
+            usdc_after: self.usdc,
+            locked_after: self.locked,
+            reason: reason.to_string(),
+            reference: reference.map(String::from),
+        });
     }
 
     /// Record a trade result
@@ -69,19 +193,19 @@ impl Wallet {
     }
 
     /// Get current equity (cash + position value)
-    pub fn equity(&self, current_prices: &HashMap<String, f64>) -> f64 {
+    pub fn equity(&self, current_prices: &HashMap<TokenId, f64>) -> f64 {
         let position_value: f64 = self.positions.iter()
             .map(|(token_id, pos)| {
                 let current_price = current_prices.get(token_id).unwrap_or(&0.0);
                 pos.size * current_price
             })
             .sum();
-        self.usdc + position_value
+        self.usdc.value() + position_value
     }
 
     /// Get profit/loss from starting balance
-    pub fn pnl(&self, current_prices: &HashMap<String, f64>) -> f64 {
-        self.equity(current_prices) - self.starting_balance
+    pub fn pnl(&self, current_prices: &HashMap<TokenId, f64>) -> f64 {
+        self.equity(current_prices) - self.starting_balance.value()
     }
 
     /// Get win rate
@@ -93,28 +217,212 @@ impl Wallet {
         }
     }
 
+    /// Mark positions to market, updating each one's maximum adverse excursion
+    pub fn update_excursions(&mut self, current_prices: &HashMap<TokenId, f64>) {
+        for (token_id, price) in current_prices {
+            self.mark_excursion(token_id, *price);
+        }
+    }
+
+    /// Mark one position at `price`, updating its maximum adverse excursion
+    pub fn mark_excursion(&mut self, token_id: &TokenId, price: f64) {
+        if let Some(pos) = self.positions.get_mut(token_id) {
+            let loss = -pos.unrealized_pnl(price);
+            pos.max_adverse_excursion = pos.max_adverse_excursion.max(loss);
+        }
+    }
+
     /// Open a new position
-    pub fn open_position(&mut self, token_id: String, side: Side, size: f64, price: f64, timestamp: u64) {
+    pub fn open_position(&mut self, token_id: TokenId, side: Side, size: f64, price: f64, timestamp: u64, reason: EntryReason) {
         self.positions.insert(token_id.clone(), Position {
             token_id,
             side,
             size,
             entry_price: price,
             entry_time: timestamp,
+            max_adverse_excursion: 0.0,
+            reason,
         });
     }
 
+    /// Add to a position, averaging the entry price (opens it if missing). A fill on the
+    /// opposite side nets against the position first and only the excess opens a new one.
+    /// Long additions also open a tax lot carrying the fee.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_to_position(&mut self, token_id: &TokenId, side: Side, size: f64, price: f64, fee: f64, timestamp: u64, reason: EntryReason) {
+        let netted = match self.positions.get_mut(token_id) {
+            Some(pos) if pos.side == side => {
+                let total = pos.size + size;
+                if total > 0.0 {
+                    pos.entry_price = (pos.entry_price * pos.size + price * size) / total;
+                }
+                pos.size = total;
+                if side == Side::Buy {
+                    self.lots.buy(token_id, size, price, fee, timestamp, reason);
+                }
+                return;
+            }
+            Some(pos) => {
+                let netted = size.min(pos.size);
+                pos.size -= netted;
+                if pos.size <= 1e-9 {
+                    self.positions.remove(token_id);
+                }
+                netted
+            }
+            None => 0.0,
+        };
+        // The fee is split pro rata between the closing and the opening part
+        let excess = size - netted;
+        let fee_share = |part: f64| if size > 0.0 { fee * part / size } else { 0.0 };
+        if netted > 0.0 && side == Side::Sell {
+            self.lots.sell(token_id, netted, price, fee_share(netted), timestamp);
+        }
+        if excess > 1e-9 {
+            if side == Side::Buy {
+                self.lots.buy(token_id, excess, price, fee_share(excess), timestamp, reason);
+            }
+            self.open_position(token_id.clone(), side, excess, price, timestamp, reason);
+        }
+    }
+
+    /// Sell out of a long position without touching cash, removing it once empty.
+    /// Returns the tax lots the sale closed.
+    pub fn sell_from_position(&mut self, token_id: &TokenId, size: f64, price: f64, fee: f64, timestamp: u64) -> Vec<ClosedLot> {
+        if let Some(pos) = self.positions.get_mut(token_id) {
+            pos.size -= size;
+            if pos.size <= 1e-9 {
+                self.positions.remove(token_id);
+            }
+        }
+        self.lots.sell(token_id, size, price, fee, timestamp)
+    }
+
+    /// Close part of a position at one price, crediting the proceeds (debiting the cost when
+    /// covering a short). Returns realized PnL on the reduced size against the weighted
+    /// entry; the remainder keeps that entry price. Reducing by more than is held closes
+    /// the position.
+    pub fn reduce_position(&mut self, token_id: &TokenId, size: f64, price: f64, timestamp: u64) -> Option<f64> {
+        self.reduce(token_id, size, price, 0.0, timestamp, "position reduced")
+    }
+
+    /// `reduce_position` for a taker exit whose fee comes out of the proceeds: credits
+    /// them net of `fee` and records it. Returns realized PnL after the fee.
+    pub fn exit_position(&mut self, token_id: &TokenId, size: f64, price: f64, fee: f64, timestamp: u64, reason: &str) -> Option<f64> {
+        let pnl = self.reduce(token_id, size, price, fee, timestamp, reason)?;
+        self.record_fee(Usdc(fee), Some(token_id));
+        Some(pnl - fee)
+    }
+
     /// Close a position and return PnL
-    pub fn close_position(&mut self, token_id: &str, exit_price: f64) -> Option<f64> {
-        if let Some(pos) = self.positions.remove(token_id) {
-            let pnl = match pos.side {
-                Side::Buy => (exit_price - pos.entry_price) * pos.size,
-                Side::Sell => (pos.entry_price - exit_price) * pos.size,
-            };
-            self.credit(pos.size * exit_price);
-            Some(pnl)
-        } else {
-            None
+    pub fn close_position(&mut self, token_id: &TokenId, exit_price: f64, timestamp: u64) -> Option<f64> {
+        let size = self.positions.get(token_id)?.size;
+        self.reduce(token_id, size, exit_price, 0.0, timestamp, "position closed")
+    }
+
+    /// Redeem a position in a resolved market at its final payout per share (1 or 0,
+    /// or in between for split resolutions). Returns PnL.
+    pub fn settle_position(&mut self, token_id: &TokenId, payout: f64, timestamp: u64) -> Option<f64> {
+        let size = self.positions.get(token_id)?.size;
+        self.reduce(token_id, size, payout, 0.0, timestamp, "settlement")
+    }
+
+    fn reduce(&mut self, token_id: &TokenId, size: f64, price: f64, fee: f64, timestamp: u64, reason: &str) -> Option<f64> {
+        let pos = self.positions.get_mut(token_id)?;
+        let size = size.min(pos.size).max(0.0);
+        let (side, entry_price) = (pos.side, pos.entry_price);
+        pos.size -= size;
+        if pos.size <= 1e-9 {
+            self.positions.remove(token_id);
+        }
+        if side == Side::Buy {
+            self.lots.sell(token_id, size, price, fee, timestamp);
+        }
+        match side {
+            Side::Buy => self.credit(Usdc(size * price - fee), reason, Some(token_id)),
+            // Buying back a short costs the price plus the fee
+            Side::Sell => self.debit_unchecked(Usdc(size * price + fee), reason, Some(token_id)),
+        }
+        Some(match side {
+            Side::Buy => (price - entry_price) * size,
+            Side::Sell => (entry_price - price) * size,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    /// 1000 USDC minus 20 shares bought at 0.40
+    fn long(token: &TokenId) -> Wallet {
+        let mut wallet = Wallet::new(1000.0);
+        wallet.deduct(Usdc(8.0), "buy", None);
+        wallet.add_to_position(token, Side::Buy, 20.0, 0.40, 0.0, 0, EntryReason::ArbLeg);
+        wallet
+    }
+
+    #[test]
+    fn mae_is_the_worst_loss_along_the_price_path() {
+        let token = TokenId::from("yes");
+        let mut wallet = long(&token);
+        for price in [0.38, 0.30, 0.35, 0.55] {
+            wallet.mark_excursion(&token, price);
+        }
+        // 20 shares from 0.40 down to 0.30; the recovery doesn't shrink it
+        assert!(close(wallet.positions[&token].max_adverse_excursion, 2.0));
+        wallet.mark_excursion(&token, 0.25);
+        assert!(close(wallet.positions[&token].max_adverse_excursion, 3.0));
+    }
+
+    #[test]
+    fn a_position_that_only_gains_has_no_mae() {
+        let token = TokenId::from("yes");
+        let mut wallet = long(&token);
+        for price in [0.45, 0.50, 0.41] {
+            wallet.mark_excursion(&token, price);
         }
+        assert!(close(wallet.positions[&token].max_adverse_excursion, 0.0));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn partial_reduce_keeps_the_entry_price() {
+        let token = TokenId::from("yes");
+        let mut wallet = long(&token);
+        let pnl = wallet.reduce_position(&token, 5.0, 0.60, 60).unwrap();
+        assert!(close(pnl, 1.0));
+        assert!(close(wallet.usdc.value(), 995.0));
+        let pos = &wallet.positions[&token];
+        assert!(close(pos.size, 15.0));
+        assert!(close(pos.entry_price, 0.40));
+        assert!(close(wallet.lots.open[&token][0].size, 15.0));
+    }
+
+    #[test]
+    fn reducing_more_than_held_closes_the_position() {
+        let token = TokenId::from("yes");
+        let mut wallet = long(&token);
+        let pnl = wallet.reduce_position(&token, 50.0, 0.30, 60).unwrap();
+        // Only the 20 held are sold
+        assert!(close(pnl, -2.0));
+        assert!(close(wallet.usdc.value(), 998.0));
+        assert!(!wallet.positions.contains_key(&token));
+        assert!(!wallet.lots.open.contains_key(&token));
+        assert!(wallet.reduce_position(&token, 1.0, 0.30, 120).is_none());
+    }
+
+    #[test]
+    fn short_reduce_pays_to_cover() {
+        let token = TokenId::from("no");
+        let mut wallet = Wallet::new(1000.0);
+        wallet.open_position(token.clone(), Side::Sell, 10.0, 0.70, 0, EntryReason::Manual);
+        let pnl = wallet.reduce_position(&token, 4.0, 0.50, 60).unwrap();
+        assert!(close(pnl, 0.8));
+        assert!(close(wallet.usdc.value(), 998.0));
+        assert!(close(wallet.positions[&token].size, 6.0));
+    }
+}
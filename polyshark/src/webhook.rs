@@ -0,0 +1,124 @@
+// Most imports are only used by the webhook sender, which needs the `network` feature
+#![cfg_attr(not(feature = "network"), allow(unused_imports))]
+
+use crate::bus::{EventBus, Signal};
+use crate::execution::MultiLegExecutionReport;
+use crate::sparkline::{PriceContext, SharedPriceHistory};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+
+/// Outbound signal webhooks, for users executing on their own infrastructure
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WebhookConfig {
+    #[serde(default)]
+    pub urls: Vec<String>,            // Every signal is POSTed to each URL
+    #[serde(default)]
+    pub token_env: Option<String>,    // Env var holding a bearer token (never the token itself)
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_timeout_ms() -> u64 {
+    2000
+}
+
+/// JSON body of one webhook call
+#[derive(Debug, Clone, Serialize)]
+pub struct SignalPayload<'a> {
+    pub sent_at_ms: u64,
+    #[serde(flatten)]
+    pub signal: &'a Signal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_context: Option<PriceContext>,  // Recent mid history of a binary signal's market
+}
+
+#[cfg(feature = "network")]
+/// Width of the sparkline sent with each signal
+const CONTEXT_WIDTH: usize = 24;
+
+/// JSON body for a completed (or partially completed) multi-leg trade
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionPayload<'a> {
+    pub sent_at_ms: u64,
+    pub kind: &'static str,  // Always "execution"
+    pub execution: &'a MultiLegExecutionReport,
+    pub net_cost: f64,
+    pub cost_vs_predicted: f64,
+    pub complete: bool,
+}
+
+#[cfg(feature = "network")]
+/// Publishes every detected signal and multi-leg execution as JSON to the configured endpoints.
+/// Delivery is best effort: a slow or failing endpoint is logged, never retried, and
+/// never holds up detection. With a price history, binary signals carry a sparkline of
+/// their market's recent mids.
+pub fn spawn_signal_webhooks(bus: &EventBus, config: WebhookConfig, history: Option<SharedPriceHistory>) -> Option<JoinHandle<()>> {
+    if config.urls.is_empty() {
+        return None;
+    }
+    let mut signals = bus.signals.subscribe();
+    let mut executions = bus.executions.subscribe();
+    let http = reqwest::Client::builder()
+        .timeout(Duration::from_millis(config.timeout_ms))
+        .build()
+        .unwrap_or_default();
+    let token = config.token_env.as_ref().and_then(|var| std::env::var(var).ok());
+
+    Some(tokio::spawn(async move {
+        loop {
+            let sent_at_ms = || SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0);
+            let body = tokio::select! {
+                signal = signals.recv() => match signal {
+                    Ok(signal) => {
+                        let price_context = match (&signal, &history) {
+                            (Signal::Binary(arb), Some(history)) => history.lock()
+                                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                                .context(&arb.market_id, CONTEXT_WIDTH),
+                            _ => None,
+                        };
+                        serde_json::to_string(&SignalPayload { sent_at_ms: sent_at_ms(), signal: &signal, price_context })
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        eprintln!("⚠️  webhooks fell behind, {} signals not delivered", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                report = executions.recv() => match report {
+                    Ok(report) => serde_json::to_string(&ExecutionPayload {
+                        sent_at_ms: sent_at_ms(),
+                        kind: "execution",
+                        execution: &report,
+                        net_cost: report.net_cost(),
+                        cost_vs_predicted: report.cost_vs_predicted(),
+                        complete: report.is_complete(),
+                    }),
+                    Err(RecvError::Lagged(skipped)) => {
+                        eprintln!("⚠️  webhooks fell behind, {} executions not delivered", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            };
+            let Ok(body) = body else {
+                continue;
+            };
+            for url in &config.urls {
+                let mut request = http.post(url).header("Content-Type", "application/json").body(body.clone());
+                if let Some(token) = &token {
+                    request = request.bearer_auth(token);
+                }
+                let url = url.clone();
+                // Fire and forget so one slow consumer doesn't delay the others
+                tokio::spawn(async move {
+                    match request.send().await.and_then(|r| r.error_for_status()) {
+                        Ok(_) => {}
+                        Err(err) => eprintln!("⚠️  webhook {} failed: {}", url, err),
+                    }
+                });
+            }
+        }
+    }))
+}
@@ -0,0 +1,421 @@
+use crate::types::{OrderBook, Price, PriceLevel, Side, TokenId, Trade};
+#[cfg(feature = "network")]
+use futures_util::{SinkExt, StreamExt};
+use serde_json::Value;
+use std::collections::HashMap;
+#[cfg(feature = "network")]
+use std::time::Duration;
+#[cfg(feature = "network")]
+use tokio::sync::mpsc;
+#[cfg(feature = "network")]
+use tokio::task::JoinHandle;
+#[cfg(feature = "network")]
+use tokio_tungstenite::connect_async;
+#[cfg(feature = "network")]
+use tokio_tungstenite::tungstenite::Message;
+
+/// Public market channel
+pub const MARKET_WS_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/market";
+
+/// Assets Polymarket accepts on a single connection
+pub const MAX_ASSETS_PER_CONNECTION: usize = 500;
+
+/// Parsed market channel event
+#[derive(Debug, Clone)]
+pub enum MarketEvent {
+    /// Full snapshot of one token's book
+    Book { book: OrderBook, hash: Option<String>, seq: Option<u64> },
+    /// Single level replaced (size 0 removes the level)
+    PriceChange {
+        token_id: TokenId,
+        side: Side,
+        price: f64,
+        size: f64,
+        timestamp: u64,
+        hash: Option<String>,
+        seq: Option<u64>,
+    },
+    /// Periodic hash of the exchange's current book, with no changes
+    Heartbeat { token_id: TokenId, timestamp: u64, hash: Option<String>, seq: Option<u64> },
+    /// Last sale print
+    LastTrade(Trade),
+}
+
+impl MarketEvent {
+    pub fn token_id(&self) -> &TokenId {
+        match self {
+            MarketEvent::Book { book, .. } => &book.token_id,
+            MarketEvent::PriceChange { token_id, .. } | MarketEvent::Heartbeat { token_id, .. } => token_id,
+            MarketEvent::LastTrade(trade) => &trade.token_id,
+        }
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        match self {
+            MarketEvent::Book { book, .. } => book.timestamp,
+            MarketEvent::PriceChange { timestamp, .. } | MarketEvent::Heartbeat { timestamp, .. } => *timestamp,
+            MarketEvent::LastTrade(trade) => trade.timestamp,
+        }
+    }
+
+    pub fn hash(&self) -> Option<&str> {
+        match self {
+            MarketEvent::Book { hash, .. } | MarketEvent::PriceChange { hash, .. } | MarketEvent::Heartbeat { hash, .. } => hash.as_deref(),
+            MarketEvent::LastTrade(_) => None,
+        }
+    }
+
+    /// Per-token sequence number, when the feed sends one
+    pub fn seq(&self) -> Option<u64> {
+        match self {
+            MarketEvent::Book { seq, .. } | MarketEvent::PriceChange { seq, .. } | MarketEvent::Heartbeat { seq, .. } => *seq,
+            MarketEvent::LastTrade(_) => None,
+        }
+    }
+}
+
+/// Parse one text frame (object or array of objects) into events
+pub fn parse_market_message(text: &str) -> Vec<MarketEvent> {
+    let value: Value = match serde_json::from_str(text) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+    let items = match value {
+        Value::Array(items) => items,
+        other => vec![other],
+    };
+
+    let mut events = Vec::new();
+    for item in &items {
+        let timestamp = num_field(item, "timestamp").unwrap_or(0.0) as u64;
+        let seq = num_field(item, "seq").map(|s| s as u64);
+        match item["event_type"].as_str() {
+            Some("book") => {
+                let mut book = OrderBook {
+                    token_id: str_field(item, "asset_id").into(),
+                    bids: parse_levels(&item["bids"]),
+                    asks: parse_levels(&item["asks"]),
+                    timestamp,
+                };
+                book.sort_levels();
+                events.push(MarketEvent::Book { book, hash: item["hash"].as_str().map(String::from), seq });
+            }
+            Some("price_change") => {
+                // Newer payloads nest per-asset changes, older ones use `changes` with a top-level asset
+                let changes = item["price_changes"].as_array().or(item["changes"].as_array());
+                for change in changes.into_iter().flatten() {
+                    let token_id: TokenId = change["asset_id"].as_str().map(String::from).unwrap_or_else(|| str_field(item, "asset_id")).into();
+                    let side = match change["side"].as_str() {
+                        Some("BUY") => Side::Buy,
+                        Some("SELL") => Side::Sell,
+                        _ => continue,
+                    };
+                    if let (Some(price), Some(size)) = (num_field(change, "price"), num_field(change, "size")) {
+                        events.push(MarketEvent::PriceChange {
+                            token_id,
+                            side,
+                            price,
+                            size,
+                            timestamp,
+                            hash: change["hash"].as_str().map(String::from),
+                            seq: num_field(change, "seq").map(|s| s as u64).or(seq),
+                        });
+                    }
+                }
+            }
+            Some("heartbeat") | Some("book_hash") => {
+                events.push(MarketEvent::Heartbeat {
+                    token_id: str_field(item, "asset_id").into(),
+                    timestamp,
+                    hash: item["hash"].as_str().map(String::from),
+                    seq,
+                });
+            }
+            Some("last_trade_price") => {
+                let side = match item["side"].as_str() {
+                    Some("BUY") => Side::Buy,
+                    Some("SELL") => Side::Sell,
+                    _ => continue,
+                };
+                if let (Some(price), Some(size)) = (num_field(item, "price"), num_field(item, "size")) {
+                    let token_id: TokenId = str_field(item, "asset_id").into();
+                    events.push(MarketEvent::LastTrade(Trade {
+                        id: format!("{}-{}", token_id, timestamp),
+                        token_id,
+                        price,
+                        size,
+                        side,
+                        timestamp,
+                    }));
+                }
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+/// Read a number the API may send as a string or a JSON number
+pub(crate) fn num_field(value: &Value, key: &str) -> Option<f64> {
+    match &value[key] {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) => n.as_f64(),
+        _ => None,
+    }
+}
+
+fn str_field(value: &Value, key: &str) -> String {
+    value[key].as_str().unwrap_or_default().to_string()
+}
+
+pub(crate) fn parse_levels(value: &Value) -> Vec<PriceLevel> {
+    value.as_array()
+        .map(|levels| {
+            levels.iter()
+                // Levels priced outside [0, 1] are malformed; drop them rather than trade on them
+                .filter_map(|l| Some(PriceLevel { price: Price::new(num_field(l, "price")?)?.value(), size: num_field(l, "size")? }))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Subscribe/unsubscribe work produced by a rebalance
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShardChange {
+    pub shard: usize,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Spreads token subscriptions across connections, moving as few as possible.
+/// Prioritized tokens get small dedicated shards so busy background markets on a
+/// shared connection never delay them.
+#[derive(Debug, Clone)]
+pub struct ShardManager {
+    pub max_per_shard: usize,
+    pub max_per_priority_shard: usize,
+    pub shards: Vec<Vec<String>>,  // shard index -> token_ids
+    pub priority: Vec<bool>,       // shard index -> dedicated to prioritized tokens
+}
+
+impl ShardManager {
+    pub fn new(max_per_shard: usize) -> Self {
+        Self {
+            max_per_shard: max_per_shard.max(1),
+            max_per_priority_shard: max_per_shard.max(1),
+            shards: Vec::new(),
+            priority: Vec::new(),
+        }
+    }
+
+    pub fn with_priority_shards(mut self, max_per_priority_shard: usize) -> Self {
+        self.max_per_priority_shard = max_per_priority_shard.max(1);
+        self
+    }
+
+    /// Which shard a token is currently on
+    pub fn shard_of(&self, token_id: &str) -> Option<usize> {
+        self.shards.iter().position(|s| s.iter().any(|t| t == token_id))
+    }
+
+    /// Reconcile shards with a new watchlist, returning per-shard changes
+    pub fn rebalance(&mut self, watchlist: &[String]) -> Vec<ShardChange> {
+        self.rebalance_prioritized(watchlist, &[])
+    }
+
+    /// Reconcile shards with a new watchlist, keeping `hot` tokens (a subset of the
+    /// watchlist) on dedicated shards and everything else on shared ones
+    pub fn rebalance_prioritized(&mut self, watchlist: &[String], hot: &[String]) -> Vec<ShardChange> {
+        let mut changes: HashMap<usize, ShardChange> = HashMap::new();
+
+        // 1. Drop tokens no longer watched, and tokens whose priority changed
+        for (idx, shard) in self.shards.iter_mut().enumerate() {
+            let dedicated = self.priority[idx];
+            let (keep, drop): (Vec<String>, Vec<String>) = shard.drain(..)
+                .partition(|t| watchlist.contains(t) && hot.contains(t) == dedicated);
+            *shard = keep;
+            if !drop.is_empty() {
+                changes.entry(idx).or_insert_with(|| empty_change(idx)).removed = drop;
+            }
+        }
+
+        // 2. Place new tokens on the least-loaded shard of their kind, opening shards when all are full
+        for token in watchlist {
+            if self.shard_of(token).is_some() {
+                continue;
+            }
+            let dedicated = hot.contains(token);
+            let capacity = if dedicated { self.max_per_priority_shard } else { self.max_per_shard };
+            let idx = match self.shards.iter().enumerate()
+                .filter(|(i, s)| self.priority[*i] == dedicated && s.len() < capacity)
+                .min_by_key(|(_, s)| s.len())
+            {
+                Some((idx, _)) => idx,
+                None => {
+                    self.shards.push(Vec::new());
+                    self.priority.push(dedicated);
+                    self.shards.len() - 1
+                }
+            };
+            self.shards[idx].push(token.clone());
+            changes.entry(idx).or_insert_with(|| empty_change(idx)).added.push(token.clone());
+        }
+
+        let mut changes: Vec<ShardChange> = changes.into_values().collect();
+        changes.sort_by_key(|c| c.shard);
+        changes
+    }
+}
+
+fn empty_change(shard: usize) -> ShardChange {
+    ShardChange { shard, added: Vec::new(), removed: Vec::new() }
+}
+
+/// Merges events from every shard into one ordered stream per token
+#[derive(Debug, Clone, Default)]
+pub struct OrderedMerger {
+    last_seen: HashMap<TokenId, (u64, Option<String>)>,  // token_id -> (timestamp, hash)
+}
+
+impl OrderedMerger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept an event unless it is older than, or a duplicate of, the last one seen
+    pub fn accept(&mut self, event: &MarketEvent) -> bool {
+        let ts = event.timestamp();
+        let hash = event.hash().map(String::from);
+        match self.last_seen.get(event.token_id()) {
+            Some((last_ts, _)) if ts < *last_ts => false,
+            Some((last_ts, last_hash)) if ts == *last_ts && hash.is_some() && hash == *last_hash => false,
+            _ => {
+                self.last_seen.insert(event.token_id().clone(), (ts, hash));
+                true
+            }
+        }
+    }
+}
+
+#[cfg(feature = "network")]
+/// Hold one shard's connection open, forwarding parsed events until it drops
+pub async fn run_shard(
+    url: &str,
+    assets: Vec<String>,
+    tx: mpsc::UnboundedSender<MarketEvent>,
+) -> Result<(), tokio_tungstenite::tungstenite::Error> {
+    let (mut ws, _) = connect_async(url).await?;
+    let subscribe = serde_json::json!({ "assets_ids": assets, "type": "market" });
+    ws.send(Message::Text(subscribe.to_string().into())).await?;
+
+    while let Some(msg) = ws.next().await {
+        match msg? {
+            Message::Text(text) => {
+                for event in parse_market_message(&text) {
+                    if tx.send(event).is_err() {
+                        return Ok(()); // Consumer went away
+                    }
+                }
+            }
+            Message::Ping(payload) => ws.send(Message::Pong(payload)).await?,
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "network")]
+/// Delay before the first reconnect of a dropped shard; doubles on each failure in a row
+const RECONNECT_MIN: Duration = Duration::from_secs(1);
+
+#[cfg(feature = "network")]
+const RECONNECT_MAX: Duration = Duration::from_secs(60);
+
+#[cfg(feature = "network")]
+/// `run_shard` forever: a dropped or failed connection is logged and reopened with
+/// exponential backoff until the consumer goes away
+async fn keep_shard(url: String, assets: Vec<String>, tx: mpsc::UnboundedSender<MarketEvent>) {
+    let mut delay = RECONNECT_MIN;
+    loop {
+        match run_shard(&url, assets.clone(), tx.clone()).await {
+            Ok(()) if tx.is_closed() => return,
+            Ok(()) => {
+                eprintln!("market feed: shard of {} tokens closed, reconnecting", assets.len());
+                delay = RECONNECT_MIN;
+            }
+            Err(err) => {
+                eprintln!("market feed: shard of {} tokens failed: {} (retrying in {}s)", assets.len(), err, delay.as_secs());
+            }
+        }
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(RECONNECT_MAX);
+    }
+}
+
+#[cfg(feature = "network")]
+/// Sharded market feed exposing a single merged receiver
+#[derive(Debug)]
+pub struct ShardedMarketStream {
+    pub url: String,
+    pub manager: ShardManager,
+    tasks: Vec<Option<JoinHandle<()>>>,
+    tx: mpsc::UnboundedSender<MarketEvent>,
+    rx: mpsc::UnboundedReceiver<MarketEvent>,
+    merger: OrderedMerger,
+}
+
+#[cfg(feature = "network")]
+impl ShardedMarketStream {
+    pub fn new(url: &str, max_per_shard: usize) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            url: url.to_string(),
+            manager: ShardManager::new(max_per_shard),
+            tasks: Vec::new(),
+            tx,
+            rx,
+            merger: OrderedMerger::new(),
+        }
+    }
+
+    /// Stream prioritized tokens on dedicated shards of at most this many tokens
+    pub fn with_priority_shards(mut self, max_per_priority_shard: usize) -> Self {
+        self.manager.max_per_priority_shard = max_per_priority_shard.max(1);
+        self
+    }
+
+    /// Apply a new watchlist, reconnecting only the shards that changed
+    pub fn set_watchlist(&mut self, watchlist: &[String]) {
+        self.set_watchlist_prioritized(watchlist, &[]);
+    }
+
+    /// Apply a new watchlist with `hot` tokens streamed on dedicated shards
+    pub fn set_watchlist_prioritized(&mut self, watchlist: &[String], hot: &[String]) {
+        for change in self.manager.rebalance_prioritized(watchlist, hot) {
+            if self.tasks.len() <= change.shard {
+                self.tasks.resize_with(change.shard + 1, || None);
+            }
+            if let Some(task) = self.tasks[change.shard].take() {
+                task.abort();
+            }
+            let assets = self.manager.shards[change.shard].clone();
+            if assets.is_empty() {
+                continue;
+            }
+            let url = self.url.clone();
+            let tx = self.tx.clone();
+            self.tasks[change.shard] = Some(tokio::spawn(keep_shard(url, assets, tx)));
+        }
+    }
+
+    /// Next in-order event across all shards
+    pub async fn next(&mut self) -> Option<MarketEvent> {
+        while let Some(event) = self.rx.recv().await {
+            if self.merger.accept(&event) {
+                return Some(event);
+            }
+        }
+        None
+    }
+}